@@ -0,0 +1,321 @@
+use std::slice;
+
+use num::bigint::BigInt;
+use num::ToPrimitive;
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{context_tag, explicit, explicit_inner, expect_end, next_block, sequence};
+use error::X509DecodeErr;
+use oid::ObjectIdentifier;
+use oids;
+
+/// The parameters field of an AlgorithmIdentifier.
+///
+/// Absent and NULL parameters are distinct on the wire: RSA algorithms
+/// require an explicit NULL, while ECDSA and EdDSA require the field to be
+/// left out. Getting this wrong produces certificates that strict verifiers
+/// reject, so the two are kept apart here.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AlgorithmParameters {
+    Absent,
+    Null,
+    /// A bare OID, such as the namedCurve of an EC public key.
+    ObjectIdentifier(ObjectIdentifier),
+    /// Any other parameters, such as RSASSA-PSS-params.
+    Other(ASN1Block),
+}
+
+/// An algorithm OID and its parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlgorithmIdentifier {
+    pub algorithm: ObjectIdentifier,
+    pub parameters: AlgorithmParameters,
+}
+
+impl AlgorithmIdentifier {
+    /// An identifier with absent parameters.
+    pub fn new(algorithm: ObjectIdentifier) -> AlgorithmIdentifier {
+        AlgorithmIdentifier { algorithm, parameters: AlgorithmParameters::Absent }
+    }
+
+    /// An identifier with NULL parameters, as the RSA algorithms use.
+    pub fn with_null_parameters(algorithm: ObjectIdentifier) -> AlgorithmIdentifier {
+        AlgorithmIdentifier { algorithm, parameters: AlgorithmParameters::Null }
+    }
+
+    /// An RSASSA-PSS identifier carrying `params`, for a signature or for a
+    /// key restricted to PSS.
+    pub fn rsa_pss(params: &RsaPssParameters) -> Result<AlgorithmIdentifier, ASN1EncodeErr> {
+        let block = params.to_asn1()?.remove(0);
+        Ok(AlgorithmIdentifier { algorithm: oids::RSASSA_PSS, parameters: AlgorithmParameters::Other(block) })
+    }
+
+    /// The RSASSA-PSS-params of an RSASSA-PSS identifier. A key restricted
+    /// to PSS may leave them out (RFC 4055 §3.1), giving `None`; a
+    /// signature algorithm always has them.
+    pub fn rsa_pss_parameters(&self) -> Result<Option<RsaPssParameters>, X509DecodeErr> {
+        if self.algorithm != oids::RSASSA_PSS {
+            return Err(X509DecodeErr::InvalidField("algorithm"));
+        }
+        match self.parameters {
+            AlgorithmParameters::Absent => Ok(None),
+            AlgorithmParameters::Other(ref params) => {
+                let (params, rest) = RsaPssParameters::from_asn1(slice::from_ref(params))?;
+                expect_end(rest, "RSASSA-PSS-params")?;
+                Ok(Some(params))
+            }
+            _ => Err(X509DecodeErr::InvalidField("parameters")),
+        }
+    }
+}
+
+impl ToASN1 for AlgorithmIdentifier {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = vec![self.algorithm.to_block()];
+        match self.parameters {
+            AlgorithmParameters::Absent => {}
+            AlgorithmParameters::Null => items.push(ASN1Block::Null(0)),
+            AlgorithmParameters::ObjectIdentifier(ref oid) => items.push(oid.to_block()),
+            AlgorithmParameters::Other(ref params) => items.push(params.clone()),
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for AlgorithmIdentifier {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "AlgorithmIdentifier")?;
+        let items = sequence(head, "AlgorithmIdentifier")?;
+        let (algorithm, rest) = next_block(items, "algorithm")?;
+        let algorithm = ObjectIdentifier::from_block(algorithm, "algorithm")?;
+        let (parameters, rest) = match rest.split_first() {
+            None => (AlgorithmParameters::Absent, rest),
+            Some((&ASN1Block::Null(_), rest)) => (AlgorithmParameters::Null, rest),
+            Some((oid @ ASN1Block::ObjectIdentifier(..), rest)) => {
+                (AlgorithmParameters::ObjectIdentifier(ObjectIdentifier::from_block(oid, "parameters")?), rest)
+            }
+            Some((params, rest)) => (AlgorithmParameters::Other(params.clone()), rest),
+        };
+        expect_end(rest, "AlgorithmIdentifier")?;
+        Ok((AlgorithmIdentifier { algorithm, parameters }, tail))
+    }
+}
+
+/// RSASSA-PSS-params (RFC 4055 §3.1): the hash, mask generation function,
+/// salt length and trailer field of an RSASSA-PSS signature.
+///
+/// The defaults are SHA-1, MGF1 with SHA-1, a 20 byte salt and trailer
+/// field 1. Fields equal to their default are left out when encoding, as
+/// DER requires.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RsaPssParameters {
+    pub hash_algorithm: AlgorithmIdentifier,
+    pub mask_gen_algorithm: AlgorithmIdentifier,
+    pub salt_length: u64,
+    pub trailer_field: u64,
+}
+
+impl RsaPssParameters {
+    /// Parameters for `hash`, with MGF1 over the same hash as RFC 4055
+    /// recommends. The hash identifiers get NULL parameters, as OpenSSL
+    /// and most CAs write them.
+    pub fn new(hash: ObjectIdentifier, salt_length: u64) -> RsaPssParameters {
+        RsaPssParameters {
+            hash_algorithm: AlgorithmIdentifier::with_null_parameters(hash.clone()),
+            mask_gen_algorithm: mgf1(hash),
+            salt_length,
+            trailer_field: 1,
+        }
+    }
+
+    /// The hash MGF1 is used with, if MGF1 is the mask generation
+    /// function.
+    pub fn mgf1_hash_algorithm(&self) -> Option<AlgorithmIdentifier> {
+        match self.mask_gen_algorithm.parameters {
+            AlgorithmParameters::Other(ref params) if self.mask_gen_algorithm.algorithm == oids::MGF1 => {
+                AlgorithmIdentifier::from_asn1(slice::from_ref(params)).ok().map(|(hash, _)| hash)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for RsaPssParameters {
+    fn default() -> RsaPssParameters {
+        RsaPssParameters::new(oids::SHA1, 20)
+    }
+}
+
+// MGF1 with `hash`.
+fn mgf1(hash: ObjectIdentifier) -> AlgorithmIdentifier {
+    let hash = ASN1Block::Sequence(0, vec![hash.to_block(), ASN1Block::Null(0)]);
+    AlgorithmIdentifier { algorithm: oids::MGF1, parameters: AlgorithmParameters::Other(hash) }
+}
+
+impl ToASN1 for RsaPssParameters {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let defaults = RsaPssParameters::default();
+        let mut items = Vec::new();
+        if self.hash_algorithm != defaults.hash_algorithm {
+            items.push(explicit(0, self.hash_algorithm.to_asn1()?.remove(0)));
+        }
+        if self.mask_gen_algorithm != defaults.mask_gen_algorithm {
+            items.push(explicit(1, self.mask_gen_algorithm.to_asn1()?.remove(0)));
+        }
+        if self.salt_length != defaults.salt_length {
+            items.push(explicit(2, ASN1Block::Integer(0, BigInt::from(self.salt_length))));
+        }
+        if self.trailer_field != defaults.trailer_field {
+            items.push(explicit(3, ASN1Block::Integer(0, BigInt::from(self.trailer_field))));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for RsaPssParameters {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "RSASSA-PSS-params")?;
+        let mut params = RsaPssParameters::default();
+        let mut last = None;
+        for item in sequence(head, "RSASSA-PSS-params")? {
+            let tag = context_tag(item).ok_or(X509DecodeErr::InvalidField("RSASSA-PSS-params"))?;
+            // The fields are in tag order, each at most once.
+            if last.is_some_and(|last| tag <= last) {
+                return Err(X509DecodeErr::InvalidField("RSASSA-PSS-params"));
+            }
+            last = Some(tag);
+            match (tag, explicit_inner(item, tag)) {
+                (0, Some(inner)) => params.hash_algorithm = AlgorithmIdentifier::from_asn1(slice::from_ref(inner))?.0,
+                (1, Some(inner)) => {
+                    params.mask_gen_algorithm = AlgorithmIdentifier::from_asn1(slice::from_ref(inner))?.0;
+                }
+                (2, Some(ASN1Block::Integer(_, n))) => {
+                    params.salt_length = n.to_u64().ok_or(X509DecodeErr::InvalidField("saltLength"))?;
+                }
+                (3, Some(ASN1Block::Integer(_, n))) => {
+                    params.trailer_field = n.to_u64().ok_or(X509DecodeErr::InvalidField("trailerField"))?;
+                }
+                _ => return Err(X509DecodeErr::InvalidField("RSASSA-PSS-params")),
+            }
+        }
+        Ok((params, tail))
+    }
+}
+
+#[cfg(test)]
+mod algorithm_identifier_tests {
+    use simple_asn1::{der_encode, ASN1Block};
+
+    use super::{AlgorithmIdentifier, AlgorithmParameters, RsaPssParameters};
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use oids;
+    use x509::Certificate;
+
+    // sha256WithRSAEncryption, NULL parameters
+    const SHA256_RSA: &[u8] = &[
+        0x30, 0x0D, 0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0B, 0x05, 0x00,
+    ];
+
+    // ecdsa-with-SHA256, absent parameters
+    const ECDSA_SHA256: &[u8] = &[0x30, 0x0A, 0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02];
+
+    // id-ecPublicKey with the prime256v1 named curve
+    const EC_P256: &[u8] = &[
+        0x30, 0x13, 0x06, 0x07, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01, 0x06, 0x08, 0x2A, 0x86, 0x48,
+        0xCE, 0x3D, 0x03, 0x01, 0x07,
+    ];
+
+    // RSASSA-PSS with default (empty) RSASSA-PSS-params
+    const RSA_PSS_DEFAULTS: &[u8] = &[
+        0x30, 0x0D, 0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0A, 0x30, 0x00,
+    ];
+
+    #[test]
+    fn algorithm_identifier_decodes_with_null_parameters() {
+        let actual = der_decode::<AlgorithmIdentifier>(SHA256_RSA).unwrap();
+        assert_eq!(AlgorithmIdentifier::with_null_parameters(oid!(1, 2, 840, 113549, 1, 1, 11)), actual);
+    }
+
+    #[test]
+    fn algorithm_identifier_decodes_without_parameters() {
+        let actual = der_decode::<AlgorithmIdentifier>(ECDSA_SHA256).unwrap();
+        assert_eq!(AlgorithmIdentifier::new(oid!(1, 2, 840, 10045, 4, 3, 2)), actual);
+    }
+
+    #[test]
+    fn algorithm_identifier_decodes_named_curve() {
+        let actual = der_decode::<AlgorithmIdentifier>(EC_P256).unwrap();
+        assert_eq!(oid!(1, 2, 840, 10045, 2, 1), actual.algorithm);
+        assert_eq!(AlgorithmParameters::ObjectIdentifier(oid!(1, 2, 840, 10045, 3, 1, 7)), actual.parameters);
+    }
+
+    #[test]
+    fn algorithm_identifier_decodes_structured_parameters() {
+        let actual = der_decode::<AlgorithmIdentifier>(RSA_PSS_DEFAULTS).unwrap();
+        assert_eq!(AlgorithmParameters::Other(ASN1Block::Sequence(0, vec![])), actual.parameters);
+    }
+
+    #[test]
+    fn algorithm_identifier_round_trips() {
+        for input in &[SHA256_RSA, ECDSA_SHA256, EC_P256, RSA_PSS_DEFAULTS] {
+            let decoded = der_decode::<AlgorithmIdentifier>(input).unwrap();
+            assert_eq!(input.to_vec(), der_encode(&decoded).unwrap());
+        }
+    }
+
+    #[test]
+    fn algorithm_identifier_keeps_null_and_absent_apart() {
+        let null = der_encode(&AlgorithmIdentifier::with_null_parameters(oid!(1, 2, 840, 10045, 4, 3, 2))).unwrap();
+        let absent = der_encode(&AlgorithmIdentifier::new(oid!(1, 2, 840, 10045, 4, 3, 2))).unwrap();
+        assert_eq!(ECDSA_SHA256.to_vec(), absent);
+        assert_eq!(absent.len() + 2, null.len());
+    }
+
+    #[test]
+    fn algorithm_identifier_rejects_non_oid_algorithm() {
+        let error = der_decode::<AlgorithmIdentifier>(&[0x30, 0x03, 0x02, 0x01, 0x00]).unwrap_err();
+        assert_eq!(X509DecodeErr::InvalidField("algorithm"), error);
+    }
+
+    #[test]
+    fn algorithm_identifier_rsa_pss_parameters() {
+        let defaults = der_decode::<AlgorithmIdentifier>(RSA_PSS_DEFAULTS).unwrap();
+        assert_eq!(Ok(Some(RsaPssParameters::default())), defaults.rsa_pss_parameters());
+        assert_eq!(Ok(defaults), AlgorithmIdentifier::rsa_pss(&RsaPssParameters::default()));
+
+        // OpenSSL's SHA-256, MGF1 with SHA-256 and a 32 byte salt.
+        let cert = der_decode::<Certificate>(include_bytes!("../testdata/rsa-pss-root.der")).unwrap();
+        let params = cert.signature_algorithm.rsa_pss_parameters().unwrap().unwrap();
+        assert_eq!(RsaPssParameters::new(oids::SHA256, 32), params);
+        assert_eq!(Some(AlgorithmIdentifier::with_null_parameters(oids::SHA256)), params.mgf1_hash_algorithm());
+        assert_eq!(Ok(cert.signature_algorithm), AlgorithmIdentifier::rsa_pss(&params));
+
+        let mut odd = RsaPssParameters::new(oids::SHA512, 20);
+        odd.trailer_field = 2;
+        let encoded = AlgorithmIdentifier::rsa_pss(&odd).unwrap();
+        let decoded = der_decode::<AlgorithmIdentifier>(&der_encode(&encoded).unwrap()).unwrap();
+        assert_eq!(Ok(Some(odd)), decoded.rsa_pss_parameters());
+
+        assert_eq!(Ok(None), AlgorithmIdentifier::new(oids::RSASSA_PSS).rsa_pss_parameters());
+        let error = AlgorithmIdentifier::with_null_parameters(oids::RSA_ENCRYPTION).rsa_pss_parameters();
+        assert_eq!(Err(X509DecodeErr::InvalidField("algorithm")), error);
+        let error = AlgorithmIdentifier::with_null_parameters(oids::RSASSA_PSS).rsa_pss_parameters();
+        assert_eq!(Err(X509DecodeErr::InvalidField("parameters")), error);
+    }
+
+    #[test]
+    fn algorithm_identifier_rejects_extra_parameters() {
+        let input = [0x30, 0x0E, 0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02, 0x05, 0x00, 0x05, 0x00];
+        let error = der_decode::<AlgorithmIdentifier>(&input).unwrap_err();
+        assert_eq!(X509DecodeErr::TrailingData("AlgorithmIdentifier"), error);
+    }
+}
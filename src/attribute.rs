@@ -0,0 +1,86 @@
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{expect_end, next_block, sequence};
+use error::X509DecodeErr;
+use oid::ObjectIdentifier;
+
+/// An attribute type with one or more values (X.501 §8.2).
+///
+/// The values are kept as the blocks they were decoded from, in encoding
+/// order, since their type depends on the attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attribute {
+    pub attr_type: ObjectIdentifier,
+    pub values: Vec<ASN1Block>,
+}
+
+impl Attribute {
+    pub fn new(attr_type: ObjectIdentifier, values: Vec<ASN1Block>) -> Attribute {
+        Attribute { attr_type, values }
+    }
+}
+
+impl ToASN1 for Attribute {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![ASN1Block::Sequence(0, vec![self.attr_type.to_block(), ASN1Block::Set(0, self.values.clone())])])
+    }
+}
+
+impl FromASN1 for Attribute {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "Attribute")?;
+        let items = sequence(head, "Attribute")?;
+        let (attr_type, rest) = next_block(items, "type")?;
+        let attr_type = ObjectIdentifier::from_block(attr_type, "type")?;
+        let (values, rest) = match next_block(rest, "values")? {
+            // at least one value is required
+            (ASN1Block::Set(_, values), rest) if !values.is_empty() => (values.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("values")),
+        };
+        expect_end(rest, "Attribute")?;
+        Ok((Attribute { attr_type, values }, tail))
+    }
+}
+
+#[cfg(test)]
+mod attribute_tests {
+    use simple_asn1::{der_encode, ASN1Block};
+
+    use super::Attribute;
+    use der::der_decode;
+    use error::X509DecodeErr;
+
+    // countryOfCitizenship: CA, FR
+    const CITIZENSHIP: &[u8] = &[
+        0x30, 0x14, 0x06, 0x08, 0x2B, 0x06, 0x01, 0x05, 0x05, 0x07, 0x09, 0x04, 0x31, 0x08, 0x13, 0x02,
+        0x43, 0x41, 0x13, 0x02, 0x46, 0x52,
+    ];
+
+    #[test]
+    fn attribute_decodes() {
+        let actual = der_decode::<Attribute>(CITIZENSHIP).unwrap();
+        assert_eq!(oid!(1, 3, 6, 1, 5, 5, 7, 9, 4), actual.attr_type);
+        assert_eq!(
+            vec![ASN1Block::PrintableString(0, "CA".to_string()), ASN1Block::PrintableString(0, "FR".to_string())],
+            actual.values
+        );
+    }
+
+    #[test]
+    fn attribute_round_trips() {
+        let decoded = der_decode::<Attribute>(CITIZENSHIP).unwrap();
+        assert_eq!(CITIZENSHIP.to_vec(), der_encode(&decoded).unwrap());
+    }
+
+    #[test]
+    fn attribute_requires_set() {
+        let input = [0x30, 0x07, 0x06, 0x01, 0x2A, 0x30, 0x02, 0x05, 0x00];
+        assert_eq!(Err(X509DecodeErr::InvalidField("values")), der_decode::<Attribute>(&input));
+        let empty = [0x30, 0x05, 0x06, 0x01, 0x2A, 0x31, 0x00];
+        assert_eq!(Err(X509DecodeErr::InvalidField("values")), der_decode::<Attribute>(&empty));
+    }
+}
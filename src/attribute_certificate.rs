@@ -0,0 +1,926 @@
+//! Attribute certificates (RFC 5755), which bind attributes such as roles
+//! and clearances to the holder of a public-key certificate.
+//!
+//! An attribute certificate has no key of its own. Its holder is named by
+//! reference to their public-key certificate, and its attributes are kept
+//! as `Attribute`s; `RoleSyntax` and `Clearance` decode the two RFC 5755
+//! defines for authorization.
+
+use std::ops::BitOr;
+
+use num::bigint::BigInt;
+use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+use time::PrimitiveDateTime;
+
+use algorithm::AlgorithmIdentifier;
+use attribute::Attribute;
+use der::{
+    context_tag, decode_with, enumerated, enumerated_value, expect_end, explicit, explicit_inner, implicit,
+    implicit_inner, named_bits, named_bits_value, next_block, sequence, SignedBytes,
+};
+use error::X509DecodeErr;
+use extensions::{Extensions, X509Extension};
+use general_name::{GeneralName, GeneralNames};
+use oid::ObjectIdentifier;
+use oids;
+use pem::{self, Pem};
+use sign::{AsyncSigner, SignError, Signer, Signing};
+use spki::SubjectPublicKeyInfo;
+#[cfg(feature = "verify")]
+use verify::RustCrypto;
+use verify::{SignatureVerifier, VerifyError};
+use x509::{Certificate, CertificateSerialNumber, UniqueIdentifier};
+
+// The next block as an untagged SEQUENCE, if it is an `[tag] IMPLICIT
+// SEQUENCE`, moving `rest` past it.
+fn tagged(rest: &mut &[ASN1Block], tag: u64, field: &'static str) -> Result<Option<ASN1Block>, X509DecodeErr> {
+    match rest.split_first() {
+        Some((b, remaining)) if context_tag(b) == Some(tag) => {
+            *rest = remaining;
+            implicit_inner(b, tag, 0x10).map(Some).ok_or(X509DecodeErr::InvalidField(field))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// A public-key certificate named by its issuer and serial number.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IssuerSerial {
+    pub issuer: GeneralNames,
+    pub serial: CertificateSerialNumber,
+    pub issuer_uid: Option<UniqueIdentifier>,
+}
+
+impl IssuerSerial {
+    pub fn for_certificate(cert: &Certificate) -> IssuerSerial {
+        let tbs = &cert.tbs_certificate;
+        IssuerSerial {
+            issuer: GeneralNames::new(vec![GeneralName::DirectoryName(tbs.issuer.clone())]),
+            serial: tbs.serial_number.clone(),
+            issuer_uid: tbs.issuer_unique_id.clone(),
+        }
+    }
+
+    /// Whether this names `cert`. The issuer must be given as a
+    /// directoryName, as RFC 5755 §4.2.2 requires.
+    pub fn matches(&self, cert: &Certificate) -> bool {
+        let tbs = &cert.tbs_certificate;
+        self.serial == tbs.serial_number
+            && self.issuer.0.len() == 1
+            && self.issuer.directory_names().any(|name| *name == tbs.issuer)
+            && self.issuer_uid.as_ref().is_none_or(|uid| Some(uid) == tbs.issuer_unique_id.as_ref())
+    }
+}
+
+impl ToASN1 for IssuerSerial {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.issuer.to_asn1_class(c)?;
+        items.extend(self.serial.to_asn1_class(c)?);
+        if let Some(ref uid) = self.issuer_uid {
+            items.push(ASN1Block::BitString(0, uid.nbits(), uid.bytes().to_vec()));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for IssuerSerial {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "IssuerSerial")?;
+        let items = sequence(head, "IssuerSerial")?;
+        let (issuer, rest) = GeneralNames::from_asn1(items)?;
+        let (serial, rest) = CertificateSerialNumber::from_asn1(rest)?;
+        let (issuer_uid, rest) = match rest.split_first() {
+            Some((ASN1Block::BitString(_, nbits, bytes), rest)) => {
+                (Some(UniqueIdentifier::new(bytes.clone(), *nbits)?), rest)
+            }
+            _ => (None, rest),
+        };
+        expect_end(rest, "IssuerSerial")?;
+        Ok((IssuerSerial { issuer, serial, issuer_uid }, tail))
+    }
+}
+
+/// What an `ObjectDigestInfo` is a digest of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigestedObjectType {
+    PublicKey,
+    PublicKeyCert,
+    OtherObjectTypes,
+}
+
+/// A holder or issuer identified by a digest, such as of their public key
+/// (RFC 5755 §7.3).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectDigestInfo {
+    pub digested_object_type: DigestedObjectType,
+    /// Must not be used in conforming attribute certificates.
+    pub other_object_type_id: Option<ObjectIdentifier>,
+    pub digest_algorithm: AlgorithmIdentifier,
+    pub object_digest: Vec<u8>,
+}
+
+impl ToASN1 for ObjectDigestInfo {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = vec![enumerated(self.digested_object_type as u8)];
+        if let Some(ref oid) = self.other_object_type_id {
+            items.push(oid.to_block());
+        }
+        items.extend(self.digest_algorithm.to_asn1_class(c)?);
+        let digest = self.object_digest.clone();
+        items.push(ASN1Block::BitString(0, digest.len() * 8, digest));
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for ObjectDigestInfo {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "ObjectDigestInfo")?;
+        let items = sequence(head, "ObjectDigestInfo")?;
+        let (digested_object_type, rest) = next_block(items, "digestedObjectType")?;
+        let digested_object_type = match enumerated_value(digested_object_type) {
+            Some(0) => DigestedObjectType::PublicKey,
+            Some(1) => DigestedObjectType::PublicKeyCert,
+            Some(2) => DigestedObjectType::OtherObjectTypes,
+            _ => return Err(X509DecodeErr::InvalidField("digestedObjectType")),
+        };
+        let (other_object_type_id, rest) = match rest.split_first() {
+            Some((oid @ ASN1Block::ObjectIdentifier(..), rest)) => {
+                (Some(ObjectIdentifier::from_block(oid, "otherObjectTypeID")?), rest)
+            }
+            _ => (None, rest),
+        };
+        let (digest_algorithm, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+        let (object_digest, rest) = match next_block(rest, "objectDigest")? {
+            (ASN1Block::BitString(_, nbits, bytes), rest) if *nbits == bytes.len() * 8 => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("objectDigest")),
+        };
+        expect_end(rest, "ObjectDigestInfo")?;
+        Ok((ObjectDigestInfo { digested_object_type, other_object_type_id, digest_algorithm, object_digest }, tail))
+    }
+}
+
+/// Who an attribute certificate is for (RFC 5755 §4.2.2). RFC 5755 asks
+/// for only one of the three to be used.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Holder {
+    /// The holder's public-key certificate.
+    pub base_certificate_id: Option<IssuerSerial>,
+    /// The holder's names, which must match those in their public-key
+    /// certificate.
+    pub entity_name: Option<GeneralNames>,
+    pub object_digest_info: Option<ObjectDigestInfo>,
+}
+
+impl Holder {
+    /// The holder of `cert`, by its issuer and serial number.
+    pub fn for_certificate(cert: &Certificate) -> Holder {
+        Holder { base_certificate_id: Some(IssuerSerial::for_certificate(cert)), ..Default::default() }
+    }
+
+    /// Whether `cert` is the holder's public-key certificate, going by
+    /// `base_certificate_id` or else by `entity_name` and the certificate's
+    /// subject. A holder given only by digest matches nothing.
+    pub fn matches(&self, cert: &Certificate) -> bool {
+        match (&self.base_certificate_id, &self.entity_name) {
+            (Some(id), _) => id.matches(cert),
+            (None, Some(names)) => names.directory_names().any(|name| *name == cert.tbs_certificate.subject),
+            (None, None) => false,
+        }
+    }
+}
+
+impl ToASN1 for Holder {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        if let Some(ref id) = self.base_certificate_id {
+            items.push(implicit(0, &id.to_asn1_class(c)?[0])?);
+        }
+        if let Some(ref names) = self.entity_name {
+            items.push(implicit(1, &names.to_asn1_class(c)?[0])?);
+        }
+        if let Some(ref info) = self.object_digest_info {
+            items.push(implicit(2, &info.to_asn1_class(c)?[0])?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for Holder {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "Holder")?;
+        let mut rest = sequence(head, "Holder")?;
+        let mut holder = Holder::default();
+        if let Some(b) = tagged(&mut rest, 0, "baseCertificateID")? {
+            holder.base_certificate_id = Some(IssuerSerial::from_asn1(&[b])?.0);
+        }
+        if let Some(b) = tagged(&mut rest, 1, "entityName")? {
+            holder.entity_name = Some(GeneralNames::from_asn1(&[b])?.0);
+        }
+        if let Some(b) = tagged(&mut rest, 2, "objectDigestInfo")? {
+            holder.object_digest_info = Some(ObjectDigestInfo::from_asn1(&[b])?.0);
+        }
+        expect_end(rest, "Holder")?;
+        Ok((holder, tail))
+    }
+}
+
+/// The issuer of an attribute certificate, the v2Form of AttCertIssuer
+/// (RFC 5755 §4.2.3). Conforming certificates name the issuer in
+/// `issuer_name` alone; the v1Form is not supported.
+#[derive(Clone, Debug, PartialEq)]
+pub struct V2Form {
+    pub issuer_name: Option<GeneralNames>,
+    pub base_certificate_id: Option<IssuerSerial>,
+    pub object_digest_info: Option<ObjectDigestInfo>,
+}
+
+impl V2Form {
+    pub fn new(issuer_name: GeneralNames) -> V2Form {
+        V2Form { issuer_name: Some(issuer_name), base_certificate_id: None, object_digest_info: None }
+    }
+}
+
+impl ToASN1 for V2Form {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        if let Some(ref names) = self.issuer_name {
+            items.extend(names.to_asn1_class(c)?);
+        }
+        if let Some(ref id) = self.base_certificate_id {
+            items.push(implicit(0, &id.to_asn1_class(c)?[0])?);
+        }
+        if let Some(ref info) = self.object_digest_info {
+            items.push(implicit(1, &info.to_asn1_class(c)?[0])?);
+        }
+        Ok(vec![implicit(0, &ASN1Block::Sequence(0, items))?])
+    }
+}
+
+impl FromASN1 for V2Form {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "issuer")?;
+        // An untagged GeneralNames is the v1Form.
+        let block = implicit_inner(head, 0, 0x10).ok_or(X509DecodeErr::InvalidField("issuer"))?;
+        let mut rest = sequence(&block, "V2Form")?;
+        let issuer_name = match rest.first() {
+            Some(ASN1Block::Sequence(..)) => {
+                let (names, remaining) = GeneralNames::from_asn1(rest)?;
+                rest = remaining;
+                Some(names)
+            }
+            _ => None,
+        };
+        let mut form = V2Form { issuer_name, base_certificate_id: None, object_digest_info: None };
+        if let Some(b) = tagged(&mut rest, 0, "baseCertificateID")? {
+            form.base_certificate_id = Some(IssuerSerial::from_asn1(&[b])?.0);
+        }
+        if let Some(b) = tagged(&mut rest, 1, "objectDigestInfo")? {
+            form.object_digest_info = Some(ObjectDigestInfo::from_asn1(&[b])?.0);
+        }
+        expect_end(rest, "V2Form")?;
+        Ok((form, tail))
+    }
+}
+
+/// The period an attribute certificate is valid for, both ends inclusive.
+/// Always written as GeneralizedTime.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttCertValidityPeriod {
+    pub not_before_time: PrimitiveDateTime,
+    pub not_after_time: PrimitiveDateTime,
+}
+
+impl AttCertValidityPeriod {
+    pub fn new(not_before_time: PrimitiveDateTime, not_after_time: PrimitiveDateTime) -> AttCertValidityPeriod {
+        AttCertValidityPeriod { not_before_time, not_after_time }
+    }
+
+    /// Whether `t`, in UTC, falls within the period.
+    pub fn is_valid_at(&self, t: PrimitiveDateTime) -> bool {
+        self.not_before_time <= t && t <= self.not_after_time
+    }
+}
+
+impl ToASN1 for AttCertValidityPeriod {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let items = vec![
+            ASN1Block::GeneralizedTime(0, self.not_before_time),
+            ASN1Block::GeneralizedTime(0, self.not_after_time),
+        ];
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for AttCertValidityPeriod {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "AttCertValidityPeriod")?;
+        match sequence(head, "AttCertValidityPeriod")? {
+            [ASN1Block::GeneralizedTime(_, not_before_time), ASN1Block::GeneralizedTime(_, not_after_time)] => {
+                Ok((AttCertValidityPeriod::new(*not_before_time, *not_after_time), tail))
+            }
+            _ => Err(X509DecodeErr::InvalidField("AttCertValidityPeriod")),
+        }
+    }
+}
+
+/// The signed body of an attribute certificate (RFC 5755 §4.1). Only v2
+/// is supported, and it is the only version written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeCertificateInfo {
+    pub holder: Holder,
+    pub issuer: V2Form,
+    pub signature: AlgorithmIdentifier,
+    pub serial_number: CertificateSerialNumber,
+    pub attr_cert_validity_period: AttCertValidityPeriod,
+    pub attributes: Vec<Attribute>,
+    pub issuer_unique_id: Option<UniqueIdentifier>,
+    pub extensions: Option<Extensions>,
+}
+
+impl AttributeCertificateInfo {
+    /// Sign the attribute certificate with `signer`, setting `signature`
+    /// to the signer's algorithm first so the two match.
+    pub fn sign<S: Signer + ?Sized>(mut self, signer: &S) -> Result<AttributeCertificate, SignError> {
+        self.signature = signer.signature_algorithm();
+        let signature_value = signer.sign(&der_encode(&self)?)?;
+        Ok(AttributeCertificate {
+            signature_algorithm: self.signature.clone(),
+            acinfo: self,
+            signature_value,
+            signed_bytes: SignedBytes::default(),
+        })
+    }
+
+    /// Sign the attribute certificate with `signer`, as for `sign`, once
+    /// the returned future is awaited.
+    pub fn sign_async<S: AsyncSigner + ?Sized>(mut self, signer: &S) -> Signing<'_, AttributeCertificate> {
+        self.signature = signer.signature_algorithm();
+        let message = der_encode(&self);
+        Signing::new(signer, message, move |signature_value| AttributeCertificate {
+            signature_algorithm: self.signature.clone(),
+            acinfo: self,
+            signature_value,
+            signed_bytes: SignedBytes::default(),
+        })
+    }
+}
+
+impl ToASN1 for AttributeCertificateInfo {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = vec![ASN1Block::Integer(0, BigInt::from(1))];
+        items.extend(self.holder.to_asn1_class(c)?);
+        items.extend(self.issuer.to_asn1_class(c)?);
+        items.extend(self.signature.to_asn1_class(c)?);
+        items.extend(self.serial_number.to_asn1_class(c)?);
+        items.extend(self.attr_cert_validity_period.to_asn1_class(c)?);
+        let mut attributes = Vec::new();
+        for attribute in &self.attributes {
+            attributes.extend(attribute.to_asn1_class(c)?);
+        }
+        items.push(ASN1Block::Sequence(0, attributes));
+        if let Some(ref id) = self.issuer_unique_id {
+            items.push(ASN1Block::BitString(0, id.nbits(), id.bytes().to_vec()));
+        }
+        if let Some(ref extensions) = self.extensions {
+            items.extend(extensions.to_asn1_class(c)?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for AttributeCertificateInfo {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "AttributeCertificateInfo")?;
+        let items = sequence(head, "AttributeCertificateInfo")?;
+        let rest = match next_block(items, "version")? {
+            (ASN1Block::Integer(_, n), rest) if *n == BigInt::from(1) => rest,
+            (ASN1Block::Integer(_, n), _) => return Err(X509DecodeErr::UnsupportedVersion(n.clone())),
+            _ => return Err(X509DecodeErr::InvalidField("version")),
+        };
+        let (holder, rest) = Holder::from_asn1(rest)?;
+        let (issuer, rest) = V2Form::from_asn1(rest)?;
+        let (signature, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+        let (serial_number, rest) = CertificateSerialNumber::from_asn1(rest)?;
+        let (attr_cert_validity_period, rest) = AttCertValidityPeriod::from_asn1(rest)?;
+        let (attributes, rest) = match next_block(rest, "attributes")? {
+            (ASN1Block::Sequence(_, blocks), rest) => {
+                let mut attributes = Vec::new();
+                let mut blocks = &blocks[..];
+                while !blocks.is_empty() {
+                    let (attribute, remaining) = Attribute::from_asn1(blocks)?;
+                    attributes.push(attribute);
+                    blocks = remaining;
+                }
+                (attributes, rest)
+            }
+            _ => return Err(X509DecodeErr::InvalidField("attributes")),
+        };
+        let (issuer_unique_id, rest) = match rest.split_first() {
+            Some((ASN1Block::BitString(_, nbits, bytes), rest)) => {
+                (Some(UniqueIdentifier::new(bytes.clone(), *nbits)?), rest)
+            }
+            _ => (None, rest),
+        };
+        let (extensions, rest) = if rest.is_empty() {
+            (None, rest)
+        } else {
+            let (extensions, rest) = Extensions::from_asn1(rest)?;
+            (Some(extensions), rest)
+        };
+        expect_end(rest, "AttributeCertificateInfo")?;
+
+        Ok((AttributeCertificateInfo {
+            holder,
+            issuer,
+            signature,
+            serial_number,
+            attr_cert_validity_period,
+            attributes,
+            issuer_unique_id,
+            extensions,
+        }, tail))
+    }
+}
+
+/// A signed attribute certificate (RFC 5755 §4.1).
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeCertificate {
+    pub acinfo: AttributeCertificateInfo,
+    pub signature_algorithm: AlgorithmIdentifier,
+    pub signature_value: Vec<u8>,
+    pub(crate) signed_bytes: SignedBytes,
+}
+
+impl AttributeCertificate {
+    /// Decode the first `ATTRIBUTE CERTIFICATE` PEM block in `input`.
+    pub fn from_pem(input: &str) -> Result<AttributeCertificate, X509DecodeErr> {
+        let der = pem::parse(input)?.into_contents(pem::ATTRIBUTE_CERTIFICATE)?;
+        decode_with(&der, |blocks| {
+            let (cert, rest) = AttributeCertificate::from_asn1(blocks)?;
+            expect_end(rest, "AttributeCertificate").map(|_| cert)
+        })
+    }
+
+    /// The attribute certificate as an `ATTRIBUTE CERTIFICATE` PEM block.
+    pub fn to_pem(&self) -> Result<String, ASN1EncodeErr> {
+        Ok(Pem::new(pem::ATTRIBUTE_CERTIFICATE, der_encode(self)?).encode())
+    }
+
+    /// The extension of type `T`, decoded, if the certificate has one.
+    pub fn extension<T: X509Extension>(&self) -> Result<Option<T>, X509DecodeErr> {
+        match self.acinfo.extensions {
+            Some(ref extensions) => extensions.get(),
+            None => Ok(None),
+        }
+    }
+
+    /// The values of every attribute of type `attr_type`, in order.
+    pub fn attribute_values<'a>(&'a self, attr_type: &'a ObjectIdentifier) -> impl Iterator<Item = &'a ASN1Block> {
+        let attributes = self.acinfo.attributes.iter();
+        attributes.filter(move |a| a.attr_type == *attr_type).flat_map(|a| a.values.iter())
+    }
+
+    /// The holder's roles, from any role attributes.
+    pub fn roles(&self) -> Result<Vec<RoleSyntax>, X509DecodeErr> {
+        let role = oids::ROLE;
+        self.attribute_values(&role).map(|value| Ok(RoleSyntax::from_asn1(std::slice::from_ref(value))?.0)).collect()
+    }
+
+    /// The holder's clearances, from clearance attributes under either
+    /// the RFC 5755 or the older RFC 3281 identifier.
+    pub fn clearances(&self) -> Result<Vec<Clearance>, X509DecodeErr> {
+        let (clearance, rfc3281) = (oids::CLEARANCE, oids::CLEARANCE_RFC3281);
+        let values = self.attribute_values(&clearance).chain(self.attribute_values(&rfc3281));
+        values.map(|value| Ok(Clearance::from_asn1(std::slice::from_ref(value))?.0)).collect()
+    }
+
+    /// Check that the attribute certificate was signed by the private half
+    /// of `issuer_key`, the key of the attribute authority named as its
+    /// issuer.
+    #[cfg(feature = "verify")]
+    pub fn verify_signed_by(&self, issuer_key: &SubjectPublicKeyInfo) -> Result<(), VerifyError> {
+        self.verify_signed_by_with(issuer_key, &RustCrypto)
+    }
+
+    /// Check the signature with the given backend. The signature is
+    /// checked over the bytes `acinfo` was decoded from, or its DER
+    /// encoding if it was built or has been changed.
+    pub fn verify_signed_by_with<V: SignatureVerifier + ?Sized>(
+        &self,
+        issuer_key: &SubjectPublicKeyInfo,
+        verifier: &V,
+    ) -> Result<(), VerifyError> {
+        let message = self.signed_bytes.message(&self.acinfo)?;
+        verifier.verify(&self.signature_algorithm, issuer_key, &message, &self.signature_value)
+    }
+}
+
+impl ToASN1 for AttributeCertificate {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.acinfo.to_asn1_class(c)?;
+        items.extend(self.signature_algorithm.to_asn1_class(c)?);
+        let signature = self.signature_value.clone();
+        items.push(ASN1Block::BitString(0, signature.len() * 8, signature));
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for AttributeCertificate {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "AttributeCertificate")?;
+        let items = sequence(head, "AttributeCertificate")?;
+        let (acinfo, rest) = AttributeCertificateInfo::from_asn1(items)?;
+        let signed_bytes = SignedBytes::decoded(&items[0], &acinfo);
+        let (signature_algorithm, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+        let (signature_value, rest) = match next_block(rest, "signatureValue")? {
+            (ASN1Block::BitString(_, nbits, bytes), rest) if *nbits == bytes.len() * 8 => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("signatureValue")),
+        };
+        expect_end(rest, "AttributeCertificate")?;
+        Ok((AttributeCertificate { acinfo, signature_algorithm, signature_value, signed_bytes }, tail))
+    }
+}
+
+/// The value of a role attribute (RFC 5755 §4.4.5): a role, and
+/// optionally the authority that assigns it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoleSyntax {
+    pub role_authority: Option<GeneralNames>,
+    pub role_name: GeneralName,
+}
+
+impl RoleSyntax {
+    pub fn new(role_name: GeneralName) -> RoleSyntax {
+        RoleSyntax { role_authority: None, role_name }
+    }
+
+    /// A role attribute with the given roles as its values.
+    pub fn attribute(roles: &[RoleSyntax]) -> Result<Attribute, ASN1EncodeErr> {
+        let mut values = Vec::new();
+        for role in roles {
+            values.extend(role.to_asn1()?);
+        }
+        Ok(Attribute::new(oids::ROLE, values))
+    }
+}
+
+impl ToASN1 for RoleSyntax {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        if let Some(ref names) = self.role_authority {
+            items.push(implicit(0, &names.to_asn1_class(c)?[0])?);
+        }
+        // GeneralName is a CHOICE, so its tag is EXPLICIT.
+        items.push(explicit(1, self.role_name.to_asn1_class(c)?.remove(0)));
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for RoleSyntax {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "RoleSyntax")?;
+        let mut rest = sequence(head, "RoleSyntax")?;
+        let role_authority = match tagged(&mut rest, 0, "roleAuthority")? {
+            Some(b) => Some(GeneralNames::from_asn1(&[b])?.0),
+            None => None,
+        };
+        let (role_name, rest) = next_block(rest, "roleName")?;
+        let role_name = explicit_inner(role_name, 1).ok_or(X509DecodeErr::InvalidField("roleName"))?;
+        let (role_name, _) = GeneralName::from_asn1(std::slice::from_ref(role_name))?;
+        expect_end(rest, "RoleSyntax")?;
+        Ok((RoleSyntax { role_authority, role_name }, tail))
+    }
+}
+
+/// The classifications a clearance covers (RFC 5755 §4.4.6), combined
+/// with `|`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClassList(u16);
+
+impl ClassList {
+    pub const UNMARKED: ClassList = ClassList(1 << 0);
+    pub const UNCLASSIFIED: ClassList = ClassList(1 << 1);
+    pub const RESTRICTED: ClassList = ClassList(1 << 2);
+    pub const CONFIDENTIAL: ClassList = ClassList(1 << 3);
+    pub const SECRET: ClassList = ClassList(1 << 4);
+    pub const TOP_SECRET: ClassList = ClassList(1 << 5);
+
+    /// The classifications as named bits: bit 0 is unmarked.
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn from_bits(bits: u16) -> ClassList {
+        ClassList(bits & 0x3F)
+    }
+
+    /// Whether every classification in `other` is also in `self`.
+    pub fn contains(self, other: ClassList) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// unclassified, the DEFAULT.
+impl Default for ClassList {
+    fn default() -> ClassList {
+        ClassList::UNCLASSIFIED
+    }
+}
+
+impl BitOr for ClassList {
+    type Output = ClassList;
+
+    fn bitor(self, other: ClassList) -> ClassList {
+        ClassList(self.0 | other.0)
+    }
+}
+
+/// The value of a clearance attribute (RFC 5755 §4.4.6): the holder's
+/// clearance under a security policy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clearance {
+    pub policy_id: ObjectIdentifier,
+    pub class_list: ClassList,
+    /// The SecurityCategory SEQUENCEs, whose values depend on the policy.
+    pub security_categories: Vec<ASN1Block>,
+}
+
+impl Clearance {
+    pub fn new(policy_id: ObjectIdentifier, class_list: ClassList) -> Clearance {
+        Clearance { policy_id, class_list, security_categories: Vec::new() }
+    }
+
+    /// A clearance attribute with the given clearances as its values.
+    pub fn attribute(clearances: &[Clearance]) -> Result<Attribute, ASN1EncodeErr> {
+        let mut values = Vec::new();
+        for clearance in clearances {
+            values.extend(clearance.to_asn1()?);
+        }
+        Ok(Attribute::new(oids::CLEARANCE, values))
+    }
+}
+
+impl ToASN1 for Clearance {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = vec![self.policy_id.to_block()];
+        if self.class_list != ClassList::default() {
+            items.push(named_bits(self.class_list.0));
+        }
+        if !self.security_categories.is_empty() {
+            items.push(ASN1Block::Set(0, self.security_categories.clone()));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for Clearance {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "Clearance")?;
+        let items = sequence(head, "Clearance")?;
+        let (policy_id, mut rest) = next_block(items, "policyId")?;
+        let mut clearance = Clearance::new(ObjectIdentifier::from_block(policy_id, "policyId")?, ClassList::default());
+        if let Some((ASN1Block::BitString(_, nbits, bytes), remaining)) = rest.split_first() {
+            clearance.class_list = ClassList(named_bits_value(*nbits, bytes, 6));
+            rest = remaining;
+        }
+        if let Some((ASN1Block::Set(_, categories), remaining)) = rest.split_first() {
+            clearance.security_categories = categories.clone();
+            rest = remaining;
+        }
+        expect_end(rest, "Clearance")?;
+        Ok((clearance, tail))
+    }
+}
+
+#[cfg(test)]
+mod attribute_certificate_tests {
+    use simple_asn1::der_encode;
+    use time::{Date, Month, PrimitiveDateTime, Time};
+
+    use super::{
+        AttCertValidityPeriod, AttributeCertificate, AttributeCertificateInfo, ClassList, Clearance, Holder,
+        RoleSyntax, V2Form,
+    };
+    use algorithm::AlgorithmIdentifier;
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use general_name::{GeneralName, GeneralNames};
+    use oids;
+    use x509::{Certificate, CertificateSerialNumber};
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../testdata/ec-leaf.der");
+
+    // roleAuthority URI:https://quicklime.example/, roleName
+    // URI:urn:quicklime:role:operator
+    const OPERATOR_ROLE: &[u8] = &[
+        0x30, 0x3D, 0xA0, 0x1C, 0x86, 0x1A, 0x68, 0x74, 0x74, 0x70, 0x73, 0x3A, 0x2F, 0x2F, 0x71, 0x75, 0x69, 0x63,
+        0x6B, 0x6C, 0x69, 0x6D, 0x65, 0x2E, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x2F, 0xA1, 0x1D, 0x86, 0x1B,
+        0x75, 0x72, 0x6E, 0x3A, 0x71, 0x75, 0x69, 0x63, 0x6B, 0x6C, 0x69, 0x6D, 0x65, 0x3A, 0x72, 0x6F, 0x6C, 0x65,
+        0x3A, 0x6F, 0x70, 0x65, 0x72, 0x61, 0x74, 0x6F, 0x72,
+    ];
+
+    fn at(year: i32, month: Month, day: u8) -> PrimitiveDateTime {
+        PrimitiveDateTime::new(Date::from_calendar_date(year, month, day).unwrap(), Time::MIDNIGHT)
+    }
+
+    fn operator() -> RoleSyntax {
+        RoleSyntax {
+            role_authority: Some(GeneralNames::new(vec![GeneralName::URI("https://quicklime.example/".to_string())])),
+            role_name: GeneralName::URI("urn:quicklime:role:operator".to_string()),
+        }
+    }
+
+    // An attribute certificate for EC_LEAF's holder, issued by EC_ROOT's
+    // subject, unsigned.
+    fn acinfo() -> AttributeCertificateInfo {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let issuer = GeneralNames::new(vec![GeneralName::DirectoryName(root.tbs_certificate.subject)]);
+        let secret = Clearance::new(oid!(1, 3, 6, 1, 4, 1, 99999, 4, 1), ClassList::CONFIDENTIAL | ClassList::SECRET);
+        let period = AttCertValidityPeriod::new(at(2026, Month::October, 15), at(2027, Month::April, 15));
+        AttributeCertificateInfo {
+            holder: Holder::for_certificate(&leaf),
+            issuer: V2Form::new(issuer),
+            signature: AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA256),
+            serial_number: CertificateSerialNumber(7.into()),
+            attr_cert_validity_period: period,
+            attributes: vec![
+                RoleSyntax::attribute(&[operator(), RoleSyntax::new(GeneralName::DNSName("ops".to_string()))]).unwrap(),
+                Clearance::attribute(&[secret]).unwrap(),
+            ],
+            issuer_unique_id: None,
+            extensions: None,
+        }
+    }
+
+    #[test]
+    fn role_syntax_round_trips() {
+        assert_eq!(operator(), der_decode::<RoleSyntax>(OPERATOR_ROLE).unwrap());
+        assert_eq!(OPERATOR_ROLE.to_vec(), der_encode(&operator()).unwrap());
+    }
+
+    #[test]
+    fn clearance_round_trips() {
+        let policy = oid!(1, 3, 6, 1, 4, 1, 99999, 4, 1);
+        // The default classList is left out.
+        let unclassified = Clearance::new(policy.clone(), ClassList::UNCLASSIFIED);
+        let der = der_encode(&unclassified).unwrap();
+        assert_eq!(vec![0x30, 0x0C, 0x06, 0x0A], der[..4].to_vec());
+        assert_eq!(unclassified, der_decode::<Clearance>(&der).unwrap());
+
+        let secret = Clearance::new(policy, ClassList::SECRET | ClassList::TOP_SECRET);
+        let decoded = der_decode::<Clearance>(&der_encode(&secret).unwrap()).unwrap();
+        assert!(decoded.class_list.contains(ClassList::TOP_SECRET));
+        assert!(!decoded.class_list.contains(ClassList::CONFIDENTIAL));
+        assert_eq!(secret, decoded);
+    }
+
+    #[test]
+    fn attribute_certificate_round_trips() {
+        let acinfo = acinfo();
+        let cert = AttributeCertificate {
+            acinfo: acinfo.clone(),
+            signature_algorithm: acinfo.signature.clone(),
+            signature_value: vec![0; 8],
+            signed_bytes: Default::default(),
+        };
+        // Attribute values keep the offsets they were decoded at, so
+        // compare encodings.
+        let der = der_encode(&cert).unwrap();
+        let decoded = der_decode::<AttributeCertificate>(&der).unwrap();
+        assert_eq!(der, der_encode(&decoded).unwrap());
+        assert_eq!(cert.acinfo.holder, decoded.acinfo.holder);
+        assert_eq!(cert.acinfo.issuer, decoded.acinfo.issuer);
+        assert_eq!(decoded, AttributeCertificate::from_pem(&decoded.to_pem().unwrap()).unwrap());
+
+        let roles = decoded.roles().unwrap();
+        assert_eq!(vec![operator(), RoleSyntax::new(GeneralName::DNSName("ops".to_string()))], roles);
+        let clearances = decoded.clearances().unwrap();
+        assert_eq!(ClassList::CONFIDENTIAL | ClassList::SECRET, clearances[0].class_list);
+        assert!(cert.acinfo.attr_cert_validity_period.is_valid_at(at(2027, Month::January, 1)));
+        assert!(!cert.acinfo.attr_cert_validity_period.is_valid_at(at(2027, Month::May, 1)));
+    }
+
+    #[test]
+    fn attribute_certificate_requires_v2() {
+        let cert = AttributeCertificate {
+            acinfo: acinfo(),
+            signature_algorithm: AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA256),
+            signature_value: vec![0; 8],
+            signed_bytes: Default::default(),
+        };
+        let mut der = der_encode(&cert).unwrap();
+        // The version INTEGER follows the two SEQUENCE headers.
+        let version = der.windows(3).position(|w| w == [0x02, 0x01, 0x01]).unwrap();
+        der[version + 2] = 0;
+        assert_eq!(
+            Err(X509DecodeErr::UnsupportedVersion(0.into())),
+            der_decode::<AttributeCertificate>(&der)
+        );
+    }
+
+    #[test]
+    fn holder_matches_its_certificate() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let holder = Holder::for_certificate(&leaf);
+        assert!(holder.matches(&leaf));
+        assert!(!holder.matches(&root));
+
+        let subject = GeneralName::DirectoryName(leaf.tbs_certificate.subject.clone());
+        let by_name = Holder {
+            entity_name: Some(GeneralNames::new(vec![subject])),
+            ..Default::default()
+        };
+        assert!(by_name.matches(&leaf));
+        assert!(!by_name.matches(&root));
+        assert!(!Holder::default().matches(&leaf));
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn attribute_certificate_signs_and_verifies() {
+        use p256;
+
+        const EC_ROOT_KEY: [u8; 32] = [
+            0xBE, 0x92, 0x7C, 0xE1, 0x0C, 0x45, 0x2F, 0x7E, 0x07, 0xF0, 0x8C, 0xEC, 0x0A, 0x8C, 0x74, 0xDB, 0xFE,
+            0x33, 0x74, 0x4C, 0x7F, 0x09, 0x1B, 0xAF, 0x55, 0x0B, 0x5A, 0x52, 0x6F, 0xD3, 0x2D, 0x60,
+        ];
+
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let key = p256::ecdsa::SigningKey::from_bytes(&EC_ROOT_KEY.into()).unwrap();
+        let mut acinfo = acinfo();
+        acinfo.signature = AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA512);
+        let cert = acinfo.sign(&key).unwrap();
+        assert_eq!(AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA256), cert.acinfo.signature);
+        assert_eq!(Ok(()), cert.verify_signed_by(&root.tbs_certificate.subject_public_key_info));
+
+        let decoded = der_decode::<AttributeCertificate>(&der_encode(&cert).unwrap()).unwrap();
+        assert_eq!(Ok(()), decoded.verify_signed_by(&root.tbs_certificate.subject_public_key_info));
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        assert!(decoded.verify_signed_by(&leaf.tbs_certificate.subject_public_key_info).is_err());
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn attribute_certificate_verifies_over_decoded_bytes() {
+        use p256;
+
+        use der::pad_first_integer;
+        use verify::VerifyError;
+
+        const EC_ROOT_KEY: [u8; 32] = [
+            0xBE, 0x92, 0x7C, 0xE1, 0x0C, 0x45, 0x2F, 0x7E, 0x07, 0xF0, 0x8C, 0xEC, 0x0A, 0x8C, 0x74, 0xDB, 0xFE,
+            0x33, 0x74, 0x4C, 0x7F, 0x09, 0x1B, 0xAF, 0x55, 0x0B, 0x5A, 0x52, 0x6F, 0xD3, 0x2D, 0x60,
+        ];
+
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let root_key = &root.tbs_certificate.subject_public_key_info;
+        let key = p256::ecdsa::SigningKey::from_bytes(&EC_ROOT_KEY.into()).unwrap();
+        let der = der_encode(&acinfo().sign(&key).unwrap()).unwrap();
+        let signed = pad_first_integer(&der, Some(&key));
+        let cert = der_decode::<AttributeCertificate>(&signed).unwrap();
+        assert_ne!(signed, der_encode(&cert).unwrap());
+        assert_eq!(Ok(()), cert.verify_signed_by(root_key));
+
+        let altered = der_decode::<AttributeCertificate>(&pad_first_integer(&der, None)).unwrap();
+        assert_eq!(Err(VerifyError::BadSignature), altered.verify_signed_by(root_key));
+    }
+}
@@ -0,0 +1,245 @@
+//! Composite ML-DSA keys and signatures (draft-ietf-lamps-pq-composite-sigs).
+//!
+//! A composite algorithm pairs ML-DSA with a traditional RSA, ECDSA or
+//! EdDSA algorithm, so a hybrid certificate holds up as long as either
+//! does. A composite key is the ML-DSA key followed by the traditional
+//! one, and a composite signature the ML-DSA signature followed by the
+//! traditional one, each encoded as its own algorithm has it. Nothing
+//! marks where one ends; the ML-DSA part has a length fixed by its
+//! parameter set.
+//!
+//! This module splits the two apart and joins them back, so hybrid
+//! certificates round-trip through the crate. Checking a composite
+//! signature, which means checking both components over the draft's
+//! prefixed message representative, is left to a `SignatureVerifier`.
+
+use algorithm::{AlgorithmIdentifier, AlgorithmParameters};
+use der::der_decode;
+use error::X509DecodeErr;
+use oid::ObjectIdentifier;
+use oids;
+use spki::{post_quantum_key_len, RsaPublicKey, SubjectPublicKeyInfo};
+
+// The traditional half of a composite algorithm, by its key.
+enum Traditional {
+    Rsa,
+    Ec(ObjectIdentifier),
+    Edwards(ObjectIdentifier),
+}
+
+static COMPOSITES: &[(ObjectIdentifier, ObjectIdentifier, Traditional)] = &[
+    (oids::MLDSA44_RSA2048_PSS_SHA256, oids::ML_DSA_44, Traditional::Rsa),
+    (oids::MLDSA44_RSA2048_PKCS15_SHA256, oids::ML_DSA_44, Traditional::Rsa),
+    (oids::MLDSA44_ED25519_SHA512, oids::ML_DSA_44, Traditional::Edwards(oids::ED25519)),
+    (oids::MLDSA44_ECDSA_P256_SHA256, oids::ML_DSA_44, Traditional::Ec(oids::SECP256R1)),
+    (oids::MLDSA65_RSA3072_PSS_SHA512, oids::ML_DSA_65, Traditional::Rsa),
+    (oids::MLDSA65_RSA3072_PKCS15_SHA512, oids::ML_DSA_65, Traditional::Rsa),
+    (oids::MLDSA65_RSA4096_PSS_SHA512, oids::ML_DSA_65, Traditional::Rsa),
+    (oids::MLDSA65_RSA4096_PKCS15_SHA512, oids::ML_DSA_65, Traditional::Rsa),
+    (oids::MLDSA65_ECDSA_P256_SHA512, oids::ML_DSA_65, Traditional::Ec(oids::SECP256R1)),
+    (oids::MLDSA65_ECDSA_P384_SHA512, oids::ML_DSA_65, Traditional::Ec(oids::SECP384R1)),
+    (oids::MLDSA65_ECDSA_BRAINPOOLP256R1_SHA512, oids::ML_DSA_65, Traditional::Ec(oids::BRAINPOOL_P256R1)),
+    (oids::MLDSA65_ED25519_SHA512, oids::ML_DSA_65, Traditional::Edwards(oids::ED25519)),
+    (oids::MLDSA87_ECDSA_P384_SHA512, oids::ML_DSA_87, Traditional::Ec(oids::SECP384R1)),
+    (oids::MLDSA87_ECDSA_BRAINPOOLP384R1_SHA512, oids::ML_DSA_87, Traditional::Ec(oids::BRAINPOOL_P384R1)),
+    (oids::MLDSA87_ED448_SHAKE256, oids::ML_DSA_87, Traditional::Edwards(oids::ED448)),
+    (oids::MLDSA87_RSA3072_PSS_SHA512, oids::ML_DSA_87, Traditional::Rsa),
+    (oids::MLDSA87_RSA4096_PSS_SHA512, oids::ML_DSA_87, Traditional::Rsa),
+    (oids::MLDSA87_ECDSA_P521_SHA512, oids::ML_DSA_87, Traditional::Ec(oids::SECP521R1)),
+];
+
+/// The two algorithms a composite algorithm pairs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompositeAlgorithm {
+    pub algorithm: ObjectIdentifier,
+    /// `ML_DSA_44`, `ML_DSA_65` or `ML_DSA_87`.
+    pub ml_dsa: ObjectIdentifier,
+    /// The traditional key's algorithm: rsaEncryption, id-ecPublicKey
+    /// with its named curve, Ed25519 or Ed448.
+    pub traditional_key: AlgorithmIdentifier,
+}
+
+impl CompositeAlgorithm {
+    /// The components of `algorithm`, if it is a composite algorithm.
+    pub fn from_oid(algorithm: &ObjectIdentifier) -> Option<CompositeAlgorithm> {
+        let (_, ml_dsa, traditional) = COMPOSITES.iter().find(|&(oid, _, _)| oid == algorithm)?;
+        let traditional_key = match *traditional {
+            Traditional::Rsa => AlgorithmIdentifier::with_null_parameters(oids::RSA_ENCRYPTION),
+            Traditional::Ec(ref curve) => AlgorithmIdentifier {
+                algorithm: oids::EC_PUBLIC_KEY,
+                parameters: AlgorithmParameters::ObjectIdentifier(curve.clone()),
+            },
+            Traditional::Edwards(ref oid) => AlgorithmIdentifier::new(oid.clone()),
+        };
+        Some(CompositeAlgorithm { algorithm: algorithm.clone(), ml_dsa: ml_dsa.clone(), traditional_key })
+    }
+
+    // The length in bytes of an ML-DSA signature (FIPS 204 §4).
+    fn ml_dsa_signature_len(&self) -> usize {
+        if self.ml_dsa == oids::ML_DSA_44 {
+            2420
+        } else if self.ml_dsa == oids::ML_DSA_65 {
+            3309
+        } else {
+            4627
+        }
+    }
+}
+
+/// The two keys of a composite SPKI, each as an SPKI of its own.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompositePublicKey {
+    pub ml_dsa: SubjectPublicKeyInfo,
+    pub traditional: SubjectPublicKeyInfo,
+}
+
+impl SubjectPublicKeyInfo {
+    /// The component keys of a composite SPKI, which must have absent
+    /// parameters. Each component is checked as its own SPKI would be.
+    pub fn composite_public_key(&self) -> Result<CompositePublicKey, X509DecodeErr> {
+        let composite =
+            CompositeAlgorithm::from_oid(&self.algorithm.algorithm).ok_or(X509DecodeErr::InvalidField("algorithm"))?;
+        if self.algorithm.parameters != AlgorithmParameters::Absent {
+            return Err(X509DecodeErr::InvalidField("parameters"));
+        }
+        let split = post_quantum_key_len(&composite.ml_dsa).unwrap_or(0);
+        if self.subject_public_key.len() <= split {
+            return Err(X509DecodeErr::InvalidField("subjectPublicKey"));
+        }
+        let (ml_dsa, traditional) = self.subject_public_key.split_at(split);
+        let traditional =
+            SubjectPublicKeyInfo { algorithm: composite.traditional_key, subject_public_key: traditional.to_vec() };
+        if traditional.algorithm.algorithm == oids::RSA_ENCRYPTION {
+            der_decode::<RsaPublicKey>(&traditional.subject_public_key)?;
+        } else if traditional.algorithm.algorithm == oids::EC_PUBLIC_KEY {
+            traditional.ec_public_key()?;
+        } else {
+            traditional.curve_public_key()?;
+        }
+        Ok(CompositePublicKey {
+            ml_dsa: SubjectPublicKeyInfo::from_post_quantum_public_key(composite.ml_dsa, ml_dsa)?,
+            traditional,
+        })
+    }
+
+    /// A composite SPKI joining the component keys, which must be of the
+    /// two algorithms `algorithm` pairs.
+    pub fn from_composite_public_key(
+        algorithm: ObjectIdentifier,
+        key: &CompositePublicKey,
+    ) -> Result<SubjectPublicKeyInfo, X509DecodeErr> {
+        let composite = CompositeAlgorithm::from_oid(&algorithm).ok_or(X509DecodeErr::InvalidField("algorithm"))?;
+        if key.ml_dsa.algorithm.algorithm != composite.ml_dsa || key.traditional.algorithm != composite.traditional_key
+        {
+            return Err(X509DecodeErr::InvalidField("algorithm"));
+        }
+        let mut subject_public_key = key.ml_dsa.subject_public_key.clone();
+        subject_public_key.extend_from_slice(&key.traditional.subject_public_key);
+        let spki = SubjectPublicKeyInfo { algorithm: AlgorithmIdentifier::new(algorithm), subject_public_key };
+        spki.composite_public_key()?;
+        Ok(spki)
+    }
+}
+
+/// The two signatures of a composite signature value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompositeSignature {
+    pub ml_dsa: Vec<u8>,
+    /// The traditional signature as its algorithm encodes it: the DER
+    /// Ecdsa-Sig-Value for ECDSA, for instance.
+    pub traditional: Vec<u8>,
+}
+
+impl CompositeSignature {
+    /// Split a signature value made with the composite `algorithm`.
+    pub fn from_bytes(algorithm: &ObjectIdentifier, signature: &[u8]) -> Result<CompositeSignature, X509DecodeErr> {
+        let composite = CompositeAlgorithm::from_oid(algorithm).ok_or(X509DecodeErr::InvalidField("algorithm"))?;
+        let split = composite.ml_dsa_signature_len();
+        if signature.len() <= split {
+            return Err(X509DecodeErr::InvalidField("signature"));
+        }
+        let (ml_dsa, traditional) = signature.split_at(split);
+        Ok(CompositeSignature { ml_dsa: ml_dsa.to_vec(), traditional: traditional.to_vec() })
+    }
+
+    /// The signature value, to go in the signature BIT STRING.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut signature = self.ml_dsa.clone();
+        signature.extend_from_slice(&self.traditional);
+        signature
+    }
+}
+
+#[cfg(test)]
+mod composite_tests {
+    use simple_asn1::der_encode;
+
+    use super::{CompositeAlgorithm, CompositePublicKey, CompositeSignature};
+    use algorithm::AlgorithmIdentifier;
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use oids;
+    use spki::SubjectPublicKeyInfo;
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+
+    fn components() -> CompositePublicKey {
+        CompositePublicKey {
+            ml_dsa: SubjectPublicKeyInfo::from_post_quantum_public_key(oids::ML_DSA_44, &[0x44; 1312]).unwrap(),
+            traditional: der_decode::<Certificate>(EC_ROOT).unwrap().tbs_certificate.subject_public_key_info,
+        }
+    }
+
+    #[test]
+    fn composite_algorithm_names_its_components() {
+        let composite = CompositeAlgorithm::from_oid(&oids::MLDSA87_ED448_SHAKE256).unwrap();
+        assert_eq!(oids::ML_DSA_87, composite.ml_dsa);
+        assert_eq!(AlgorithmIdentifier::new(oids::ED448), composite.traditional_key);
+        let composite = CompositeAlgorithm::from_oid(&oids::MLDSA65_RSA3072_PSS_SHA512).unwrap();
+        assert_eq!(AlgorithmIdentifier::with_null_parameters(oids::RSA_ENCRYPTION), composite.traditional_key);
+        assert_eq!(None, CompositeAlgorithm::from_oid(&oids::ML_DSA_44));
+    }
+
+    #[test]
+    fn composite_public_key_round_trips_through_certificates() {
+        let key = components();
+        let spki = SubjectPublicKeyInfo::from_composite_public_key(oids::MLDSA44_ECDSA_P256_SHA256, &key).unwrap();
+        assert_eq!(1312 + 65, spki.subject_public_key.len());
+
+        let mut cert = der_decode::<Certificate>(EC_ROOT).unwrap();
+        cert.tbs_certificate.subject_public_key_info = spki;
+        let decoded = der_decode::<Certificate>(&der_encode(&cert).unwrap()).unwrap();
+        assert_eq!(cert, decoded);
+        assert_eq!(Ok(key), decoded.tbs_certificate.subject_public_key_info.composite_public_key());
+    }
+
+    #[test]
+    fn composite_public_key_checks_components() {
+        let key = components();
+        let error = SubjectPublicKeyInfo::from_composite_public_key(oids::MLDSA65_ECDSA_P256_SHA512, &key);
+        assert_eq!(Err(X509DecodeErr::InvalidField("algorithm")), error);
+        // A P-256 point one byte short.
+        let mut short = key.clone();
+        short.traditional.subject_public_key.pop();
+        let error = SubjectPublicKeyInfo::from_composite_public_key(oids::MLDSA44_ECDSA_P256_SHA256, &short);
+        assert!(error.is_err());
+
+        let mut spki = SubjectPublicKeyInfo::from_composite_public_key(oids::MLDSA44_ECDSA_P256_SHA256, &key).unwrap();
+        spki.subject_public_key.truncate(1312);
+        assert_eq!(Err(X509DecodeErr::InvalidField("subjectPublicKey")), spki.composite_public_key());
+        spki.algorithm = AlgorithmIdentifier::with_null_parameters(oids::MLDSA44_ECDSA_P256_SHA256);
+        assert_eq!(Err(X509DecodeErr::InvalidField("parameters")), spki.composite_public_key());
+    }
+
+    #[test]
+    fn composite_signature_splits_and_joins() {
+        let signature = CompositeSignature { ml_dsa: vec![1; 3309], traditional: vec![2; 64] };
+        let bytes = signature.to_bytes();
+        assert_eq!(Ok(signature), CompositeSignature::from_bytes(&oids::MLDSA65_ED25519_SHA512, &bytes));
+        let error = CompositeSignature::from_bytes(&oids::MLDSA87_ED448_SHAKE256, &bytes);
+        assert_eq!(Err(X509DecodeErr::InvalidField("signature")), error);
+        let error = CompositeSignature::from_bytes(&oids::ED25519, &bytes);
+        assert_eq!(Err(X509DecodeErr::InvalidField("algorithm")), error);
+    }
+}
@@ -0,0 +1,561 @@
+//! Certificate revocation lists (RFC 5280 §5).
+
+use num::bigint::BigInt;
+use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+use time::PrimitiveDateTime;
+
+use algorithm::AlgorithmIdentifier;
+use der::{context_tag, decode_with, expect_end, explicit, explicit_inner, next_block, sequence, SignedBytes};
+use error::X509DecodeErr;
+use extensions::{CRLReason, CertificateIssuer, Extensions, InvalidityDate, X509Extension};
+use general_name::{GeneralName, GeneralNames};
+use name::Name;
+use pem::{self, Pem};
+use sign::{AsyncSigner, SignError, Signer, Signing};
+use spki::SubjectPublicKeyInfo;
+use validity::Time;
+#[cfg(feature = "verify")]
+use verify::RustCrypto;
+use verify::{SignatureVerifier, VerifyError};
+use x509::{CertificateSerialNumber, Version};
+
+/// A revoked certificate's entry in a CRL.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RevokedCertificate {
+    pub user_certificate: CertificateSerialNumber,
+    pub revocation_date: Time,
+    pub crl_entry_extensions: Option<Extensions>,
+}
+
+impl RevokedCertificate {
+    pub fn new(user_certificate: CertificateSerialNumber, revocation_date: Time) -> RevokedCertificate {
+        RevokedCertificate { user_certificate, revocation_date, crl_entry_extensions: None }
+    }
+
+    /// The entry extension of type `T`, decoded, if the entry has one.
+    pub fn extension<T: X509Extension>(&self) -> Result<Option<T>, X509DecodeErr> {
+        match self.crl_entry_extensions {
+            Some(ref extensions) => extensions.get(),
+            None => Ok(None),
+        }
+    }
+
+    /// The reasonCode entry extension. CAs leave it out rather than give
+    /// `CRLReason::Unspecified`.
+    pub fn reason(&self) -> Result<Option<CRLReason>, X509DecodeErr> {
+        self.extension()
+    }
+
+    /// The invalidityDate entry extension.
+    pub fn invalidity_date(&self) -> Result<Option<PrimitiveDateTime>, X509DecodeErr> {
+        Ok(self.extension::<InvalidityDate>()?.map(|date| date.0))
+    }
+}
+
+impl ToASN1 for RevokedCertificate {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.user_certificate.to_asn1_class(c)?;
+        items.push(self.revocation_date.to_block());
+        if let Some(ref extensions) = self.crl_entry_extensions {
+            items.extend(extensions.to_asn1_class(c)?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for RevokedCertificate {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "revokedCertificate")?;
+        let items = sequence(head, "revokedCertificate")?;
+        let (user_certificate, rest) = CertificateSerialNumber::from_asn1(items)?;
+        let (revocation_date, rest) = next_block(rest, "revocationDate")?;
+        let revocation_date = Time::from_block(revocation_date, "revocationDate")?;
+        let (crl_entry_extensions, rest) = if rest.is_empty() {
+            (None, rest)
+        } else {
+            let (extensions, rest) = Extensions::from_asn1(rest)?;
+            (Some(extensions), rest)
+        };
+        expect_end(rest, "revokedCertificate")?;
+        Ok((RevokedCertificate { user_certificate, revocation_date, crl_entry_extensions }, tail))
+    }
+}
+
+/// The to-be-signed body of a CRL (RFC 5280 §5.1.2).
+///
+/// `version` is `V1` or `V2`, and must be `V2` for a CRL with extensions.
+/// An empty `revoked_certificates` is left out of the encoding, as DER
+/// requires.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TBSCertList {
+    pub version: Version,
+    pub signature: AlgorithmIdentifier,
+    pub issuer: Name,
+    pub this_update: Time,
+    pub next_update: Option<Time>,
+    pub revoked_certificates: Vec<RevokedCertificate>,
+    pub crl_extensions: Option<Extensions>,
+}
+
+impl TBSCertList {
+    /// Sign the CRL with `signer`, setting `signature` to the signer's
+    /// algorithm first so the two match.
+    pub fn sign<S: Signer + ?Sized>(mut self, signer: &S) -> Result<CertificateList, SignError> {
+        self.signature = signer.signature_algorithm();
+        let signature_value = signer.sign(&der_encode(&self)?)?;
+        Ok(CertificateList {
+            signature_algorithm: self.signature.clone(),
+            tbs_cert_list: self,
+            signature_value,
+            signed_bytes: SignedBytes::default(),
+        })
+    }
+
+    /// Sign the CRL with `signer`, as for `sign`, once the returned future
+    /// is awaited.
+    pub fn sign_async<S: AsyncSigner + ?Sized>(mut self, signer: &S) -> Signing<'_, CertificateList> {
+        self.signature = signer.signature_algorithm();
+        let message = der_encode(&self);
+        Signing::new(signer, message, move |signature_value| CertificateList {
+            signature_algorithm: self.signature.clone(),
+            tbs_cert_list: self,
+            signature_value,
+            signed_bytes: SignedBytes::default(),
+        })
+    }
+
+    /// The entry for the certificate with `serial_number`, if it is
+    /// revoked.
+    pub fn find_revoked(&self, serial_number: &CertificateSerialNumber) -> Option<&RevokedCertificate> {
+        self.revoked_certificates.iter().find(|entry| entry.user_certificate == *serial_number)
+    }
+}
+
+impl ToASN1 for TBSCertList {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        // version is OPTIONAL, and left out of v1 CRLs.
+        if self.version != Version::V1 {
+            items.extend(self.version.to_asn1_class(c)?);
+        }
+        items.extend(self.signature.to_asn1_class(c)?);
+        items.extend(self.issuer.to_asn1_class(c)?);
+        items.push(self.this_update.to_block());
+        if let Some(next_update) = self.next_update {
+            items.push(next_update.to_block());
+        }
+        if !self.revoked_certificates.is_empty() {
+            let mut entries = Vec::new();
+            for entry in &self.revoked_certificates {
+                entries.extend(entry.to_asn1_class(c)?);
+            }
+            items.push(ASN1Block::Sequence(0, entries));
+        }
+        if let Some(ref extensions) = self.crl_extensions {
+            items.push(explicit(0, extensions.to_asn1_class(c)?.remove(0)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for TBSCertList {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "TBSCertList")?;
+        let items = sequence(head, "TBSCertList")?;
+
+        let (version, rest) = match items.split_first() {
+            Some((ASN1Block::Integer(_, n), rest)) if *n == BigInt::from(1) => (Version::V2, rest),
+            Some((ASN1Block::Integer(_, n), _)) => return Err(X509DecodeErr::UnsupportedVersion(n.clone())),
+            _ => (Version::V1, items),
+        };
+        let (signature, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+        let (issuer, rest) = Name::from_asn1(rest)?;
+        let (this_update, rest) = next_block(rest, "thisUpdate")?;
+        let this_update = Time::from_block(this_update, "thisUpdate")?;
+        let (next_update, rest) = match rest.split_first() {
+            Some((block @ ASN1Block::UTCTime(..), rest)) | Some((block @ ASN1Block::GeneralizedTime(..), rest)) => {
+                (Some(Time::from_block(block, "nextUpdate")?), rest)
+            }
+            _ => (None, rest),
+        };
+        let (revoked_certificates, rest) = match rest.split_first() {
+            Some((ASN1Block::Sequence(_, entries), rest)) => {
+                let mut revoked_certificates = Vec::new();
+                let mut entries = &entries[..];
+                while !entries.is_empty() {
+                    let (entry, remaining) = RevokedCertificate::from_asn1(entries)?;
+                    revoked_certificates.push(entry);
+                    entries = remaining;
+                }
+                (revoked_certificates, rest)
+            }
+            _ => (Vec::new(), rest),
+        };
+        let (crl_extensions, rest) = match rest.split_first() {
+            Some((block, rest)) if context_tag(block) == Some(0) => {
+                let inner = explicit_inner(block, 0).ok_or(X509DecodeErr::InvalidField("crlExtensions"))?;
+                let (extensions, _) = Extensions::from_asn1(::std::slice::from_ref(inner))?;
+                (Some(extensions), rest)
+            }
+            _ => (None, rest),
+        };
+        expect_end(rest, "TBSCertList")?;
+
+        Ok((TBSCertList {
+            version,
+            signature,
+            issuer,
+            this_update,
+            next_update,
+            revoked_certificates,
+            crl_extensions,
+        }, tail))
+    }
+}
+
+/// A signed CRL (RFC 5280 §5.1).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertificateList {
+    pub tbs_cert_list: TBSCertList,
+    pub signature_algorithm: AlgorithmIdentifier,
+    pub signature_value: Vec<u8>,
+    pub(crate) signed_bytes: SignedBytes,
+}
+
+impl CertificateList {
+    /// Decode the first `X509 CRL` PEM block in `input`.
+    pub fn from_pem(input: &str) -> Result<CertificateList, X509DecodeErr> {
+        let der = pem::parse(input)?.into_contents(pem::X509_CRL)?;
+        decode_with(&der, |blocks| {
+            let (crl, rest) = CertificateList::from_asn1(blocks)?;
+            expect_end(rest, "CertificateList").map(|_| crl)
+        })
+    }
+
+    /// The CRL as an `X509 CRL` PEM block.
+    pub fn to_pem(&self) -> Result<String, ASN1EncodeErr> {
+        Ok(Pem::new(pem::X509_CRL, der_encode(self)?).encode())
+    }
+
+    /// The revoked certificates' entries, in order.
+    pub fn revoked_certificates(&self) -> ::std::slice::Iter<'_, RevokedCertificate> {
+        self.tbs_cert_list.revoked_certificates.iter()
+    }
+
+    /// The entry for the certificate with `serial_number`, if it is
+    /// revoked.
+    pub fn find_revoked(&self, serial_number: &CertificateSerialNumber) -> Option<&RevokedCertificate> {
+        self.tbs_cert_list.find_revoked(serial_number)
+    }
+
+    /// The revoked certificates' entries, each with the issuer of the
+    /// certificate it revokes. That is the CRL issuer unless, in an
+    /// indirect CRL, a certificateIssuer entry extension names another,
+    /// which then holds for the following entries too.
+    pub fn revoked_certificates_by_issuer(&self) -> Result<Vec<(GeneralNames, &RevokedCertificate)>, X509DecodeErr> {
+        let mut issuer = GeneralNames::new(vec![GeneralName::DirectoryName(self.tbs_cert_list.issuer.clone())]);
+        let mut entries = Vec::new();
+        for entry in self.revoked_certificates() {
+            if let Some(CertificateIssuer(names)) = entry.extension()? {
+                issuer = names;
+            }
+            entries.push((issuer.clone(), entry));
+        }
+        Ok(entries)
+    }
+
+    /// The entry for the certificate with `serial_number` issued by
+    /// `issuer`, if it is revoked, following any certificateIssuer entry
+    /// extensions.
+    pub fn find_revoked_issued_by(
+        &self,
+        issuer: &Name,
+        serial_number: &CertificateSerialNumber,
+    ) -> Result<Option<&RevokedCertificate>, X509DecodeErr> {
+        let entries = self.revoked_certificates_by_issuer()?;
+        let found = entries.into_iter().find(|(names, entry)| {
+            entry.user_certificate == *serial_number && names.directory_names().any(|name| name.matches(issuer))
+        });
+        Ok(found.map(|(_, entry)| entry))
+    }
+
+    /// The CRL extension of type `T`, decoded, if the CRL has one.
+    pub fn extension<T: X509Extension>(&self) -> Result<Option<T>, X509DecodeErr> {
+        match self.tbs_cert_list.crl_extensions {
+            Some(ref extensions) => extensions.get(),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether `t`, in UTC, falls between thisUpdate and nextUpdate
+    /// inclusive. A CRL without a nextUpdate never goes stale.
+    pub fn is_current_at(&self, t: PrimitiveDateTime) -> bool {
+        let tbs = &self.tbs_cert_list;
+        tbs.this_update.date_time() <= t && tbs.next_update.is_none_or(|next| t <= next.date_time())
+    }
+
+    /// Check that the CRL was signed by the private half of `issuer_key`,
+    /// the key of the CA named as its issuer.
+    #[cfg(feature = "verify")]
+    pub fn verify_signed_by(&self, issuer_key: &SubjectPublicKeyInfo) -> Result<(), VerifyError> {
+        self.verify_signed_by_with(issuer_key, &RustCrypto)
+    }
+
+    /// Check the CRL's signature with the given backend. The signature is
+    /// checked over the bytes `tbs_cert_list` was decoded from, or its DER
+    /// encoding if it was built or has been changed.
+    pub fn verify_signed_by_with<V: SignatureVerifier + ?Sized>(
+        &self,
+        issuer_key: &SubjectPublicKeyInfo,
+        verifier: &V,
+    ) -> Result<(), VerifyError> {
+        let message = self.signed_bytes.message(&self.tbs_cert_list)?;
+        verifier.verify(&self.signature_algorithm, issuer_key, &message, &self.signature_value)
+    }
+}
+
+impl ToASN1 for CertificateList {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.tbs_cert_list.to_asn1_class(c)?;
+        items.extend(self.signature_algorithm.to_asn1_class(c)?);
+        let signature = self.signature_value.clone();
+        items.push(ASN1Block::BitString(0, signature.len() * 8, signature));
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for CertificateList {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "CertificateList")?;
+        let items = sequence(head, "CertificateList")?;
+        let (tbs_cert_list, rest) = TBSCertList::from_asn1(items)?;
+        let signed_bytes = SignedBytes::decoded(&items[0], &tbs_cert_list);
+        let (signature_algorithm, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+        let (signature_value, rest) = match next_block(rest, "signatureValue")? {
+            (ASN1Block::BitString(_, nbits, bytes), rest) if *nbits == bytes.len() * 8 => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("signatureValue")),
+        };
+        expect_end(rest, "CertificateList")?;
+        Ok((CertificateList { tbs_cert_list, signature_algorithm, signature_value, signed_bytes }, tail))
+    }
+}
+
+#[cfg(test)]
+mod crl_tests {
+    use num::bigint::{BigInt, BigUint};
+    use simple_asn1::{der_encode, ASN1Block, FromASN1, ToASN1};
+    use time::{Date, Month, PrimitiveDateTime, Time as TimeOfDay};
+
+    use super::{CertificateList, RevokedCertificate, TBSCertList};
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use extensions::{
+        AuthorityKeyIdentifier, CRLNumber, CRLReason, CertificateIssuer, Extensions, SubjectKeyIdentifier,
+    };
+    use general_name::{GeneralName, GeneralNames};
+    use oids;
+    use validity::Time;
+    #[cfg(feature = "verify")]
+    use verify::VerifyError;
+    use x509::{Certificate, CertificateSerialNumber, Version};
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../testdata/ec-leaf.der");
+    const EC_ROOT_CRL: &[u8] = include_bytes!("../testdata/ec-root-crl.der");
+    const EC_ROOT_CRL_PEM: &str = include_str!("../testdata/ec-root-crl.pem");
+
+    fn at(year: i32, month: Month, day: u8) -> PrimitiveDateTime {
+        PrimitiveDateTime::new(Date::from_calendar_date(year, month, day).unwrap(), TimeOfDay::MIDNIGHT)
+    }
+
+    fn serial(n: i64) -> CertificateSerialNumber {
+        CertificateSerialNumber(BigInt::from(n))
+    }
+
+    #[test]
+    fn certificate_list_decodes() {
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let tbs = &crl.tbs_cert_list;
+        assert_eq!(Version::V2, tbs.version);
+        assert_eq!(oids::ECDSA_WITH_SHA256, tbs.signature.algorithm);
+        assert_eq!(root.tbs_certificate.subject, tbs.issuer);
+        assert_eq!(at(2026, Month::October, 14), tbs.this_update.date_time().replace_time(TimeOfDay::MIDNIGHT));
+        let next_update = tbs.next_update.unwrap().date_time();
+        assert_eq!(at(2026, Month::November, 13), next_update.replace_time(TimeOfDay::MIDNIGHT));
+
+        let serials: Vec<_> = crl.revoked_certificates().map(|entry| entry.user_certificate.clone()).collect();
+        assert_eq!(vec![serial(0x1001), serial(0x1003), serial(0x1004)], serials);
+        assert!(crl.revoked_certificates().nth(1).unwrap().crl_entry_extensions.is_none());
+
+        assert_eq!(Some(CRLNumber(BigUint::from(5u8))), crl.extension().unwrap());
+        let aki = crl.extension::<AuthorityKeyIdentifier>().unwrap().unwrap();
+        let ski = root.extension::<SubjectKeyIdentifier>().unwrap().unwrap();
+        assert_eq!(Some(ski.0), aki.key_identifier);
+    }
+
+    #[test]
+    fn certificate_list_round_trips() {
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        assert_eq!(EC_ROOT_CRL.to_vec(), der_encode(&crl).unwrap());
+    }
+
+    #[test]
+    fn certificate_list_pem_round_trips() {
+        let crl = CertificateList::from_pem(EC_ROOT_CRL_PEM).unwrap();
+        assert_eq!(der_decode::<CertificateList>(EC_ROOT_CRL).unwrap(), crl);
+        assert_eq!(EC_ROOT_CRL_PEM, crl.to_pem().unwrap());
+    }
+
+    #[test]
+    fn certificate_list_finds_revoked_serials() {
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let entry = crl.find_revoked(&leaf.tbs_certificate.serial_number).unwrap();
+        assert_eq!(at(2026, Month::October, 14), entry.revocation_date.date_time().replace_time(TimeOfDay::MIDNIGHT));
+        assert!(entry.crl_entry_extensions.is_some());
+        assert_eq!(None, crl.find_revoked(&serial(0x1002)));
+    }
+
+    #[test]
+    fn revoked_certificate_entry_extensions() {
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        let entry = crl.find_revoked(&serial(0x1001)).unwrap();
+        assert_eq!(Some(CRLReason::KeyCompromise), entry.reason().unwrap());
+        assert_eq!(Some(at(2026, Month::October, 1)), entry.invalidity_date().unwrap());
+        let entry = crl.find_revoked(&serial(0x1003)).unwrap();
+        assert_eq!(None, entry.reason().unwrap());
+        assert_eq!(None, entry.invalidity_date().unwrap());
+        let entry = crl.find_revoked(&serial(0x1004)).unwrap();
+        assert_eq!(Some(CRLReason::CessationOfOperation), entry.reason().unwrap());
+    }
+
+    #[test]
+    fn certificate_list_tracks_certificate_issuer() {
+        let mut crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let other = leaf.tbs_certificate.subject.clone();
+        let mut extensions = Extensions::new();
+        let names = GeneralNames::new(vec![GeneralName::DirectoryName(other.clone())]);
+        extensions.insert(&CertificateIssuer(names.clone()), true).unwrap();
+        crl.tbs_cert_list.revoked_certificates[1].crl_entry_extensions = Some(extensions);
+
+        let entries = crl.revoked_certificates_by_issuer().unwrap();
+        let issuers: Vec<_> = entries.into_iter().map(|(names, _)| names).collect();
+        let own = GeneralNames::new(vec![GeneralName::DirectoryName(root.tbs_certificate.subject.clone())]);
+        assert_eq!(vec![own, names.clone(), names], issuers);
+
+        let root_name = &root.tbs_certificate.subject;
+        assert!(crl.find_revoked_issued_by(root_name, &serial(0x1001)).unwrap().is_some());
+        assert!(crl.find_revoked_issued_by(root_name, &serial(0x1004)).unwrap().is_none());
+        assert!(crl.find_revoked_issued_by(&other, &serial(0x1004)).unwrap().is_some());
+        assert!(crl.find_revoked_issued_by(&other, &serial(0x1001)).unwrap().is_none());
+    }
+
+    #[test]
+    fn certificate_list_is_current_between_updates() {
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        assert!(!crl.is_current_at(at(2026, Month::October, 14)));
+        assert!(crl.is_current_at(at(2026, Month::October, 15)));
+        assert!(crl.is_current_at(at(2026, Month::November, 13)));
+        assert!(!crl.is_current_at(at(2026, Month::November, 14)));
+
+        let mut crl = crl;
+        crl.tbs_cert_list.next_update = None;
+        assert!(crl.is_current_at(at(2036, Month::January, 1)));
+    }
+
+    #[test]
+    fn tbs_cert_list_v1_round_trips() {
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        let tbs = TBSCertList {
+            version: Version::V1,
+            signature: crl.tbs_cert_list.signature.clone(),
+            issuer: crl.tbs_cert_list.issuer.clone(),
+            this_update: Time::new(at(2026, Month::October, 14)),
+            next_update: None,
+            revoked_certificates: Vec::new(),
+            crl_extensions: None,
+        };
+        let items = match tbs.to_asn1().unwrap().remove(0) {
+            ASN1Block::Sequence(_, items) => items,
+            _ => unreachable!(),
+        };
+        // signature, issuer and thisUpdate only.
+        assert_eq!(3, items.len());
+        assert_eq!(tbs, der_decode(&der_encode(&tbs).unwrap()).unwrap());
+
+        let mut tbs = tbs;
+        tbs.revoked_certificates.push(RevokedCertificate::new(serial(7), Time::new(at(2026, Month::October, 1))));
+        assert_eq!(tbs, der_decode(&der_encode(&tbs).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn tbs_cert_list_rejects_unknown_version() {
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        let mut items = match crl.tbs_cert_list.to_asn1().unwrap().remove(0) {
+            ASN1Block::Sequence(_, items) => items,
+            _ => unreachable!(),
+        };
+        items[0] = ASN1Block::Integer(0, BigInt::from(2));
+        assert_eq!(
+            Err(X509DecodeErr::UnsupportedVersion(BigInt::from(2))),
+            TBSCertList::from_asn1(&[ASN1Block::Sequence(0, items)]).map(|(tbs, _)| tbs)
+        );
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn certificate_list_verifies_over_decoded_bytes() {
+        use p256::ecdsa::SigningKey;
+
+        use der::pad_first_integer;
+
+        const EC_ROOT_KEY: [u8; 32] = [
+            0xBE, 0x92, 0x7C, 0xE1, 0x0C, 0x45, 0x2F, 0x7E, 0x07, 0xF0, 0x8C, 0xEC, 0x0A, 0x8C, 0x74, 0xDB, 0xFE,
+            0x33, 0x74, 0x4C, 0x7F, 0x09, 0x1B, 0xAF, 0x55, 0x0B, 0x5A, 0x52, 0x6F, 0xD3, 0x2D, 0x60,
+        ];
+
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let root_key = &root.tbs_certificate.subject_public_key_info;
+        let key = SigningKey::from_bytes(&EC_ROOT_KEY.into()).unwrap();
+        let signed = pad_first_integer(EC_ROOT_CRL, Some(&key));
+        let crl = der_decode::<CertificateList>(&signed).unwrap();
+        assert_ne!(signed, der_encode(&crl).unwrap());
+        assert_eq!(Ok(()), crl.verify_signed_by(root_key));
+
+        let altered = der_decode::<CertificateList>(&pad_first_integer(EC_ROOT_CRL, None)).unwrap();
+        assert_eq!(Err(VerifyError::BadSignature), altered.verify_signed_by(root_key));
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn certificate_list_verifies_signature() {
+        let mut crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        assert_eq!(Ok(()), crl.verify_signed_by(&root.tbs_certificate.subject_public_key_info));
+        assert_eq!(
+            Err(VerifyError::BadSignature),
+            crl.verify_signed_by(&leaf.tbs_certificate.subject_public_key_info)
+        );
+        crl.tbs_cert_list.revoked_certificates.remove(0);
+        assert_eq!(
+            Err(VerifyError::BadSignature),
+            crl.verify_signed_by(&root.tbs_certificate.subject_public_key_info)
+        );
+    }
+}
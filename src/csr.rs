@@ -0,0 +1,412 @@
+//! PKCS#10 certification requests (RFC 2986).
+
+use num::bigint::BigInt;
+use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use algorithm::AlgorithmIdentifier;
+use attribute::Attribute;
+use der::{context_tag, decode_with, expect_end, implicit, implicit_inner, next_block, sequence, SignedBytes};
+use error::X509DecodeErr;
+use extensions::Extensions;
+use name::{DirectoryString, Name};
+use oid::ObjectIdentifier;
+use oids;
+use pem::{self, Pem};
+use sign::{AsyncSigner, SignError, Signer, Signing};
+use spki::SubjectPublicKeyInfo;
+#[cfg(feature = "verify")]
+use verify::RustCrypto;
+use verify::{SignatureVerifier, VerifyError};
+
+/// Label some older software writes in place of `CERTIFICATE REQUEST`.
+const NEW_CERTIFICATE_REQUEST: &str = "NEW CERTIFICATE REQUEST";
+
+/// The signed part of a certification request: the name and key to be
+/// certified, and any attributes such as requested extensions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertificationRequestInfo {
+    pub subject: Name,
+    pub subject_pk_info: SubjectPublicKeyInfo,
+    pub attributes: Vec<Attribute>,
+}
+
+impl CertificationRequestInfo {
+    /// A request for `subject` and `subject_pk_info`, without attributes.
+    pub fn new(subject: Name, subject_pk_info: SubjectPublicKeyInfo) -> CertificationRequestInfo {
+        CertificationRequestInfo { subject, subject_pk_info, attributes: Vec::new() }
+    }
+
+    /// Sign the request with `signer`, which must hold the private half of
+    /// `subject_pk_info` for the request to prove possession of it.
+    pub fn sign<S: Signer + ?Sized>(self, signer: &S) -> Result<CertificationRequest, SignError> {
+        let signature = signer.sign(&der_encode(&self)?)?;
+        Ok(CertificationRequest {
+            certification_request_info: self,
+            signature_algorithm: signer.signature_algorithm(),
+            signature,
+            signed_bytes: SignedBytes::default(),
+        })
+    }
+
+    /// Sign the request with `signer`, as for `sign`, once the returned
+    /// future is awaited.
+    pub fn sign_async<S: AsyncSigner + ?Sized>(self, signer: &S) -> Signing<'_, CertificationRequest> {
+        let signature_algorithm = signer.signature_algorithm();
+        let message = der_encode(&self);
+        Signing::new(signer, message, move |signature| CertificationRequest {
+            certification_request_info: self,
+            signature_algorithm,
+            signature,
+            signed_bytes: SignedBytes::default(),
+        })
+    }
+
+    /// The attribute of the given type, if present.
+    pub fn attribute(&self, attr_type: &ObjectIdentifier) -> Option<&Attribute> {
+        self.attributes.iter().find(|a| a.attr_type == *attr_type)
+    }
+
+    /// Add `attribute`, replacing any attribute of the same type.
+    pub fn set_attribute(&mut self, attribute: Attribute) {
+        match self.attributes.iter_mut().find(|a| a.attr_type == attribute.attr_type) {
+            Some(existing) => *existing = attribute,
+            None => self.attributes.push(attribute),
+        }
+    }
+
+    /// The only value of a SINGLE VALUE attribute, if present.
+    fn single_value(
+        &self,
+        attr_type: &ObjectIdentifier,
+        field: &'static str,
+    ) -> Result<Option<&ASN1Block>, X509DecodeErr> {
+        match self.attribute(attr_type) {
+            Some(attribute) if attribute.values.len() == 1 => Ok(Some(&attribute.values[0])),
+            Some(_) => Err(X509DecodeErr::InvalidField(field)),
+            None => Ok(None),
+        }
+    }
+
+    /// The challengePassword attribute (RFC 2985 §5.4.1), which some CAs
+    /// use to authenticate revocation requests.
+    pub fn challenge_password(&self) -> Result<Option<String>, X509DecodeErr> {
+        match self.single_value(&oids::CHALLENGE_PASSWORD, "challengePassword")? {
+            Some(value) => match DirectoryString::from_block(value) {
+                Some(password) => Ok(Some(password.as_str().to_string())),
+                None => Err(X509DecodeErr::InvalidField("challengePassword")),
+            },
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_challenge_password(&mut self, password: &str) {
+        let value = DirectoryString::new(password).to_block();
+        self.set_attribute(Attribute::new(oids::CHALLENGE_PASSWORD, vec![value]));
+    }
+
+    /// The extensions requested for the certificate through the
+    /// extensionRequest attribute (RFC 2985 §5.4.2).
+    pub fn extension_request(&self) -> Result<Option<Extensions>, X509DecodeErr> {
+        match self.single_value(&oids::EXTENSION_REQUEST, "extensionRequest")? {
+            Some(value) => {
+                let (extensions, _) = Extensions::from_asn1(::std::slice::from_ref(value))?;
+                Ok(Some(extensions))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_extension_request(&mut self, extensions: &Extensions) -> Result<(), ASN1EncodeErr> {
+        let value = extensions.to_asn1()?.remove(0);
+        self.set_attribute(Attribute::new(oids::EXTENSION_REQUEST, vec![value]));
+        Ok(())
+    }
+}
+
+impl ToASN1 for CertificationRequestInfo {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        // version is v1(0), the only one defined.
+        let mut items = vec![ASN1Block::Integer(0, BigInt::from(0))];
+        items.extend(self.subject.to_asn1_class(c)?);
+        items.extend(self.subject_pk_info.to_asn1_class(c)?);
+        let mut attributes = Vec::new();
+        for attribute in &self.attributes {
+            attributes.extend(attribute.to_asn1_class(c)?);
+        }
+        items.push(implicit(0, &ASN1Block::Set(0, attributes))?);
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for CertificationRequestInfo {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "CertificationRequestInfo")?;
+        let items = sequence(head, "CertificationRequestInfo")?;
+        let (version, rest) = next_block(items, "version")?;
+        match *version {
+            ASN1Block::Integer(_, ref n) if *n == BigInt::from(0) => {}
+            ASN1Block::Integer(_, ref n) => return Err(X509DecodeErr::UnsupportedVersion(n.clone())),
+            _ => return Err(X509DecodeErr::InvalidField("version")),
+        }
+        let (subject, rest) = Name::from_asn1(rest)?;
+        let (subject_pk_info, rest) = SubjectPublicKeyInfo::from_asn1(rest)?;
+        let (block, rest) = next_block(rest, "attributes")?;
+        let set = if context_tag(block) == Some(0) { implicit_inner(block, 0, 0x11) } else { None };
+        let mut items = match set {
+            Some(ASN1Block::Set(_, ref items)) => &items[..],
+            _ => return Err(X509DecodeErr::InvalidField("attributes")),
+        };
+        let mut attributes = Vec::new();
+        while !items.is_empty() {
+            let (attribute, remaining) = Attribute::from_asn1(items)?;
+            attributes.push(attribute);
+            items = remaining;
+        }
+        expect_end(rest, "CertificationRequestInfo")?;
+        Ok((CertificationRequestInfo { subject, subject_pk_info, attributes }, tail))
+    }
+}
+
+/// A signed certification request (RFC 2986 §4.2). The signature is made
+/// with the private key of `subject_pk_info`, proving its possession.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertificationRequest {
+    pub certification_request_info: CertificationRequestInfo,
+    pub signature_algorithm: AlgorithmIdentifier,
+    pub signature: Vec<u8>,
+    pub(crate) signed_bytes: SignedBytes,
+}
+
+impl CertificationRequest {
+    /// Decode the first `CERTIFICATE REQUEST` PEM block in `input`.
+    pub fn from_pem(input: &str) -> Result<CertificationRequest, X509DecodeErr> {
+        let block = pem::parse(input)?;
+        let der = if block.label == NEW_CERTIFICATE_REQUEST {
+            block.contents
+        } else {
+            block.into_contents(pem::CERTIFICATE_REQUEST)?
+        };
+        decode_with(&der, |blocks| {
+            let (request, rest) = CertificationRequest::from_asn1(blocks)?;
+            expect_end(rest, "CertificationRequest").map(|_| request)
+        })
+    }
+
+    /// The request as a `CERTIFICATE REQUEST` PEM block.
+    pub fn to_pem(&self) -> Result<String, ASN1EncodeErr> {
+        Ok(Pem::new(pem::CERTIFICATE_REQUEST, der_encode(self)?).encode())
+    }
+
+    /// Check the proof of possession: that the request was signed with the
+    /// private key it asks to be certified. A CA should do this before
+    /// issuing from the request.
+    #[cfg(feature = "verify")]
+    pub fn verify_signature(&self) -> Result<(), VerifyError> {
+        self.verify_signature_with(&RustCrypto)
+    }
+
+    /// Check the proof of possession with the given backend.
+    ///
+    /// The signature is checked over the bytes `certification_request_info`
+    /// was decoded from, or its DER encoding if it was built or has been
+    /// changed.
+    pub fn verify_signature_with<V: SignatureVerifier + ?Sized>(&self, verifier: &V) -> Result<(), VerifyError> {
+        let info = &self.certification_request_info;
+        let message = self.signed_bytes.message(info)?;
+        verifier.verify(&self.signature_algorithm, &info.subject_pk_info, &message, &self.signature)
+    }
+}
+
+impl ToASN1 for CertificationRequest {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.certification_request_info.to_asn1_class(c)?;
+        items.extend(self.signature_algorithm.to_asn1_class(c)?);
+        let signature = self.signature.clone();
+        items.push(ASN1Block::BitString(0, signature.len() * 8, signature));
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for CertificationRequest {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "CertificationRequest")?;
+        let items = sequence(head, "CertificationRequest")?;
+        let (certification_request_info, rest) = CertificationRequestInfo::from_asn1(items)?;
+        let signed_bytes = SignedBytes::decoded(&items[0], &certification_request_info);
+        let (signature_algorithm, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+        let (signature, rest) = match next_block(rest, "signature")? {
+            (ASN1Block::BitString(_, nbits, bytes), rest) if *nbits == bytes.len() * 8 => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("signature")),
+        };
+        expect_end(rest, "CertificationRequest")?;
+        Ok((CertificationRequest { certification_request_info, signature_algorithm, signature, signed_bytes }, tail))
+    }
+}
+
+#[cfg(test)]
+mod csr_tests {
+    use num::bigint::BigInt;
+    use simple_asn1::{der_encode, ASN1Block, FromASN1, ToASN1};
+
+    use super::{CertificationRequest, CertificationRequestInfo};
+    use attribute::Attribute;
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use extensions::{Extensions, KeyUsage, SubjectAltName};
+    use general_name::{GeneralName, GeneralNames};
+    use oids;
+    use pem::PemDecodeErr;
+    #[cfg(feature = "verify")]
+    use verify::VerifyError;
+    use x509::Certificate;
+
+    const EC_LEAF: &[u8] = include_bytes!("../testdata/ec-leaf.der");
+    const EC_LEAF_CSR: &[u8] = include_bytes!("../testdata/ec-leaf-csr.der");
+    const EC_LEAF_CSR_PEM: &str = include_str!("../testdata/ec-leaf-csr.pem");
+    const EC_LEAF_ATTRS_CSR: &[u8] = include_bytes!("../testdata/ec-leaf-attrs-csr.der");
+
+    #[test]
+    fn certification_request_decodes() {
+        let csr = der_decode::<CertificationRequest>(EC_LEAF_CSR).unwrap();
+        let info = &csr.certification_request_info;
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        assert_eq!(Some("example.com"), info.subject.common_name());
+        assert_eq!(leaf.tbs_certificate.subject_public_key_info, info.subject_pk_info);
+        assert!(info.attributes.is_empty());
+        assert_eq!(oid!(1, 2, 840, 10045, 4, 3, 2), csr.signature_algorithm.algorithm);
+    }
+
+    #[test]
+    fn certification_request_round_trips() {
+        let csr = der_decode::<CertificationRequest>(EC_LEAF_CSR).unwrap();
+        assert_eq!(EC_LEAF_CSR.to_vec(), der_encode(&csr).unwrap());
+    }
+
+    #[test]
+    fn certification_request_pem_round_trips() {
+        let csr = CertificationRequest::from_pem(EC_LEAF_CSR_PEM).unwrap();
+        assert_eq!(der_decode::<CertificationRequest>(EC_LEAF_CSR).unwrap(), csr);
+        assert_eq!(EC_LEAF_CSR_PEM, csr.to_pem().unwrap());
+        let legacy = EC_LEAF_CSR_PEM.replace("CERTIFICATE REQUEST", "NEW CERTIFICATE REQUEST");
+        assert_eq!(csr, CertificationRequest::from_pem(&legacy).unwrap());
+        let wrong = EC_LEAF_CSR_PEM.replace("CERTIFICATE REQUEST", "CERTIFICATE");
+        assert_eq!(
+            Err(X509DecodeErr::PemError(PemDecodeErr::UnexpectedLabel("CERTIFICATE".to_string()))),
+            CertificationRequest::from_pem(&wrong)
+        );
+    }
+
+    #[test]
+    fn certification_request_info_constructs() {
+        let csr = der_decode::<CertificationRequest>(EC_LEAF_CSR).unwrap();
+        let original = csr.certification_request_info;
+        let info = CertificationRequestInfo::new(original.subject.clone(), original.subject_pk_info.clone());
+        assert_eq!(original, info);
+        assert_eq!(der_encode(&original).unwrap(), der_encode(&info).unwrap());
+    }
+
+    #[test]
+    fn certification_request_info_rejects_unknown_version() {
+        let csr = der_decode::<CertificationRequest>(EC_LEAF_CSR).unwrap();
+        let mut items = match csr.certification_request_info.to_asn1().unwrap().remove(0) {
+            ASN1Block::Sequence(_, items) => items,
+            _ => unreachable!(),
+        };
+        items[0] = ASN1Block::Integer(0, BigInt::from(1));
+        assert_eq!(
+            Err(X509DecodeErr::UnsupportedVersion(BigInt::from(1))),
+            CertificationRequestInfo::from_asn1(&[ASN1Block::Sequence(0, items)]).map(|(info, _)| info)
+        );
+    }
+
+    #[test]
+    fn certification_request_attributes_decode() {
+        let csr = der_decode::<CertificationRequest>(EC_LEAF_ATTRS_CSR).unwrap();
+        let info = &csr.certification_request_info;
+        assert_eq!(Some("hunter22".to_string()), info.challenge_password().unwrap());
+        let requested = info.extension_request().unwrap().unwrap();
+        assert_eq!(Some(KeyUsage::DIGITAL_SIGNATURE), requested.get().unwrap());
+        assert!(requested.find(&oids::KEY_USAGE).unwrap().critical);
+        let san = requested.get::<SubjectAltName>().unwrap().unwrap();
+        assert_eq!(vec!["example.com", "www.example.com"], san.dns_names().collect::<Vec<_>>());
+        assert_eq!(EC_LEAF_ATTRS_CSR.to_vec(), der_encode(&csr).unwrap());
+    }
+
+    #[test]
+    fn certification_request_attributes_construct() {
+        let original = der_decode::<CertificationRequest>(EC_LEAF_ATTRS_CSR).unwrap().certification_request_info;
+        let mut info = CertificationRequestInfo::new(original.subject.clone(), original.subject_pk_info.clone());
+        let mut requested = Extensions::new();
+        requested.insert(&KeyUsage::DIGITAL_SIGNATURE, true).unwrap();
+        let names = vec![
+            GeneralName::DNSName("example.com".to_string()),
+            GeneralName::DNSName("www.example.com".to_string()),
+        ];
+        requested.insert(&SubjectAltName(GeneralNames::new(names)), false).unwrap();
+        info.set_challenge_password("hunter22");
+        info.set_extension_request(&requested).unwrap();
+        assert_eq!(original.challenge_password(), info.challenge_password());
+        // OpenSSL encodes the password as a UTF8String, not PrintableString.
+        assert_eq!(original.attributes[1], info.attributes[1]);
+
+        info.set_challenge_password("correct horse");
+        assert_eq!(2, info.attributes.len());
+        assert_eq!(Some("correct horse".to_string()), info.challenge_password().unwrap());
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn certification_request_verifies_signature() {
+        for der in &[EC_LEAF_CSR, EC_LEAF_ATTRS_CSR] {
+            assert_eq!(Ok(()), der_decode::<CertificationRequest>(der).unwrap().verify_signature());
+        }
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn certification_request_verifies_over_decoded_bytes() {
+        use p256::ecdsa::SigningKey;
+
+        use der::pad_first_integer;
+
+        // The private half of the key EC_LEAF_CSR asks to be certified.
+        const EC_LEAF_KEY: [u8; 32] = [
+            0x51, 0x37, 0xB1, 0xAF, 0xBC, 0x7E, 0xEA, 0x34, 0x16, 0x50, 0x4E, 0x9E, 0xC5, 0xE5, 0xC3, 0xD9, 0x67,
+            0x37, 0x8F, 0x24, 0x01, 0xA4, 0x12, 0x56, 0x36, 0x25, 0x88, 0xCB, 0xC7, 0xFD, 0x3B, 0x4B,
+        ];
+
+        let key = SigningKey::from_bytes(&EC_LEAF_KEY.into()).unwrap();
+        let signed = pad_first_integer(EC_LEAF_CSR, Some(&key));
+        let csr = der_decode::<CertificationRequest>(&signed).unwrap();
+        assert_ne!(signed, der_encode(&csr).unwrap());
+        assert_eq!(Ok(()), csr.verify_signature());
+
+        let altered = der_decode::<CertificationRequest>(&pad_first_integer(EC_LEAF_CSR, None)).unwrap();
+        assert_eq!(Err(VerifyError::BadSignature), altered.verify_signature());
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn certification_request_rejects_altered_info() {
+        let mut csr = der_decode::<CertificationRequest>(EC_LEAF_ATTRS_CSR).unwrap();
+        csr.certification_request_info.set_challenge_password("hunter23");
+        assert_eq!(Err(VerifyError::BadSignature), csr.verify_signature());
+    }
+
+    #[test]
+    fn certification_request_attributes_are_single_valued() {
+        let mut info = der_decode::<CertificationRequest>(EC_LEAF_CSR).unwrap().certification_request_info;
+        assert_eq!(None, info.extension_request().unwrap());
+        let empty = Extensions::new().to_asn1().unwrap().remove(0);
+        info.set_attribute(Attribute::new(oids::EXTENSION_REQUEST, vec![empty.clone(), empty]));
+        assert_eq!(Err(X509DecodeErr::InvalidField("extensionRequest")), info.extension_request());
+    }
+}
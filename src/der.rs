@@ -0,0 +1,745 @@
+//! A DER reader producing `simple_asn1` blocks.
+//!
+//! `simple_asn1::from_der` rejects empty SEQUENCEs and SETs and reads
+//! BMPString, UniversalString and TeletexString bodies as UTF-8, all of
+//! which turn up in real certificates. The reader here produces the same `ASN1Block`s but
+//! handles those cases, and is what every decoder in this crate goes through.
+
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+use num::bigint::{BigInt, BigUint};
+use num::{ToPrimitive, Zero};
+use simple_asn1::{der_encode, to_der, ASN1Block, ASN1Class, ASN1DecodeErr, ASN1EncodeErr, FromASN1, ToASN1, OID};
+use time::{Date, Month, PrimitiveDateTime, Time};
+
+use error::X509DecodeErr;
+
+/// Translate a binary blob into a series of `ASN1Block`s.
+pub fn from_der(i: &[u8]) -> Result<Vec<ASN1Block>, ASN1DecodeErr> {
+    let blocks = from_der_(i, 0, 0)?;
+    if blocks.is_empty() {
+        return Err(ASN1DecodeErr::EmptyBuffer);
+    }
+    Ok(blocks)
+}
+
+/// Decode a type from DER using this crate's reader.
+pub fn der_decode<T: FromASN1>(v: &[u8]) -> Result<T, T::Error> {
+    decode_with(v, |blocks| T::from_asn1(blocks).map(|(t, _)| t))
+}
+
+thread_local! {
+    // The inputs of the `decode_with` calls in progress on this thread,
+    // innermost last.
+    static SOURCES: RefCell<Vec<Rc<[u8]>>> = const { RefCell::new(Vec::new()) };
+}
+
+// Pops the input `decode_with` pushed, even if decoding panics.
+struct SourceGuard;
+
+impl Drop for SourceGuard {
+    fn drop(&mut self) {
+        SOURCES.with(|sources| sources.borrow_mut().pop());
+    }
+}
+
+/// Decode `der` and hand the blocks to `f`, keeping `der` at hand
+/// meanwhile so that `SignedBytes::decoded` can recover the exact bytes a
+/// block was read from.
+pub(crate) fn decode_with<T, E, F>(der: &[u8], f: F) -> Result<T, E>
+where
+    E: From<ASN1DecodeErr>,
+    F: FnOnce(&[ASN1Block]) -> Result<T, E>,
+{
+    let blocks = from_der(der)?;
+    SOURCES.with(|sources| sources.borrow_mut().push(Rc::from(der)));
+    let _guard = SourceGuard;
+    f(&blocks)
+}
+
+// The bytes `b` was read from, if it came from the innermost input being
+// decoded. The offsets the reader records place `b` in that input, and
+// reading those bytes back must give `b` again, so a block from anywhere
+// else is never matched.
+fn source_bytes(b: &ASN1Block) -> Option<Vec<u8>> {
+    let source = SOURCES.with(|sources| sources.borrow().last().cloned())?;
+    let start = match *b {
+        ASN1Block::Sequence(offset, _) | ASN1Block::Set(offset, _) => offset,
+        _ => return None,
+    };
+    let mut index = start;
+    decode_tag(&source, &mut index).ok()?;
+    let len = decode_length(&source, &mut index).ok()?;
+    let raw = source.get(start..index.checked_add(len)?)?;
+    match from_der_(raw, start, 0) {
+        Ok(ref blocks) if blocks.len() == 1 && blocks[0] == *b => Some(raw.to_vec()),
+        _ => None,
+    }
+}
+
+// How many constructed blocks enclose `b`, a context-specific block, in
+// the innermost input being decoded: 0 if it didn't come from there. As
+// with `source_bytes`, the bytes at `b`'s offset must read back as `b`.
+fn source_depth(b: &ASN1Block) -> usize {
+    let source = match SOURCES.with(|sources| sources.borrow().last().cloned()) {
+        Some(source) => source,
+        None => return 0,
+    };
+    let target = b.offset();
+    let (mut start, mut end, mut depth) = (0, source.len(), 0);
+    while start < end {
+        let mut index = start;
+        let (tag, constructed, class) = match decode_tag(&source, &mut index) {
+            Ok(header) => header,
+            Err(_) => return 0,
+        };
+        let next = match decode_length(&source, &mut index).ok().and_then(|len| index.checked_add(len)) {
+            Some(next) if next <= end => next,
+            _ => return 0,
+        };
+        if start == target {
+            let body = &source[index..next];
+            let same = match *b {
+                ASN1Block::Unknown(c, k, _, ref t, ref bytes) => {
+                    c == class && k == constructed && *t == tag && bytes == body
+                }
+                ASN1Block::Explicit(c, _, ref t, ref inner) => {
+                    c == class && constructed && *t == tag && to_der(inner).is_ok_and(|der| der == body)
+                }
+                _ => false,
+            };
+            return if same { depth } else { 0 };
+        }
+        if target < next {
+            if !constructed {
+                return 0;
+            }
+            depth += 1;
+            start = index;
+            end = next;
+        } else {
+            start = next;
+        }
+    }
+    0
+}
+
+/// The bytes a signed structure's to-be-signed part was decoded from,
+/// which are what its signature covers. Re-encoding the decoded value
+/// gives different bytes when the input wasn't DER, such as when it spells
+/// out a DEFAULT value, so signatures are checked over these instead.
+///
+/// Nothing is kept for values that re-encode exactly, or that were built
+/// rather than decoded. Values compare equal whatever they were decoded
+/// from.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SignedBytes(Option<(Vec<u8>, Vec<u8>)>);
+
+impl SignedBytes {
+    /// The bytes `block` was read from, which decoded as `value`.
+    pub(crate) fn decoded<T: ToASN1<Error = ASN1EncodeErr>>(block: &ASN1Block, value: &T) -> SignedBytes {
+        let raw = source_bytes(block).or_else(|| to_der(block).ok());
+        match (raw, der_encode(value)) {
+            (Some(raw), Ok(encoded)) if raw != encoded => SignedBytes(Some((raw, encoded))),
+            _ => SignedBytes(None),
+        }
+    }
+
+    /// The bytes a signature over `value` is checked against: those it
+    /// was decoded from, unless it has been changed since.
+    pub(crate) fn message<T: ToASN1<Error = ASN1EncodeErr>>(&self, value: &T) -> Result<Vec<u8>, ASN1EncodeErr> {
+        let encoded = der_encode(value)?;
+        match self.0 {
+            Some((ref raw, ref decoded)) if *decoded == encoded => Ok(raw.clone()),
+            _ => Ok(encoded),
+        }
+    }
+}
+
+impl PartialEq for SignedBytes {
+    fn eq(&self, _other: &SignedBytes) -> bool {
+        true
+    }
+}
+
+// How deeply constructed blocks may nest. Certificates go a dozen or so
+// levels deep; the limit keeps hostile input from exhausting the stack.
+const MAX_DEPTH: usize = 64;
+
+// The blocks of `i`, which sits `depth` constructed blocks deep. Nesting
+// past `MAX_DEPTH` fails with `Overflow`.
+fn from_der_(i: &[u8], start_offset: usize, depth: usize) -> Result<Vec<ASN1Block>, ASN1DecodeErr> {
+    if depth > MAX_DEPTH {
+        return Err(ASN1DecodeErr::Overflow);
+    }
+    let mut result = Vec::new();
+    let mut index = 0;
+
+    while index < i.len() {
+        let soff = start_offset + index;
+        let (tag, constructed, class) = decode_tag(i, &mut index)?;
+        let len = decode_length(i, &mut index)?;
+        let end = index.checked_add(len).ok_or(ASN1DecodeErr::LengthTooLarge(len))?;
+        if end > i.len() {
+            return Err(ASN1DecodeErr::Incomplete);
+        }
+        let body = &i[index..end];
+        let body_offset = start_offset + index;
+        index = end;
+
+        if class != ASN1Class::Universal {
+            if constructed {
+                match from_der_(body, body_offset, depth + 1) {
+                    Ok(mut items) if items.len() == 1 => {
+                        result.push(ASN1Block::Explicit(class, soff, tag, Box::new(items.remove(0))));
+                        continue;
+                    }
+                    Err(ASN1DecodeErr::Overflow) => return Err(ASN1DecodeErr::Overflow),
+                    _ => {}
+                }
+            }
+            result.push(ASN1Block::Unknown(class, constructed, soff, tag, body.to_vec()));
+            continue;
+        }
+
+        let block = match (tag.to_u8(), constructed) {
+            (Some(0x10), true) => ASN1Block::Sequence(soff, from_der_(body, body_offset, depth + 1)?),
+            (Some(0x11), true) => ASN1Block::Set(soff, from_der_(body, body_offset, depth + 1)?),
+            (Some(t), false) => decode_primitive(t, soff, body)?,
+            _ => ASN1Block::Unknown(class, constructed, soff, tag, body.to_vec()),
+        };
+        result.push(block);
+    }
+
+    Ok(result)
+}
+
+fn decode_primitive(tag: u8, soff: usize, body: &[u8]) -> Result<ASN1Block, ASN1DecodeErr> {
+    match tag {
+        0x01 => {
+            if body.len() != 1 {
+                return Err(ASN1DecodeErr::BadBooleanLength(body.len()));
+            }
+            // DER spells TRUE as 0xFF and nothing else.
+            match body[0] {
+                0x00 => Ok(ASN1Block::Boolean(soff, false)),
+                0xFF => Ok(ASN1Block::Boolean(soff, true)),
+                _ => Err(ASN1DecodeErr::Incomplete),
+            }
+        }
+        0x02 => {
+            if body.is_empty() {
+                return Err(ASN1DecodeErr::Incomplete);
+            }
+            Ok(ASN1Block::Integer(soff, BigInt::from_signed_bytes_be(body)))
+        }
+        0x03 => {
+            if body.is_empty() {
+                return Err(ASN1DecodeErr::InvalidBitStringLength(0));
+            }
+            let unused = body[0] as usize;
+            let bits = body[1..].to_vec();
+            if unused > 7 || (bits.is_empty() && unused != 0) {
+                return Err(ASN1DecodeErr::InvalidBitStringLength(unused as isize));
+            }
+            Ok(ASN1Block::BitString(soff, bits.len() * 8 - unused, bits))
+        }
+        0x04 => Ok(ASN1Block::OctetString(soff, body.to_vec())),
+        0x05 => Ok(ASN1Block::Null(soff)),
+        0x06 => Ok(ASN1Block::ObjectIdentifier(soff, decode_oid(body)?)),
+        0x0C => match String::from_utf8(body.to_vec()) {
+            Ok(s) => Ok(ASN1Block::UTF8String(soff, s)),
+            Err(e) => Err(ASN1DecodeErr::UTF8DecodeFailure(e.utf8_error())),
+        },
+        0x13 => Ok(ASN1Block::PrintableString(soff, latin1(body))),
+        // T.61 has no sensible mapping to Unicode. Reading it byte for byte
+        // as Latin-1 is right for the common case and keeps the original
+        // bytes recoverable.
+        0x14 => Ok(ASN1Block::TeletexString(soff, latin1(body))),
+        0x16 => Ok(ASN1Block::IA5String(soff, latin1(body))),
+        0x17 => Ok(ASN1Block::UTCTime(soff, decode_utc_time(body)?)),
+        0x18 => Ok(ASN1Block::GeneralizedTime(soff, decode_generalized_time(body)?)),
+        0x1C => Ok(ASN1Block::UniversalString(soff, decode_ucs4(body)?)),
+        0x1E => Ok(ASN1Block::BMPString(soff, decode_ucs2(body)?)),
+        _ => Ok(ASN1Block::Unknown(ASN1Class::Universal, false, soff, BigUint::from(tag), body.to_vec())),
+    }
+}
+
+fn decode_tag(i: &[u8], index: &mut usize) -> Result<(BigUint, bool, ASN1Class), ASN1DecodeErr> {
+    let tagbyte = *i.get(*index).ok_or(ASN1DecodeErr::Incomplete)?;
+    *index += 1;
+
+    let class = match tagbyte >> 6 {
+        0b00 => ASN1Class::Universal,
+        0b01 => ASN1Class::Application,
+        0b10 => ASN1Class::ContextSpecific,
+        _ => ASN1Class::Private,
+    };
+    let constructed = tagbyte & 0b0010_0000 != 0;
+    let basetag = tagbyte & 0b1_1111;
+
+    if basetag == 0b1_1111 {
+        Ok((decode_base128(i, index)?, constructed, class))
+    } else {
+        Ok((BigUint::from(basetag), constructed, class))
+    }
+}
+
+fn decode_base128(i: &[u8], index: &mut usize) -> Result<BigUint, ASN1DecodeErr> {
+    let mut res = BigUint::zero();
+
+    loop {
+        let byte = *i.get(*index).ok_or(ASN1DecodeErr::Incomplete)?;
+        *index += 1;
+        res = (res << 7) + BigUint::from(byte & 0x7f);
+        if byte & 0x80 == 0 {
+            return Ok(res);
+        }
+    }
+}
+
+fn decode_length(i: &[u8], index: &mut usize) -> Result<usize, ASN1DecodeErr> {
+    let startbyte = *i.get(*index).ok_or(ASN1DecodeErr::Incomplete)?;
+    *index += 1;
+
+    if startbyte < 0x80 {
+        return Ok(startbyte as usize);
+    }
+
+    // 0x80 is the BER indefinite length form, which DER forbids.
+    let lenlen = (startbyte & 0x7f) as usize;
+    if lenlen == 0 {
+        return Err(ASN1DecodeErr::Incomplete);
+    }
+    if lenlen > ::std::mem::size_of::<usize>() {
+        return Err(ASN1DecodeErr::LengthTooLarge(lenlen));
+    }
+
+    let mut res = 0usize;
+    for _ in 0..lenlen {
+        let byte = *i.get(*index).ok_or(ASN1DecodeErr::Incomplete)?;
+        *index += 1;
+        res = (res << 8) | byte as usize;
+    }
+    Ok(res)
+}
+
+fn decode_oid(body: &[u8]) -> Result<OID, ASN1DecodeErr> {
+    if body.is_empty() {
+        return Err(ASN1DecodeErr::Incomplete);
+    }
+
+    let mut index = 0;
+    let first = decode_base128(body, &mut index)?;
+    let forty = BigUint::from(40u8);
+    let mut arcs = if first < forty {
+        vec![BigUint::zero(), first]
+    } else if first < BigUint::from(80u8) {
+        vec![BigUint::from(1u8), first - forty]
+    } else {
+        vec![BigUint::from(2u8), first - BigUint::from(80u8)]
+    };
+    while index < body.len() {
+        arcs.push(decode_base128(body, &mut index)?);
+    }
+    Ok(OID::new(arcs))
+}
+
+fn latin1(body: &[u8]) -> String {
+    body.iter().map(|&b| b as char).collect()
+}
+
+fn decode_ucs2(body: &[u8]) -> Result<String, ASN1DecodeErr> {
+    if !body.len().is_multiple_of(2) {
+        return Err(ASN1DecodeErr::Incomplete);
+    }
+    let units: Vec<u16> = body.chunks(2).map(|c| (c[0] as u16) << 8 | c[1] as u16).collect();
+    String::from_utf16(&units).map_err(|_| ASN1DecodeErr::Incomplete)
+}
+
+fn decode_ucs4(body: &[u8]) -> Result<String, ASN1DecodeErr> {
+    if !body.len().is_multiple_of(4) {
+        return Err(ASN1DecodeErr::Incomplete);
+    }
+    body.chunks(4)
+        .map(|c| {
+            let v = (c[0] as u32) << 24 | (c[1] as u32) << 16 | (c[2] as u32) << 8 | c[3] as u32;
+            ::std::char::from_u32(v).ok_or(ASN1DecodeErr::Incomplete)
+        })
+        .collect()
+}
+
+fn digits(s: &[u8], field: &str) -> Result<u32, ASN1DecodeErr> {
+    let mut res = 0;
+    for &c in s {
+        if !c.is_ascii_digit() {
+            return Err(ASN1DecodeErr::InvalidDateValue(field.to_string()));
+        }
+        res = res * 10 + (c - b'0') as u32;
+    }
+    Ok(res)
+}
+
+fn make_time(raw: &str, year: i32, rest: &[u8], nanos: u32) -> Result<PrimitiveDateTime, ASN1DecodeErr> {
+    let bad = || ASN1DecodeErr::InvalidDateValue(raw.to_string());
+    let month = Month::try_from(digits(&rest[0..2], raw)? as u8).map_err(|_| bad())?;
+    let date = Date::from_calendar_date(year, month, digits(&rest[2..4], raw)? as u8).map_err(|_| bad())?;
+    let time = Time::from_hms_nano(
+        digits(&rest[4..6], raw)? as u8,
+        digits(&rest[6..8], raw)? as u8,
+        digits(&rest[8..10], raw)? as u8,
+        nanos,
+    )
+    .map_err(|_| bad())?;
+    Ok(PrimitiveDateTime::new(date, time))
+}
+
+// RFC 5280 §4.1.2.5.1: YYMMDDHHMMSSZ, with YY >= 50 meaning 19YY.
+fn decode_utc_time(body: &[u8]) -> Result<PrimitiveDateTime, ASN1DecodeErr> {
+    let raw = String::from_utf8_lossy(body).into_owned();
+    if body.len() != 13 || body[12] != b'Z' {
+        return Err(ASN1DecodeErr::InvalidDateValue(raw));
+    }
+    let yy = digits(&body[0..2], &raw)? as i32;
+    let year = if yy >= 50 { 1900 + yy } else { 2000 + yy };
+    make_time(&raw, year, &body[2..12], 0)
+}
+
+// YYYYMMDDHHMMSS[.fff]Z
+fn decode_generalized_time(body: &[u8]) -> Result<PrimitiveDateTime, ASN1DecodeErr> {
+    let raw = String::from_utf8_lossy(body).into_owned();
+    if body.len() < 15 || body[body.len() - 1] != b'Z' {
+        return Err(ASN1DecodeErr::InvalidDateValue(raw));
+    }
+    let year = digits(&body[0..4], &raw)? as i32;
+    let fraction = &body[14..body.len() - 1];
+    let nanos = match fraction.split_first() {
+        None => 0,
+        Some((&b'.', frac)) if !frac.is_empty() && frac.len() <= 9 => {
+            digits(frac, &raw)? * 10u32.pow(9 - frac.len() as u32)
+        }
+        _ => return Err(ASN1DecodeErr::InvalidDateValue(raw)),
+    };
+    make_time(&raw, year, &body[4..14], nanos)
+}
+
+/// Split the next block off `v`, failing if there isn't one.
+pub(crate) fn next_block<'a>(
+    v: &'a [ASN1Block],
+    field: &'static str,
+) -> Result<(&'a ASN1Block, &'a [ASN1Block]), X509DecodeErr> {
+    v.split_first().ok_or(X509DecodeErr::MissingField(field))
+}
+
+/// The blocks inside a SEQUENCE.
+pub(crate) fn sequence<'a>(b: &'a ASN1Block, field: &'static str) -> Result<&'a [ASN1Block], X509DecodeErr> {
+    match *b {
+        ASN1Block::Sequence(_, ref items) => Ok(items),
+        _ => Err(X509DecodeErr::InvalidField(field)),
+    }
+}
+
+/// Fail if anything is left over after decoding `field`.
+pub(crate) fn expect_end(rest: &[ASN1Block], field: &'static str) -> Result<(), X509DecodeErr> {
+    if rest.is_empty() {
+        Ok(())
+    } else {
+        Err(X509DecodeErr::TrailingData(field))
+    }
+}
+
+/// The tag number of a context-specific block, if `b` is one.
+pub(crate) fn context_tag(b: &ASN1Block) -> Option<u64> {
+    match *b {
+        ASN1Block::Explicit(ASN1Class::ContextSpecific, _, ref tag, _) => tag.to_u64(),
+        ASN1Block::Unknown(ASN1Class::ContextSpecific, _, _, ref tag, _) => tag.to_u64(),
+        _ => None,
+    }
+}
+
+/// The block wrapped by an `[tag] EXPLICIT` context-specific tag.
+pub(crate) fn explicit_inner(b: &ASN1Block, tag: u64) -> Option<&ASN1Block> {
+    match *b {
+        ASN1Block::Explicit(ASN1Class::ContextSpecific, _, ref t, ref inner) if t.to_u64() == Some(tag) => {
+            Some(inner)
+        }
+        _ => None,
+    }
+}
+
+/// The contents of an `[tag] IMPLICIT` primitive, such as an IA5String.
+pub(crate) fn implicit_bytes(b: &ASN1Block, tag: u64) -> Option<&[u8]> {
+    match *b {
+        ASN1Block::Unknown(ASN1Class::ContextSpecific, false, _, ref t, ref body) if t.to_u64() == Some(tag) => {
+            Some(body)
+        }
+        _ => None,
+    }
+}
+
+/// Wrap `inner` in an `[tag] EXPLICIT` context-specific tag.
+pub(crate) fn explicit(tag: u64, inner: ASN1Block) -> ASN1Block {
+    ASN1Block::Explicit(ASN1Class::ContextSpecific, 0, BigUint::from(tag), Box::new(inner))
+}
+
+/// An `[tag] IMPLICIT` primitive with the given contents.
+pub(crate) fn implicit_primitive(tag: u64, body: Vec<u8>) -> ASN1Block {
+    ASN1Block::Unknown(ASN1Class::ContextSpecific, false, 0, BigUint::from(tag), body)
+}
+
+/// The block an `[tag] IMPLICIT` tag replaced, given the universal tag
+/// number `universal` it stands in for. The body is read again as deep as
+/// `b` sat in the input, so it nests no further than `MAX_DEPTH` allows.
+pub(crate) fn implicit_inner(b: &ASN1Block, tag: u64, universal: u8) -> Option<ASN1Block> {
+    // A SEQUENCE or SET keeps the offsets of its members, so that
+    // `SignedBytes` can find them in the input.
+    if universal == 0x10 || universal == 0x11 {
+        let items = match *b {
+            ASN1Block::Unknown(ASN1Class::ContextSpecific, true, soff, ref t, ref body) if t.to_u64() == Some(tag) => {
+                let tag_len = if tag < 31 { 1 } else { 1 + (64 - tag.leading_zeros() as usize).div_ceil(7) };
+                from_der_(body, soff + tag_len + encode_length(body.len()).len(), source_depth(b) + 1).ok()?
+            }
+            ASN1Block::Explicit(ASN1Class::ContextSpecific, _, ref t, ref inner) if t.to_u64() == Some(tag) => {
+                vec![(**inner).clone()]
+            }
+            _ => return None,
+        };
+        let soff = b.offset();
+        return Some(if universal == 0x10 { ASN1Block::Sequence(soff, items) } else { ASN1Block::Set(soff, items) });
+    }
+    let (constructed, body) = match *b {
+        ASN1Block::Unknown(ASN1Class::ContextSpecific, constructed, _, ref t, ref body) if t.to_u64() == Some(tag) => {
+            (constructed, body.clone())
+        }
+        // A constructed body holding exactly one block reads as EXPLICIT.
+        ASN1Block::Explicit(ASN1Class::ContextSpecific, _, ref t, ref inner) if t.to_u64() == Some(tag) => {
+            (true, to_der(inner).ok()?)
+        }
+        _ => return None,
+    };
+    let mut der = vec![if constructed { universal | 0b0010_0000 } else { universal }];
+    der.extend(encode_length(body.len()));
+    der.extend(body);
+    let mut blocks = from_der_(&der, 0, source_depth(b)).ok()?;
+    if blocks.len() == 1 {
+        blocks.pop()
+    } else {
+        None
+    }
+}
+
+/// `block` re-tagged as `[tag] IMPLICIT`.
+pub(crate) fn implicit(tag: u64, block: &ASN1Block) -> Result<ASN1Block, ASN1EncodeErr> {
+    let der = to_der(block)?;
+    let constructed = der[0] & 0b0010_0000 != 0;
+    let header = if der[1] < 0x80 { 2 } else { 2 + (der[1] & 0x7f) as usize };
+    Ok(ASN1Block::Unknown(ASN1Class::ContextSpecific, constructed, 0, BigUint::from(tag), der[header..].to_vec()))
+}
+
+/// A named bit list, where bit 0 of `bits` is the first named bit.
+pub(crate) fn named_bits(bits: u16) -> ASN1Block {
+    // DER drops trailing zero bits from a named bit list.
+    let nbits = 16 - bits.leading_zeros() as usize;
+    if nbits == 0 {
+        // simple_asn1 writes an empty BIT STRING without its unused-bits
+        // octet.
+        return ASN1Block::Unknown(ASN1Class::Universal, false, 0, BigUint::from(3u8), vec![0]);
+    }
+    let bytes = bits.reverse_bits().to_be_bytes();
+    ASN1Block::BitString(0, nbits, bytes[..nbits.div_ceil(8)].to_vec())
+}
+
+/// The first `max` named bits of a BIT STRING, as packed by `named_bits`.
+pub(crate) fn named_bits_value(nbits: usize, bytes: &[u8], max: usize) -> u16 {
+    let mut bits = 0;
+    for i in 0..nbits.min(max) {
+        if bytes[i / 8] & (0x80 >> (i % 8)) != 0 {
+            bits |= 1 << i;
+        }
+    }
+    bits
+}
+
+/// An ENUMERATED, which simple_asn1 has no block type for. Only values
+/// below 128 are supported.
+pub(crate) fn enumerated(value: u8) -> ASN1Block {
+    ASN1Block::Unknown(ASN1Class::Universal, false, 0, BigUint::from(10u8), vec![value])
+}
+
+/// The value of an ENUMERATED written by `enumerated`.
+pub(crate) fn enumerated_value(b: &ASN1Block) -> Option<u8> {
+    match *b {
+        ASN1Block::Unknown(ASN1Class::Universal, false, _, ref tag, ref body) if *tag == BigUint::from(10u8) => {
+            match body[..] {
+                [value] if value < 0x80 => Some(value),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn encode_length(len: usize) -> Vec<u8> {
+    if len < 0x80 {
+        return vec![len as u8];
+    }
+    let bytes: Vec<u8> = len.to_be_bytes().iter().cloned().skip_while(|&b| b == 0).collect();
+    let mut res = vec![0x80 | bytes.len() as u8];
+    res.extend(bytes);
+    res
+}
+
+#[cfg(all(test, feature = "verify"))]
+use sign::Signer;
+
+/// `signed`, the DER of a signed structure such as a certificate, with a
+/// redundant leading zero octet, which DER forbids, on the first INTEGER
+/// of its to-be-signed part. It is signed again by `signer`, or keeps its
+/// old signature without one.
+#[cfg(all(test, feature = "verify"))]
+pub(crate) fn pad_first_integer(signed: &[u8], signer: Option<&dyn Signer>) -> Vec<u8> {
+    fn pad(b: &mut ASN1Block) -> bool {
+        let body = match *b {
+            ASN1Block::Integer(_, ref n) => [vec![0], n.to_signed_bytes_be()].concat(),
+            ASN1Block::Sequence(_, ref mut items) | ASN1Block::Set(_, ref mut items) => {
+                return items.iter_mut().any(pad)
+            }
+            ASN1Block::Explicit(_, _, _, ref mut inner) => return pad(inner),
+            _ => return false,
+        };
+        *b = ASN1Block::Unknown(ASN1Class::Universal, false, 0, BigUint::from(2u8), body);
+        true
+    }
+
+    let mut blocks = from_der(signed).unwrap();
+    let items = match blocks[0] {
+        ASN1Block::Sequence(_, ref mut items) => items,
+        _ => panic!("Not a sequence"),
+    };
+    assert!(pad(&mut items[0]));
+    if let Some(signer) = signer {
+        let signature = signer.sign(&to_der(&items[0]).unwrap()).unwrap();
+        items[2] = ASN1Block::BitString(0, signature.len() * 8, signature);
+    }
+    to_der(&blocks[0]).unwrap()
+}
+
+#[cfg(test)]
+mod der_tests {
+    use num::bigint::BigUint;
+    use simple_asn1::{ASN1Block, ASN1Class, ASN1DecodeErr};
+    use time::{Date, Month, PrimitiveDateTime, Time};
+
+    use super::{decode_with, from_der, implicit_inner, source_depth};
+
+    #[test]
+    fn empty_sequence_decodes() {
+        let actual = from_der(&[0x30, 0x00]).unwrap();
+        assert_eq!(vec![ASN1Block::Sequence(0, vec![])], actual);
+    }
+
+    #[test]
+    fn nested_empty_set_decodes() {
+        let actual = from_der(&[0x30, 0x02, 0x31, 0x00]).unwrap();
+        assert_eq!(vec![ASN1Block::Sequence(0, vec![ASN1Block::Set(0, vec![])])], actual);
+    }
+
+    #[test]
+    fn bmp_string_decodes_as_ucs2() {
+        let actual = from_der(&[0x1E, 0x04, 0x00, 0x41, 0x04, 0x1F]).unwrap();
+        assert_eq!(vec![ASN1Block::BMPString(0, "A\u{41F}".to_string())], actual);
+    }
+
+    #[test]
+    fn boolean_must_be_der() {
+        assert_eq!(vec![ASN1Block::Boolean(0, true)], from_der(&[0x01, 0x01, 0xFF]).unwrap());
+        assert_eq!(vec![ASN1Block::Boolean(0, false)], from_der(&[0x01, 0x01, 0x00]).unwrap());
+        assert_eq!(Err(ASN1DecodeErr::Incomplete), from_der(&[0x01, 0x01, 0x01]));
+    }
+
+    // `depth` blocks tagged `tag`, each holding the next, around `inner`.
+    fn nested(depth: usize, tag: u8, inner: Vec<u8>) -> Vec<u8> {
+        (0..depth).fold(inner, |inner, _| {
+            let mut outer = vec![tag, 0x84];
+            outer.extend((inner.len() as u32).to_be_bytes());
+            outer.extend(inner);
+            outer
+        })
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected() {
+        let nested = |depth, tag| nested(depth, tag, vec![0x05, 0x00]);
+        assert!(from_der(&nested(64, 0x30)).is_ok());
+        assert_eq!(Err(ASN1DecodeErr::Overflow), from_der(&nested(65, 0x30)));
+        assert_eq!(Err(ASN1DecodeErr::Overflow), from_der(&nested(65, 0xA0)));
+        assert_eq!(Err(ASN1DecodeErr::Overflow), from_der(&nested(1000, 0x30)));
+    }
+
+    #[test]
+    fn implicit_inner_reads_at_source_depth() {
+        // An [0] IMPLICIT SEQUENCE of two members, 60 SEQUENCEs deep, whose
+        // second member nests to the greatest depth allowed.
+        let body = [vec![0x05, 0x00], nested(3, 0x30, vec![0x05, 0x00])].concat();
+        let der = nested(60, 0x30, nested(1, 0xA0, body));
+        let implicit_at = |blocks: &[ASN1Block]| {
+            let mut b = &blocks[0];
+            while let ASN1Block::Sequence(_, ref items) = *b {
+                b = &items[0];
+            }
+            (source_depth(b), implicit_inner(b, 0, 0x10).map(|inner| inner.offset()))
+        };
+        let outside = implicit_at(&from_der(&der).unwrap());
+        let inside = decode_with(&der, |blocks| Ok::<_, ASN1DecodeErr>(implicit_at(blocks))).unwrap();
+        assert_eq!((0, Some(60 * 6)), outside);
+        assert_eq!((60, Some(60 * 6)), inside);
+
+        // A block from another input is not placed in this one.
+        let other = from_der(&nested(1, 0xA0, vec![0x05, 0x00, 0x05, 0x00])).unwrap();
+        let depth = decode_with(&der, |_| Ok::<_, ASN1DecodeErr>(source_depth(&other[0]))).unwrap();
+        assert_eq!(0, depth);
+    }
+
+    #[test]
+    fn context_specific_single_child_is_explicit() {
+        let actual = from_der(&[0xA0, 0x03, 0x02, 0x01, 0x02]).unwrap();
+        match actual[0] {
+            ASN1Block::Explicit(ASN1Class::ContextSpecific, 0, ref tag, ref inner) => {
+                assert_eq!(&BigUint::from(0u8), tag);
+                assert_eq!(ASN1Block::Integer(0, 2.into()), **inner);
+            }
+            _ => panic!("Not an explicit tag"),
+        }
+    }
+
+    #[test]
+    fn utc_time_applies_two_digit_year_window() {
+        let actual = from_der(b"\x17\x0D491231235959Z").unwrap();
+        let expected = PrimitiveDateTime::new(
+            Date::from_calendar_date(2049, Month::December, 31).unwrap(),
+            Time::from_hms(23, 59, 59).unwrap(),
+        );
+        assert_eq!(vec![ASN1Block::UTCTime(0, expected)], actual);
+
+        let actual = from_der(b"\x17\x0D500101000000Z").unwrap();
+        let expected = PrimitiveDateTime::new(
+            Date::from_calendar_date(1950, Month::January, 1).unwrap(),
+            Time::MIDNIGHT,
+        );
+        assert_eq!(vec![ASN1Block::UTCTime(0, expected)], actual);
+    }
+
+    #[test]
+    fn indefinite_length_is_rejected() {
+        let error = from_der(&[0x30, 0x80, 0x00, 0x00]).unwrap_err();
+        assert_eq!(ASN1DecodeErr::Incomplete, error);
+    }
+
+    #[test]
+    fn truncated_input_is_rejected() {
+        let error = from_der(&[0x30, 0x05, 0x02, 0x01]).unwrap_err();
+        assert_eq!(ASN1DecodeErr::Incomplete, error);
+    }
+}
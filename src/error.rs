@@ -0,0 +1,57 @@
+use std::error::Error;
+use std::fmt;
+
+use num::bigint::BigInt;
+use simple_asn1::ASN1DecodeErr;
+
+use oid::ObjectIdentifier;
+use pem::PemDecodeErr;
+
+/// An error that can arise decoding X.509 structures from ASN.1 blocks.
+#[derive(Clone, Debug, PartialEq)]
+pub enum X509DecodeErr {
+    /// The underlying DER could not be decoded into ASN.1 blocks.
+    ASN1Error(ASN1DecodeErr),
+    /// The PEM armor around the DER could not be read.
+    PemError(PemDecodeErr),
+    /// The input ended before the named field was found.
+    MissingField(&'static str),
+    /// The named field was present but had the wrong type or contents.
+    InvalidField(&'static str),
+    /// The structure's version is not one this crate supports, such as a
+    /// certificate version other than v1, v2 or v3.
+    UnsupportedVersion(BigInt),
+    /// The named structure was followed by blocks it does not define.
+    TrailingData(&'static str),
+    /// A critical extension has no type to decode it with, so the
+    /// certificate must not be relied on (RFC 5280 §4.2).
+    UnhandledCriticalExtension(ObjectIdentifier),
+}
+
+impl From<ASN1DecodeErr> for X509DecodeErr {
+    fn from(e: ASN1DecodeErr) -> X509DecodeErr {
+        X509DecodeErr::ASN1Error(e)
+    }
+}
+
+impl From<PemDecodeErr> for X509DecodeErr {
+    fn from(e: PemDecodeErr) -> X509DecodeErr {
+        X509DecodeErr::PemError(e)
+    }
+}
+
+impl fmt::Display for X509DecodeErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            X509DecodeErr::ASN1Error(ref e) => write!(f, "ASN.1 decoding failed: {}", e),
+            X509DecodeErr::PemError(ref e) => write!(f, "PEM decoding failed: {}", e),
+            X509DecodeErr::MissingField(field) => write!(f, "missing field: {}", field),
+            X509DecodeErr::InvalidField(field) => write!(f, "invalid field: {}", field),
+            X509DecodeErr::UnsupportedVersion(ref v) => write!(f, "unsupported version: {}", v),
+            X509DecodeErr::TrailingData(field) => write!(f, "unexpected data after {}", field),
+            X509DecodeErr::UnhandledCriticalExtension(ref oid) => write!(f, "unhandled critical extension: {}", oid),
+        }
+    }
+}
+
+impl Error for X509DecodeErr {}
@@ -0,0 +1,410 @@
+use std::any::Any;
+
+use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{expect_end, from_der, next_block, sequence};
+use error::X509DecodeErr;
+use oid::ObjectIdentifier;
+
+mod alt_name;
+mod basic_constraints;
+mod certificate_policies;
+mod crl_entry;
+mod crl_number;
+mod distribution_points;
+mod ext_key_usage;
+mod info_access;
+mod key_identifier;
+mod key_usage;
+mod name_constraints;
+mod policy_constraints;
+mod policy_mappings;
+mod registry;
+mod subject_directory_attributes;
+
+pub use self::alt_name::{CertificateIssuer, IssuerAltName, SubjectAltName};
+pub use self::basic_constraints::BasicConstraints;
+pub use self::certificate_policies::{
+    CertificatePolicies, DisplayText, NoticeReference, PolicyInformation, PolicyQualifierInfo, UserNotice,
+};
+pub use self::crl_entry::{CRLReason, InvalidityDate};
+pub use self::crl_number::CRLNumber;
+pub use self::distribution_points::{
+    CRLDistributionPoints, DistributionPoint, DistributionPointName, FreshestCRL, IssuingDistributionPoint,
+    ReasonFlags,
+};
+pub use self::ext_key_usage::ExtendedKeyUsage;
+pub use self::info_access::{AccessDescription, AuthorityInfoAccess, SubjectInfoAccess};
+pub use self::key_identifier::{AuthorityKeyIdentifier, KeyIdentifierMethod, SubjectKeyIdentifier};
+pub use self::key_usage::KeyUsage;
+pub use self::name_constraints::{GeneralSubtree, NameConstraints};
+pub use self::policy_constraints::{InhibitAnyPolicy, PolicyConstraints};
+pub use self::policy_mappings::{PolicyMapping, PolicyMappings};
+pub use self::registry::ExtensionRegistry;
+pub use self::subject_directory_attributes::SubjectDirectoryAttributes;
+
+/// A specific extension type, which is carried DER-encoded in the
+/// extnValue of an `Extension` with the type's `OID`.
+pub trait X509Extension: ToASN1<Error = ASN1EncodeErr> + FromASN1<Error = X509DecodeErr> {
+    const OID: ObjectIdentifier;
+}
+
+/// A single certificate extension. `extn_value` holds the DER encoding of
+/// the extension's own ASN.1 type.
+///
+/// The value is only interpreted when decoded as a specific type, so an
+/// extension this crate knows nothing about is kept as decoded and encoded
+/// back byte for byte. An explicit `critical FALSE`, which DER leaves out
+/// but some issuers write anyway, is kept too; build one with `raw` or
+/// `new`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Extension {
+    pub extn_id: ObjectIdentifier,
+    pub critical: bool,
+    pub extn_value: Vec<u8>,
+    pub(crate) explicit_not_critical: bool,
+}
+
+impl Extension {
+    /// An extension from its parts, for one this crate has no type for.
+    /// `extn_value` is the DER encoding of the extension's value.
+    pub fn raw(extn_id: ObjectIdentifier, critical: bool, extn_value: Vec<u8>) -> Extension {
+        Extension { extn_id, critical, extn_value, explicit_not_critical: false }
+    }
+
+    /// Encode `value` as an extension of its type.
+    pub fn new<T: X509Extension>(value: &T, critical: bool) -> Result<Extension, ASN1EncodeErr> {
+        Ok(Extension::raw(T::OID, critical, der_encode(value)?))
+    }
+
+    /// Decode the extension value as `T`. This does not check `extn_id`.
+    pub fn decode<T: X509Extension>(&self) -> Result<T, X509DecodeErr> {
+        let blocks = from_der(&self.extn_value)?;
+        let (value, rest) = T::from_asn1(&blocks)?;
+        expect_end(rest, "extnValue")?;
+        Ok(value)
+    }
+
+    /// Whether this crate has a type for the extension.
+    pub fn is_supported(&self) -> bool {
+        registry::BUILTIN.iter().any(|(oid, _)| *oid == self.extn_id)
+    }
+}
+
+impl ToASN1 for Extension {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = vec![self.extn_id.to_block()];
+        // critical is DEFAULT FALSE, so DER leaves it out unless it's set
+        // or was decoded spelled out.
+        if self.critical || self.explicit_not_critical {
+            items.push(ASN1Block::Boolean(0, self.critical));
+        }
+        items.push(ASN1Block::OctetString(0, self.extn_value.clone()));
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for Extension {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "Extension")?;
+        let items = sequence(head, "Extension")?;
+        let (extn_id, rest) = next_block(items, "extnID")?;
+        let extn_id = ObjectIdentifier::from_block(extn_id, "extnID")?;
+        let (critical, explicit_not_critical, rest) = match rest.split_first() {
+            Some((&ASN1Block::Boolean(_, critical), rest)) => (critical, !critical, rest),
+            _ => (false, false, rest),
+        };
+        let (extn_value, rest) = match next_block(rest, "extnValue")? {
+            (ASN1Block::OctetString(_, bytes), rest) => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("extnValue")),
+        };
+        expect_end(rest, "Extension")?;
+        Ok((Extension { extn_id, critical, extn_value, explicit_not_critical }, tail))
+    }
+}
+
+/// The extensions of a certificate, in encoding order.
+///
+/// Decoding rejects a list that holds the same extension twice, which
+/// RFC 5280 §4.2 forbids.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Extensions(pub Vec<Extension>);
+
+impl Extensions {
+    pub fn new() -> Extensions {
+        Extensions(Vec::new())
+    }
+
+    pub fn iter(&self) -> ::std::slice::Iter<'_, Extension> {
+        self.0.iter()
+    }
+
+    /// The extension with the given OID, if present.
+    pub fn find(&self, extn_id: &ObjectIdentifier) -> Option<&Extension> {
+        self.0.iter().find(|e| e.extn_id == *extn_id)
+    }
+
+    /// The extension of type `T`, decoded, if present.
+    pub fn get<T: X509Extension>(&self) -> Result<Option<T>, X509DecodeErr> {
+        match self.find(&T::OID) {
+            Some(extension) => extension.decode().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// The extensions this crate has no type for, in encoding order.
+    pub fn unsupported(&self) -> impl Iterator<Item = &Extension> {
+        self.0.iter().filter(|e| !e.is_supported())
+    }
+
+    /// The extension with the given OID, decoded as the type `registry`
+    /// has for it. `None` if it is absent or no type is registered.
+    pub fn get_registered(
+        &self,
+        extn_id: &ObjectIdentifier,
+        registry: &ExtensionRegistry,
+    ) -> Result<Option<Box<dyn Any>>, X509DecodeErr> {
+        match self.find(extn_id).and_then(|e| registry.decode(e)) {
+            Some(value) => value.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// The critical extensions `registry` has no type for, which a relying
+    /// party must not ignore.
+    pub fn unhandled_critical<'a>(&'a self, registry: &'a ExtensionRegistry) -> impl Iterator<Item = &'a Extension> {
+        self.0.iter().filter(move |e| e.critical && !registry.is_registered(&e.extn_id))
+    }
+
+    /// Add `value`, replacing any extension of the same type.
+    pub fn insert<T: X509Extension>(&mut self, value: &T, critical: bool) -> Result<(), ASN1EncodeErr> {
+        let extension = Extension::new(value, critical)?;
+        match self.0.iter_mut().find(|e| e.extn_id == T::OID) {
+            Some(existing) => *existing = extension,
+            None => self.0.push(extension),
+        }
+        Ok(())
+    }
+}
+
+impl ToASN1 for Extensions {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        for extension in &self.0 {
+            items.extend(extension.to_asn1_class(c)?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for Extensions {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "extensions")?;
+        let mut items = sequence(head, "extensions")?;
+        let mut extensions: Vec<Extension> = Vec::new();
+        while !items.is_empty() {
+            let (extension, rest) = Extension::from_asn1(items)?;
+            if extensions.iter().any(|e| e.extn_id == extension.extn_id) {
+                return Err(X509DecodeErr::InvalidField("extensions"));
+            }
+            extensions.push(extension);
+            items = rest;
+        }
+        Ok((Extensions(extensions), tail))
+    }
+}
+
+#[cfg(test)]
+mod extension_tests {
+    use simple_asn1::{der_encode, ASN1DecodeErr};
+
+    use super::Extension;
+    use der::der_decode;
+    use error::X509DecodeErr;
+
+    // basicConstraints, critical, cA TRUE
+    const CRITICAL_BASIC_CONSTRAINTS: &[u8] = &[
+        0x30, 0x0F, 0x06, 0x03, 0x55, 0x1D, 0x13, 0x01, 0x01, 0xFF, 0x04, 0x05, 0x30, 0x03, 0x01, 0x01,
+        0xFF,
+    ];
+
+    // subjectKeyIdentifier, not critical
+    const SUBJECT_KEY_IDENTIFIER: &[u8] = &[
+        0x30, 0x0B, 0x06, 0x03, 0x55, 0x1D, 0x0E, 0x04, 0x04, 0x04, 0x02, 0xAB, 0xCD,
+    ];
+
+    // subjectKeyIdentifier, critical FALSE spelled out
+    const EXPLICIT_NOT_CRITICAL: &[u8] = &[
+        0x30, 0x0E, 0x06, 0x03, 0x55, 0x1D, 0x0E, 0x01, 0x01, 0x00, 0x04, 0x04, 0x04, 0x02, 0xAB, 0xCD,
+    ];
+
+    #[test]
+    fn extension_decodes_critical_flag() {
+        let actual = der_decode::<Extension>(CRITICAL_BASIC_CONSTRAINTS).unwrap();
+        assert_eq!(oid!(2, 5, 29, 19), actual.extn_id);
+        assert!(actual.critical);
+        assert_eq!(vec![0x30, 0x03, 0x01, 0x01, 0xFF], actual.extn_value);
+    }
+
+    #[test]
+    fn extension_defaults_to_not_critical() {
+        let actual = der_decode::<Extension>(SUBJECT_KEY_IDENTIFIER).unwrap();
+        assert_eq!(oid!(2, 5, 29, 14), actual.extn_id);
+        assert!(!actual.critical);
+        assert_eq!(vec![0x04, 0x02, 0xAB, 0xCD], actual.extn_value);
+    }
+
+    #[test]
+    fn extension_round_trips() {
+        for input in &[CRITICAL_BASIC_CONSTRAINTS, SUBJECT_KEY_IDENTIFIER, EXPLICIT_NOT_CRITICAL] {
+            let decoded = der_decode::<Extension>(input).unwrap();
+            assert_eq!(input.to_vec(), der_encode(&decoded).unwrap());
+        }
+    }
+
+    #[test]
+    fn extension_keeps_explicit_not_critical() {
+        let actual = der_decode::<Extension>(EXPLICIT_NOT_CRITICAL).unwrap();
+        assert!(!actual.critical);
+        assert_ne!(der_decode::<Extension>(SUBJECT_KEY_IDENTIFIER).unwrap(), actual);
+    }
+
+    #[test]
+    fn extension_from_raw_parts() {
+        let extension = Extension::raw(oid!(2, 5, 29, 14), false, vec![0x04, 0x02, 0xAB, 0xCD]);
+        assert_eq!(der_decode::<Extension>(SUBJECT_KEY_IDENTIFIER).unwrap(), extension);
+        assert_eq!(SUBJECT_KEY_IDENTIFIER.to_vec(), der_encode(&extension).unwrap());
+    }
+
+    #[test]
+    fn extension_rejects_non_der_critical() {
+        let mut input = CRITICAL_BASIC_CONSTRAINTS.to_vec();
+        input[9] = 0x01;
+        assert_eq!(Err(X509DecodeErr::ASN1Error(ASN1DecodeErr::Incomplete)), der_decode::<Extension>(&input));
+    }
+
+    #[test]
+    fn extension_requires_value() {
+        let error = der_decode::<Extension>(&[0x30, 0x05, 0x06, 0x03, 0x55, 0x1D, 0x0E]).unwrap_err();
+        assert_eq!(X509DecodeErr::MissingField("extnValue"), error);
+    }
+}
+
+#[cfg(test)]
+mod extensions_tests {
+    use num::bigint::BigInt;
+    use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+    use super::{BasicConstraints, Extension, Extensions, X509Extension};
+    use der::{der_decode, next_block};
+    use error::X509DecodeErr;
+    use oid::ObjectIdentifier;
+    use x509::Certificate;
+
+    const EC_UNKNOWN_EXT: &[u8] = include_bytes!("../testdata/ec-unknown-ext.der");
+
+    // A private extension whose value is a bare INTEGER.
+    #[derive(Debug, PartialEq)]
+    struct Counter(BigInt);
+
+    impl ToASN1 for Counter {
+        type Error = ASN1EncodeErr;
+
+        fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+            Ok(vec![ASN1Block::Integer(0, self.0.clone())])
+        }
+    }
+
+    impl FromASN1 for Counter {
+        type Error = X509DecodeErr;
+
+        fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+            match next_block(v, "Counter")? {
+                (ASN1Block::Integer(_, n), rest) => Ok((Counter(n.clone()), rest)),
+                _ => Err(X509DecodeErr::InvalidField("Counter")),
+            }
+        }
+    }
+
+    impl X509Extension for Counter {
+        const OID: ObjectIdentifier = oid!(1, 3, 6, 1, 4, 1, 99999, 1);
+    }
+
+    #[test]
+    fn extensions_get_typed() {
+        let mut extensions = Extensions::new();
+        extensions.insert(&Counter(BigInt::from(5)), false).unwrap();
+        assert_eq!(Some(Counter(BigInt::from(5))), extensions.get::<Counter>().unwrap());
+        assert_eq!(vec![0x02, 0x01, 0x05], extensions.find(&Counter::OID).unwrap().extn_value);
+    }
+
+    #[test]
+    fn extensions_get_absent() {
+        assert_eq!(None, Extensions::new().get::<Counter>().unwrap());
+    }
+
+    #[test]
+    fn extensions_insert_replaces() {
+        let mut extensions = Extensions::new();
+        extensions.insert(&Counter(BigInt::from(5)), false).unwrap();
+        extensions.insert(&Counter(BigInt::from(6)), true).unwrap();
+        assert_eq!(1, extensions.0.len());
+        assert!(extensions.0[0].critical);
+        assert_eq!(Some(Counter(BigInt::from(6))), extensions.get::<Counter>().unwrap());
+    }
+
+    #[test]
+    fn extensions_get_rejects_trailing_data() {
+        let extension = Extension::raw(Counter::OID, false, vec![0x02, 0x01, 0x05, 0x05, 0x00]);
+        let extensions = Extensions(vec![extension]);
+        assert_eq!(Err(X509DecodeErr::TrailingData("extnValue")), extensions.get::<Counter>());
+    }
+
+    #[test]
+    fn extensions_round_trip() {
+        let mut extensions = Extensions::new();
+        extensions.insert(&Counter(BigInt::from(5)), true).unwrap();
+        let encoded = der_encode(&extensions).unwrap();
+        assert_eq!(extensions, der_decode::<Extensions>(&encoded).unwrap());
+    }
+
+    #[test]
+    fn extensions_reject_duplicates() {
+        let extension = Extension::new(&Counter(BigInt::from(5)), false).unwrap();
+        let encoded = der_encode(&Extensions(vec![extension.clone(), extension])).unwrap();
+        assert_eq!(Err(X509DecodeErr::InvalidField("extensions")), der_decode::<Extensions>(&encoded));
+    }
+
+    #[test]
+    fn extensions_keep_unsupported() {
+        let cert = der_decode::<Certificate>(EC_UNKNOWN_EXT).unwrap();
+        let extensions = cert.tbs_certificate.extensions.as_ref().unwrap();
+        let unsupported: Vec<_> = extensions.unsupported().collect();
+        assert_eq!(2, unsupported.len());
+        assert_eq!(oid!(1, 3, 6, 1, 4, 1, 99999, 2, 1), unsupported[0].extn_id);
+        assert!(unsupported[0].critical);
+        // Not valid DER, so only ever handled as raw bytes.
+        assert_eq!(vec![0xDE, 0xAD, 0xBE, 0xEF], unsupported[0].extn_value);
+        assert_eq!(oid!(1, 3, 6, 1, 4, 1, 99999, 2, 2), unsupported[1].extn_id);
+        assert!(!unsupported[1].critical);
+        assert_eq!(EC_UNKNOWN_EXT.to_vec(), der_encode(&cert).unwrap());
+    }
+
+    #[test]
+    fn extension_is_supported() {
+        let mut extensions = Extensions::new();
+        extensions.insert(&BasicConstraints::end_entity(), true).unwrap();
+        extensions.insert(&Counter(BigInt::from(5)), false).unwrap();
+        assert!(extensions.0[0].is_supported());
+        assert!(!extensions.0[1].is_supported());
+        assert_eq!(vec![&Counter::OID], extensions.unsupported().map(|e| &e.extn_id).collect::<Vec<_>>());
+    }
+}
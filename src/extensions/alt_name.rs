@@ -0,0 +1,111 @@
+use std::ops::Deref;
+
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use general_name::GeneralNames;
+use oid::ObjectIdentifier;
+use oids;
+
+/// The names the subject is also known by (RFC 5280 §4.2.1.6).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubjectAltName(pub GeneralNames);
+
+/// The names the issuer is also known by (RFC 5280 §4.2.1.7).
+#[derive(Clone, Debug, PartialEq)]
+pub struct IssuerAltName(pub GeneralNames);
+
+/// The issuer of the certificate a CRL entry revokes, in an indirect CRL
+/// (RFC 5280 §5.3.3). It also applies to the entries that follow, up to
+/// the next entry with this extension.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertificateIssuer(pub GeneralNames);
+
+macro_rules! alt_name {
+    ($name:ident, $oid:expr) => {
+        impl Deref for $name {
+            type Target = GeneralNames;
+
+            fn deref(&self) -> &GeneralNames {
+                &self.0
+            }
+        }
+
+        impl X509Extension for $name {
+            const OID: ObjectIdentifier = $oid;
+        }
+
+        impl ToASN1 for $name {
+            type Error = ASN1EncodeErr;
+
+            fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+                self.0.to_asn1_class(c)
+            }
+        }
+
+        impl FromASN1 for $name {
+            type Error = X509DecodeErr;
+
+            fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+                let (names, rest) = GeneralNames::from_asn1(v)?;
+                Ok(($name(names), rest))
+            }
+        }
+    };
+}
+
+alt_name!(SubjectAltName, oids::SUBJECT_ALT_NAME);
+alt_name!(IssuerAltName, oids::ISSUER_ALT_NAME);
+alt_name!(CertificateIssuer, oids::CERTIFICATE_ISSUER);
+
+#[cfg(test)]
+mod alt_name_tests {
+    use std::net::IpAddr;
+
+    use simple_asn1::{der_encode, ASN1Block};
+
+    use super::{IssuerAltName, SubjectAltName};
+    use der::der_decode;
+    use general_name::GeneralName;
+    use x509::Certificate;
+
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+
+    #[test]
+    fn subject_alt_name_from_certificate() {
+        let cert = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        let san = extensions.get::<SubjectAltName>().unwrap().unwrap();
+        assert_eq!(vec!["example.com", "*.example.com"], san.dns_names().collect::<Vec<_>>());
+        let ips: Vec<IpAddr> = vec!["192.0.2.1".parse().unwrap(), "2001:db8::1".parse().unwrap()];
+        assert_eq!(ips, san.ip_addrs().collect::<Vec<_>>());
+        assert_eq!(vec!["admin@example.com"], san.rfc822_names().collect::<Vec<_>>());
+        assert_eq!(vec!["https://example.com/"], san.uris().collect::<Vec<_>>());
+        let dir = san.directory_names().next().unwrap();
+        assert_eq!(Some("Directory Name"), dir.common_name());
+        assert!(san.iter().any(|n| *n == GeneralName::RegisteredID(oid!(1, 2, 3, 4))));
+        let upn = GeneralName::OtherName(
+            oid!(1, 3, 6, 1, 4, 1, 311, 20, 2, 3),
+            ASN1Block::UTF8String(0, "user@example.com".to_string()),
+        );
+        assert!(san.iter().any(|n| *n == upn));
+    }
+
+    #[test]
+    fn subject_alt_name_round_trips() {
+        let cert = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        let raw = extensions.find(&oid!(2, 5, 29, 17)).unwrap();
+        let san = raw.decode::<SubjectAltName>().unwrap();
+        assert_eq!(raw.extn_value, der_encode(&san).unwrap());
+    }
+
+    #[test]
+    fn issuer_alt_name_from_certificate() {
+        let cert = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        let ian = extensions.get::<IssuerAltName>().unwrap().unwrap();
+        assert_eq!(vec!["https://ca.example.com/"], ian.uris().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,116 @@
+use num::bigint::BigInt;
+use num::ToPrimitive;
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{expect_end, next_block, sequence};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use oid::ObjectIdentifier;
+use oids;
+
+/// Whether the subject is a CA, and how many intermediate CAs may follow
+/// it in a path (RFC 5280 §4.2.1.9).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BasicConstraints {
+    pub ca: bool,
+    pub path_len_constraint: Option<u64>,
+}
+
+impl BasicConstraints {
+    /// The constraints of a CA certificate.
+    pub fn ca(path_len_constraint: Option<u64>) -> BasicConstraints {
+        BasicConstraints { ca: true, path_len_constraint }
+    }
+
+    /// The constraints of an end-entity certificate.
+    pub fn end_entity() -> BasicConstraints {
+        BasicConstraints::default()
+    }
+}
+
+impl X509Extension for BasicConstraints {
+    const OID: ObjectIdentifier = oids::BASIC_CONSTRAINTS;
+}
+
+impl ToASN1 for BasicConstraints {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        // cA is DEFAULT FALSE, so DER leaves it out unless it's set.
+        if self.ca {
+            items.push(ASN1Block::Boolean(0, true));
+        }
+        if let Some(path_len) = self.path_len_constraint {
+            items.push(ASN1Block::Integer(0, BigInt::from(path_len)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for BasicConstraints {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "BasicConstraints")?;
+        let items = sequence(head, "BasicConstraints")?;
+        let (ca, rest) = match items.split_first() {
+            Some((&ASN1Block::Boolean(_, ca), rest)) => (ca, rest),
+            _ => (false, items),
+        };
+        let (path_len_constraint, rest) = match rest.split_first() {
+            Some((ASN1Block::Integer(_, n), rest)) => match n.to_u64() {
+                Some(n) => (Some(n), rest),
+                None => return Err(X509DecodeErr::InvalidField("pathLenConstraint")),
+            },
+            _ => (None, rest),
+        };
+        expect_end(rest, "BasicConstraints")?;
+        Ok((BasicConstraints { ca, path_len_constraint }, tail))
+    }
+}
+
+#[cfg(test)]
+mod basic_constraints_tests {
+    use simple_asn1::der_encode;
+
+    use super::BasicConstraints;
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../../testdata/ec-root.der");
+
+    macro_rules! basic_constraints_test {
+        ($name:ident, $der:expr, $expected:expr) => {
+            #[test]
+            fn $name() {
+                let der: &[u8] = $der;
+                let expected: BasicConstraints = $expected;
+                assert_eq!(expected, der_decode::<BasicConstraints>(der).unwrap());
+                assert_eq!(der.to_vec(), der_encode(&expected).unwrap());
+            }
+        };
+    }
+
+    basic_constraints_test!(basic_constraints_end_entity, &[0x30, 0x00], BasicConstraints::end_entity());
+    basic_constraints_test!(basic_constraints_ca, &[0x30, 0x03, 0x01, 0x01, 0xFF], BasicConstraints::ca(None));
+    basic_constraints_test!(
+        basic_constraints_ca_path_len_0,
+        &[0x30, 0x06, 0x01, 0x01, 0xFF, 0x02, 0x01, 0x00],
+        BasicConstraints::ca(Some(0))
+    );
+
+    #[test]
+    fn basic_constraints_rejects_negative_path_len() {
+        let error = der_decode::<BasicConstraints>(&[0x30, 0x06, 0x01, 0x01, 0xFF, 0x02, 0x01, 0xFF]).unwrap_err();
+        assert_eq!(X509DecodeErr::InvalidField("pathLenConstraint"), error);
+    }
+
+    #[test]
+    fn basic_constraints_from_certificate() {
+        let cert = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        assert_eq!(Some(BasicConstraints::ca(None)), extensions.get::<BasicConstraints>().unwrap());
+    }
+}
@@ -0,0 +1,348 @@
+use num::bigint::{BigInt, BigUint};
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{expect_end, next_block, sequence};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use oid::ObjectIdentifier;
+use oids;
+
+/// The string types a UserNotice may be written in (RFC 5280 §4.2.1.4).
+#[derive(Clone, Debug, PartialEq)]
+pub enum DisplayText {
+    IA5String(String),
+    VisibleString(String),
+    BMPString(String),
+    UTF8String(String),
+}
+
+impl DisplayText {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            DisplayText::IA5String(ref s)
+            | DisplayText::VisibleString(ref s)
+            | DisplayText::BMPString(ref s)
+            | DisplayText::UTF8String(ref s) => s,
+        }
+    }
+
+    fn from_block(b: &ASN1Block, field: &'static str) -> Result<DisplayText, X509DecodeErr> {
+        match *b {
+            ASN1Block::IA5String(_, ref s) => Ok(DisplayText::IA5String(s.clone())),
+            ASN1Block::BMPString(_, ref s) => Ok(DisplayText::BMPString(s.clone())),
+            ASN1Block::UTF8String(_, ref s) => Ok(DisplayText::UTF8String(s.clone())),
+            ASN1Block::Unknown(ASN1Class::Universal, false, _, ref tag, ref body)
+                if *tag == BigUint::from(0x1Au8) && body.is_ascii() =>
+            {
+                Ok(DisplayText::VisibleString(body.iter().map(|&c| c as char).collect()))
+            }
+            _ => Err(X509DecodeErr::InvalidField(field)),
+        }
+    }
+
+    // simple_asn1 has no VisibleString, and writes BMPString out as UTF-8,
+    // so those are encoded by hand.
+    fn to_block(&self) -> ASN1Block {
+        let raw = |tag: u8, body: Vec<u8>| ASN1Block::Unknown(ASN1Class::Universal, false, 0, BigUint::from(tag), body);
+        match *self {
+            DisplayText::IA5String(ref s) => ASN1Block::IA5String(0, s.clone()),
+            DisplayText::VisibleString(ref s) => raw(0x1A, s.as_bytes().to_vec()),
+            DisplayText::BMPString(ref s) => {
+                raw(0x1E, s.encode_utf16().flat_map(|u| vec![(u >> 8) as u8, u as u8]).collect())
+            }
+            DisplayText::UTF8String(ref s) => ASN1Block::UTF8String(0, s.clone()),
+        }
+    }
+}
+
+/// A reference to a numbered notice published by an organization.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoticeReference {
+    pub organization: DisplayText,
+    pub notice_numbers: Vec<BigInt>,
+}
+
+/// Text to show relying parties when the certificate is used.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UserNotice {
+    pub notice_ref: Option<NoticeReference>,
+    pub explicit_text: Option<DisplayText>,
+}
+
+impl UserNotice {
+    fn to_block(&self) -> ASN1Block {
+        let mut items = Vec::new();
+        if let Some(ref notice_ref) = self.notice_ref {
+            let numbers = notice_ref.notice_numbers.iter().map(|n| ASN1Block::Integer(0, n.clone())).collect();
+            items.push(ASN1Block::Sequence(0, vec![notice_ref.organization.to_block(), ASN1Block::Sequence(0, numbers)]));
+        }
+        if let Some(ref text) = self.explicit_text {
+            items.push(text.to_block());
+        }
+        ASN1Block::Sequence(0, items)
+    }
+
+    fn from_block(b: &ASN1Block) -> Result<UserNotice, X509DecodeErr> {
+        let items = sequence(b, "UserNotice")?;
+        let mut notice = UserNotice::default();
+        let mut rest = items;
+        if let Some((ASN1Block::Sequence(_, reference), remaining)) = rest.split_first() {
+            let (organization, numbers) = match **reference {
+                [ref organization, ASN1Block::Sequence(_, ref numbers)] => (organization, numbers),
+                _ => return Err(X509DecodeErr::InvalidField("noticeRef")),
+            };
+            let notice_numbers = numbers
+                .iter()
+                .map(|n| match *n {
+                    ASN1Block::Integer(_, ref n) => Ok(n.clone()),
+                    _ => Err(X509DecodeErr::InvalidField("noticeNumbers")),
+                })
+                .collect::<Result<_, _>>()?;
+            let organization = DisplayText::from_block(organization, "organization")?;
+            notice.notice_ref = Some(NoticeReference { organization, notice_numbers });
+            rest = remaining;
+        }
+        if let Some((text, remaining)) = rest.split_first() {
+            notice.explicit_text = Some(DisplayText::from_block(text, "explicitText")?);
+            rest = remaining;
+        }
+        expect_end(rest, "UserNotice")?;
+        Ok(notice)
+    }
+}
+
+/// Additional information qualifying a certificate policy.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PolicyQualifierInfo {
+    /// A pointer to the Certification Practice Statement.
+    CPS(String),
+    UserNotice(UserNotice),
+    /// A qualifier of any other type, kept as the block it was decoded
+    /// from.
+    Other(ObjectIdentifier, ASN1Block),
+}
+
+impl ToASN1 for PolicyQualifierInfo {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let (id, qualifier) = match *self {
+            PolicyQualifierInfo::CPS(ref uri) => (oids::QT_CPS, ASN1Block::IA5String(0, uri.clone())),
+            PolicyQualifierInfo::UserNotice(ref notice) => (oids::QT_UNOTICE, notice.to_block()),
+            PolicyQualifierInfo::Other(ref id, ref qualifier) => (id.clone(), qualifier.clone()),
+        };
+        Ok(vec![ASN1Block::Sequence(0, vec![id.to_block(), qualifier])])
+    }
+}
+
+impl FromASN1 for PolicyQualifierInfo {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "PolicyQualifierInfo")?;
+        let items = sequence(head, "PolicyQualifierInfo")?;
+        let (id, rest) = next_block(items, "policyQualifierId")?;
+        let id = ObjectIdentifier::from_block(id, "policyQualifierId")?;
+        let (qualifier, rest) = next_block(rest, "qualifier")?;
+        expect_end(rest, "PolicyQualifierInfo")?;
+        let info = if id == oids::QT_CPS {
+            match *qualifier {
+                ASN1Block::IA5String(_, ref uri) => PolicyQualifierInfo::CPS(uri.clone()),
+                _ => return Err(X509DecodeErr::InvalidField("qualifier")),
+            }
+        } else if id == oids::QT_UNOTICE {
+            PolicyQualifierInfo::UserNotice(UserNotice::from_block(qualifier)?)
+        } else {
+            PolicyQualifierInfo::Other(id, qualifier.clone())
+        };
+        Ok((info, tail))
+    }
+}
+
+/// A policy the certificate was issued under, with any qualifiers. An
+/// empty `policy_qualifiers` is left out of the encoding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolicyInformation {
+    pub policy_identifier: ObjectIdentifier,
+    pub policy_qualifiers: Vec<PolicyQualifierInfo>,
+}
+
+impl PolicyInformation {
+    pub fn new(policy_identifier: ObjectIdentifier) -> PolicyInformation {
+        PolicyInformation { policy_identifier, policy_qualifiers: Vec::new() }
+    }
+
+    /// The CPS URIs among the qualifiers.
+    pub fn cps_uris(&self) -> impl Iterator<Item = &str> {
+        self.policy_qualifiers.iter().filter_map(|q| match *q {
+            PolicyQualifierInfo::CPS(ref uri) => Some(uri.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl ToASN1 for PolicyInformation {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = vec![self.policy_identifier.to_block()];
+        if !self.policy_qualifiers.is_empty() {
+            let mut qualifiers = Vec::new();
+            for qualifier in &self.policy_qualifiers {
+                qualifiers.extend(qualifier.to_asn1_class(c)?);
+            }
+            items.push(ASN1Block::Sequence(0, qualifiers));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for PolicyInformation {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "PolicyInformation")?;
+        let items = sequence(head, "PolicyInformation")?;
+        let (policy_identifier, rest) = next_block(items, "policyIdentifier")?;
+        let policy_identifier = ObjectIdentifier::from_block(policy_identifier, "policyIdentifier")?;
+        let mut policy_qualifiers = Vec::new();
+        let rest = match rest.split_first() {
+            Some((qualifiers, rest)) => {
+                let mut qualifiers = sequence(qualifiers, "policyQualifiers")?;
+                // SIZE (1..MAX)
+                if qualifiers.is_empty() {
+                    return Err(X509DecodeErr::InvalidField("policyQualifiers"));
+                }
+                while !qualifiers.is_empty() {
+                    let (qualifier, remaining) = PolicyQualifierInfo::from_asn1(qualifiers)?;
+                    policy_qualifiers.push(qualifier);
+                    qualifiers = remaining;
+                }
+                rest
+            }
+            None => rest,
+        };
+        expect_end(rest, "PolicyInformation")?;
+        Ok((PolicyInformation { policy_identifier, policy_qualifiers }, tail))
+    }
+}
+
+/// The policies a certificate was issued under (RFC 5280 §4.2.1.4).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertificatePolicies(pub Vec<PolicyInformation>);
+
+impl CertificatePolicies {
+    pub fn policy_identifiers(&self) -> impl Iterator<Item = &ObjectIdentifier> {
+        self.0.iter().map(|p| &p.policy_identifier)
+    }
+
+    /// The information for the given policy, if it is listed.
+    pub fn get(&self, policy_identifier: &ObjectIdentifier) -> Option<&PolicyInformation> {
+        self.0.iter().find(|p| p.policy_identifier == *policy_identifier)
+    }
+}
+
+impl X509Extension for CertificatePolicies {
+    const OID: ObjectIdentifier = oids::CERTIFICATE_POLICIES;
+}
+
+impl ToASN1 for CertificatePolicies {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        for policy in &self.0 {
+            items.extend(policy.to_asn1_class(c)?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for CertificatePolicies {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "certificatePolicies")?;
+        let mut items = sequence(head, "certificatePolicies")?;
+        // SIZE (1..MAX)
+        if items.is_empty() {
+            return Err(X509DecodeErr::InvalidField("certificatePolicies"));
+        }
+        let mut policies: Vec<PolicyInformation> = Vec::new();
+        while !items.is_empty() {
+            let (policy, rest) = PolicyInformation::from_asn1(items)?;
+            // A policy OID must not appear more than once.
+            if policies.iter().any(|p| p.policy_identifier == policy.policy_identifier) {
+                return Err(X509DecodeErr::InvalidField("certificatePolicies"));
+            }
+            policies.push(policy);
+            items = rest;
+        }
+        Ok((CertificatePolicies(policies), tail))
+    }
+}
+
+#[cfg(test)]
+mod certificate_policies_tests {
+    use num::bigint::BigInt;
+    use simple_asn1::der_encode;
+
+    use super::{CertificatePolicies, DisplayText, NoticeReference, PolicyInformation, PolicyQualifierInfo, UserNotice};
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use oids;
+    use x509::Certificate;
+
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+
+    #[test]
+    fn certificate_policies_from_certificate() {
+        let cert = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let policies = cert.extension::<CertificatePolicies>().unwrap().unwrap();
+        let private = oid!(1, 3, 6, 1, 4, 1, 99999, 1, 1);
+        assert_eq!(
+            vec![&oids::CABF_DOMAIN_VALIDATED, &private],
+            policies.policy_identifiers().collect::<Vec<_>>()
+        );
+        assert!(policies.get(&oids::CABF_DOMAIN_VALIDATED).unwrap().policy_qualifiers.is_empty());
+        let policy = policies.get(&private).unwrap();
+        assert_eq!(vec!["https://example.com/cps"], policy.cps_uris().collect::<Vec<_>>());
+        let notice = UserNotice { notice_ref: None, explicit_text: Some(DisplayText::VisibleString("Test policy".to_string())) };
+        assert_eq!(PolicyQualifierInfo::UserNotice(notice), policy.policy_qualifiers[1]);
+    }
+
+    #[test]
+    fn certificate_policies_round_trip() {
+        let cert = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        let raw = extensions.find(&oids::CERTIFICATE_POLICIES).unwrap();
+        let policies = raw.decode::<CertificatePolicies>().unwrap();
+        assert_eq!(raw.extn_value, der_encode(&policies).unwrap());
+    }
+
+    #[test]
+    fn certificate_policies_notice_reference_round_trips() {
+        let notice = UserNotice {
+            notice_ref: Some(NoticeReference {
+                organization: DisplayText::VisibleString("QuickLime".to_string()),
+                notice_numbers: vec![BigInt::from(1), BigInt::from(2)],
+            }),
+            explicit_text: Some(DisplayText::BMPString("caf\u{e9}".to_string())),
+        };
+        let mut policy = PolicyInformation::new(oids::ANY_POLICY);
+        policy.policy_qualifiers.push(PolicyQualifierInfo::UserNotice(notice));
+        let policies = CertificatePolicies(vec![policy]);
+        let encoded = der_encode(&policies).unwrap();
+        assert_eq!(policies, der_decode::<CertificatePolicies>(&encoded).unwrap());
+    }
+
+    #[test]
+    fn certificate_policies_rejects_duplicates() {
+        let policy = PolicyInformation::new(oids::ANY_POLICY);
+        let encoded = der_encode(&CertificatePolicies(vec![policy.clone(), policy])).unwrap();
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("certificatePolicies")),
+            der_decode::<CertificatePolicies>(&encoded)
+        );
+    }
+}
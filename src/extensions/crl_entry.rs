@@ -0,0 +1,159 @@
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+use time::PrimitiveDateTime;
+
+use der::{enumerated, enumerated_value, next_block};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use oid::ObjectIdentifier;
+use oids;
+
+/// Why a certificate was revoked (RFC 5280 §5.3.1).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CRLReason {
+    Unspecified,
+    KeyCompromise,
+    CACompromise,
+    AffiliationChanged,
+    Superseded,
+    CessationOfOperation,
+    CertificateHold,
+    /// Used in delta CRLs to take a certificate that was on hold off the
+    /// base CRL.
+    RemoveFromCRL,
+    PrivilegeWithdrawn,
+    AACompromise,
+}
+
+impl CRLReason {
+    fn value(self) -> u8 {
+        match self {
+            CRLReason::Unspecified => 0,
+            CRLReason::KeyCompromise => 1,
+            CRLReason::CACompromise => 2,
+            CRLReason::AffiliationChanged => 3,
+            CRLReason::Superseded => 4,
+            CRLReason::CessationOfOperation => 5,
+            CRLReason::CertificateHold => 6,
+            // 7 is not used.
+            CRLReason::RemoveFromCRL => 8,
+            CRLReason::PrivilegeWithdrawn => 9,
+            CRLReason::AACompromise => 10,
+        }
+    }
+
+    fn from_value(value: u8) -> Option<CRLReason> {
+        match value {
+            0 => Some(CRLReason::Unspecified),
+            1 => Some(CRLReason::KeyCompromise),
+            2 => Some(CRLReason::CACompromise),
+            3 => Some(CRLReason::AffiliationChanged),
+            4 => Some(CRLReason::Superseded),
+            5 => Some(CRLReason::CessationOfOperation),
+            6 => Some(CRLReason::CertificateHold),
+            8 => Some(CRLReason::RemoveFromCRL),
+            9 => Some(CRLReason::PrivilegeWithdrawn),
+            10 => Some(CRLReason::AACompromise),
+            _ => None,
+        }
+    }
+}
+
+impl X509Extension for CRLReason {
+    const OID: ObjectIdentifier = oids::CRL_REASON;
+}
+
+impl ToASN1 for CRLReason {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![enumerated(self.value())])
+    }
+}
+
+impl FromASN1 for CRLReason {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "CRLReason")?;
+        match enumerated_value(head).and_then(CRLReason::from_value) {
+            Some(reason) => Ok((reason, tail)),
+            None => Err(X509DecodeErr::InvalidField("CRLReason")),
+        }
+    }
+}
+
+/// When the private key is known or suspected to have been compromised,
+/// or the certificate otherwise became invalid, which may be before it
+/// was revoked (RFC 5280 §5.3.2).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidityDate(pub PrimitiveDateTime);
+
+impl X509Extension for InvalidityDate {
+    const OID: ObjectIdentifier = oids::INVALIDITY_DATE;
+}
+
+impl ToASN1 for InvalidityDate {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![ASN1Block::GeneralizedTime(0, self.0)])
+    }
+}
+
+impl FromASN1 for InvalidityDate {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        match next_block(v, "InvalidityDate")? {
+            (&ASN1Block::GeneralizedTime(_, t), tail) => Ok((InvalidityDate(t), tail)),
+            _ => Err(X509DecodeErr::InvalidField("InvalidityDate")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod crl_entry_tests {
+    use simple_asn1::der_encode;
+    use time::{Date, Month, PrimitiveDateTime, Time};
+
+    use super::{CRLReason, InvalidityDate};
+    use der::der_decode;
+    use error::X509DecodeErr;
+
+    #[test]
+    fn crl_reason_round_trips() {
+        for value in (0..=10).filter(|&v| v != 7) {
+            let der = [0x0A, 0x01, value];
+            let reason = der_decode::<CRLReason>(&der).unwrap();
+            assert_eq!(der.to_vec(), der_encode(&reason).unwrap());
+        }
+        assert_eq!(CRLReason::KeyCompromise, der_decode(&[0x0A, 0x01, 0x01]).unwrap());
+        assert_eq!(CRLReason::AACompromise, der_decode(&[0x0A, 0x01, 0x0A]).unwrap());
+    }
+
+    #[test]
+    fn crl_reason_rejects_unused_values() {
+        for der in &[[0x0A, 0x01, 0x07], [0x0A, 0x01, 0x0B], [0x02, 0x01, 0x01]] {
+            assert_eq!(Err(X509DecodeErr::InvalidField("CRLReason")), der_decode::<CRLReason>(der));
+        }
+    }
+
+    #[test]
+    fn invalidity_date_round_trips() {
+        // 20261001000000Z
+        let der = [
+            0x18, 0x0F, 0x32, 0x30, 0x32, 0x36, 0x31, 0x30, 0x30, 0x31, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x5A,
+        ];
+        let date = Date::from_calendar_date(2026, Month::October, 1).unwrap();
+        let expected = InvalidityDate(PrimitiveDateTime::new(date, Time::MIDNIGHT));
+        assert_eq!(expected, der_decode::<InvalidityDate>(&der).unwrap());
+        assert_eq!(der.to_vec(), der_encode(&expected).unwrap());
+    }
+
+    #[test]
+    fn invalidity_date_requires_generalized_time() {
+        // 261001000000Z
+        let der = [0x17, 0x0D, 0x32, 0x36, 0x31, 0x30, 0x30, 0x31, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x5A];
+        assert_eq!(Err(X509DecodeErr::InvalidField("InvalidityDate")), der_decode::<InvalidityDate>(&der));
+    }
+}
@@ -0,0 +1,61 @@
+use num::bigint::{BigInt, BigUint, Sign};
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::next_block;
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use oid::ObjectIdentifier;
+use oids;
+
+/// A CRL's sequence number, which increases with each CRL its issuer
+/// publishes for a given scope (RFC 5280 §5.2.3). It may be up to 20
+/// octets long.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CRLNumber(pub BigUint);
+
+impl X509Extension for CRLNumber {
+    const OID: ObjectIdentifier = oids::CRL_NUMBER;
+}
+
+impl ToASN1 for CRLNumber {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![ASN1Block::Integer(0, BigInt::from_biguint(Sign::Plus, self.0.clone()))])
+    }
+}
+
+impl FromASN1 for CRLNumber {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        match next_block(v, "CRLNumber")? {
+            (ASN1Block::Integer(_, n), tail) if n.sign() != Sign::Minus => Ok((CRLNumber(n.magnitude().clone()), tail)),
+            _ => Err(X509DecodeErr::InvalidField("CRLNumber")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod crl_number_tests {
+    use num::bigint::BigUint;
+    use simple_asn1::der_encode;
+
+    use super::CRLNumber;
+    use der::der_decode;
+    use error::X509DecodeErr;
+
+    #[test]
+    fn crl_number_round_trips() {
+        for (der, n) in &[(&[0x02, 0x01, 0x00][..], 0u64), (&[0x02, 0x01, 0x05], 5), (&[0x02, 0x02, 0x00, 0x80], 128)] {
+            let number = der_decode::<CRLNumber>(der).unwrap();
+            assert_eq!(CRLNumber(BigUint::from(*n)), number);
+            assert_eq!(der.to_vec(), der_encode(&number).unwrap());
+        }
+    }
+
+    #[test]
+    fn crl_number_rejects_negative() {
+        assert_eq!(Err(X509DecodeErr::InvalidField("CRLNumber")), der_decode::<CRLNumber>(&[0x02, 0x01, 0xFF]));
+    }
+}
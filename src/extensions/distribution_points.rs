@@ -0,0 +1,529 @@
+use std::ops::{BitOr, Deref};
+
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{
+    context_tag, explicit, explicit_inner, expect_end, implicit, implicit_inner, named_bits, named_bits_value,
+    next_block, sequence,
+};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use general_name::{GeneralName, GeneralNames};
+use name::{Name, RelativeDistinguishedName};
+use oid::ObjectIdentifier;
+use oids;
+
+/// The revocation reasons a CRL covers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReasonFlags(u16);
+
+impl ReasonFlags {
+    pub const KEY_COMPROMISE: ReasonFlags = ReasonFlags(1 << 1);
+    pub const CA_COMPROMISE: ReasonFlags = ReasonFlags(1 << 2);
+    pub const AFFILIATION_CHANGED: ReasonFlags = ReasonFlags(1 << 3);
+    pub const SUPERSEDED: ReasonFlags = ReasonFlags(1 << 4);
+    pub const CESSATION_OF_OPERATION: ReasonFlags = ReasonFlags(1 << 5);
+    pub const CERTIFICATE_HOLD: ReasonFlags = ReasonFlags(1 << 6);
+    pub const PRIVILEGE_WITHDRAWN: ReasonFlags = ReasonFlags(1 << 7);
+    pub const AA_COMPROMISE: ReasonFlags = ReasonFlags(1 << 8);
+
+    pub fn empty() -> ReasonFlags {
+        ReasonFlags(0)
+    }
+
+    /// Every reason.
+    pub fn all() -> ReasonFlags {
+        ReasonFlags(0x1FE)
+    }
+
+    /// The reasons as named bits: bit 0 is the unused bit.
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn from_bits(bits: u16) -> ReasonFlags {
+        ReasonFlags(bits & 0x1FF)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether every reason in `other` is also in `self`.
+    pub fn contains(self, other: ReasonFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    fn from_implicit(b: &ASN1Block, tag: u64, field: &'static str) -> Result<ReasonFlags, X509DecodeErr> {
+        match implicit_inner(b, tag, 0x03) {
+            Some(ASN1Block::BitString(_, nbits, bytes)) => Ok(ReasonFlags(named_bits_value(nbits, &bytes, 9))),
+            _ => Err(X509DecodeErr::InvalidField(field)),
+        }
+    }
+}
+
+impl BitOr for ReasonFlags {
+    type Output = ReasonFlags;
+
+    fn bitor(self, other: ReasonFlags) -> ReasonFlags {
+        ReasonFlags(self.0 | other.0)
+    }
+}
+
+/// The name of a distribution point, either in full or relative to the
+/// CRL issuer's name.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DistributionPointName {
+    FullName(GeneralNames),
+    NameRelativeToCRLIssuer(RelativeDistinguishedName),
+}
+
+impl DistributionPointName {
+    /// The name in full, taking a relative name as relative to
+    /// `crl_issuer`.
+    pub fn full_names(&self, crl_issuer: &Name) -> Vec<GeneralName> {
+        match *self {
+            DistributionPointName::FullName(ref names) => names.0.clone(),
+            DistributionPointName::NameRelativeToCRLIssuer(ref rdn) => {
+                let mut name = crl_issuer.clone();
+                name.0.push(rdn.clone());
+                vec![GeneralName::DirectoryName(name)]
+            }
+        }
+    }
+}
+
+impl ToASN1 for DistributionPointName {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let block = match *self {
+            DistributionPointName::FullName(ref names) => implicit(0, &names.to_asn1_class(c)?.remove(0))?,
+            DistributionPointName::NameRelativeToCRLIssuer(ref rdn) => implicit(1, &rdn.to_asn1_class(c)?.remove(0))?,
+        };
+        Ok(vec![block])
+    }
+}
+
+impl FromASN1 for DistributionPointName {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "DistributionPointName")?;
+        let name = match context_tag(head) {
+            Some(0) => {
+                let names = implicit_inner(head, 0, 0x10).ok_or(X509DecodeErr::InvalidField("fullName"))?;
+                let (names, _) = GeneralNames::from_asn1(::std::slice::from_ref(&names))?;
+                DistributionPointName::FullName(names)
+            }
+            Some(1) => {
+                let rdn = implicit_inner(head, 1, 0x11).ok_or(X509DecodeErr::InvalidField("nameRelativeToCRLIssuer"))?;
+                let (rdn, _) = RelativeDistinguishedName::from_asn1(::std::slice::from_ref(&rdn))?;
+                DistributionPointName::NameRelativeToCRLIssuer(rdn)
+            }
+            _ => return Err(X509DecodeErr::InvalidField("DistributionPointName")),
+        };
+        Ok((name, tail))
+    }
+}
+
+/// Where to fetch a CRL, which reasons it covers and who issues it. At
+/// least one of `distribution_point` and `crl_issuer` must be present.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DistributionPoint {
+    pub distribution_point: Option<DistributionPointName>,
+    pub reasons: Option<ReasonFlags>,
+    pub crl_issuer: Option<GeneralNames>,
+}
+
+impl DistributionPoint {
+    /// A distribution point with a single URI as its full name.
+    pub fn uri(uri: &str) -> DistributionPoint {
+        let names = GeneralNames::new(vec![GeneralName::URI(uri.to_string())]);
+        DistributionPoint { distribution_point: Some(DistributionPointName::FullName(names)), ..Default::default() }
+    }
+
+    /// The names a CRL from the distribution point may give in its
+    /// issuingDistributionPoint: those of `distribution_point`, or failing
+    /// that of `crl_issuer`. A relative name is relative to the cRLIssuer's
+    /// directory name if there is one, or else to `issuer`, the
+    /// certificate's issuer (RFC 5280 §6.3.3).
+    pub fn names(&self, issuer: &Name) -> Vec<GeneralName> {
+        let crl_issuer = self.crl_issuer.as_ref();
+        match self.distribution_point {
+            Some(ref name) => name.full_names(crl_issuer.and_then(|n| n.directory_names().next()).unwrap_or(issuer)),
+            None => crl_issuer.map_or_else(Vec::new, |names| names.0.clone()),
+        }
+    }
+
+    /// The URIs in the distribution point's full name.
+    pub fn uris(&self) -> impl Iterator<Item = &str> {
+        let names = match self.distribution_point {
+            Some(DistributionPointName::FullName(ref names)) => Some(names.uris()),
+            _ => None,
+        };
+        names.into_iter().flatten()
+    }
+}
+
+impl ToASN1 for DistributionPoint {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        if let Some(ref name) = self.distribution_point {
+            items.push(explicit(0, name.to_asn1_class(c)?.remove(0)));
+        }
+        if let Some(reasons) = self.reasons {
+            items.push(implicit(1, &named_bits(reasons.0))?);
+        }
+        if let Some(ref issuer) = self.crl_issuer {
+            items.push(implicit(2, &issuer.to_asn1_class(c)?.remove(0))?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for DistributionPoint {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "DistributionPoint")?;
+        let mut rest = sequence(head, "DistributionPoint")?;
+        let mut point = DistributionPoint::default();
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(0)) {
+            let name = explicit_inner(block, 0).ok_or(X509DecodeErr::InvalidField("distributionPoint"))?;
+            let (name, _) = DistributionPointName::from_asn1(::std::slice::from_ref(name))?;
+            point.distribution_point = Some(name);
+            rest = remaining;
+        }
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(1)) {
+            point.reasons = Some(ReasonFlags::from_implicit(block, 1, "reasons")?);
+            rest = remaining;
+        }
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(2)) {
+            let names = implicit_inner(block, 2, 0x10).ok_or(X509DecodeErr::InvalidField("cRLIssuer"))?;
+            let (names, _) = GeneralNames::from_asn1(::std::slice::from_ref(&names))?;
+            point.crl_issuer = Some(names);
+            rest = remaining;
+        }
+        expect_end(rest, "DistributionPoint")?;
+        if point.distribution_point.is_none() && point.crl_issuer.is_none() {
+            return Err(X509DecodeErr::InvalidField("DistributionPoint"));
+        }
+        Ok((point, tail))
+    }
+}
+
+/// The scope of a CRL: where it is published, and which certificates and
+/// reasons it covers (RFC 5280 §5.2.5). A certificate outside the scope
+/// must be checked against another CRL.
+///
+/// At most one of the `only_contains_*` flags may be set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IssuingDistributionPoint {
+    pub distribution_point: Option<DistributionPointName>,
+    pub only_contains_user_certs: bool,
+    pub only_contains_ca_certs: bool,
+    pub only_some_reasons: Option<ReasonFlags>,
+    /// Whether the CRL lists certificates from issuers other than its own,
+    /// named by certificateIssuer entry extensions.
+    pub indirect_crl: bool,
+    pub only_contains_attribute_certs: bool,
+}
+
+impl IssuingDistributionPoint {
+    /// Whether a public-key certificate, a CA's if `is_ca`, is within the
+    /// CRL's scope.
+    pub fn covers_certificate(&self, is_ca: bool) -> bool {
+        if is_ca {
+            !self.only_contains_user_certs && !self.only_contains_attribute_certs
+        } else {
+            !self.only_contains_ca_certs && !self.only_contains_attribute_certs
+        }
+    }
+
+    /// The reasons the CRL covers: all of them unless it is limited to
+    /// `only_some_reasons`.
+    pub fn reasons(&self) -> ReasonFlags {
+        self.only_some_reasons.unwrap_or_else(ReasonFlags::all)
+    }
+}
+
+impl X509Extension for IssuingDistributionPoint {
+    const OID: ObjectIdentifier = oids::ISSUING_DISTRIBUTION_POINT;
+}
+
+impl ToASN1 for IssuingDistributionPoint {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        if let Some(ref name) = self.distribution_point {
+            items.push(explicit(0, name.to_asn1_class(c)?.remove(0)));
+        }
+        // The flags are DEFAULT FALSE, so DER leaves them out unless set.
+        if self.only_contains_user_certs {
+            items.push(implicit(1, &ASN1Block::Boolean(0, true))?);
+        }
+        if self.only_contains_ca_certs {
+            items.push(implicit(2, &ASN1Block::Boolean(0, true))?);
+        }
+        if let Some(reasons) = self.only_some_reasons {
+            items.push(implicit(3, &named_bits(reasons.0))?);
+        }
+        if self.indirect_crl {
+            items.push(implicit(4, &ASN1Block::Boolean(0, true))?);
+        }
+        if self.only_contains_attribute_certs {
+            items.push(implicit(5, &ASN1Block::Boolean(0, true))?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+// An `[tag] IMPLICIT BOOLEAN DEFAULT FALSE`, if it's next.
+fn flag<'a>(v: &'a [ASN1Block], tag: u64, field: &'static str) -> Result<(bool, &'a [ASN1Block]), X509DecodeErr> {
+    match v.split_first() {
+        Some((block, rest)) if context_tag(block) == Some(tag) => match implicit_inner(block, tag, 0x01) {
+            Some(ASN1Block::Boolean(_, value)) => Ok((value, rest)),
+            _ => Err(X509DecodeErr::InvalidField(field)),
+        },
+        _ => Ok((false, v)),
+    }
+}
+
+impl FromASN1 for IssuingDistributionPoint {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "IssuingDistributionPoint")?;
+        let mut rest = sequence(head, "IssuingDistributionPoint")?;
+        // RFC 5280 forbids an empty sequence here.
+        if rest.is_empty() {
+            return Err(X509DecodeErr::InvalidField("IssuingDistributionPoint"));
+        }
+        let mut idp = IssuingDistributionPoint::default();
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(0)) {
+            let name = explicit_inner(block, 0).ok_or(X509DecodeErr::InvalidField("distributionPoint"))?;
+            let (name, _) = DistributionPointName::from_asn1(::std::slice::from_ref(name))?;
+            idp.distribution_point = Some(name);
+            rest = remaining;
+        }
+        let (only_contains_user_certs, rest) = flag(rest, 1, "onlyContainsUserCerts")?;
+        let (only_contains_ca_certs, mut rest) = flag(rest, 2, "onlyContainsCACerts")?;
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(3)) {
+            idp.only_some_reasons = Some(ReasonFlags::from_implicit(block, 3, "onlySomeReasons")?);
+            rest = remaining;
+        }
+        let (indirect_crl, rest) = flag(rest, 4, "indirectCRL")?;
+        let (only_contains_attribute_certs, rest) = flag(rest, 5, "onlyContainsAttributeCerts")?;
+        expect_end(rest, "IssuingDistributionPoint")?;
+        let only = [only_contains_user_certs, only_contains_ca_certs, only_contains_attribute_certs];
+        if only.iter().filter(|&&set| set).count() > 1 {
+            return Err(X509DecodeErr::InvalidField("IssuingDistributionPoint"));
+        }
+        idp.only_contains_user_certs = only_contains_user_certs;
+        idp.only_contains_ca_certs = only_contains_ca_certs;
+        idp.indirect_crl = indirect_crl;
+        idp.only_contains_attribute_certs = only_contains_attribute_certs;
+        Ok((idp, tail))
+    }
+}
+
+/// Where to fetch the complete CRLs covering the certificate (RFC 5280
+/// §4.2.1.13).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CRLDistributionPoints(pub Vec<DistributionPoint>);
+
+/// Where to fetch the delta CRLs covering the certificate (RFC 5280
+/// §4.2.1.15).
+#[derive(Clone, Debug, PartialEq)]
+pub struct FreshestCRL(pub Vec<DistributionPoint>);
+
+macro_rules! distribution_points {
+    ($name:ident, $oid:expr) => {
+        impl $name {
+            /// The URIs of every distribution point, in order.
+            pub fn uris(&self) -> impl Iterator<Item = &str> {
+                self.0.iter().flat_map(|p| p.uris())
+            }
+        }
+
+        impl Deref for $name {
+            type Target = [DistributionPoint];
+
+            fn deref(&self) -> &[DistributionPoint] {
+                &self.0
+            }
+        }
+
+        impl X509Extension for $name {
+            const OID: ObjectIdentifier = $oid;
+        }
+
+        impl ToASN1 for $name {
+            type Error = ASN1EncodeErr;
+
+            fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+                let mut items = Vec::new();
+                for point in &self.0 {
+                    items.extend(point.to_asn1_class(c)?);
+                }
+                Ok(vec![ASN1Block::Sequence(0, items)])
+            }
+        }
+
+        impl FromASN1 for $name {
+            type Error = X509DecodeErr;
+
+            fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+                let (head, tail) = next_block(v, stringify!($name))?;
+                let mut items = sequence(head, stringify!($name))?;
+                // SIZE (1..MAX)
+                if items.is_empty() {
+                    return Err(X509DecodeErr::InvalidField(stringify!($name)));
+                }
+                let mut points = Vec::new();
+                while !items.is_empty() {
+                    let (point, rest) = DistributionPoint::from_asn1(items)?;
+                    points.push(point);
+                    items = rest;
+                }
+                Ok(($name(points), tail))
+            }
+        }
+    };
+}
+
+distribution_points!(CRLDistributionPoints, oids::CRL_DISTRIBUTION_POINTS);
+distribution_points!(FreshestCRL, oids::FRESHEST_CRL);
+
+#[cfg(test)]
+mod distribution_points_tests {
+    use simple_asn1::der_encode;
+
+    use super::{
+        CRLDistributionPoints, DistributionPoint, DistributionPointName, FreshestCRL, IssuingDistributionPoint,
+        ReasonFlags,
+    };
+    use crl::CertificateList;
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use general_name::{GeneralName, GeneralNames};
+    use name::{AttributeTypeAndValue, AttributeValue, DirectoryString, Name, RelativeDistinguishedName};
+    use oids;
+    use x509::Certificate;
+
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+    const EC_ROOT_IDP_CRL: &[u8] = include_bytes!("../../testdata/ec-root-idp-crl.der");
+
+    #[test]
+    fn crl_distribution_points_from_certificate() {
+        let cert = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        let cdp = extensions.get::<CRLDistributionPoints>().unwrap().unwrap();
+        assert_eq!(vec!["http://crl.example.com/root.crl"], cdp.uris().collect::<Vec<_>>());
+        assert_eq!(DistributionPoint::uri("http://crl.example.com/root.crl"), cdp[0]);
+        assert_eq!(extensions.find(&oids::CRL_DISTRIBUTION_POINTS).unwrap().extn_value, der_encode(&cdp).unwrap());
+    }
+
+    #[test]
+    fn freshest_crl_round_trips() {
+        let freshest = FreshestCRL(vec![DistributionPoint {
+            reasons: Some(ReasonFlags::KEY_COMPROMISE | ReasonFlags::CA_COMPROMISE),
+            crl_issuer: Some(GeneralNames::new(vec![GeneralName::DNSName("ca.example".to_string())])),
+            ..DistributionPoint::uri("http://crl.example.com/delta.crl")
+        }]);
+        let encoded = der_encode(&freshest).unwrap();
+        // reasons [1] IMPLICIT BIT STRING: two unused bits, 0110_0000
+        assert!(encoded.windows(4).any(|w| w == [0x81, 0x02, 0x05, 0x60]));
+        assert_eq!(freshest, der_decode::<FreshestCRL>(&encoded).unwrap());
+        assert_eq!(vec!["http://crl.example.com/delta.crl"], freshest.uris().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn distribution_point_relative_name_round_trips() {
+        let rdn = RelativeDistinguishedName(vec![AttributeTypeAndValue {
+            attr_type: oids::COMMON_NAME,
+            value: AttributeValue::String(DirectoryString::new("CRL1")),
+        }]);
+        let cdp = CRLDistributionPoints(vec![DistributionPoint {
+            distribution_point: Some(DistributionPointName::NameRelativeToCRLIssuer(rdn.clone())),
+            ..Default::default()
+        }]);
+        let encoded = der_encode(&cdp).unwrap();
+        assert_eq!(cdp, der_decode::<CRLDistributionPoints>(&encoded).unwrap());
+        assert_eq!(0, cdp.uris().count());
+
+        let mut issuer = Name::new();
+        issuer.push(oids::ORGANIZATION_NAME, DirectoryString::new("QuickLime"));
+        let mut full = issuer.clone();
+        full.0.push(rdn);
+        assert_eq!(vec![GeneralName::DirectoryName(full.clone())], cdp[0].names(&issuer));
+
+        // Relative to the cRLIssuer in its place, if there is one.
+        let mut other = Name::new();
+        other.push(oids::ORGANIZATION_NAME, DirectoryString::new("Other"));
+        let mut point = cdp[0].clone();
+        point.crl_issuer = Some(GeneralNames::new(vec![GeneralName::DirectoryName(issuer)]));
+        assert_eq!(vec![GeneralName::DirectoryName(full)], point.names(&other));
+        point.distribution_point = None;
+        assert_eq!(point.crl_issuer.clone().unwrap().0, point.names(&other));
+    }
+
+    #[test]
+    fn distribution_point_requires_name_or_issuer() {
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("DistributionPoint")),
+            der_decode::<CRLDistributionPoints>(&[0x30, 0x02, 0x30, 0x00])
+        );
+    }
+
+    #[test]
+    fn issuing_distribution_point_from_crl() {
+        let crl = der_decode::<CertificateList>(EC_ROOT_IDP_CRL).unwrap();
+        let extensions = crl.tbs_cert_list.crl_extensions.unwrap();
+        let raw = extensions.find(&oids::ISSUING_DISTRIBUTION_POINT).unwrap();
+        assert!(raw.critical);
+        let idp = raw.decode::<IssuingDistributionPoint>().unwrap();
+        let expected = IssuingDistributionPoint {
+            distribution_point: DistributionPoint::uri("http://crl.example.com/users.crl").distribution_point,
+            only_contains_user_certs: true,
+            only_some_reasons: Some(ReasonFlags::KEY_COMPROMISE | ReasonFlags::CA_COMPROMISE),
+            indirect_crl: true,
+            ..Default::default()
+        };
+        assert_eq!(expected, idp);
+        assert_eq!(raw.extn_value, der_encode(&idp).unwrap());
+    }
+
+    #[test]
+    fn issuing_distribution_point_scope() {
+        let idp = IssuingDistributionPoint { indirect_crl: true, ..Default::default() };
+        assert!(idp.covers_certificate(true) && idp.covers_certificate(false));
+        assert_eq!(ReasonFlags::all(), idp.reasons());
+        let idp = IssuingDistributionPoint { only_contains_user_certs: true, ..Default::default() };
+        assert!(!idp.covers_certificate(true) && idp.covers_certificate(false));
+        let idp = IssuingDistributionPoint { only_contains_ca_certs: true, ..Default::default() };
+        assert!(idp.covers_certificate(true) && !idp.covers_certificate(false));
+        let idp = IssuingDistributionPoint { only_contains_attribute_certs: true, ..Default::default() };
+        assert!(!idp.covers_certificate(true) && !idp.covers_certificate(false));
+        let idp = IssuingDistributionPoint { only_some_reasons: Some(ReasonFlags::SUPERSEDED), ..Default::default() };
+        assert_eq!(ReasonFlags::SUPERSEDED, idp.reasons());
+    }
+
+    #[test]
+    fn issuing_distribution_point_rejects_invalid() {
+        let invalid = Err(X509DecodeErr::InvalidField("IssuingDistributionPoint"));
+        assert_eq!(invalid, der_decode::<IssuingDistributionPoint>(&[0x30, 0x00]));
+        // onlyContainsUserCerts and onlyContainsCACerts
+        let both = [0x30, 0x06, 0x81, 0x01, 0xFF, 0x82, 0x01, 0xFF];
+        assert_eq!(invalid, der_decode::<IssuingDistributionPoint>(&both));
+    }
+
+    #[test]
+    fn freshest_crl_rejects_empty() {
+        assert_eq!(Err(X509DecodeErr::InvalidField("FreshestCRL")), der_decode::<FreshestCRL>(&[0x30, 0x00]));
+    }
+}
@@ -0,0 +1,136 @@
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{next_block, sequence};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use oid::ObjectIdentifier;
+use oids;
+
+/// The purposes, beyond those in KeyUsage, that a certified key may be
+/// used for (RFC 5280 §4.2.1.12).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtendedKeyUsage(pub Vec<ObjectIdentifier>);
+
+impl ExtendedKeyUsage {
+    pub const ANY: ObjectIdentifier = oids::ANY_EXTENDED_KEY_USAGE;
+    pub const SERVER_AUTH: ObjectIdentifier = oids::KP_SERVER_AUTH;
+    pub const CLIENT_AUTH: ObjectIdentifier = oids::KP_CLIENT_AUTH;
+    pub const CODE_SIGNING: ObjectIdentifier = oids::KP_CODE_SIGNING;
+    pub const EMAIL_PROTECTION: ObjectIdentifier = oids::KP_EMAIL_PROTECTION;
+    pub const TIME_STAMPING: ObjectIdentifier = oids::KP_TIME_STAMPING;
+    pub const OCSP_SIGNING: ObjectIdentifier = oids::KP_OCSP_SIGNING;
+
+    pub fn new(purposes: Vec<ObjectIdentifier>) -> ExtendedKeyUsage {
+        ExtendedKeyUsage(purposes)
+    }
+
+    /// Whether `purpose` is listed explicitly.
+    pub fn contains(&self, purpose: &ObjectIdentifier) -> bool {
+        self.0.contains(purpose)
+    }
+
+    /// Whether the key may be used for `purpose`, either because it is
+    /// listed or because anyExtendedKeyUsage is.
+    pub fn permits(&self, purpose: &ObjectIdentifier) -> bool {
+        self.contains(purpose) || self.contains(&ExtendedKeyUsage::ANY)
+    }
+
+    pub fn server_auth(&self) -> bool {
+        self.contains(&ExtendedKeyUsage::SERVER_AUTH)
+    }
+
+    pub fn client_auth(&self) -> bool {
+        self.contains(&ExtendedKeyUsage::CLIENT_AUTH)
+    }
+
+    pub fn code_signing(&self) -> bool {
+        self.contains(&ExtendedKeyUsage::CODE_SIGNING)
+    }
+
+    pub fn email_protection(&self) -> bool {
+        self.contains(&ExtendedKeyUsage::EMAIL_PROTECTION)
+    }
+
+    pub fn time_stamping(&self) -> bool {
+        self.contains(&ExtendedKeyUsage::TIME_STAMPING)
+    }
+
+    pub fn ocsp_signing(&self) -> bool {
+        self.contains(&ExtendedKeyUsage::OCSP_SIGNING)
+    }
+}
+
+impl X509Extension for ExtendedKeyUsage {
+    const OID: ObjectIdentifier = oids::EXT_KEY_USAGE;
+}
+
+impl ToASN1 for ExtendedKeyUsage {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![ASN1Block::Sequence(0, self.0.iter().map(|p| p.to_block()).collect())])
+    }
+}
+
+impl FromASN1 for ExtendedKeyUsage {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "ExtKeyUsageSyntax")?;
+        let items = sequence(head, "ExtKeyUsageSyntax")?;
+        // SIZE (1..MAX)
+        if items.is_empty() {
+            return Err(X509DecodeErr::InvalidField("ExtKeyUsageSyntax"));
+        }
+        let purposes = items
+            .iter()
+            .map(|b| ObjectIdentifier::from_block(b, "KeyPurposeId"))
+            .collect::<Result<_, _>>()?;
+        Ok((ExtendedKeyUsage(purposes), tail))
+    }
+}
+
+#[cfg(test)]
+mod ext_key_usage_tests {
+    use simple_asn1::der_encode;
+
+    use super::ExtendedKeyUsage;
+    use der::der_decode;
+    use error::X509DecodeErr;
+
+    // serverAuth, clientAuth
+    const TLS: &[u8] = &[
+        0x30, 0x14, 0x06, 0x08, 0x2B, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x01, 0x06, 0x08, 0x2B, 0x06,
+        0x01, 0x05, 0x05, 0x07, 0x03, 0x02,
+    ];
+
+    #[test]
+    fn ext_key_usage_decodes() {
+        let actual = der_decode::<ExtendedKeyUsage>(TLS).unwrap();
+        assert_eq!(ExtendedKeyUsage::new(vec![ExtendedKeyUsage::SERVER_AUTH, ExtendedKeyUsage::CLIENT_AUTH]), actual);
+        assert!(actual.server_auth() && actual.client_auth());
+        assert!(!actual.code_signing());
+        assert!(!actual.permits(&ExtendedKeyUsage::OCSP_SIGNING));
+    }
+
+    #[test]
+    fn ext_key_usage_round_trips() {
+        let decoded = der_decode::<ExtendedKeyUsage>(TLS).unwrap();
+        assert_eq!(TLS.to_vec(), der_encode(&decoded).unwrap());
+    }
+
+    #[test]
+    fn ext_key_usage_any_permits_everything() {
+        let usage = ExtendedKeyUsage::new(vec![ExtendedKeyUsage::ANY]);
+        assert!(usage.permits(&ExtendedKeyUsage::TIME_STAMPING));
+        assert!(!usage.time_stamping());
+    }
+
+    #[test]
+    fn ext_key_usage_rejects_empty() {
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("ExtKeyUsageSyntax")),
+            der_decode::<ExtendedKeyUsage>(&[0x30, 0x00])
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::ops::Deref;
+
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{expect_end, next_block, sequence};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use general_name::GeneralName;
+use oid::ObjectIdentifier;
+use oids;
+
+/// Where to find a service or resource, and how to use it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessDescription {
+    pub access_method: ObjectIdentifier,
+    pub access_location: GeneralName,
+}
+
+impl AccessDescription {
+    pub const OCSP: ObjectIdentifier = oids::AD_OCSP;
+    pub const CA_ISSUERS: ObjectIdentifier = oids::AD_CA_ISSUERS;
+    pub const TIME_STAMPING: ObjectIdentifier = oids::AD_TIME_STAMPING;
+    pub const CA_REPOSITORY: ObjectIdentifier = oids::AD_CA_REPOSITORY;
+
+    /// A description whose location is the given URI.
+    pub fn uri(access_method: ObjectIdentifier, uri: &str) -> AccessDescription {
+        AccessDescription { access_method, access_location: GeneralName::URI(uri.to_string()) }
+    }
+}
+
+impl ToASN1 for AccessDescription {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = vec![self.access_method.to_block()];
+        items.extend(self.access_location.to_asn1_class(c)?);
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for AccessDescription {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "AccessDescription")?;
+        let items = sequence(head, "AccessDescription")?;
+        let (access_method, rest) = next_block(items, "accessMethod")?;
+        let access_method = ObjectIdentifier::from_block(access_method, "accessMethod")?;
+        let (access_location, rest) = GeneralName::from_asn1(rest)?;
+        expect_end(rest, "AccessDescription")?;
+        Ok((AccessDescription { access_method, access_location }, tail))
+    }
+}
+
+/// How to reach services of the certificate's issuer (RFC 5280
+/// §4.2.2.1), such as its OCSP responder.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuthorityInfoAccess(pub Vec<AccessDescription>);
+
+/// How to reach services of the certificate's subject (RFC 5280
+/// §4.2.2.2).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubjectInfoAccess(pub Vec<AccessDescription>);
+
+/// The URIs of every description in `descriptions` using `method`.
+fn uris_for(descriptions: &[AccessDescription], method: ObjectIdentifier) -> impl Iterator<Item = &str> {
+    descriptions.iter().filter(move |d| d.access_method == method).filter_map(|d| match d.access_location {
+        GeneralName::URI(ref uri) => Some(uri.as_str()),
+        _ => None,
+    })
+}
+
+impl AuthorityInfoAccess {
+    pub fn ocsp_urls(&self) -> impl Iterator<Item = &str> {
+        uris_for(&self.0, AccessDescription::OCSP)
+    }
+
+    pub fn ca_issuers_urls(&self) -> impl Iterator<Item = &str> {
+        uris_for(&self.0, AccessDescription::CA_ISSUERS)
+    }
+}
+
+macro_rules! info_access {
+    ($name:ident, $oid:expr) => {
+        impl Deref for $name {
+            type Target = [AccessDescription];
+
+            fn deref(&self) -> &[AccessDescription] {
+                &self.0
+            }
+        }
+
+        impl X509Extension for $name {
+            const OID: ObjectIdentifier = $oid;
+        }
+
+        impl ToASN1 for $name {
+            type Error = ASN1EncodeErr;
+
+            fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+                let mut items = Vec::new();
+                for description in &self.0 {
+                    items.extend(description.to_asn1_class(c)?);
+                }
+                Ok(vec![ASN1Block::Sequence(0, items)])
+            }
+        }
+
+        impl FromASN1 for $name {
+            type Error = X509DecodeErr;
+
+            fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+                let (head, tail) = next_block(v, stringify!($name))?;
+                let mut items = sequence(head, stringify!($name))?;
+                // SIZE (1..MAX)
+                if items.is_empty() {
+                    return Err(X509DecodeErr::InvalidField(stringify!($name)));
+                }
+                let mut descriptions = Vec::new();
+                while !items.is_empty() {
+                    let (description, rest) = AccessDescription::from_asn1(items)?;
+                    descriptions.push(description);
+                    items = rest;
+                }
+                Ok(($name(descriptions), tail))
+            }
+        }
+    };
+}
+
+info_access!(AuthorityInfoAccess, oids::AUTHORITY_INFO_ACCESS);
+info_access!(SubjectInfoAccess, oids::SUBJECT_INFO_ACCESS);
+
+#[cfg(test)]
+mod info_access_tests {
+    use simple_asn1::der_encode;
+
+    use super::{AccessDescription, AuthorityInfoAccess, SubjectInfoAccess};
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use x509::Certificate;
+
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+
+    #[test]
+    fn authority_info_access_from_certificate() {
+        let cert = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        let aia = extensions.get::<AuthorityInfoAccess>().unwrap().unwrap();
+        assert_eq!(vec!["http://ocsp.example.com/"], aia.ocsp_urls().collect::<Vec<_>>());
+        assert_eq!(vec!["http://ca.example.com/root.der"], aia.ca_issuers_urls().collect::<Vec<_>>());
+        assert_eq!(extensions.find(&oid!(1, 3, 6, 1, 5, 5, 7, 1, 1)).unwrap().extn_value, der_encode(&aia).unwrap());
+    }
+
+    #[test]
+    fn subject_info_access_round_trips() {
+        let sia = SubjectInfoAccess(vec![AccessDescription::uri(
+            AccessDescription::CA_REPOSITORY,
+            "http://ca.example.com/issued/",
+        )]);
+        let encoded = der_encode(&sia).unwrap();
+        assert_eq!(sia, der_decode::<SubjectInfoAccess>(&encoded).unwrap());
+        assert_eq!(AccessDescription::CA_REPOSITORY, sia[0].access_method);
+    }
+
+    #[test]
+    fn authority_info_access_rejects_empty() {
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("AuthorityInfoAccess")),
+            der_decode::<AuthorityInfoAccess>(&[0x30, 0x00])
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{context_tag, expect_end, implicit, implicit_bytes, implicit_inner, implicit_primitive, next_block, sequence};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use general_name::GeneralNames;
+use oid::ObjectIdentifier;
+use oids;
+use spki::SubjectPublicKeyInfo;
+use x509::CertificateSerialNumber;
+
+/// How to derive a key identifier from a public key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyIdentifierMethod {
+    /// The SHA-1 hash of the subjectPublicKey bits (RFC 5280 §4.2.1.2).
+    Sha1,
+    /// The leftmost 160 bits of the SHA-256 hash of the subjectPublicKey
+    /// bits (RFC 7093 §2).
+    Sha256Truncated,
+}
+
+impl KeyIdentifierMethod {
+    /// The identifier of `spki`'s key under this method.
+    pub fn key_identifier(self, spki: &SubjectPublicKeyInfo) -> Vec<u8> {
+        match self {
+            KeyIdentifierMethod::Sha1 => Sha1::digest(&spki.subject_public_key).to_vec(),
+            KeyIdentifierMethod::Sha256Truncated => Sha256::digest(&spki.subject_public_key)[..20].to_vec(),
+        }
+    }
+}
+
+/// An identifier for the certified public key (RFC 5280 §4.2.1.2).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubjectKeyIdentifier(pub Vec<u8>);
+
+impl SubjectKeyIdentifier {
+    pub fn from_spki(spki: &SubjectPublicKeyInfo, method: KeyIdentifierMethod) -> SubjectKeyIdentifier {
+        SubjectKeyIdentifier(method.key_identifier(spki))
+    }
+}
+
+impl X509Extension for SubjectKeyIdentifier {
+    const OID: ObjectIdentifier = oids::SUBJECT_KEY_IDENTIFIER;
+}
+
+impl ToASN1 for SubjectKeyIdentifier {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![ASN1Block::OctetString(0, self.0.clone())])
+    }
+}
+
+impl FromASN1 for SubjectKeyIdentifier {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        match next_block(v, "SubjectKeyIdentifier")? {
+            (ASN1Block::OctetString(_, bytes), rest) => Ok((SubjectKeyIdentifier(bytes.clone()), rest)),
+            _ => Err(X509DecodeErr::InvalidField("SubjectKeyIdentifier")),
+        }
+    }
+}
+
+/// Identifies the key that signed a certificate (RFC 5280 §4.2.1.1),
+/// either by key identifier or by the issuer's own issuer and serial
+/// number.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AuthorityKeyIdentifier {
+    pub key_identifier: Option<Vec<u8>>,
+    pub authority_cert_issuer: Option<GeneralNames>,
+    pub authority_cert_serial_number: Option<CertificateSerialNumber>,
+}
+
+impl AuthorityKeyIdentifier {
+    /// An identifier naming the issuer's key by its SubjectKeyIdentifier.
+    pub fn from_key_identifier(ski: &SubjectKeyIdentifier) -> AuthorityKeyIdentifier {
+        AuthorityKeyIdentifier { key_identifier: Some(ski.0.clone()), ..Default::default() }
+    }
+}
+
+impl X509Extension for AuthorityKeyIdentifier {
+    const OID: ObjectIdentifier = oids::AUTHORITY_KEY_IDENTIFIER;
+}
+
+impl ToASN1 for AuthorityKeyIdentifier {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        if let Some(ref key_identifier) = self.key_identifier {
+            items.push(implicit_primitive(0, key_identifier.clone()));
+        }
+        if let Some(ref issuer) = self.authority_cert_issuer {
+            items.push(implicit(1, &issuer.to_asn1_class(c)?.remove(0))?);
+        }
+        if let Some(ref serial) = self.authority_cert_serial_number {
+            items.push(implicit(2, &serial.to_asn1_class(c)?.remove(0))?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for AuthorityKeyIdentifier {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "AuthorityKeyIdentifier")?;
+        let mut rest = sequence(head, "AuthorityKeyIdentifier")?;
+        let mut aki = AuthorityKeyIdentifier::default();
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(0)) {
+            let bytes = implicit_bytes(block, 0).ok_or(X509DecodeErr::InvalidField("keyIdentifier"))?;
+            aki.key_identifier = Some(bytes.to_vec());
+            rest = remaining;
+        }
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(1)) {
+            let names = implicit_inner(block, 1, 0x10).ok_or(X509DecodeErr::InvalidField("authorityCertIssuer"))?;
+            let (names, _) = GeneralNames::from_asn1(::std::slice::from_ref(&names))?;
+            aki.authority_cert_issuer = Some(names);
+            rest = remaining;
+        }
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(2)) {
+            let serial = implicit_inner(block, 2, 0x02)
+                .ok_or(X509DecodeErr::InvalidField("authorityCertSerialNumber"))?;
+            let (serial, _) = CertificateSerialNumber::from_asn1(::std::slice::from_ref(&serial))?;
+            aki.authority_cert_serial_number = Some(serial);
+            rest = remaining;
+        }
+        expect_end(rest, "AuthorityKeyIdentifier")?;
+        Ok((aki, tail))
+    }
+}
+
+#[cfg(test)]
+mod key_identifier_tests {
+    use num::bigint::BigInt;
+    use simple_asn1::der_encode;
+
+    use super::{AuthorityKeyIdentifier, KeyIdentifierMethod, SubjectKeyIdentifier};
+    use der::der_decode;
+    use general_name::{GeneralName, GeneralNames};
+    use x509::{Certificate, CertificateSerialNumber};
+
+    const EC_ROOT: &[u8] = include_bytes!("../../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+
+    const ROOT_KEY_ID: &[u8] = &[
+        0x3A, 0xBF, 0x1F, 0x2F, 0x47, 0x80, 0x54, 0xFF, 0xF4, 0xD0, 0xA2, 0x12, 0xF0, 0x10, 0x6D, 0x99,
+        0x3A, 0x16, 0x0C, 0x0C,
+    ];
+
+    #[test]
+    fn subject_key_identifier_from_certificate() {
+        let cert = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        let ski = extensions.get::<SubjectKeyIdentifier>().unwrap().unwrap();
+        assert_eq!(ROOT_KEY_ID, &ski.0[..]);
+    }
+
+    #[test]
+    fn subject_key_identifier_sha1_method() {
+        let cert = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let spki = &cert.tbs_certificate.subject_public_key_info;
+        let ski = SubjectKeyIdentifier::from_spki(spki, KeyIdentifierMethod::Sha1);
+        assert_eq!(ROOT_KEY_ID, &ski.0[..]);
+    }
+
+    #[test]
+    fn subject_key_identifier_sha256_method() {
+        let cert = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let spki = &cert.tbs_certificate.subject_public_key_info;
+        let ski = SubjectKeyIdentifier::from_spki(spki, KeyIdentifierMethod::Sha256Truncated);
+        assert_eq!(20, ski.0.len());
+        assert_ne!(ROOT_KEY_ID, &ski.0[..]);
+    }
+
+    #[test]
+    fn authority_key_identifier_from_certificate() {
+        let cert = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        let aki = extensions.get::<AuthorityKeyIdentifier>().unwrap().unwrap();
+        let expected = AuthorityKeyIdentifier::from_key_identifier(&SubjectKeyIdentifier(ROOT_KEY_ID.to_vec()));
+        assert_eq!(expected, aki);
+    }
+
+    #[test]
+    fn authority_key_identifier_issuer_and_serial_round_trip() {
+        let aki = AuthorityKeyIdentifier {
+            key_identifier: Some(vec![1, 2, 3]),
+            authority_cert_issuer: Some(GeneralNames::new(vec![GeneralName::DNSName("ca.example".to_string())])),
+            authority_cert_serial_number: Some(CertificateSerialNumber(BigInt::from(4660))),
+        };
+        let encoded = der_encode(&aki).unwrap();
+        assert_eq!(
+            vec![
+                0x30, 0x17, 0x80, 0x03, 0x01, 0x02, 0x03, 0xA1, 0x0C, 0x82, 0x0A, 0x63, 0x61, 0x2E, 0x65, 0x78,
+                0x61, 0x6D, 0x70, 0x6C, 0x65, 0x82, 0x02, 0x12, 0x34,
+            ],
+            encoded
+        );
+        assert_eq!(aki, der_decode::<AuthorityKeyIdentifier>(&encoded).unwrap());
+    }
+}
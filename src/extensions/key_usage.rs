@@ -0,0 +1,194 @@
+use std::ops::BitOr;
+
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{named_bits, named_bits_value, next_block};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use oid::ObjectIdentifier;
+use oids;
+
+/// The purposes a certified key may be used for (RFC 5280 §4.2.1.3).
+///
+/// Usages combine with `|`:
+///
+/// ```
+/// use x509::extensions::KeyUsage;
+///
+/// let usage = KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN;
+/// assert!(usage.key_cert_sign());
+/// assert!(!usage.digital_signature());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeyUsage(u16);
+
+impl KeyUsage {
+    pub const DIGITAL_SIGNATURE: KeyUsage = KeyUsage(1 << 0);
+    /// Also known as nonRepudiation.
+    pub const CONTENT_COMMITMENT: KeyUsage = KeyUsage(1 << 1);
+    pub const KEY_ENCIPHERMENT: KeyUsage = KeyUsage(1 << 2);
+    pub const DATA_ENCIPHERMENT: KeyUsage = KeyUsage(1 << 3);
+    pub const KEY_AGREEMENT: KeyUsage = KeyUsage(1 << 4);
+    pub const KEY_CERT_SIGN: KeyUsage = KeyUsage(1 << 5);
+    pub const CRL_SIGN: KeyUsage = KeyUsage(1 << 6);
+    pub const ENCIPHER_ONLY: KeyUsage = KeyUsage(1 << 7);
+    pub const DECIPHER_ONLY: KeyUsage = KeyUsage(1 << 8);
+
+    /// No usages at all. RFC 5280 requires at least one to be set.
+    pub fn empty() -> KeyUsage {
+        KeyUsage(0)
+    }
+
+    /// The usages as named bits: bit 0 is digitalSignature.
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn from_bits(bits: u16) -> KeyUsage {
+        KeyUsage(bits & 0x1FF)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether every usage in `other` is also in `self`.
+    pub fn contains(self, other: KeyUsage) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// `self` with the usages in `other` added.
+    pub fn with(self, other: KeyUsage) -> KeyUsage {
+        KeyUsage(self.0 | other.0)
+    }
+
+    pub fn digital_signature(self) -> bool {
+        self.contains(KeyUsage::DIGITAL_SIGNATURE)
+    }
+
+    pub fn content_commitment(self) -> bool {
+        self.contains(KeyUsage::CONTENT_COMMITMENT)
+    }
+
+    pub fn key_encipherment(self) -> bool {
+        self.contains(KeyUsage::KEY_ENCIPHERMENT)
+    }
+
+    pub fn data_encipherment(self) -> bool {
+        self.contains(KeyUsage::DATA_ENCIPHERMENT)
+    }
+
+    pub fn key_agreement(self) -> bool {
+        self.contains(KeyUsage::KEY_AGREEMENT)
+    }
+
+    pub fn key_cert_sign(self) -> bool {
+        self.contains(KeyUsage::KEY_CERT_SIGN)
+    }
+
+    pub fn crl_sign(self) -> bool {
+        self.contains(KeyUsage::CRL_SIGN)
+    }
+
+    pub fn encipher_only(self) -> bool {
+        self.contains(KeyUsage::ENCIPHER_ONLY)
+    }
+
+    pub fn decipher_only(self) -> bool {
+        self.contains(KeyUsage::DECIPHER_ONLY)
+    }
+}
+
+impl BitOr for KeyUsage {
+    type Output = KeyUsage;
+
+    fn bitor(self, other: KeyUsage) -> KeyUsage {
+        self.with(other)
+    }
+}
+
+impl X509Extension for KeyUsage {
+    const OID: ObjectIdentifier = oids::KEY_USAGE;
+}
+
+impl ToASN1 for KeyUsage {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![named_bits(self.0)])
+    }
+}
+
+impl FromASN1 for KeyUsage {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        match next_block(v, "KeyUsage")? {
+            (&ASN1Block::BitString(_, nbits, ref bytes), rest) => Ok((KeyUsage(named_bits_value(nbits, bytes, 9)), rest)),
+            _ => Err(X509DecodeErr::InvalidField("KeyUsage")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod key_usage_tests {
+    use simple_asn1::der_encode;
+
+    use super::KeyUsage;
+    use der::der_decode;
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../../testdata/ec-root.der");
+
+    macro_rules! key_usage_test {
+        ($name:ident, $der:expr, $expected:expr) => {
+            #[test]
+            fn $name() {
+                let der: &[u8] = $der;
+                let expected: KeyUsage = $expected;
+                assert_eq!(expected, der_decode::<KeyUsage>(der).unwrap());
+                assert_eq!(der.to_vec(), der_encode(&expected).unwrap());
+            }
+        };
+    }
+
+    key_usage_test!(key_usage_digital_signature, &[0x03, 0x02, 0x07, 0x80], KeyUsage::DIGITAL_SIGNATURE);
+    key_usage_test!(
+        key_usage_ca,
+        &[0x03, 0x02, 0x01, 0x06],
+        KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN
+    );
+    key_usage_test!(
+        key_usage_signature_and_encipherment,
+        &[0x03, 0x02, 0x05, 0xA0],
+        KeyUsage::DIGITAL_SIGNATURE | KeyUsage::KEY_ENCIPHERMENT
+    );
+    key_usage_test!(key_usage_decipher_only, &[0x03, 0x03, 0x07, 0x00, 0x80], KeyUsage::DECIPHER_ONLY);
+    key_usage_test!(key_usage_empty, &[0x03, 0x01, 0x00], KeyUsage::empty());
+
+    #[test]
+    fn key_usage_queries() {
+        let usage = KeyUsage::empty().with(KeyUsage::KEY_AGREEMENT).with(KeyUsage::ENCIPHER_ONLY);
+        assert!(usage.key_agreement());
+        assert!(usage.encipher_only());
+        assert!(!usage.decipher_only());
+        assert!(usage.contains(KeyUsage::KEY_AGREEMENT));
+        assert!(!usage.contains(KeyUsage::KEY_AGREEMENT | KeyUsage::CRL_SIGN));
+        assert_eq!(0b1001_0000, usage.bits());
+    }
+
+    #[test]
+    fn key_usage_ignores_padding_bits() {
+        // One used bit, but the unused padding bits are not zero.
+        assert_eq!(KeyUsage::DIGITAL_SIGNATURE, der_decode::<KeyUsage>(&[0x03, 0x02, 0x07, 0xFF]).unwrap());
+    }
+
+    #[test]
+    fn key_usage_from_certificate() {
+        let cert = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        let usage = extensions.get::<KeyUsage>().unwrap().unwrap();
+        assert!(usage.key_cert_sign() && usage.crl_sign());
+        assert!(!usage.digital_signature());
+    }
+}
@@ -0,0 +1,408 @@
+use std::mem;
+
+use num::bigint::BigInt;
+use num::ToPrimitive;
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{context_tag, expect_end, implicit, implicit_inner, next_block, sequence};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use general_name::GeneralName;
+use name::Name;
+use oid::ObjectIdentifier;
+use oids;
+
+/// The names under `base`. RFC 5280 fixes `minimum` at 0 and leaves
+/// `maximum` out; a subtree that sets either can't be compared against.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneralSubtree {
+    pub base: GeneralName,
+    pub minimum: u64,
+    pub maximum: Option<u64>,
+}
+
+impl GeneralSubtree {
+    pub fn new(base: GeneralName) -> GeneralSubtree {
+        GeneralSubtree { base, minimum: 0, maximum: None }
+    }
+
+    // Whether `name` is in the subtree: false for a name of another form,
+    // None for one of the same form that can't be compared.
+    fn contains(&self, name: &GeneralName) -> Option<bool> {
+        if mem::discriminant(&self.base) != mem::discriminant(name) {
+            return Some(false);
+        }
+        if self.minimum != 0 || self.maximum.is_some() {
+            return None;
+        }
+        match (&self.base, name) {
+            (GeneralName::DNSName(base), GeneralName::DNSName(name)) => Some(dns_within(base, name)),
+            (GeneralName::RFC822Name(base), GeneralName::RFC822Name(name)) => email_within(base, name),
+            (GeneralName::URI(base), GeneralName::URI(name)) => uri_host(name).map(|host| host_within(base, host)),
+            (GeneralName::IPAddress(base), GeneralName::IPAddress(name)) => ip_within(base, name),
+            (GeneralName::DirectoryName(base), GeneralName::DirectoryName(name)) => Some(dn_within(base, name)),
+            _ => None,
+        }
+    }
+}
+
+// A dNSName constraint covers the name and every name below it. A leading
+// period, which RFC 5280 leaves undefined, is taken to mean only the names
+// below it.
+fn dns_within(base: &str, name: &str) -> bool {
+    if base.is_empty() {
+        return true;
+    }
+    if base.starts_with('.') {
+        return host_within(base, name);
+    }
+    name.eq_ignore_ascii_case(base) || ends_with_ignore_case(name, &format!(".{}", base))
+}
+
+// A host constraint in an rfc822Name or URI: exactly that host, or with a
+// leading period any host below it.
+fn host_within(base: &str, host: &str) -> bool {
+    if base.starts_with('.') {
+        host.len() > base.len() && ends_with_ignore_case(host, base)
+    } else {
+        host.eq_ignore_ascii_case(base)
+    }
+}
+
+fn ends_with_ignore_case(s: &str, suffix: &str) -> bool {
+    s.len() >= suffix.len()
+        && s.is_char_boundary(s.len() - suffix.len())
+        && s[s.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+}
+
+// An rfc822Name constraint is a whole mailbox, or a host as for URIs.
+fn email_within(base: &str, name: &str) -> Option<bool> {
+    let at = name.rfind('@')?;
+    let (local, host) = (&name[..at], &name[at + 1..]);
+    Some(match base.rfind('@') {
+        Some(base_at) => local == &base[..base_at] && host.eq_ignore_ascii_case(&base[base_at + 1..]),
+        None => host_within(base, host),
+    })
+}
+
+// The host of a URI with an authority component, without userinfo or port.
+fn uri_host(uri: &str) -> Option<&str> {
+    let (_, rest) = uri.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    let host =
+        if host.starts_with('[') { &host[..host.find(']')? + 1] } else { host.split(':').next().unwrap_or(host) };
+    if host.is_empty() {
+        None
+    } else {
+        Some(host)
+    }
+}
+
+// An iPAddress constraint is an address followed by a mask of the same
+// length.
+fn ip_within(base: &[u8], name: &[u8]) -> Option<bool> {
+    if base.len() != 8 && base.len() != 32 {
+        return None;
+    }
+    if name.len() != 4 && name.len() != 16 {
+        return None;
+    }
+    if base.len() != name.len() * 2 {
+        return Some(false);
+    }
+    let (addr, mask) = base.split_at(name.len());
+    Some(name.iter().zip(addr).zip(mask).all(|((n, a), m)| n & m == a & m))
+}
+
+// A directoryName constraint covers the names that start with its RDNs.
+fn dn_within(base: &Name, name: &Name) -> bool {
+    base.0.len() <= name.0.len() && base.0.iter().zip(&name.0).all(|(b, n)| b.matches(n))
+}
+
+impl ToASN1 for GeneralSubtree {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.base.to_asn1_class(c)?;
+        // minimum is DEFAULT 0, so DER leaves it out unless it's set.
+        if self.minimum != 0 {
+            items.push(implicit(0, &ASN1Block::Integer(0, BigInt::from(self.minimum)))?);
+        }
+        if let Some(maximum) = self.maximum {
+            items.push(implicit(1, &ASN1Block::Integer(0, BigInt::from(maximum)))?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for GeneralSubtree {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "GeneralSubtree")?;
+        let items = sequence(head, "GeneralSubtree")?;
+        let (base, mut rest) = GeneralName::from_asn1(items)?;
+        let mut subtree = GeneralSubtree::new(base);
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(0)) {
+            subtree.minimum = base_distance(block, 0, "minimum")?;
+            rest = remaining;
+        }
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(1)) {
+            subtree.maximum = Some(base_distance(block, 1, "maximum")?);
+            rest = remaining;
+        }
+        expect_end(rest, "GeneralSubtree")?;
+        Ok((subtree, tail))
+    }
+}
+
+// BaseDistance ::= INTEGER (0..MAX)
+fn base_distance(b: &ASN1Block, tag: u64, field: &'static str) -> Result<u64, X509DecodeErr> {
+    match implicit_inner(b, tag, 0x02) {
+        Some(ASN1Block::Integer(_, ref n)) => n.to_u64().ok_or(X509DecodeErr::InvalidField(field)),
+        _ => Err(X509DecodeErr::InvalidField(field)),
+    }
+}
+
+/// The name space a CA may issue certificates in (RFC 5280 §4.2.1.10).
+/// Names of a form that has permitted subtrees must be in one of them,
+/// and no name may be in an excluded subtree. An empty list means the
+/// field is absent.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NameConstraints {
+    pub permitted_subtrees: Vec<GeneralSubtree>,
+    pub excluded_subtrees: Vec<GeneralSubtree>,
+}
+
+impl NameConstraints {
+    /// Whether `name` is in a permitted subtree, or there are none of its
+    /// form. A name that can't be compared with a subtree of its form is
+    /// taken to be outside it.
+    pub fn permits(&self, name: &GeneralName) -> bool {
+        let mut subtrees =
+            self.permitted_subtrees.iter().filter(|s| mem::discriminant(&s.base) == mem::discriminant(name)).peekable();
+        subtrees.peek().is_none() || subtrees.any(|s| s.contains(name) == Some(true))
+    }
+
+    /// Whether `name` is in an excluded subtree. A name that can't be
+    /// compared with a subtree of its form is taken to be inside it.
+    pub fn excludes(&self, name: &GeneralName) -> bool {
+        self.excluded_subtrees.iter().any(|s| s.contains(name) != Some(false))
+    }
+}
+
+impl X509Extension for NameConstraints {
+    const OID: ObjectIdentifier = oids::NAME_CONSTRAINTS;
+}
+
+fn subtrees_block(tag: u64, subtrees: &[GeneralSubtree], c: ASN1Class) -> Result<ASN1Block, ASN1EncodeErr> {
+    let mut items = Vec::new();
+    for subtree in subtrees {
+        items.extend(subtree.to_asn1_class(c)?);
+    }
+    implicit(tag, &ASN1Block::Sequence(0, items))
+}
+
+// GeneralSubtrees ::= SEQUENCE SIZE (1..MAX) OF GeneralSubtree
+fn subtrees(b: &ASN1Block, tag: u64, field: &'static str) -> Result<Vec<GeneralSubtree>, X509DecodeErr> {
+    let block = implicit_inner(b, tag, 0x10).ok_or(X509DecodeErr::InvalidField(field))?;
+    let mut items = sequence(&block, field)?;
+    if items.is_empty() {
+        return Err(X509DecodeErr::InvalidField(field));
+    }
+    let mut subtrees = Vec::new();
+    while !items.is_empty() {
+        let (subtree, rest) = GeneralSubtree::from_asn1(items)?;
+        subtrees.push(subtree);
+        items = rest;
+    }
+    Ok(subtrees)
+}
+
+impl ToASN1 for NameConstraints {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        if !self.permitted_subtrees.is_empty() {
+            items.push(subtrees_block(0, &self.permitted_subtrees, c)?);
+        }
+        if !self.excluded_subtrees.is_empty() {
+            items.push(subtrees_block(1, &self.excluded_subtrees, c)?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for NameConstraints {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "NameConstraints")?;
+        let mut rest = sequence(head, "NameConstraints")?;
+        // RFC 5280 forbids an empty sequence here.
+        if rest.is_empty() {
+            return Err(X509DecodeErr::InvalidField("NameConstraints"));
+        }
+        let mut constraints = NameConstraints::default();
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(0)) {
+            constraints.permitted_subtrees = subtrees(block, 0, "permittedSubtrees")?;
+            rest = remaining;
+        }
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(1)) {
+            constraints.excluded_subtrees = subtrees(block, 1, "excludedSubtrees")?;
+            rest = remaining;
+        }
+        expect_end(rest, "NameConstraints")?;
+        Ok((constraints, tail))
+    }
+}
+
+#[cfg(test)]
+mod name_constraints_tests {
+    use simple_asn1::der_encode;
+
+    use super::{GeneralSubtree, NameConstraints};
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use general_name::GeneralName;
+    use name::{DirectoryString, Name};
+    use oids;
+
+    // permitted DNS:example.com, IP:192.168.0.0/16, email:.example.com;
+    // excluded DNS:bad.example.com
+    const CONSTRAINTS: &[u8] = &[
+        0x30, 0x42, 0xA0, 0x2B, 0x30, 0x0D, 0x82, 0x0B, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x2E, 0x63, 0x6F,
+        0x6D, 0x30, 0x0A, 0x87, 0x08, 0xC0, 0xA8, 0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0x30, 0x0E, 0x81, 0x0C, 0x2E,
+        0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x2E, 0x63, 0x6F, 0x6D, 0xA1, 0x13, 0x30, 0x11, 0x82, 0x0F, 0x62,
+        0x61, 0x64, 0x2E, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x2E, 0x63, 0x6F, 0x6D,
+    ];
+
+    fn dns(s: &str) -> GeneralName {
+        GeneralName::DNSName(s.to_string())
+    }
+
+    fn email(s: &str) -> GeneralName {
+        GeneralName::RFC822Name(s.to_string())
+    }
+
+    fn uri(s: &str) -> GeneralName {
+        GeneralName::URI(s.to_string())
+    }
+
+    fn permitting(names: Vec<GeneralName>) -> NameConstraints {
+        NameConstraints {
+            permitted_subtrees: names.into_iter().map(GeneralSubtree::new).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn dn(parts: &[&str]) -> GeneralName {
+        let mut name = Name::new();
+        for (attr_type, value) in [oids::COUNTRY_NAME, oids::ORGANIZATION_NAME, oids::COMMON_NAME].iter().zip(parts) {
+            name.push(attr_type.clone(), DirectoryString::new(value));
+        }
+        GeneralName::DirectoryName(name)
+    }
+
+    #[test]
+    fn name_constraints_round_trips() {
+        let decoded = der_decode::<NameConstraints>(CONSTRAINTS).unwrap();
+        assert_eq!(
+            NameConstraints {
+                permitted_subtrees: vec![
+                    GeneralSubtree::new(dns("example.com")),
+                    GeneralSubtree::new(GeneralName::IPAddress(vec![192, 168, 0, 0, 255, 255, 0, 0])),
+                    GeneralSubtree::new(email(".example.com")),
+                ],
+                excluded_subtrees: vec![GeneralSubtree::new(dns("bad.example.com"))],
+            },
+            decoded
+        );
+        assert_eq!(CONSTRAINTS.to_vec(), der_encode(&decoded).unwrap());
+    }
+
+    #[test]
+    fn name_constraints_rejects_empty() {
+        assert_eq!(Err(X509DecodeErr::InvalidField("NameConstraints")), der_decode::<NameConstraints>(&[0x30, 0x00]));
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("permittedSubtrees")),
+            der_decode::<NameConstraints>(&[0x30, 0x02, 0xA0, 0x00])
+        );
+    }
+
+    #[test]
+    fn name_constraints_matches_dns_names() {
+        let constraints = der_decode::<NameConstraints>(CONSTRAINTS).unwrap();
+        assert!(constraints.permits(&dns("example.com")));
+        assert!(constraints.permits(&dns("WWW.Example.COM")));
+        assert!(!constraints.permits(&dns("badexample.com")));
+        assert!(!constraints.permits(&dns("example.org")));
+        assert!(constraints.excludes(&dns("bad.example.com")));
+        assert!(constraints.excludes(&dns("www.bad.example.com")));
+        assert!(!constraints.excludes(&dns("good.example.com")));
+
+        let subdomains = permitting(vec![dns(".example.com")]);
+        assert!(!subdomains.permits(&dns("example.com")));
+        assert!(subdomains.permits(&dns("www.example.com")));
+    }
+
+    #[test]
+    fn name_constraints_matches_email_addresses() {
+        let constraints = der_decode::<NameConstraints>(CONSTRAINTS).unwrap();
+        assert!(constraints.permits(&email("user@mail.example.com")));
+        assert!(!constraints.permits(&email("user@example.com")));
+        assert!(!constraints.permits(&email("not-a-mailbox")));
+
+        let host = permitting(vec![email("example.com")]);
+        assert!(host.permits(&email("user@EXAMPLE.com")));
+        assert!(!host.permits(&email("user@mail.example.com")));
+        let mailbox = permitting(vec![email("user@example.com")]);
+        assert!(mailbox.permits(&email("user@example.com")));
+        assert!(!mailbox.permits(&email("other@example.com")));
+    }
+
+    #[test]
+    fn name_constraints_matches_ip_addresses() {
+        let constraints = der_decode::<NameConstraints>(CONSTRAINTS).unwrap();
+        assert!(constraints.permits(&"192.168.10.1".parse::<::std::net::IpAddr>().unwrap().into()));
+        assert!(!constraints.permits(&"192.169.0.1".parse::<::std::net::IpAddr>().unwrap().into()));
+        // An IPv6 address is not in an IPv4 subtree.
+        assert!(!constraints.permits(&"::1".parse::<::std::net::IpAddr>().unwrap().into()));
+    }
+
+    #[test]
+    fn name_constraints_matches_uris() {
+        let constraints = permitting(vec![uri(".example.com")]);
+        assert!(constraints.permits(&uri("https://user@www.example.com:8443/path")));
+        assert!(!constraints.permits(&uri("https://example.com/")));
+        assert!(!constraints.permits(&uri("urn:example:com")));
+        assert!(permitting(vec![uri("[::1]")]).permits(&uri("http://[::1]:80/")));
+    }
+
+    #[test]
+    fn name_constraints_matches_directory_names() {
+        let constraints = permitting(vec![dn(&["CA", "QuickLime"])]);
+        assert!(constraints.permits(&dn(&["CA", "QuickLime", "Test Root"])));
+        assert!(constraints.permits(&dn(&["ca", "quicklime  "])));
+        assert!(!constraints.permits(&dn(&["CA"])));
+        assert!(!constraints.permits(&dn(&["CA", "Other", "Test Root"])));
+        // Other forms are not constrained.
+        assert!(constraints.permits(&dns("example.org")));
+    }
+
+    #[test]
+    fn name_constraints_fails_closed() {
+        let registered = GeneralName::RegisteredID(oid!(1, 2, 3));
+        let limited = GeneralSubtree { maximum: Some(1), ..GeneralSubtree::new(dns("example.com")) };
+        let constraints = NameConstraints {
+            permitted_subtrees: vec![GeneralSubtree::new(registered.clone()), limited.clone()],
+            excluded_subtrees: vec![GeneralSubtree::new(registered.clone())],
+        };
+        assert!(!constraints.permits(&registered));
+        assert!(constraints.excludes(&registered));
+        assert!(!constraints.permits(&dns("example.com")));
+        assert!(NameConstraints { excluded_subtrees: vec![limited], ..Default::default() }.excludes(&dns("a.org")));
+    }
+}
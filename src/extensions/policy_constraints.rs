@@ -0,0 +1,153 @@
+use num::bigint::BigInt;
+use num::ToPrimitive;
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{context_tag, expect_end, implicit, implicit_inner, next_block, sequence};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use oid::ObjectIdentifier;
+use oids;
+
+// SkipCerts ::= INTEGER (0..MAX)
+fn skip_certs(b: &ASN1Block, field: &'static str) -> Result<u64, X509DecodeErr> {
+    match *b {
+        ASN1Block::Integer(_, ref n) => n.to_u64().ok_or(X509DecodeErr::InvalidField(field)),
+        _ => Err(X509DecodeErr::InvalidField(field)),
+    }
+}
+
+/// Limits on policy processing in the certification path below a CA
+/// (RFC 5280 §4.2.1.11). Each value is the number of further certificates
+/// in the path after which the constraint takes effect.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolicyConstraints {
+    pub require_explicit_policy: Option<u64>,
+    pub inhibit_policy_mapping: Option<u64>,
+}
+
+impl X509Extension for PolicyConstraints {
+    const OID: ObjectIdentifier = oids::POLICY_CONSTRAINTS;
+}
+
+impl ToASN1 for PolicyConstraints {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        if let Some(skip) = self.require_explicit_policy {
+            items.push(implicit(0, &ASN1Block::Integer(0, BigInt::from(skip)))?);
+        }
+        if let Some(skip) = self.inhibit_policy_mapping {
+            items.push(implicit(1, &ASN1Block::Integer(0, BigInt::from(skip)))?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for PolicyConstraints {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "PolicyConstraints")?;
+        let mut rest = sequence(head, "PolicyConstraints")?;
+        // RFC 5280 forbids an empty sequence here.
+        if rest.is_empty() {
+            return Err(X509DecodeErr::InvalidField("PolicyConstraints"));
+        }
+        let mut constraints = PolicyConstraints::default();
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(0)) {
+            let n = implicit_inner(block, 0, 0x02).ok_or(X509DecodeErr::InvalidField("requireExplicitPolicy"))?;
+            constraints.require_explicit_policy = Some(skip_certs(&n, "requireExplicitPolicy")?);
+            rest = remaining;
+        }
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(1)) {
+            let n = implicit_inner(block, 1, 0x02).ok_or(X509DecodeErr::InvalidField("inhibitPolicyMapping"))?;
+            constraints.inhibit_policy_mapping = Some(skip_certs(&n, "inhibitPolicyMapping")?);
+            rest = remaining;
+        }
+        expect_end(rest, "PolicyConstraints")?;
+        Ok((constraints, tail))
+    }
+}
+
+/// The number of further certificates in the path after which anyPolicy
+/// no longer matches every policy (RFC 5280 §4.2.1.14).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InhibitAnyPolicy(pub u64);
+
+impl X509Extension for InhibitAnyPolicy {
+    const OID: ObjectIdentifier = oids::INHIBIT_ANY_POLICY;
+}
+
+impl ToASN1 for InhibitAnyPolicy {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![ASN1Block::Integer(0, BigInt::from(self.0))])
+    }
+}
+
+impl FromASN1 for InhibitAnyPolicy {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "InhibitAnyPolicy")?;
+        Ok((InhibitAnyPolicy(skip_certs(head, "InhibitAnyPolicy")?), tail))
+    }
+}
+
+#[cfg(test)]
+mod policy_constraints_tests {
+    use simple_asn1::der_encode;
+
+    use super::{InhibitAnyPolicy, PolicyConstraints};
+    use der::der_decode;
+    use error::X509DecodeErr;
+
+    macro_rules! round_trip_test {
+        ($name:ident, $t:ty, $der:expr, $expected:expr) => {
+            #[test]
+            fn $name() {
+                let der: &[u8] = $der;
+                let expected: $t = $expected;
+                assert_eq!(expected, der_decode::<$t>(der).unwrap());
+                assert_eq!(der.to_vec(), der_encode(&expected).unwrap());
+            }
+        };
+    }
+
+    round_trip_test!(
+        policy_constraints_both,
+        PolicyConstraints,
+        &[0x30, 0x06, 0x80, 0x01, 0x00, 0x81, 0x01, 0x02],
+        PolicyConstraints { require_explicit_policy: Some(0), inhibit_policy_mapping: Some(2) }
+    );
+    round_trip_test!(
+        policy_constraints_inhibit_mapping_only,
+        PolicyConstraints,
+        &[0x30, 0x03, 0x81, 0x01, 0x01],
+        PolicyConstraints { require_explicit_policy: None, inhibit_policy_mapping: Some(1) }
+    );
+    round_trip_test!(inhibit_any_policy_zero, InhibitAnyPolicy, &[0x02, 0x01, 0x00], InhibitAnyPolicy(0));
+
+    #[test]
+    fn policy_constraints_rejects_empty() {
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("PolicyConstraints")),
+            der_decode::<PolicyConstraints>(&[0x30, 0x00])
+        );
+    }
+
+    #[test]
+    fn policy_constraints_rejects_negative() {
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("requireExplicitPolicy")),
+            der_decode::<PolicyConstraints>(&[0x30, 0x03, 0x80, 0x01, 0xFF])
+        );
+    }
+
+    #[test]
+    fn inhibit_any_policy_rejects_negative() {
+        assert_eq!(Err(X509DecodeErr::InvalidField("InhibitAnyPolicy")), der_decode::<InhibitAnyPolicy>(&[0x02, 0x01, 0xFF]));
+    }
+}
@@ -0,0 +1,125 @@
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{expect_end, next_block, sequence};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use oid::ObjectIdentifier;
+use oids;
+
+/// A statement that the issuer's `issuer_domain_policy` is equivalent to
+/// the subject's `subject_domain_policy`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolicyMapping {
+    pub issuer_domain_policy: ObjectIdentifier,
+    pub subject_domain_policy: ObjectIdentifier,
+}
+
+/// Policy equivalences between a CA and the CA it certifies (RFC 5280
+/// §4.2.1.5), as used when cross-certifying between PKIs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolicyMappings(pub Vec<PolicyMapping>);
+
+impl PolicyMappings {
+    /// The subject domain policies `issuer_domain_policy` maps to.
+    pub fn subject_policies<'a>(
+        &'a self,
+        issuer_domain_policy: &'a ObjectIdentifier,
+    ) -> impl Iterator<Item = &'a ObjectIdentifier> + 'a {
+        self.0
+            .iter()
+            .filter(move |m| m.issuer_domain_policy == *issuer_domain_policy)
+            .map(|m| &m.subject_domain_policy)
+    }
+
+    /// Whether anyPolicy appears on either side of a mapping, which RFC
+    /// 5280 forbids.
+    pub fn maps_any_policy(&self) -> bool {
+        self.0.iter().any(|m| m.issuer_domain_policy == oids::ANY_POLICY || m.subject_domain_policy == oids::ANY_POLICY)
+    }
+}
+
+impl X509Extension for PolicyMappings {
+    const OID: ObjectIdentifier = oids::POLICY_MAPPINGS;
+}
+
+impl ToASN1 for PolicyMappings {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let items = self
+            .0
+            .iter()
+            .map(|m| ASN1Block::Sequence(0, vec![m.issuer_domain_policy.to_block(), m.subject_domain_policy.to_block()]))
+            .collect();
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for PolicyMappings {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "PolicyMappings")?;
+        let items = sequence(head, "PolicyMappings")?;
+        // SIZE (1..MAX)
+        if items.is_empty() {
+            return Err(X509DecodeErr::InvalidField("PolicyMappings"));
+        }
+        let mut mappings = Vec::new();
+        for item in items {
+            let pair = sequence(item, "PolicyMapping")?;
+            let (issuer, rest) = next_block(pair, "issuerDomainPolicy")?;
+            let (subject, rest) = next_block(rest, "subjectDomainPolicy")?;
+            expect_end(rest, "PolicyMapping")?;
+            mappings.push(PolicyMapping {
+                issuer_domain_policy: ObjectIdentifier::from_block(issuer, "issuerDomainPolicy")?,
+                subject_domain_policy: ObjectIdentifier::from_block(subject, "subjectDomainPolicy")?,
+            });
+        }
+        Ok((PolicyMappings(mappings), tail))
+    }
+}
+
+#[cfg(test)]
+mod policy_mappings_tests {
+    use simple_asn1::der_encode;
+
+    use super::{PolicyMapping, PolicyMappings};
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use oids;
+
+    // 1.2.3.1 -> 1.2.4.1, 1.2.3.1 -> 1.2.4.2
+    const MAPPINGS: &[u8] = &[
+        0x30, 0x18, 0x30, 0x0A, 0x06, 0x03, 0x2A, 0x03, 0x01, 0x06, 0x03, 0x2A, 0x04, 0x01, 0x30, 0x0A,
+        0x06, 0x03, 0x2A, 0x03, 0x01, 0x06, 0x03, 0x2A, 0x04, 0x02,
+    ];
+
+    #[test]
+    fn policy_mappings_decodes() {
+        let actual = der_decode::<PolicyMappings>(MAPPINGS).unwrap();
+        let issuer = oid!(1, 2, 3, 1);
+        assert_eq!(vec![&oid!(1, 2, 4, 1), &oid!(1, 2, 4, 2)], actual.subject_policies(&issuer).collect::<Vec<_>>());
+        assert!(!actual.maps_any_policy());
+    }
+
+    #[test]
+    fn policy_mappings_round_trips() {
+        let decoded = der_decode::<PolicyMappings>(MAPPINGS).unwrap();
+        assert_eq!(MAPPINGS.to_vec(), der_encode(&decoded).unwrap());
+    }
+
+    #[test]
+    fn policy_mappings_detects_any_policy() {
+        let mappings = PolicyMappings(vec![PolicyMapping {
+            issuer_domain_policy: oids::ANY_POLICY,
+            subject_domain_policy: oid!(1, 2, 4, 1),
+        }]);
+        assert!(mappings.maps_any_policy());
+    }
+
+    #[test]
+    fn policy_mappings_rejects_empty() {
+        assert_eq!(Err(X509DecodeErr::InvalidField("PolicyMappings")), der_decode::<PolicyMappings>(&[0x30, 0x00]));
+    }
+}
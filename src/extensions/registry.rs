@@ -0,0 +1,213 @@
+use std::any::Any;
+use std::fmt;
+
+use error::X509DecodeErr;
+use extensions::{
+    AuthorityInfoAccess, AuthorityKeyIdentifier, BasicConstraints, CRLDistributionPoints, CRLNumber, CRLReason,
+    CertificateIssuer, CertificatePolicies, ExtendedKeyUsage, Extension, FreshestCRL, InhibitAnyPolicy,
+    InvalidityDate, IssuerAltName, IssuingDistributionPoint, KeyUsage, NameConstraints, PolicyConstraints,
+    PolicyMappings, SubjectAltName, SubjectDirectoryAttributes, SubjectInfoAccess, SubjectKeyIdentifier,
+    X509Extension,
+};
+use oid::ObjectIdentifier;
+
+type Decoder = fn(&Extension) -> Result<Box<dyn Any>, X509DecodeErr>;
+
+fn decode_any<T: X509Extension + 'static>(extension: &Extension) -> Result<Box<dyn Any>, X509DecodeErr> {
+    extension.decode::<T>().map(|value| Box::new(value) as Box<dyn Any>)
+}
+
+macro_rules! builtin {
+    ($($t:ty),* $(,)*) => {
+        /// The extension types this crate has a type for.
+        pub(crate) static BUILTIN: &[(ObjectIdentifier, Decoder)] = &[$((<$t>::OID, decode_any::<$t>)),*];
+    };
+}
+
+builtin![
+    AuthorityInfoAccess,
+    AuthorityKeyIdentifier,
+    BasicConstraints,
+    CertificateIssuer,
+    CertificatePolicies,
+    CRLDistributionPoints,
+    CRLNumber,
+    CRLReason,
+    ExtendedKeyUsage,
+    FreshestCRL,
+    InhibitAnyPolicy,
+    InvalidityDate,
+    IssuerAltName,
+    IssuingDistributionPoint,
+    KeyUsage,
+    NameConstraints,
+    PolicyConstraints,
+    PolicyMappings,
+    SubjectAltName,
+    SubjectDirectoryAttributes,
+    SubjectInfoAccess,
+    SubjectKeyIdentifier,
+];
+
+/// The extension types to decode when the type isn't known until run
+/// time, keyed by OID.
+///
+/// Every extension type this crate defines is registered from the start;
+/// downstream crates add their own with `register`:
+///
+/// ```
+/// # extern crate simple_asn1;
+/// # extern crate x509;
+/// # use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+/// # use x509::error::X509DecodeErr;
+/// use x509::extensions::{Extensions, ExtensionRegistry, X509Extension};
+/// use x509::oid::ObjectIdentifier;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Tier(bool);
+///
+/// impl X509Extension for Tier {
+///     const OID: ObjectIdentifier = ObjectIdentifier::from_static(&[1, 3, 6, 1, 4, 1, 99999, 7]);
+/// }
+/// # impl ToASN1 for Tier {
+/// #     type Error = ASN1EncodeErr;
+/// #     fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, ASN1EncodeErr> {
+/// #         Ok(vec![ASN1Block::Boolean(0, self.0)])
+/// #     }
+/// # }
+/// # impl FromASN1 for Tier {
+/// #     type Error = X509DecodeErr;
+/// #     fn from_asn1(v: &[ASN1Block]) -> Result<(Tier, &[ASN1Block]), X509DecodeErr> {
+/// #         match v.split_first() {
+/// #             Some((&ASN1Block::Boolean(_, b), rest)) => Ok((Tier(b), rest)),
+/// #             _ => Err(X509DecodeErr::InvalidField("Tier")),
+/// #         }
+/// #     }
+/// # }
+///
+/// # fn main() {
+/// let mut registry = ExtensionRegistry::new();
+/// registry.register::<Tier>();
+///
+/// let mut extensions = Extensions::new();
+/// extensions.insert(&Tier(true), false).unwrap();
+/// let value = extensions.get_registered(&Tier::OID, &registry).unwrap().unwrap();
+/// assert_eq!(Some(&Tier(true)), value.downcast_ref::<Tier>());
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct ExtensionRegistry {
+    custom: Vec<(ObjectIdentifier, Decoder)>,
+}
+
+impl ExtensionRegistry {
+    pub fn new() -> ExtensionRegistry {
+        ExtensionRegistry { custom: Vec::new() }
+    }
+
+    /// Decode extensions with `T::OID` as `T`, replacing any type already
+    /// registered for that OID.
+    pub fn register<T: X509Extension + 'static>(&mut self) {
+        self.custom.retain(|(oid, _)| *oid != T::OID);
+        self.custom.push((T::OID, decode_any::<T>));
+    }
+
+    /// Whether a type is registered for `extn_id`.
+    pub fn is_registered(&self, extn_id: &ObjectIdentifier) -> bool {
+        self.decoder(extn_id).is_some()
+    }
+
+    // Whether a type was registered for `extn_id` with `register`, rather
+    // than being one of the crate's own.
+    pub(crate) fn is_custom(&self, extn_id: &ObjectIdentifier) -> bool {
+        self.custom.iter().any(|(oid, _)| oid == extn_id)
+    }
+
+    /// Decode `extension` as the type registered for its OID, or `None` if
+    /// there isn't one. The value downcasts to that type.
+    pub fn decode(&self, extension: &Extension) -> Option<Result<Box<dyn Any>, X509DecodeErr>> {
+        self.decoder(&extension.extn_id).map(|decode| decode(extension))
+    }
+
+    fn decoder(&self, extn_id: &ObjectIdentifier) -> Option<Decoder> {
+        self.custom.iter().chain(BUILTIN).find(|&(oid, _)| oid == extn_id).map(|&(_, decode)| decode)
+    }
+}
+
+impl fmt::Debug for ExtensionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.custom.iter().map(|(oid, _)| oid)).finish()
+    }
+}
+
+#[cfg(test)]
+mod registry_tests {
+    use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+    use super::ExtensionRegistry;
+    use der::next_block;
+    use error::X509DecodeErr;
+    use extensions::{BasicConstraints, Extension, Extensions, X509Extension};
+    use oid::ObjectIdentifier;
+
+    #[derive(Debug, PartialEq)]
+    struct Label(String);
+
+    impl ToASN1 for Label {
+        type Error = ASN1EncodeErr;
+
+        fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+            Ok(vec![ASN1Block::UTF8String(0, self.0.clone())])
+        }
+    }
+
+    impl FromASN1 for Label {
+        type Error = X509DecodeErr;
+
+        fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+            match next_block(v, "Label")? {
+                (ASN1Block::UTF8String(_, s), rest) => Ok((Label(s.clone()), rest)),
+                _ => Err(X509DecodeErr::InvalidField("Label")),
+            }
+        }
+    }
+
+    impl X509Extension for Label {
+        const OID: ObjectIdentifier = oid!(1, 3, 6, 1, 4, 1, 99999, 2, 2);
+    }
+
+    #[test]
+    fn registry_decodes_builtin_types() {
+        let extension = Extension::new(&BasicConstraints::ca(Some(0)), true).unwrap();
+        let value = ExtensionRegistry::new().decode(&extension).unwrap().unwrap();
+        assert_eq!(Some(&BasicConstraints::ca(Some(0))), value.downcast_ref::<BasicConstraints>());
+    }
+
+    #[test]
+    fn registry_decodes_registered_types() {
+        let mut registry = ExtensionRegistry::new();
+        assert!(!registry.is_registered(&Label::OID));
+        registry.register::<Label>();
+        assert!(registry.is_registered(&Label::OID));
+
+        let mut extensions = Extensions::new();
+        extensions.insert(&Label("gold".to_string()), false).unwrap();
+        let value = extensions.get_registered(&Label::OID, &registry).unwrap().unwrap();
+        assert_eq!(Some(&Label("gold".to_string())), value.downcast_ref::<Label>());
+    }
+
+    #[test]
+    fn registry_skips_unregistered_types() {
+        let mut extensions = Extensions::new();
+        extensions.insert(&Label("gold".to_string()), false).unwrap();
+        assert!(extensions.get_registered(&Label::OID, &ExtensionRegistry::new()).unwrap().is_none());
+    }
+
+    #[test]
+    fn registry_reports_decode_errors() {
+        let mut registry = ExtensionRegistry::new();
+        registry.register::<Label>();
+        let extension = Extension::raw(Label::OID, false, vec![0x02, 0x01, 0x00]);
+        assert_eq!(Some(X509DecodeErr::InvalidField("Label")), registry.decode(&extension).unwrap().err());
+    }
+}
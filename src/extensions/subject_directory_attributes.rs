@@ -0,0 +1,121 @@
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+use time::PrimitiveDateTime;
+
+use attribute::Attribute;
+use der::{next_block, sequence};
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use oid::ObjectIdentifier;
+use oids;
+
+/// Identification attributes of the subject (RFC 5280 §4.2.1.8), such as
+/// the dateOfBirth and countryOfCitizenship attributes of RFC 3739 that
+/// national eID certificates carry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubjectDirectoryAttributes(pub Vec<Attribute>);
+
+impl SubjectDirectoryAttributes {
+    /// The attribute of the given type, if present.
+    pub fn get(&self, attr_type: &ObjectIdentifier) -> Option<&Attribute> {
+        self.0.iter().find(|a| a.attr_type == *attr_type)
+    }
+
+    /// The dateOfBirth attribute, if present and a GeneralizedTime.
+    pub fn date_of_birth(&self) -> Option<PrimitiveDateTime> {
+        match self.get(&oids::DATE_OF_BIRTH)?.values.first() {
+            Some(&ASN1Block::GeneralizedTime(_, t)) => Some(t),
+            _ => None,
+        }
+    }
+
+    /// The values of every countryOfCitizenship attribute.
+    pub fn countries_of_citizenship(&self) -> impl Iterator<Item = &str> {
+        self.0
+            .iter()
+            .filter(|a| a.attr_type == oids::COUNTRY_OF_CITIZENSHIP)
+            .flat_map(|a| a.values.iter())
+            .filter_map(|v| match *v {
+                ASN1Block::PrintableString(_, ref s) => Some(s.as_str()),
+                _ => None,
+            })
+    }
+}
+
+impl X509Extension for SubjectDirectoryAttributes {
+    const OID: ObjectIdentifier = oids::SUBJECT_DIRECTORY_ATTRIBUTES;
+}
+
+impl ToASN1 for SubjectDirectoryAttributes {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        for attribute in &self.0 {
+            items.extend(attribute.to_asn1_class(c)?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for SubjectDirectoryAttributes {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "SubjectDirectoryAttributes")?;
+        let mut items = sequence(head, "SubjectDirectoryAttributes")?;
+        // SIZE (1..MAX)
+        if items.is_empty() {
+            return Err(X509DecodeErr::InvalidField("SubjectDirectoryAttributes"));
+        }
+        let mut attributes = Vec::new();
+        while !items.is_empty() {
+            let (attribute, rest) = Attribute::from_asn1(items)?;
+            attributes.push(attribute);
+            items = rest;
+        }
+        Ok((SubjectDirectoryAttributes(attributes), tail))
+    }
+}
+
+#[cfg(test)]
+mod subject_directory_attributes_tests {
+    use simple_asn1::{der_encode, ASN1Block};
+    use time::{Date, Month, PrimitiveDateTime, Time};
+
+    use super::SubjectDirectoryAttributes;
+    use attribute::Attribute;
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use oids;
+
+    fn eid() -> SubjectDirectoryAttributes {
+        let born = PrimitiveDateTime::new(Date::from_calendar_date(1980, Month::May, 17).unwrap(), Time::MIDNIGHT);
+        SubjectDirectoryAttributes(vec![
+            Attribute::new(oids::DATE_OF_BIRTH, vec![ASN1Block::GeneralizedTime(0, born)]),
+            Attribute::new(oids::COUNTRY_OF_CITIZENSHIP, vec![ASN1Block::PrintableString(0, "CA".to_string())]),
+        ])
+    }
+
+    #[test]
+    fn subject_directory_attributes_accessors() {
+        let attributes = eid();
+        let born = attributes.date_of_birth().unwrap();
+        assert_eq!((1980, Month::May, 17), (born.year(), born.month(), born.day()));
+        assert_eq!(vec!["CA"], attributes.countries_of_citizenship().collect::<Vec<_>>());
+        assert!(attributes.get(&oids::GENDER).is_none());
+    }
+
+    #[test]
+    fn subject_directory_attributes_round_trips() {
+        let encoded = der_encode(&eid()).unwrap();
+        assert_eq!(eid(), der_decode::<SubjectDirectoryAttributes>(&encoded).unwrap());
+    }
+
+    #[test]
+    fn subject_directory_attributes_rejects_empty() {
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("SubjectDirectoryAttributes")),
+            der_decode::<SubjectDirectoryAttributes>(&[0x30, 0x00])
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{
+    context_tag, explicit, explicit_inner, implicit, implicit_bytes, implicit_inner, implicit_primitive, next_block,
+    sequence,
+};
+use error::X509DecodeErr;
+use name::Name;
+use oid::ObjectIdentifier;
+
+/// A name in one of the forms RFC 5280 §4.2.1.6 allows.
+///
+/// x400Address and ediPartyName are rare enough that they are kept as the
+/// raw blocks they were decoded from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeneralName {
+    OtherName(ObjectIdentifier, ASN1Block),
+    RFC822Name(String),
+    DNSName(String),
+    X400Address(ASN1Block),
+    DirectoryName(Name),
+    EDIPartyName(ASN1Block),
+    URI(String),
+    /// Four octets for IPv4 or sixteen for IPv6. In name constraints the
+    /// address is followed by a mask of the same length.
+    IPAddress(Vec<u8>),
+    RegisteredID(ObjectIdentifier),
+}
+
+impl GeneralName {
+    /// The address of an iPAddress name, if it is four or sixteen octets.
+    pub fn ip_addr(&self) -> Option<IpAddr> {
+        match *self {
+            GeneralName::IPAddress(ref bytes) if bytes.len() == 4 => {
+                Some(IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])))
+            }
+            GeneralName::IPAddress(ref bytes) if bytes.len() == 16 => {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(bytes);
+                Some(IpAddr::V6(Ipv6Addr::from(octets)))
+            }
+            _ => None,
+        }
+    }
+
+    fn ia5(b: &ASN1Block, tag: u64) -> Result<String, X509DecodeErr> {
+        match implicit_bytes(b, tag) {
+            Some(body) if body.is_ascii() => Ok(body.iter().map(|&c| c as char).collect()),
+            _ => Err(X509DecodeErr::InvalidField("GeneralName")),
+        }
+    }
+}
+
+impl From<IpAddr> for GeneralName {
+    fn from(addr: IpAddr) -> GeneralName {
+        match addr {
+            IpAddr::V4(addr) => GeneralName::IPAddress(addr.octets().to_vec()),
+            IpAddr::V6(addr) => GeneralName::IPAddress(addr.octets().to_vec()),
+        }
+    }
+}
+
+impl ToASN1 for GeneralName {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let block = match *self {
+            GeneralName::OtherName(ref type_id, ref value) => {
+                let other = ASN1Block::Sequence(0, vec![type_id.to_block(), explicit(0, value.clone())]);
+                implicit(0, &other)?
+            }
+            GeneralName::RFC822Name(ref s) => implicit_primitive(1, s.as_bytes().to_vec()),
+            GeneralName::DNSName(ref s) => implicit_primitive(2, s.as_bytes().to_vec()),
+            GeneralName::X400Address(ref raw) | GeneralName::EDIPartyName(ref raw) => raw.clone(),
+            // Name is a CHOICE, so its tag is EXPLICIT.
+            GeneralName::DirectoryName(ref name) => explicit(4, name.to_asn1_class(c)?.remove(0)),
+            GeneralName::URI(ref s) => implicit_primitive(6, s.as_bytes().to_vec()),
+            GeneralName::IPAddress(ref bytes) => implicit_primitive(7, bytes.clone()),
+            GeneralName::RegisteredID(ref oid) => implicit(8, &oid.to_block())?,
+        };
+        Ok(vec![block])
+    }
+}
+
+impl FromASN1 for GeneralName {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "GeneralName")?;
+        let invalid = X509DecodeErr::InvalidField("GeneralName");
+        let name = match context_tag(head) {
+            Some(0) => {
+                let other = implicit_inner(head, 0, 0x10).ok_or(invalid)?;
+                let items = sequence(&other, "OtherName")?;
+                match *items {
+                    [ref type_id, ref value] => {
+                        let type_id = ObjectIdentifier::from_block(type_id, "type-id")?;
+                        let value = explicit_inner(value, 0).ok_or(X509DecodeErr::InvalidField("OtherName"))?;
+                        GeneralName::OtherName(type_id, value.clone())
+                    }
+                    _ => return Err(X509DecodeErr::InvalidField("OtherName")),
+                }
+            }
+            Some(1) => GeneralName::RFC822Name(GeneralName::ia5(head, 1)?),
+            Some(2) => GeneralName::DNSName(GeneralName::ia5(head, 2)?),
+            Some(3) => GeneralName::X400Address(head.clone()),
+            Some(4) => {
+                let inner = explicit_inner(head, 4).ok_or(invalid)?;
+                let (name, _) = Name::from_asn1(::std::slice::from_ref(inner))?;
+                GeneralName::DirectoryName(name)
+            }
+            Some(5) => GeneralName::EDIPartyName(head.clone()),
+            Some(6) => GeneralName::URI(GeneralName::ia5(head, 6)?),
+            Some(7) => GeneralName::IPAddress(implicit_bytes(head, 7).ok_or(invalid)?.to_vec()),
+            Some(8) => {
+                let oid = implicit_inner(head, 8, 0x06).ok_or(invalid)?;
+                GeneralName::RegisteredID(ObjectIdentifier::from_block(&oid, "GeneralName")?)
+            }
+            _ => return Err(invalid),
+        };
+        Ok((name, tail))
+    }
+}
+
+/// A non-empty list of names, as carried by subjectAltName and
+/// issuerAltName among others.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeneralNames(pub Vec<GeneralName>);
+
+impl GeneralNames {
+    pub fn new(names: Vec<GeneralName>) -> GeneralNames {
+        GeneralNames(names)
+    }
+
+    pub fn iter(&self) -> ::std::slice::Iter<'_, GeneralName> {
+        self.0.iter()
+    }
+
+    pub fn dns_names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().filter_map(|n| match *n {
+            GeneralName::DNSName(ref s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn rfc822_names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().filter_map(|n| match *n {
+            GeneralName::RFC822Name(ref s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn uris(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().filter_map(|n| match *n {
+            GeneralName::URI(ref s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn ip_addrs(&self) -> impl Iterator<Item = IpAddr> + '_ {
+        self.0.iter().filter_map(|n| n.ip_addr())
+    }
+
+    /// Whether `addr` is one of the iPAddress names. Entries that are not
+    /// four or sixteen octets, such as name constraint ranges, match
+    /// nothing. An IPv4-mapped IPv6 address matches the IPv4 address it
+    /// carries, however either side is written.
+    pub fn matches_ip(&self, addr: IpAddr) -> bool {
+        let addr = unmapped(addr);
+        self.ip_addrs().any(|entry| unmapped(entry) == addr)
+    }
+
+    pub fn directory_names(&self) -> impl Iterator<Item = &Name> {
+        self.0.iter().filter_map(|n| match *n {
+            GeneralName::DirectoryName(ref name) => Some(name),
+            _ => None,
+        })
+    }
+}
+
+// `addr`, or the IPv4 address in it if it is IPv4-mapped.
+fn unmapped(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(addr, IpAddr::V4),
+        IpAddr::V4(_) => addr,
+    }
+}
+
+impl ToASN1 for GeneralNames {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        for name in &self.0 {
+            items.extend(name.to_asn1_class(c)?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for GeneralNames {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "GeneralNames")?;
+        let mut items = sequence(head, "GeneralNames")?;
+        // SIZE (1..MAX)
+        if items.is_empty() {
+            return Err(X509DecodeErr::InvalidField("GeneralNames"));
+        }
+        let mut names = Vec::new();
+        while !items.is_empty() {
+            let (name, rest) = GeneralName::from_asn1(items)?;
+            names.push(name);
+            items = rest;
+        }
+        Ok((GeneralNames(names), tail))
+    }
+}
+
+#[cfg(test)]
+mod general_name_tests {
+    use std::net::IpAddr;
+
+    use simple_asn1::{der_encode, ASN1Block};
+
+    use super::{GeneralName, GeneralNames};
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use name::{DirectoryString, Name};
+
+    macro_rules! general_name_test {
+        ($name:ident, $der:expr, $expected:expr) => {
+            #[test]
+            fn $name() {
+                let der: &[u8] = $der;
+                let expected: GeneralName = $expected;
+                assert_eq!(expected, der_decode::<GeneralName>(der).unwrap());
+                assert_eq!(der.to_vec(), der_encode(&expected).unwrap());
+            }
+        };
+    }
+
+    general_name_test!(
+        general_name_dns,
+        &[0x82, 0x0B, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x2E, 0x63, 0x6F, 0x6D],
+        GeneralName::DNSName("example.com".to_string())
+    );
+    general_name_test!(
+        general_name_rfc822,
+        &[0x81, 0x0D, 0x61, 0x40, 0x65, 0x78, 0x61, 0x6D, 0x70, 0x6C, 0x65, 0x2E, 0x63, 0x6F, 0x6D],
+        GeneralName::RFC822Name("a@example.com".to_string())
+    );
+    general_name_test!(
+        general_name_uri,
+        &[0x86, 0x0B, 0x68, 0x74, 0x74, 0x70, 0x3A, 0x2F, 0x2F, 0x61, 0x2E, 0x62, 0x2F],
+        GeneralName::URI("http://a.b/".to_string())
+    );
+    general_name_test!(
+        general_name_ipv4,
+        &[0x87, 0x04, 0xC0, 0x00, 0x02, 0x01],
+        GeneralName::IPAddress(vec![192, 0, 2, 1])
+    );
+    general_name_test!(
+        general_name_registered_id,
+        &[0x88, 0x03, 0x2A, 0x03, 0x04],
+        GeneralName::RegisteredID(oid!(1, 2, 3, 4))
+    );
+    general_name_test!(
+        general_name_other_name,
+        &[
+            0xA0, 0x0C, 0x06, 0x03, 0x2A, 0x03, 0x04, 0xA0, 0x05, 0x0C, 0x03, 0x61, 0x62, 0x63,
+        ],
+        GeneralName::OtherName(oid!(1, 2, 3, 4), ASN1Block::UTF8String(0, "abc".to_string()))
+    );
+    general_name_test!(
+        general_name_directory_name,
+        &[
+            0xA4, 0x0F, 0x30, 0x0D, 0x31, 0x0B, 0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0C, 0x02, 0x43,
+            0x41,
+        ],
+        {
+            let mut name = Name::new();
+            name.push(oid!(2, 5, 4, 3), DirectoryString::UTF8String("CA".to_string()));
+            GeneralName::DirectoryName(name)
+        }
+    );
+
+    #[test]
+    fn general_name_ip_addr() {
+        let v6: IpAddr = "2001:db8::1".parse().unwrap();
+        assert_eq!(Some(v6), GeneralName::from(v6).ip_addr());
+        assert_eq!(None, GeneralName::IPAddress(vec![192, 0, 2, 0, 255, 255, 255, 0]).ip_addr());
+    }
+
+    #[test]
+    fn general_names_matches_ip() {
+        let names = GeneralNames::new(vec![
+            GeneralName::DNSName("192.0.2.1".to_string()),
+            GeneralName::IPAddress(vec![198, 51, 100, 0, 255, 255, 255, 0]),
+            GeneralName::from("2001:db8::1".parse::<IpAddr>().unwrap()),
+            GeneralName::IPAddress(vec![203, 0, 113, 7]),
+        ]);
+        let matches = |addr: &str| names.matches_ip(addr.parse().unwrap());
+        assert!(matches("2001:db8::1"));
+        assert!(matches("2001:0db8:0:0:0:0:0:1"));
+        assert!(matches("203.0.113.7"));
+        assert!(matches("::ffff:203.0.113.7"));
+        assert!(!matches("192.0.2.1"));
+        assert!(!matches("198.51.100.0"));
+        assert!(!matches("::203.0.113.7"));
+
+        let mapped = GeneralNames::new(vec![GeneralName::from("::ffff:192.0.2.1".parse::<IpAddr>().unwrap())]);
+        assert!(mapped.matches_ip("192.0.2.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn general_name_rejects_non_ascii_dns() {
+        assert_eq!(Err(X509DecodeErr::InvalidField("GeneralName")), der_decode::<GeneralName>(&[0x82, 0x01, 0xE9]));
+    }
+
+    #[test]
+    fn general_names_rejects_empty() {
+        assert_eq!(Err(X509DecodeErr::InvalidField("GeneralNames")), der_decode::<GeneralNames>(&[0x30, 0x00]));
+    }
+}
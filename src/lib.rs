@@ -1,5 +1,55 @@
-#[macro_use]
 extern crate simple_asn1;
 extern crate num;
+extern crate getrandom;
+extern crate sha1;
+extern crate sha2;
+extern crate time;
+#[cfg(feature = "verify")]
+extern crate p256;
+#[cfg(feature = "verify")]
+extern crate p384;
+#[cfg(feature = "verify")]
+extern crate rsa;
+#[cfg(feature = "verify")]
+extern crate ed25519_dalek;
+#[cfg(feature = "ring")]
+extern crate ring;
+#[cfg(feature = "pkcs8-encryption")]
+extern crate aes;
+#[cfg(feature = "pkcs8-encryption")]
+extern crate cbc;
+#[cfg(feature = "pkcs8-encryption")]
+extern crate pbkdf2;
+#[cfg(feature = "pkcs12")]
+extern crate hmac;
+
+#[macro_use]
+pub mod oid;
 
-pub mod x509;
\ No newline at end of file
+pub mod algorithm;
+pub mod attribute;
+pub mod attribute_certificate;
+pub mod composite;
+pub mod crl;
+pub mod csr;
+pub mod der;
+pub mod error;
+pub mod extensions;
+pub mod general_name;
+pub mod name;
+pub mod ocsp;
+pub mod oids;
+pub mod path;
+pub mod pem;
+#[cfg(feature = "pkcs12")]
+pub mod pkcs12;
+pub mod pkcs7;
+pub mod pkcs8;
+pub mod profile;
+pub mod revocation;
+pub mod sign;
+pub mod spki;
+pub mod trust_anchor;
+pub mod validity;
+pub mod verify;
+pub mod x509;
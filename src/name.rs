@@ -0,0 +1,421 @@
+use num::bigint::BigUint;
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{expect_end, next_block, sequence};
+use error::X509DecodeErr;
+use oid::ObjectIdentifier;
+use oids;
+
+/// The string types a DN attribute value can be written in. The variant a
+/// value was decoded from is kept so that it re-encodes identically.
+///
+/// IA5String is not formally a DirectoryString choice, but it is what
+/// emailAddress and domainComponent use.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DirectoryString {
+    PrintableString(String),
+    UTF8String(String),
+    IA5String(String),
+    TeletexString(String),
+    BMPString(String),
+    UniversalString(String),
+}
+
+const PRINTABLE_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789'()+,-./:=? ";
+
+impl DirectoryString {
+    /// PrintableString if `s` fits in its character set, else UTF8String,
+    /// as RFC 5280 recommends for new certificates.
+    pub fn new(s: &str) -> DirectoryString {
+        if s.chars().all(|c| PRINTABLE_CHARS.contains(c)) {
+            DirectoryString::PrintableString(s.to_string())
+        } else {
+            DirectoryString::UTF8String(s.to_string())
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match *self {
+            DirectoryString::PrintableString(ref s)
+            | DirectoryString::UTF8String(ref s)
+            | DirectoryString::IA5String(ref s)
+            | DirectoryString::TeletexString(ref s)
+            | DirectoryString::BMPString(ref s)
+            | DirectoryString::UniversalString(ref s) => s,
+        }
+    }
+
+    pub(crate) fn from_block(b: &ASN1Block) -> Option<DirectoryString> {
+        match *b {
+            ASN1Block::PrintableString(_, ref s) => Some(DirectoryString::PrintableString(s.clone())),
+            ASN1Block::UTF8String(_, ref s) => Some(DirectoryString::UTF8String(s.clone())),
+            ASN1Block::IA5String(_, ref s) => Some(DirectoryString::IA5String(s.clone())),
+            ASN1Block::TeletexString(_, ref s) => Some(DirectoryString::TeletexString(s.clone())),
+            ASN1Block::BMPString(_, ref s) => Some(DirectoryString::BMPString(s.clone())),
+            ASN1Block::UniversalString(_, ref s) => Some(DirectoryString::UniversalString(s.clone())),
+            _ => None,
+        }
+    }
+
+    // simple_asn1 writes TeletexString, BMPString and UniversalString out as
+    // UTF-8, so those are encoded by hand.
+    pub(crate) fn to_block(&self) -> ASN1Block {
+        let raw = |tag: u8, body: Vec<u8>| ASN1Block::Unknown(ASN1Class::Universal, false, 0, BigUint::from(tag), body);
+        match *self {
+            DirectoryString::PrintableString(ref s) => ASN1Block::PrintableString(0, s.clone()),
+            DirectoryString::UTF8String(ref s) => ASN1Block::UTF8String(0, s.clone()),
+            DirectoryString::IA5String(ref s) => ASN1Block::IA5String(0, s.clone()),
+            DirectoryString::TeletexString(ref s) => raw(0x14, s.chars().map(|c| c as u8).collect()),
+            DirectoryString::BMPString(ref s) => {
+                raw(0x1E, s.encode_utf16().flat_map(|u| vec![(u >> 8) as u8, u as u8]).collect())
+            }
+            DirectoryString::UniversalString(ref s) => {
+                raw(0x1C, s.chars().flat_map(|c| (c as u32).to_be_bytes().to_vec()).collect())
+            }
+        }
+    }
+}
+
+/// The value of a DN attribute: almost always a string, but anything else
+/// is kept as the block it was decoded from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeValue {
+    String(DirectoryString),
+    Other(ASN1Block),
+}
+
+/// A single attribute of a distinguished name, such as `CN=example.com`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeTypeAndValue {
+    pub attr_type: ObjectIdentifier,
+    pub value: AttributeValue,
+}
+
+impl AttributeTypeAndValue {
+    /// The value as text, if it is a string.
+    pub fn value_str(&self) -> Option<&str> {
+        match self.value {
+            AttributeValue::String(ref s) => Some(s.as_str()),
+            AttributeValue::Other(_) => None,
+        }
+    }
+
+    /// Whether `self` and `other` are the same attribute. Strings are
+    /// compared ignoring case and extra whitespace, a simplification of the
+    /// RFC 4518 rules, whatever type they are encoded as.
+    pub fn matches(&self, other: &AttributeTypeAndValue) -> bool {
+        if self.attr_type != other.attr_type {
+            return false;
+        }
+        match (&self.value, &other.value) {
+            (AttributeValue::String(x), AttributeValue::String(y)) => {
+                let words = |s: &str| s.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>();
+                words(x.as_str()) == words(y.as_str())
+            }
+            (x, y) => x == y,
+        }
+    }
+}
+
+impl ToASN1 for AttributeTypeAndValue {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let value = match self.value {
+            AttributeValue::String(ref s) => s.to_block(),
+            AttributeValue::Other(ref b) => b.clone(),
+        };
+        Ok(vec![ASN1Block::Sequence(0, vec![self.attr_type.to_block(), value])])
+    }
+}
+
+impl FromASN1 for AttributeTypeAndValue {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "AttributeTypeAndValue")?;
+        let items = sequence(head, "AttributeTypeAndValue")?;
+        let (attr_type, rest) = next_block(items, "type")?;
+        let attr_type = ObjectIdentifier::from_block(attr_type, "type")?;
+        let (value, rest) = next_block(rest, "value")?;
+        expect_end(rest, "AttributeTypeAndValue")?;
+        let value = match DirectoryString::from_block(value) {
+            Some(s) => AttributeValue::String(s),
+            None => AttributeValue::Other(value.clone()),
+        };
+        Ok((AttributeTypeAndValue { attr_type, value }, tail))
+    }
+}
+
+/// A SET OF attributes that together form one component of a name. Nearly
+/// every RDN in practice holds a single attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelativeDistinguishedName(pub Vec<AttributeTypeAndValue>);
+
+impl RelativeDistinguishedName {
+    /// Whether the RDNs hold matching attributes, in any order.
+    pub fn matches(&self, other: &RelativeDistinguishedName) -> bool {
+        self.0.len() == other.0.len() && self.0.iter().all(|x| other.0.iter().any(|y| x.matches(y)))
+    }
+}
+
+impl ToASN1 for RelativeDistinguishedName {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        for attribute in &self.0 {
+            items.extend(attribute.to_asn1_class(c)?);
+        }
+        Ok(vec![ASN1Block::Set(0, items)])
+    }
+}
+
+impl FromASN1 for RelativeDistinguishedName {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "RelativeDistinguishedName")?;
+        let mut items = match *head {
+            ASN1Block::Set(_, ref items) if !items.is_empty() => &items[..],
+            _ => return Err(X509DecodeErr::InvalidField("RelativeDistinguishedName")),
+        };
+        let mut attributes = Vec::new();
+        while !items.is_empty() {
+            let (attribute, rest) = AttributeTypeAndValue::from_asn1(items)?;
+            attributes.push(attribute);
+            items = rest;
+        }
+        Ok((RelativeDistinguishedName(attributes), tail))
+    }
+}
+
+/// An X.501 distinguished name, as used for certificate issuers and
+/// subjects. The RDNs are kept in encoding order, most general first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Name(pub Vec<RelativeDistinguishedName>);
+
+impl Name {
+    pub fn new() -> Name {
+        Name(Vec::new())
+    }
+
+    /// Append an RDN holding the single attribute `attr_type=value`.
+    pub fn push(&mut self, attr_type: ObjectIdentifier, value: DirectoryString) {
+        let value = AttributeValue::String(value);
+        self.0.push(RelativeDistinguishedName(vec![AttributeTypeAndValue { attr_type, value }]));
+    }
+
+    /// Whether `self` and `other` are the same name by the comparison of
+    /// RFC 5280 §7.1, which chaining and CRL and OCSP issuers use. Unlike
+    /// `==`, this overlooks the string types the names are encoded in, and
+    /// case and extra whitespace.
+    pub fn matches(&self, other: &Name) -> bool {
+        self.0.len() == other.0.len() && self.0.iter().zip(&other.0).all(|(x, y)| x.matches(y))
+    }
+
+    /// Every attribute in the name, in encoding order.
+    pub fn attributes(&self) -> impl Iterator<Item = &AttributeTypeAndValue> {
+        self.0.iter().flat_map(|rdn| rdn.0.iter())
+    }
+
+    /// The text of every attribute of the given type, in encoding order.
+    pub fn values<'a>(&'a self, attr_type: &ObjectIdentifier) -> impl Iterator<Item = &'a str> + 'a {
+        let attr_type = attr_type.clone();
+        self.attributes()
+            .filter(move |a| a.attr_type == attr_type)
+            .filter_map(|a| a.value_str())
+    }
+
+    fn first(&self, attr_type: &ObjectIdentifier) -> Option<&str> {
+        self.values(attr_type).next()
+    }
+
+    pub fn common_name(&self) -> Option<&str> {
+        self.first(&oids::COMMON_NAME)
+    }
+
+    pub fn serial_number(&self) -> Option<&str> {
+        self.first(&oids::SERIAL_NUMBER)
+    }
+
+    pub fn country(&self) -> Option<&str> {
+        self.first(&oids::COUNTRY_NAME)
+    }
+
+    pub fn locality(&self) -> Option<&str> {
+        self.first(&oids::LOCALITY_NAME)
+    }
+
+    pub fn state_or_province(&self) -> Option<&str> {
+        self.first(&oids::STATE_OR_PROVINCE_NAME)
+    }
+
+    pub fn organization(&self) -> Option<&str> {
+        self.first(&oids::ORGANIZATION_NAME)
+    }
+
+    pub fn organizational_unit(&self) -> Option<&str> {
+        self.first(&oids::ORGANIZATIONAL_UNIT_NAME)
+    }
+}
+
+impl ToASN1 for Name {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        for rdn in &self.0 {
+            items.extend(rdn.to_asn1_class(c)?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for Name {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "Name")?;
+        let mut items = sequence(head, "Name")?;
+        let mut rdns = Vec::new();
+        while !items.is_empty() {
+            let (rdn, rest) = RelativeDistinguishedName::from_asn1(items)?;
+            rdns.push(rdn);
+            items = rest;
+        }
+        Ok((Name(rdns), tail))
+    }
+}
+
+#[cfg(test)]
+mod name_tests {
+    use simple_asn1::{der_encode, ASN1Block};
+
+    use super::{AttributeTypeAndValue, AttributeValue, DirectoryString, Name, RelativeDistinguishedName};
+    use der::der_decode;
+    use error::X509DecodeErr;
+
+    // C=CA, O=QuickLime, CN=Test Root
+    const NAME: &[u8] = &[
+        0x30, 0x35, 0x31, 0x0B, 0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x06, 0x13, 0x02, 0x43, 0x41, 0x31,
+        0x12, 0x30, 0x10, 0x06, 0x03, 0x55, 0x04, 0x0A, 0x0C, 0x09, 0x51, 0x75, 0x69, 0x63, 0x6B, 0x4C,
+        0x69, 0x6D, 0x65, 0x31, 0x12, 0x30, 0x10, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0C, 0x09, 0x54, 0x65,
+        0x73, 0x74, 0x20, 0x52, 0x6F, 0x6F, 0x74,
+    ];
+
+    #[test]
+    fn name_decodes_accessors() {
+        let name = der_decode::<Name>(NAME).unwrap();
+        assert_eq!(3, name.0.len());
+        assert_eq!(Some("CA"), name.country());
+        assert_eq!(Some("QuickLime"), name.organization());
+        assert_eq!(Some("Test Root"), name.common_name());
+        assert_eq!(None, name.organizational_unit());
+    }
+
+    #[test]
+    fn name_round_trips() {
+        let name = der_decode::<Name>(NAME).unwrap();
+        assert_eq!(NAME.to_vec(), der_encode(&name).unwrap());
+    }
+
+    #[test]
+    fn name_encodes_constructed_value() {
+        let mut name = Name::new();
+        name.push(oid!(2, 5, 4, 6), DirectoryString::PrintableString("CA".to_string()));
+        name.push(oid!(2, 5, 4, 10), DirectoryString::UTF8String("QuickLime".to_string()));
+        name.push(oid!(2, 5, 4, 3), DirectoryString::UTF8String("Test Root".to_string()));
+        assert_eq!(NAME.to_vec(), der_encode(&name).unwrap());
+    }
+
+    #[test]
+    fn name_decodes_empty_sequence() {
+        let name = der_decode::<Name>(&[0x30, 0x00]).unwrap();
+        assert_eq!(Name::new(), name);
+        assert_eq!(None, name.common_name());
+    }
+
+    #[test]
+    fn name_decodes_multi_valued_rdn() {
+        let rdn = RelativeDistinguishedName(vec![
+            AttributeTypeAndValue { attr_type: oid!(2, 5, 4, 11), value: AttributeValue::String(DirectoryString::new("Ops")) },
+            AttributeTypeAndValue { attr_type: oid!(2, 5, 4, 3), value: AttributeValue::String(DirectoryString::new("Alice")) },
+        ]);
+        let name = Name(vec![rdn]);
+        let decoded = der_decode::<Name>(&der_encode(&name).unwrap()).unwrap();
+        assert_eq!(name, decoded);
+        assert_eq!(Some("Ops"), decoded.organizational_unit());
+        assert_eq!(Some("Alice"), decoded.common_name());
+    }
+
+    #[test]
+    fn name_matches_ignoring_encoding_and_case() {
+        let name = der_decode::<Name>(NAME).unwrap();
+        let mut other = Name::new();
+        other.push(oid!(2, 5, 4, 6), DirectoryString::UTF8String("ca".to_string()));
+        other.push(oid!(2, 5, 4, 10), DirectoryString::PrintableString("QuickLime".to_string()));
+        other.push(oid!(2, 5, 4, 3), DirectoryString::PrintableString(" test  ROOT".to_string()));
+        assert_ne!(name, other);
+        assert!(name.matches(&other));
+
+        other.0.pop();
+        assert!(!name.matches(&other));
+        other.push(oid!(2, 5, 4, 3), DirectoryString::new("Test Root 2"));
+        assert!(!name.matches(&other));
+    }
+
+    #[test]
+    fn name_rejects_empty_rdn() {
+        let error = der_decode::<Name>(&[0x30, 0x02, 0x31, 0x00]).unwrap_err();
+        assert_eq!(X509DecodeErr::InvalidField("RelativeDistinguishedName"), error);
+    }
+
+    macro_rules! directory_string_test {
+        ($name:ident, $input:expr, $expected:expr) => {
+            #[test]
+            fn $name() {
+                let input: &[u8] = $input;
+                let attribute = der_decode::<AttributeTypeAndValue>(input).unwrap();
+                assert_eq!(AttributeValue::String($expected), attribute.value);
+                assert_eq!(input.to_vec(), der_encode(&attribute).unwrap());
+            }
+        }
+    }
+
+    // CN=<value>, with the value in each of the DirectoryString encodings.
+    directory_string_test!(directory_string_round_trips_printable,
+        &[0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x03, 0x13, 0x02, 0x41, 0x42],
+        DirectoryString::PrintableString("AB".to_string()));
+    directory_string_test!(directory_string_round_trips_utf8,
+        &[0x30, 0x0A, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0C, 0x03, 0x41, 0xC3, 0xA9],
+        DirectoryString::UTF8String("A\u{E9}".to_string()));
+    directory_string_test!(directory_string_round_trips_ia5,
+        &[0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x03, 0x16, 0x02, 0x61, 0x40],
+        DirectoryString::IA5String("a@".to_string()));
+    directory_string_test!(directory_string_round_trips_teletex,
+        &[0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x03, 0x14, 0x02, 0x41, 0xE9],
+        DirectoryString::TeletexString("A\u{E9}".to_string()));
+    directory_string_test!(directory_string_round_trips_bmp,
+        &[0x30, 0x0B, 0x06, 0x03, 0x55, 0x04, 0x03, 0x1E, 0x04, 0x00, 0x41, 0x04, 0x1F],
+        DirectoryString::BMPString("A\u{41F}".to_string()));
+    directory_string_test!(directory_string_round_trips_universal,
+        &[0x30, 0x0F, 0x06, 0x03, 0x55, 0x04, 0x03, 0x1C, 0x08, 0x00, 0x00, 0x00, 0x41, 0x00, 0x00, 0x00, 0xE9],
+        DirectoryString::UniversalString("A\u{E9}".to_string()));
+
+    #[test]
+    fn directory_string_new_prefers_printable() {
+        assert_eq!(DirectoryString::PrintableString("Test Root".to_string()), DirectoryString::new("Test Root"));
+        assert_eq!(DirectoryString::UTF8String("a@b".to_string()), DirectoryString::new("a@b"));
+    }
+
+    #[test]
+    fn attribute_keeps_non_string_values() {
+        // x500UniqueIdentifier is a BIT STRING.
+        let input = [0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x2D, 0x03, 0x02, 0x00, 0xAA];
+        let attribute = der_decode::<AttributeTypeAndValue>(&input).unwrap();
+        assert_eq!(AttributeValue::Other(ASN1Block::BitString(0, 8, vec![0xAA])), attribute.value);
+        assert_eq!(None, attribute.value_str());
+        assert_eq!(input.to_vec(), der_encode(&attribute).unwrap());
+    }
+}
@@ -0,0 +1,238 @@
+//! The Online Certificate Status Protocol (RFC 6960).
+//!
+//! This covers the messages only. Requests are sent to the responder
+//! URLs from `Certificate::ocsp_urls` with the transport of the caller's
+//! choosing, usually an HTTP POST of the DER with `REQUEST_CONTENT_TYPE`.
+//! The `OCSPResponse` that comes back is checked with
+//! `BasicOCSPResponse::validate`.
+
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use algorithm::AlgorithmIdentifier;
+use der::{context_tag, expect_end, explicit_inner, next_block, sequence};
+use error::X509DecodeErr;
+use extensions::Extensions;
+use name::Name;
+use oids;
+use spki::SubjectPublicKeyInfo;
+use x509::{Certificate, CertificateSerialNumber};
+
+mod nonce;
+mod request;
+mod responder;
+mod response;
+
+pub use self::nonce::{Nonce, NoncePolicy};
+pub use self::request::{OCSPRequest, Request, Signature, TBSRequest};
+pub use self::responder::Responder;
+pub use self::response::{
+    BasicOCSPResponse, CertStatus, OCSPError, OCSPResponse, OCSPResponseStatus, ResponderID, ResponseBytes,
+    ResponseData, RevokedInfo, SingleResponse,
+};
+
+/// The media type of a DER OCSPRequest sent over HTTP.
+pub const REQUEST_CONTENT_TYPE: &str = "application/ocsp-request";
+/// The media type of a DER OCSPResponse sent over HTTP.
+pub const RESPONSE_CONTENT_TYPE: &str = "application/ocsp-response";
+
+/// The hash a `CertID` identifies the issuer with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CertIDHash {
+    /// SHA-1, which every responder accepts and many require.
+    Sha1,
+    Sha256,
+}
+
+impl CertIDHash {
+    fn algorithm(self) -> AlgorithmIdentifier {
+        match self {
+            CertIDHash::Sha1 => AlgorithmIdentifier::with_null_parameters(oids::SHA1),
+            CertIDHash::Sha256 => AlgorithmIdentifier::with_null_parameters(oids::SHA256),
+        }
+    }
+}
+
+// The hash of `data` with the digest `algorithm`, if it's one we have.
+fn digest(algorithm: &AlgorithmIdentifier, data: &[u8]) -> Option<Vec<u8>> {
+    let oid = &algorithm.algorithm;
+    if *oid == oids::SHA1 {
+        Some(Sha1::digest(data).to_vec())
+    } else if *oid == oids::SHA256 {
+        Some(Sha256::digest(data).to_vec())
+    } else if *oid == oids::SHA384 {
+        Some(Sha384::digest(data).to_vec())
+    } else if *oid == oids::SHA512 {
+        Some(Sha512::digest(data).to_vec())
+    } else {
+        None
+    }
+}
+
+/// Identifies a certificate by its issuer and serial number (RFC 6960
+/// §4.1.1). The issuer is given by hashes of its name and of its public
+/// key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertID {
+    pub hash_algorithm: AlgorithmIdentifier,
+    pub issuer_name_hash: Vec<u8>,
+    pub issuer_key_hash: Vec<u8>,
+    pub serial_number: CertificateSerialNumber,
+}
+
+impl CertID {
+    /// The identifier of the certificate with `serial_number` issued by the
+    /// CA named `issuer_name` with key `issuer_key`.
+    pub fn new(
+        hash: CertIDHash,
+        issuer_name: &Name,
+        issuer_key: &SubjectPublicKeyInfo,
+        serial_number: &CertificateSerialNumber,
+    ) -> Result<CertID, ASN1EncodeErr> {
+        let hash_algorithm = hash.algorithm();
+        // Both hashes are over SHA-1 or SHA-256, which `digest` has.
+        let issuer_name_hash = digest(&hash_algorithm, &der_encode(issuer_name)?).unwrap();
+        let issuer_key_hash = digest(&hash_algorithm, &issuer_key.subject_public_key).unwrap();
+        Ok(CertID { hash_algorithm, issuer_name_hash, issuer_key_hash, serial_number: serial_number.clone() })
+    }
+
+    /// The identifier of `cert`, which `issuer` issued.
+    pub fn for_certificate(
+        hash: CertIDHash,
+        cert: &Certificate,
+        issuer: &Certificate,
+    ) -> Result<CertID, ASN1EncodeErr> {
+        let issuer = &issuer.tbs_certificate;
+        CertID::new(hash, &issuer.subject, &issuer.subject_public_key_info, &cert.tbs_certificate.serial_number)
+    }
+
+    /// Whether this identifies the certificate with `serial_number` issued
+    /// by the CA named `issuer_name` with key `issuer_key`. An identifier
+    /// hashed with an unknown algorithm matches nothing.
+    pub fn matches(
+        &self,
+        issuer_name: &Name,
+        issuer_key: &SubjectPublicKeyInfo,
+        serial_number: &CertificateSerialNumber,
+    ) -> bool {
+        let name = match der_encode(issuer_name) {
+            Ok(name) => name,
+            Err(_) => return false,
+        };
+        self.serial_number == *serial_number
+            && digest(&self.hash_algorithm, &name).as_ref() == Some(&self.issuer_name_hash)
+            && digest(&self.hash_algorithm, &issuer_key.subject_public_key).as_ref() == Some(&self.issuer_key_hash)
+    }
+}
+
+impl ToASN1 for CertID {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.hash_algorithm.to_asn1_class(c)?;
+        items.push(ASN1Block::OctetString(0, self.issuer_name_hash.clone()));
+        items.push(ASN1Block::OctetString(0, self.issuer_key_hash.clone()));
+        items.extend(self.serial_number.to_asn1_class(c)?);
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for CertID {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "CertID")?;
+        let items = sequence(head, "CertID")?;
+        let (hash_algorithm, rest) = AlgorithmIdentifier::from_asn1(items)?;
+        let (issuer_name_hash, rest) = match next_block(rest, "issuerNameHash")? {
+            (ASN1Block::OctetString(_, bytes), rest) => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("issuerNameHash")),
+        };
+        let (issuer_key_hash, rest) = match next_block(rest, "issuerKeyHash")? {
+            (ASN1Block::OctetString(_, bytes), rest) => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("issuerKeyHash")),
+        };
+        let (serial_number, rest) = CertificateSerialNumber::from_asn1(rest)?;
+        expect_end(rest, "CertID")?;
+        Ok((CertID { hash_algorithm, issuer_name_hash, issuer_key_hash, serial_number }, tail))
+    }
+}
+
+// An EXPLICIT-tagged Extensions, if it's next.
+fn explicit_extensions<'a>(
+    v: &'a [ASN1Block],
+    tag: u64,
+    field: &'static str,
+) -> Result<(Option<Extensions>, &'a [ASN1Block]), X509DecodeErr> {
+    match v.split_first() {
+        Some((block, rest)) if context_tag(block) == Some(tag) => {
+            let inner = explicit_inner(block, tag).ok_or(X509DecodeErr::InvalidField(field))?;
+            let (extensions, _) = Extensions::from_asn1(::std::slice::from_ref(inner))?;
+            Ok((Some(extensions), rest))
+        }
+        _ => Ok((None, v)),
+    }
+}
+
+/// An `[tag] EXPLICIT SEQUENCE OF Certificate`, if it's next.
+fn certificates(v: &[ASN1Block], tag: u64) -> Result<(Vec<Certificate>, &[ASN1Block]), X509DecodeErr> {
+    let (block, rest) = match v.split_first() {
+        Some((block, rest)) if context_tag(block) == Some(tag) => (block, rest),
+        _ => return Ok((Vec::new(), v)),
+    };
+    let mut items = match explicit_inner(block, tag) {
+        Some(ASN1Block::Sequence(_, items)) => &items[..],
+        _ => return Err(X509DecodeErr::InvalidField("certs")),
+    };
+    let mut certs = Vec::new();
+    while !items.is_empty() {
+        let (cert, remaining) = Certificate::from_asn1(items)?;
+        certs.push(cert);
+        items = remaining;
+    }
+    Ok((certs, rest))
+}
+
+#[cfg(test)]
+mod cert_id_tests {
+    use num::bigint::BigInt;
+    use simple_asn1::der_encode;
+
+    use super::{CertID, CertIDHash};
+    use der::der_decode;
+    use x509::{Certificate, CertificateSerialNumber};
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../testdata/ec-leaf.der");
+    // The CertID inside `openssl ocsp -issuer root.pem -cert leaf.pem`.
+    const EC_LEAF_CERT_ID: &[u8] = &[
+        0x30, 0x3B, 0x30, 0x09, 0x06, 0x05, 0x2B, 0x0E, 0x03, 0x02, 0x1A, 0x05, 0x00, 0x04, 0x14, 0x4C, 0x7F, 0x99,
+        0x34, 0x74, 0x1B, 0x7D, 0xB9, 0x56, 0x06, 0x7C, 0x82, 0x36, 0x17, 0xC8, 0x3B, 0x2A, 0xF9, 0x1E, 0xE1, 0x04,
+        0x14, 0x3A, 0xBF, 0x1F, 0x2F, 0x47, 0x80, 0x54, 0xFF, 0xF4, 0xD0, 0xA2, 0x12, 0xF0, 0x10, 0x6D, 0x99, 0x3A,
+        0x16, 0x0C, 0x0C, 0x02, 0x02, 0x10, 0x01,
+    ];
+
+    #[test]
+    fn cert_id_for_certificate() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let id = CertID::for_certificate(CertIDHash::Sha1, &leaf, &root).unwrap();
+        assert_eq!(EC_LEAF_CERT_ID.to_vec(), der_encode(&id).unwrap());
+        assert_eq!(id, der_decode(EC_LEAF_CERT_ID).unwrap());
+    }
+
+    #[test]
+    fn cert_id_matches() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap().tbs_certificate;
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap().tbs_certificate;
+        for hash in &[CertIDHash::Sha1, CertIDHash::Sha256] {
+            let id = CertID::new(*hash, &root.subject, &root.subject_public_key_info, &leaf.serial_number).unwrap();
+            assert!(id.matches(&root.subject, &root.subject_public_key_info, &leaf.serial_number));
+            let other = CertificateSerialNumber(BigInt::from(0x1002));
+            assert!(!id.matches(&root.subject, &root.subject_public_key_info, &other));
+            assert!(!id.matches(&leaf.subject, &root.subject_public_key_info, &leaf.serial_number));
+            assert!(!id.matches(&root.subject, &leaf.subject_public_key_info, &leaf.serial_number));
+        }
+    }
+}
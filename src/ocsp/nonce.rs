@@ -0,0 +1,86 @@
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::next_block;
+use error::X509DecodeErr;
+use extensions::X509Extension;
+use oid::ObjectIdentifier;
+use oids;
+
+/// A random value the client puts in a request and the responder echoes
+/// in its response, binding the response to the request (RFC 8954).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Nonce(pub Vec<u8>);
+
+impl Nonce {
+    /// The length of a generated nonce, the most RFC 8954 allows.
+    pub const LEN: usize = 32;
+
+    /// A fresh nonce from the operating system's random number generator.
+    ///
+    /// Panics if the random number generator fails.
+    pub fn generate() -> Nonce {
+        let mut bytes = vec![0; Nonce::LEN];
+        getrandom::getrandom(&mut bytes).expect("random number generator failed");
+        Nonce(bytes)
+    }
+}
+
+impl X509Extension for Nonce {
+    const OID: ObjectIdentifier = oids::OCSP_NONCE;
+}
+
+impl ToASN1 for Nonce {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![ASN1Block::OctetString(0, self.0.clone())])
+    }
+}
+
+impl FromASN1 for Nonce {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        match next_block(v, "Nonce")? {
+            (ASN1Block::OctetString(_, bytes), tail) if !bytes.is_empty() => Ok((Nonce(bytes.clone()), tail)),
+            _ => Err(X509DecodeErr::InvalidField("Nonce")),
+        }
+    }
+}
+
+/// How `BasicOCSPResponse::check_nonce` treats a response without the
+/// request's nonce.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NoncePolicy {
+    /// Accept it. Responders that serve pre-produced responses, as most
+    /// public CAs' do (RFC 5019), never echo nonces.
+    #[default]
+    Optional,
+    /// Reject it.
+    Required,
+}
+
+#[cfg(test)]
+mod nonce_tests {
+    use simple_asn1::der_encode;
+
+    use super::Nonce;
+    use der::der_decode;
+    use error::X509DecodeErr;
+
+    #[test]
+    fn nonce_round_trips() {
+        let der = [0x04, 0x03, 0x01, 0x02, 0x03];
+        let nonce = der_decode::<Nonce>(&der).unwrap();
+        assert_eq!(Nonce(vec![1, 2, 3]), nonce);
+        assert_eq!(der.to_vec(), der_encode(&nonce).unwrap());
+        assert_eq!(Err(X509DecodeErr::InvalidField("Nonce")), der_decode::<Nonce>(&[0x04, 0x00]));
+    }
+
+    #[test]
+    fn nonce_generates_distinct_values() {
+        let (a, b) = (Nonce::generate(), Nonce::generate());
+        assert_eq!(Nonce::LEN, a.0.len());
+        assert_ne!(a, b);
+    }
+}
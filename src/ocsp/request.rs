@@ -0,0 +1,311 @@
+use num::bigint::BigInt;
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use algorithm::AlgorithmIdentifier;
+use der::{context_tag, expect_end, explicit, explicit_inner, next_block, sequence};
+use error::X509DecodeErr;
+use extensions::Extensions;
+use general_name::GeneralName;
+use ocsp::{certificates, explicit_extensions, CertID, Nonce};
+use x509::Certificate;
+
+/// The status request for a single certificate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    pub req_cert: CertID,
+    pub single_request_extensions: Option<Extensions>,
+}
+
+impl Request {
+    pub fn new(req_cert: CertID) -> Request {
+        Request { req_cert, single_request_extensions: None }
+    }
+}
+
+impl ToASN1 for Request {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.req_cert.to_asn1_class(c)?;
+        if let Some(ref extensions) = self.single_request_extensions {
+            items.push(explicit(0, extensions.to_asn1_class(c)?.remove(0)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for Request {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "Request")?;
+        let items = sequence(head, "Request")?;
+        let (req_cert, rest) = CertID::from_asn1(items)?;
+        let (single_request_extensions, rest) = explicit_extensions(rest, 0, "singleRequestExtensions")?;
+        expect_end(rest, "Request")?;
+        Ok((Request { req_cert, single_request_extensions }, tail))
+    }
+}
+
+/// The signed part of an OCSP request. The version is always v1, the
+/// only one defined.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TBSRequest {
+    pub requestor_name: Option<GeneralName>,
+    pub request_list: Vec<Request>,
+    pub request_extensions: Option<Extensions>,
+}
+
+impl ToASN1 for TBSRequest {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        // version is DEFAULT v1, so DER leaves it out.
+        let mut items = Vec::new();
+        if let Some(ref name) = self.requestor_name {
+            items.push(explicit(1, name.to_asn1_class(c)?.remove(0)));
+        }
+        let mut requests = Vec::new();
+        for request in &self.request_list {
+            requests.extend(request.to_asn1_class(c)?);
+        }
+        items.push(ASN1Block::Sequence(0, requests));
+        if let Some(ref extensions) = self.request_extensions {
+            items.push(explicit(2, extensions.to_asn1_class(c)?.remove(0)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for TBSRequest {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "TBSRequest")?;
+        let mut rest = sequence(head, "TBSRequest")?;
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(0)) {
+            match explicit_inner(block, 0) {
+                Some(ASN1Block::Integer(_, n)) if *n == BigInt::from(0) => {}
+                Some(ASN1Block::Integer(_, n)) => return Err(X509DecodeErr::UnsupportedVersion(n.clone())),
+                _ => return Err(X509DecodeErr::InvalidField("version")),
+            }
+            rest = remaining;
+        }
+        let mut requestor_name = None;
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(1)) {
+            let name = explicit_inner(block, 1).ok_or(X509DecodeErr::InvalidField("requestorName"))?;
+            let (name, _) = GeneralName::from_asn1(::std::slice::from_ref(name))?;
+            requestor_name = Some(name);
+            rest = remaining;
+        }
+        let (requests, rest) = next_block(rest, "requestList")?;
+        let mut requests = sequence(requests, "requestList")?;
+        let mut request_list = Vec::new();
+        while !requests.is_empty() {
+            let (request, remaining) = Request::from_asn1(requests)?;
+            request_list.push(request);
+            requests = remaining;
+        }
+        let (request_extensions, rest) = explicit_extensions(rest, 2, "requestExtensions")?;
+        expect_end(rest, "TBSRequest")?;
+        Ok((TBSRequest { requestor_name, request_list, request_extensions }, tail))
+    }
+}
+
+/// The signature on a signed OCSP request, with any certificates that
+/// help the responder check it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+    pub signature_algorithm: AlgorithmIdentifier,
+    pub signature: Vec<u8>,
+    pub certs: Vec<Certificate>,
+}
+
+impl ToASN1 for Signature {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.signature_algorithm.to_asn1_class(c)?;
+        let signature = self.signature.clone();
+        items.push(ASN1Block::BitString(0, signature.len() * 8, signature));
+        if !self.certs.is_empty() {
+            let mut certs = Vec::new();
+            for cert in &self.certs {
+                certs.extend(cert.to_asn1_class(c)?);
+            }
+            items.push(explicit(0, ASN1Block::Sequence(0, certs)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for Signature {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "Signature")?;
+        let items = sequence(head, "Signature")?;
+        let (signature_algorithm, rest) = AlgorithmIdentifier::from_asn1(items)?;
+        let (signature, rest) = match next_block(rest, "signature")? {
+            (ASN1Block::BitString(_, nbits, bytes), rest) if *nbits == bytes.len() * 8 => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("signature")),
+        };
+        let (certs, rest) = certificates(rest, 0)?;
+        expect_end(rest, "Signature")?;
+        Ok((Signature { signature_algorithm, signature, certs }, tail))
+    }
+}
+
+/// An OCSP request (RFC 6960 §4.1), asking for the status of one or more
+/// certificates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OCSPRequest {
+    pub tbs_request: TBSRequest,
+    pub optional_signature: Option<Signature>,
+}
+
+impl OCSPRequest {
+    /// An unsigned request for the certificates identified by `cert_ids`.
+    pub fn new(cert_ids: Vec<CertID>) -> OCSPRequest {
+        let request_list = cert_ids.into_iter().map(Request::new).collect();
+        OCSPRequest {
+            tbs_request: TBSRequest { requestor_name: None, request_list, request_extensions: None },
+            optional_signature: None,
+        }
+    }
+
+    /// The identifiers of the certificates asked about, in order.
+    pub fn cert_ids(&self) -> impl Iterator<Item = &CertID> {
+        self.tbs_request.request_list.iter().map(|request| &request.req_cert)
+    }
+
+    /// The nonce from `request_extensions`, if there is one.
+    pub fn nonce(&self) -> Result<Option<Nonce>, X509DecodeErr> {
+        match self.tbs_request.request_extensions {
+            Some(ref extensions) => extensions.get(),
+            None => Ok(None),
+        }
+    }
+
+    /// Ask the responder to echo `nonce`, replacing any nonce already set.
+    /// This must happen before the request is signed.
+    pub fn set_nonce(&mut self, nonce: &Nonce) -> Result<(), ASN1EncodeErr> {
+        self.tbs_request.request_extensions.get_or_insert_with(Extensions::new).insert(nonce, false)
+    }
+}
+
+impl ToASN1 for OCSPRequest {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.tbs_request.to_asn1_class(c)?;
+        if let Some(ref signature) = self.optional_signature {
+            items.push(explicit(0, signature.to_asn1_class(c)?.remove(0)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for OCSPRequest {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "OCSPRequest")?;
+        let items = sequence(head, "OCSPRequest")?;
+        let (tbs_request, mut rest) = TBSRequest::from_asn1(items)?;
+        let mut optional_signature = None;
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(0)) {
+            let signature = explicit_inner(block, 0).ok_or(X509DecodeErr::InvalidField("optionalSignature"))?;
+            let (signature, _) = Signature::from_asn1(::std::slice::from_ref(signature))?;
+            optional_signature = Some(signature);
+            rest = remaining;
+        }
+        expect_end(rest, "OCSPRequest")?;
+        Ok((OCSPRequest { tbs_request, optional_signature }, tail))
+    }
+}
+
+#[cfg(test)]
+mod ocsp_request_tests {
+    use num::bigint::BigInt;
+    use simple_asn1::{der_encode, ASN1Block, FromASN1, ToASN1};
+
+    use super::{OCSPRequest, Signature, TBSRequest};
+    use algorithm::AlgorithmIdentifier;
+    use der::{der_decode, explicit};
+    use error::X509DecodeErr;
+    use general_name::GeneralName;
+    use ocsp::{CertID, CertIDHash, Nonce};
+    use oids;
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+    const EC_LEAF_OCSP_REQ: &[u8] = include_bytes!("../../testdata/ec-leaf-ocsp-req.der");
+    const EC_LEAF_OCSP_REQ_SHA256: &[u8] = include_bytes!("../../testdata/ec-leaf-ocsp-req-sha256.der");
+    const EC_LEAF_OCSP_REQ_NONCE: &[u8] = include_bytes!("../../testdata/ec-leaf-ocsp-req-nonce.der");
+
+    fn leaf_request(hash: CertIDHash) -> OCSPRequest {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        OCSPRequest::new(vec![CertID::for_certificate(hash, &leaf, &root).unwrap()])
+    }
+
+    #[test]
+    fn ocsp_request_matches_openssl() {
+        assert_eq!(EC_LEAF_OCSP_REQ.to_vec(), der_encode(&leaf_request(CertIDHash::Sha1)).unwrap());
+        assert_eq!(EC_LEAF_OCSP_REQ_SHA256.to_vec(), der_encode(&leaf_request(CertIDHash::Sha256)).unwrap());
+    }
+
+    #[test]
+    fn ocsp_request_decodes() {
+        let request = der_decode::<OCSPRequest>(EC_LEAF_OCSP_REQ).unwrap();
+        assert_eq!(leaf_request(CertIDHash::Sha1), request);
+        let ids: Vec<_> = request.cert_ids().collect();
+        assert_eq!(1, ids.len());
+        assert_eq!(BigInt::from(0x1001), ids[0].serial_number.0);
+    }
+
+    #[test]
+    fn ocsp_request_nonce() {
+        let openssl = der_decode::<OCSPRequest>(EC_LEAF_OCSP_REQ_NONCE).unwrap();
+        let nonce = openssl.nonce().unwrap().unwrap();
+        assert_eq!(16, nonce.0.len());
+        let mut request = leaf_request(CertIDHash::Sha1);
+        assert_eq!(None, request.nonce().unwrap());
+        request.set_nonce(&Nonce::generate()).unwrap();
+        request.set_nonce(&nonce).unwrap();
+        assert_eq!(EC_LEAF_OCSP_REQ_NONCE.to_vec(), der_encode(&request).unwrap());
+    }
+
+    #[test]
+    fn ocsp_request_signed_round_trips() {
+        let mut request = leaf_request(CertIDHash::Sha1);
+        request.tbs_request.requestor_name = Some(GeneralName::RFC822Name("ops@example.com".to_string()));
+        for certs in &[vec![], vec![der_decode::<Certificate>(EC_LEAF).unwrap()]] {
+            request.optional_signature = Some(Signature {
+                signature_algorithm: AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA256),
+                signature: vec![0x30, 0x00],
+                certs: certs.clone(),
+            });
+            assert_eq!(request, der_decode(&der_encode(&request).unwrap()).unwrap());
+        }
+    }
+
+    #[test]
+    fn tbs_request_rejects_unknown_version() {
+        let request = leaf_request(CertIDHash::Sha1);
+        let mut items = match request.tbs_request.to_asn1().unwrap().remove(0) {
+            ASN1Block::Sequence(_, items) => items,
+            _ => unreachable!(),
+        };
+        items.insert(0, explicit(0, ASN1Block::Integer(0, BigInt::from(1))));
+        assert_eq!(
+            Err(X509DecodeErr::UnsupportedVersion(BigInt::from(1))),
+            TBSRequest::from_asn1(&[ASN1Block::Sequence(0, items.clone())]).map(|(tbs, _)| tbs)
+        );
+        items[0] = explicit(0, ASN1Block::Integer(0, BigInt::from(0)));
+        let (tbs, _) = TBSRequest::from_asn1(&[ASN1Block::Sequence(0, items)]).unwrap();
+        assert_eq!(request.tbs_request, tbs);
+    }
+}
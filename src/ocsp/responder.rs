@@ -0,0 +1,243 @@
+use sha1::{Digest, Sha1};
+use simple_asn1::{der_encode, ASN1EncodeErr};
+use time::{Duration, PrimitiveDateTime};
+
+use der::SignedBytes;
+use extensions::Extensions;
+use name::Name;
+use ocsp::{
+    BasicOCSPResponse, CertStatus, OCSPRequest, OCSPResponse, OCSPResponseStatus, ResponderID, ResponseBytes,
+    ResponseData, SingleResponse,
+};
+use oids;
+use sign::{SignError, Signer};
+use spki::SubjectPublicKeyInfo;
+use x509::{Certificate, CertificateSerialNumber};
+
+impl OCSPResponse {
+    /// A response carrying `basic`.
+    pub fn successful(basic: &BasicOCSPResponse) -> Result<OCSPResponse, ASN1EncodeErr> {
+        Ok(OCSPResponse {
+            response_status: OCSPResponseStatus::Successful,
+            response_bytes: Some(ResponseBytes { response_type: oids::OCSP_BASIC, response: der_encode(basic)? }),
+        })
+    }
+
+    /// A response saying the request could not be answered, such as
+    /// `MalformedRequest` for one that does not decode.
+    pub fn unsuccessful(status: OCSPResponseStatus) -> OCSPResponse {
+        OCSPResponse { response_status: status, response_bytes: None }
+    }
+}
+
+/// The server half of OCSP: answers requests about the certificates one
+/// CA issued, from the caller's revocation database.
+///
+/// The response is signed either by the CA itself (`Responder::new`) or
+/// by a delegated responder whose certificate the CA issued with
+/// id-kp-OCSPSigning (`Responder::delegated`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Responder {
+    pub issuer_name: Name,
+    pub issuer_key: SubjectPublicKeyInfo,
+    pub responder_id: ResponderID,
+    /// Sent with each response, such as the delegated responder's
+    /// certificate.
+    pub certs: Vec<Certificate>,
+    /// How long after thisUpdate each status is good for. Without it
+    /// nextUpdate is left out, telling clients newer information is always
+    /// available.
+    pub validity: Option<Duration>,
+}
+
+impl Responder {
+    /// A responder that signs as `issuer`, identified by name.
+    pub fn new(issuer: &Certificate) -> Responder {
+        let issuer = &issuer.tbs_certificate;
+        Responder {
+            issuer_name: issuer.subject.clone(),
+            issuer_key: issuer.subject_public_key_info.clone(),
+            responder_id: ResponderID::ByName(issuer.subject.clone()),
+            certs: Vec::new(),
+            validity: None,
+        }
+    }
+
+    /// A responder for `issuer` that signs as `responder`, identified by its
+    /// key hash, and sends its certificate with each response.
+    pub fn delegated(issuer: &Certificate, responder: &Certificate) -> Responder {
+        let key = &responder.tbs_certificate.subject_public_key_info.subject_public_key;
+        Responder {
+            responder_id: ResponderID::ByKey(Sha1::digest(key).to_vec()),
+            certs: vec![responder.clone()],
+            ..Responder::new(issuer)
+        }
+    }
+
+    /// The answers to `request` as of `now`. `lookup` gives the status of
+    /// each serial number asked about; certificates from other issuers, or
+    /// identified with a hash this crate lacks, are `Unknown` without it
+    /// being asked. A nonce in the request is echoed.
+    pub fn response_data<F>(
+        &self,
+        request: &OCSPRequest,
+        now: PrimitiveDateTime,
+        mut lookup: F,
+    ) -> Result<ResponseData, ASN1EncodeErr>
+    where
+        F: FnMut(&CertificateSerialNumber) -> CertStatus,
+    {
+        let responses = request
+            .cert_ids()
+            .map(|cert_id| {
+                let serial_number = &cert_id.serial_number;
+                let cert_status = if cert_id.matches(&self.issuer_name, &self.issuer_key, serial_number) {
+                    lookup(serial_number)
+                } else {
+                    CertStatus::Unknown
+                };
+                SingleResponse {
+                    cert_id: cert_id.clone(),
+                    cert_status,
+                    this_update: now,
+                    next_update: self.validity.map(|validity| now + validity),
+                    single_extensions: None,
+                }
+            })
+            .collect();
+        let response_extensions = match request.nonce() {
+            Ok(Some(nonce)) => {
+                let mut extensions = Extensions::new();
+                extensions.insert(&nonce, false)?;
+                Some(extensions)
+            }
+            _ => None,
+        };
+        Ok(ResponseData { responder_id: self.responder_id.clone(), produced_at: now, responses, response_extensions })
+    }
+
+    /// The response to `request` as of `now`, with statuses from `lookup`
+    /// as for `response_data`, signed by `signer`, which holds the
+    /// responder's key.
+    pub fn respond<F, S>(
+        &self,
+        request: &OCSPRequest,
+        now: PrimitiveDateTime,
+        lookup: F,
+        signer: &S,
+    ) -> Result<OCSPResponse, SignError>
+    where
+        F: FnMut(&CertificateSerialNumber) -> CertStatus,
+        S: Signer + ?Sized,
+    {
+        let tbs_response_data = self.response_data(request, now, lookup)?;
+        let signature_value = signer.sign(&der_encode(&tbs_response_data)?)?;
+        let basic = BasicOCSPResponse {
+            tbs_response_data,
+            signature_algorithm: signer.signature_algorithm(),
+            signature_value,
+            certs: self.certs.clone(),
+            signed_bytes: SignedBytes::default(),
+        };
+        Ok(OCSPResponse::successful(&basic)?)
+    }
+}
+
+#[cfg(all(test, feature = "verify"))]
+mod responder_tests {
+    use num::bigint::BigInt;
+    use p256::ecdsa::SigningKey;
+    use simple_asn1::der_encode;
+    use time::{Date, Duration, Month, PrimitiveDateTime, Time};
+
+    use super::Responder;
+    use algorithm::AlgorithmIdentifier;
+    use der::der_decode;
+    use extensions::CRLReason;
+    use ocsp::{CertID, CertIDHash, CertStatus, OCSPRequest, OCSPResponse, OCSPResponseStatus, RevokedInfo};
+    use oids;
+    use x509::{Certificate, CertificateSerialNumber};
+
+    const EC_ROOT: &[u8] = include_bytes!("../../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+    const EC_LEAF_OCSP_REQ_NONCE: &[u8] = include_bytes!("../../testdata/ec-leaf-ocsp-req-nonce.der");
+    const EC_ROOT_OCSP_RESP_DELEGATED: &[u8] = include_bytes!("../../testdata/ec-root-ocsp-resp-delegated.der");
+    // The private keys of the root and of the delegated responder.
+    const EC_ROOT_KEY: [u8; 32] = [
+        0xBE, 0x92, 0x7C, 0xE1, 0x0C, 0x45, 0x2F, 0x7E, 0x07, 0xF0, 0x8C, 0xEC, 0x0A, 0x8C, 0x74, 0xDB, 0xFE, 0x33,
+        0x74, 0x4C, 0x7F, 0x09, 0x1B, 0xAF, 0x55, 0x0B, 0x5A, 0x52, 0x6F, 0xD3, 0x2D, 0x60,
+    ];
+    const EC_RESPONDER_KEY: [u8; 32] = [
+        0x39, 0x64, 0x8A, 0xBB, 0xF5, 0xA6, 0x08, 0xE3, 0x05, 0xA1, 0x24, 0xDA, 0x4C, 0x6B, 0xF6, 0xE0, 0x90, 0xAD,
+        0x14, 0x96, 0x5C, 0xBA, 0x36, 0x62, 0x7A, 0x43, 0xF8, 0xA5, 0xC9, 0xAC, 0x02, 0x38,
+    ];
+
+    fn now() -> PrimitiveDateTime {
+        let date = Date::from_calendar_date(2026, Month::October, 15).unwrap();
+        PrimitiveDateTime::new(date, Time::MIDNIGHT)
+    }
+
+    fn signer(key: &[u8; 32]) -> SigningKey {
+        SigningKey::from_bytes(key.into()).unwrap()
+    }
+
+    // The revocation database: 0x1001 is revoked, anything else is good.
+    fn lookup(serial_number: &CertificateSerialNumber) -> CertStatus {
+        if serial_number.0 == BigInt::from(0x1001) {
+            CertStatus::Revoked(RevokedInfo {
+                revocation_time: now() - Duration::days(1),
+                revocation_reason: Some(CRLReason::KeyCompromise),
+            })
+        } else {
+            CertStatus::Good
+        }
+    }
+
+    #[test]
+    fn responder_signs_as_issuer() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let request = der_decode::<OCSPRequest>(EC_LEAF_OCSP_REQ_NONCE).unwrap();
+        let mut responder = Responder::new(&root);
+        responder.validity = Some(Duration::days(7));
+
+        let response = responder.respond(&request, now(), lookup, &signer(&EC_ROOT_KEY)).unwrap();
+        let response = der_decode::<OCSPResponse>(&der_encode(&response).unwrap()).unwrap();
+        let basic = response.basic_response().unwrap();
+        assert_eq!(AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA256), basic.signature_algorithm);
+        assert_eq!(Ok(()), basic.check_nonce(&request, Default::default()));
+        let single = basic.validate(&leaf, &root, now() + Duration::days(7)).unwrap();
+        assert_eq!(lookup(&leaf.tbs_certificate.serial_number), single.cert_status);
+        assert!(basic.validate(&leaf, &root, now() + Duration::days(8)).is_err());
+    }
+
+    #[test]
+    fn responder_signs_as_delegate() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let mut leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let delegate = der_decode::<OCSPResponse>(EC_ROOT_OCSP_RESP_DELEGATED).unwrap().basic_response().unwrap();
+        let responder_cert = &delegate.certs[0];
+        let responder = Responder::delegated(&root, responder_cert);
+        assert_eq!(delegate.tbs_response_data.responder_id, responder.responder_id);
+
+        leaf.tbs_certificate.serial_number = CertificateSerialNumber(BigInt::from(0x1002));
+        // The second is the leaf as if its issuer were the leaf itself.
+        let request = OCSPRequest::new(vec![
+            CertID::for_certificate(CertIDHash::Sha256, &leaf, &root).unwrap(),
+            CertID::for_certificate(CertIDHash::Sha256, &leaf, &leaf).unwrap(),
+        ]);
+        let response = responder.respond(&request, now(), lookup, &signer(&EC_RESPONDER_KEY)).unwrap();
+        let basic = response.basic_response().unwrap();
+        assert_eq!(vec![responder_cert.clone()], basic.certs);
+        assert_eq!(None, basic.tbs_response_data.response_extensions);
+        assert_eq!(CertStatus::Good, basic.validate(&leaf, &root, now()).unwrap().cert_status);
+        assert_eq!(CertStatus::Unknown, basic.tbs_response_data.responses[1].cert_status);
+        assert_eq!(None, basic.tbs_response_data.responses[0].next_update);
+    }
+
+    #[test]
+    fn responder_reports_failure() {
+        let response = OCSPResponse::unsuccessful(OCSPResponseStatus::MalformedRequest);
+        assert_eq!(vec![0x30, 0x03, 0x0A, 0x01, 0x01], der_encode(&response).unwrap());
+    }
+}
@@ -0,0 +1,886 @@
+use std::error::Error;
+use std::fmt;
+
+use num::bigint::BigInt;
+use sha1::{Digest, Sha1};
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+use time::PrimitiveDateTime;
+
+use algorithm::AlgorithmIdentifier;
+use der::{
+    context_tag, der_decode, enumerated, enumerated_value, expect_end, explicit, explicit_inner, implicit,
+    implicit_bytes, implicit_inner, implicit_primitive, next_block, sequence, SignedBytes,
+};
+use error::X509DecodeErr;
+use extensions::{CRLReason, ExtendedKeyUsage, Extensions, X509Extension};
+use name::Name;
+use ocsp::{certificates, explicit_extensions, CertID, Nonce, NoncePolicy, OCSPRequest};
+use oid::ObjectIdentifier;
+use oids;
+use spki::SubjectPublicKeyInfo;
+#[cfg(feature = "verify")]
+use verify::RustCrypto;
+use verify::{SignatureVerifier, VerifyError};
+use x509::Certificate;
+
+fn generalized_time<'a>(
+    v: &'a [ASN1Block],
+    field: &'static str,
+) -> Result<(PrimitiveDateTime, &'a [ASN1Block]), X509DecodeErr> {
+    match next_block(v, field)? {
+        (&ASN1Block::GeneralizedTime(_, t), rest) => Ok((t, rest)),
+        _ => Err(X509DecodeErr::InvalidField(field)),
+    }
+}
+
+/// An error that can arise checking an OCSP response.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OCSPError {
+    /// The responder could not answer the request.
+    Status(OCSPResponseStatus),
+    /// The response could not be decoded, or is not a basic response.
+    Decode(X509DecodeErr),
+    /// Neither the CA nor a responder it authorized signed the response.
+    UnauthorizedResponder,
+    /// The responder's signature, or the CA's signature on a delegated
+    /// responder's certificate, did not verify.
+    Verify(VerifyError),
+    /// The response has no status for the certificate.
+    NoStatus,
+    /// The status is not current at the time it was checked at.
+    NotCurrent,
+    /// The response's nonce is not the request's.
+    NonceMismatch,
+    /// The response has no nonce, and the policy requires one.
+    MissingNonce,
+}
+
+impl From<X509DecodeErr> for OCSPError {
+    fn from(e: X509DecodeErr) -> OCSPError {
+        OCSPError::Decode(e)
+    }
+}
+
+impl From<VerifyError> for OCSPError {
+    fn from(e: VerifyError) -> OCSPError {
+        OCSPError::Verify(e)
+    }
+}
+
+impl fmt::Display for OCSPError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OCSPError::Status(status) => write!(f, "OCSP request failed: {:?}", status),
+            OCSPError::Decode(ref e) => write!(f, "bad OCSP response: {}", e),
+            OCSPError::UnauthorizedResponder => write!(f, "OCSP response signed by an unauthorized responder"),
+            OCSPError::Verify(ref e) => write!(f, "OCSP response signature check failed: {}", e),
+            OCSPError::NoStatus => write!(f, "OCSP response has no status for the certificate"),
+            OCSPError::NotCurrent => write!(f, "OCSP response is not current"),
+            OCSPError::NonceMismatch => write!(f, "OCSP response nonce does not match the request"),
+            OCSPError::MissingNonce => write!(f, "OCSP response has no nonce"),
+        }
+    }
+}
+
+impl Error for OCSPError {}
+
+/// Whether the responder could answer the request (RFC 6960 §4.2.1).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OCSPResponseStatus {
+    Successful,
+    MalformedRequest,
+    InternalError,
+    TryLater,
+    /// The responder only answers signed requests.
+    SigRequired,
+    Unauthorized,
+}
+
+impl OCSPResponseStatus {
+    fn value(self) -> u8 {
+        match self {
+            OCSPResponseStatus::Successful => 0,
+            OCSPResponseStatus::MalformedRequest => 1,
+            OCSPResponseStatus::InternalError => 2,
+            OCSPResponseStatus::TryLater => 3,
+            // 4 is not used.
+            OCSPResponseStatus::SigRequired => 5,
+            OCSPResponseStatus::Unauthorized => 6,
+        }
+    }
+
+    fn from_value(value: u8) -> Option<OCSPResponseStatus> {
+        match value {
+            0 => Some(OCSPResponseStatus::Successful),
+            1 => Some(OCSPResponseStatus::MalformedRequest),
+            2 => Some(OCSPResponseStatus::InternalError),
+            3 => Some(OCSPResponseStatus::TryLater),
+            5 => Some(OCSPResponseStatus::SigRequired),
+            6 => Some(OCSPResponseStatus::Unauthorized),
+            _ => None,
+        }
+    }
+}
+
+/// The body of a successful response, tagged with its type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResponseBytes {
+    pub response_type: ObjectIdentifier,
+    /// The DER of the response, such as a BasicOCSPResponse.
+    pub response: Vec<u8>,
+}
+
+impl ToASN1 for ResponseBytes {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.response_type.to_asn1_class(c)?;
+        items.push(ASN1Block::OctetString(0, self.response.clone()));
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for ResponseBytes {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "ResponseBytes")?;
+        let items = sequence(head, "ResponseBytes")?;
+        let (response_type, rest) = ObjectIdentifier::from_asn1(items)?;
+        let (response, rest) = match next_block(rest, "response")? {
+            (ASN1Block::OctetString(_, bytes), rest) => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("response")),
+        };
+        expect_end(rest, "ResponseBytes")?;
+        Ok((ResponseBytes { response_type, response }, tail))
+    }
+}
+
+/// An OCSP response (RFC 6960 §4.2.1), as returned by a responder.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OCSPResponse {
+    pub response_status: OCSPResponseStatus,
+    pub response_bytes: Option<ResponseBytes>,
+}
+
+impl OCSPResponse {
+    /// The basic response this carries, or why there isn't one.
+    pub fn basic_response(&self) -> Result<BasicOCSPResponse, OCSPError> {
+        if self.response_status != OCSPResponseStatus::Successful {
+            return Err(OCSPError::Status(self.response_status));
+        }
+        match self.response_bytes {
+            Some(ref bytes) if bytes.response_type == oids::OCSP_BASIC => Ok(der_decode(&bytes.response)?),
+            Some(_) => Err(X509DecodeErr::InvalidField("responseType").into()),
+            None => Err(X509DecodeErr::MissingField("responseBytes").into()),
+        }
+    }
+}
+
+impl ToASN1 for OCSPResponse {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = vec![enumerated(self.response_status.value())];
+        if let Some(ref bytes) = self.response_bytes {
+            items.push(explicit(0, bytes.to_asn1_class(c)?.remove(0)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for OCSPResponse {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "OCSPResponse")?;
+        let items = sequence(head, "OCSPResponse")?;
+        let (status, mut rest) = next_block(items, "responseStatus")?;
+        let response_status = enumerated_value(status)
+            .and_then(OCSPResponseStatus::from_value)
+            .ok_or(X509DecodeErr::InvalidField("responseStatus"))?;
+        let mut response_bytes = None;
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(0)) {
+            let bytes = explicit_inner(block, 0).ok_or(X509DecodeErr::InvalidField("responseBytes"))?;
+            let (bytes, _) = ResponseBytes::from_asn1(::std::slice::from_ref(bytes))?;
+            response_bytes = Some(bytes);
+            rest = remaining;
+        }
+        expect_end(rest, "OCSPResponse")?;
+        Ok((OCSPResponse { response_status, response_bytes }, tail))
+    }
+}
+
+/// Identifies the responder that signed a response, by its name or by
+/// the SHA-1 hash of its public key.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResponderID {
+    ByName(Name),
+    ByKey(Vec<u8>),
+}
+
+impl ResponderID {
+    /// Whether this identifies the subject of `cert`.
+    pub fn matches(&self, cert: &Certificate) -> bool {
+        let tbs = &cert.tbs_certificate;
+        match *self {
+            ResponderID::ByName(ref name) => name.matches(&tbs.subject),
+            ResponderID::ByKey(ref hash) => {
+                Sha1::digest(&tbs.subject_public_key_info.subject_public_key)[..] == hash[..]
+            }
+        }
+    }
+}
+
+impl ToASN1 for ResponderID {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![match *self {
+            ResponderID::ByName(ref name) => explicit(1, name.to_asn1_class(c)?.remove(0)),
+            ResponderID::ByKey(ref hash) => explicit(2, ASN1Block::OctetString(0, hash.clone())),
+        }])
+    }
+}
+
+impl FromASN1 for ResponderID {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "responderID")?;
+        let id = match (context_tag(head), explicit_inner(head, 1), explicit_inner(head, 2)) {
+            (Some(1), Some(name), _) => ResponderID::ByName(Name::from_asn1(::std::slice::from_ref(name))?.0),
+            (Some(2), _, Some(ASN1Block::OctetString(_, hash))) => ResponderID::ByKey(hash.clone()),
+            _ => return Err(X509DecodeErr::InvalidField("responderID")),
+        };
+        Ok((id, tail))
+    }
+}
+
+/// When, and optionally why, a certificate was revoked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RevokedInfo {
+    pub revocation_time: PrimitiveDateTime,
+    pub revocation_reason: Option<CRLReason>,
+}
+
+/// The status the responder gives a certificate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CertStatus {
+    /// The certificate is not revoked, or at least not known to be.
+    Good,
+    Revoked(RevokedInfo),
+    /// The responder knows nothing of the certificate, usually because
+    /// it is not authoritative for the issuer.
+    Unknown,
+}
+
+impl ToASN1 for CertStatus {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![match *self {
+            CertStatus::Good => implicit_primitive(0, Vec::new()),
+            CertStatus::Revoked(ref info) => {
+                let mut items = vec![ASN1Block::GeneralizedTime(0, info.revocation_time)];
+                if let Some(ref reason) = info.revocation_reason {
+                    items.push(explicit(0, reason.to_asn1_class(c)?.remove(0)));
+                }
+                implicit(1, &ASN1Block::Sequence(0, items))?
+            }
+            CertStatus::Unknown => implicit_primitive(2, Vec::new()),
+        }])
+    }
+}
+
+impl FromASN1 for CertStatus {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "certStatus")?;
+        let status = match context_tag(head) {
+            Some(0) if implicit_bytes(head, 0) == Some(&[]) => CertStatus::Good,
+            Some(2) if implicit_bytes(head, 2) == Some(&[]) => CertStatus::Unknown,
+            Some(1) => {
+                let info = implicit_inner(head, 1, 0x10).ok_or(X509DecodeErr::InvalidField("revokedInfo"))?;
+                let items = sequence(&info, "revokedInfo")?;
+                let (revocation_time, rest) = generalized_time(items, "revocationTime")?;
+                let (revocation_reason, rest) = match rest.split_first() {
+                    Some((block, rest)) if context_tag(block) == Some(0) => {
+                        let reason = explicit_inner(block, 0).ok_or(X509DecodeErr::InvalidField("revocationReason"))?;
+                        (Some(CRLReason::from_asn1(::std::slice::from_ref(reason))?.0), rest)
+                    }
+                    _ => (None, rest),
+                };
+                expect_end(rest, "revokedInfo")?;
+                CertStatus::Revoked(RevokedInfo { revocation_time, revocation_reason })
+            }
+            _ => return Err(X509DecodeErr::InvalidField("certStatus")),
+        };
+        Ok((status, tail))
+    }
+}
+
+/// The responder's answer for a single certificate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SingleResponse {
+    pub cert_id: CertID,
+    pub cert_status: CertStatus,
+    pub this_update: PrimitiveDateTime,
+    /// When newer information will be available. Without it, newer
+    /// information is always available.
+    pub next_update: Option<PrimitiveDateTime>,
+    pub single_extensions: Option<Extensions>,
+}
+
+impl SingleResponse {
+    /// Whether the status is current at `t`: no earlier than `this_update`
+    /// and no later than `next_update`.
+    pub fn is_current_at(&self, t: PrimitiveDateTime) -> bool {
+        self.this_update <= t && self.next_update.is_none_or(|next| t <= next)
+    }
+
+    /// The extension of type `T` from `single_extensions`, if it's there.
+    pub fn extension<T: X509Extension>(&self) -> Result<Option<T>, X509DecodeErr> {
+        match self.single_extensions {
+            Some(ref extensions) => extensions.get(),
+            None => Ok(None),
+        }
+    }
+}
+
+impl ToASN1 for SingleResponse {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.cert_id.to_asn1_class(c)?;
+        items.extend(self.cert_status.to_asn1_class(c)?);
+        items.push(ASN1Block::GeneralizedTime(0, self.this_update));
+        if let Some(next_update) = self.next_update {
+            items.push(explicit(0, ASN1Block::GeneralizedTime(0, next_update)));
+        }
+        if let Some(ref extensions) = self.single_extensions {
+            items.push(explicit(1, extensions.to_asn1_class(c)?.remove(0)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for SingleResponse {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "SingleResponse")?;
+        let items = sequence(head, "SingleResponse")?;
+        let (cert_id, rest) = CertID::from_asn1(items)?;
+        let (cert_status, rest) = CertStatus::from_asn1(rest)?;
+        let (this_update, rest) = generalized_time(rest, "thisUpdate")?;
+        let (next_update, rest) = match rest.split_first() {
+            Some((block, rest)) if context_tag(block) == Some(0) => match explicit_inner(block, 0) {
+                Some(&ASN1Block::GeneralizedTime(_, t)) => (Some(t), rest),
+                _ => return Err(X509DecodeErr::InvalidField("nextUpdate")),
+            },
+            _ => (None, rest),
+        };
+        let (single_extensions, rest) = explicit_extensions(rest, 1, "singleExtensions")?;
+        expect_end(rest, "SingleResponse")?;
+        Ok((SingleResponse { cert_id, cert_status, this_update, next_update, single_extensions }, tail))
+    }
+}
+
+/// The signed part of a basic response. The version is always v1, the
+/// only one defined.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResponseData {
+    pub responder_id: ResponderID,
+    pub produced_at: PrimitiveDateTime,
+    pub responses: Vec<SingleResponse>,
+    pub response_extensions: Option<Extensions>,
+}
+
+impl ToASN1 for ResponseData {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        // version is DEFAULT v1, so DER leaves it out.
+        let mut items = self.responder_id.to_asn1_class(c)?;
+        items.push(ASN1Block::GeneralizedTime(0, self.produced_at));
+        let mut responses = Vec::new();
+        for response in &self.responses {
+            responses.extend(response.to_asn1_class(c)?);
+        }
+        items.push(ASN1Block::Sequence(0, responses));
+        if let Some(ref extensions) = self.response_extensions {
+            items.push(explicit(1, extensions.to_asn1_class(c)?.remove(0)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for ResponseData {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "ResponseData")?;
+        let mut rest = sequence(head, "ResponseData")?;
+        if let Some((block, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(0)) {
+            match explicit_inner(block, 0) {
+                Some(ASN1Block::Integer(_, n)) if *n == BigInt::from(0) => {}
+                Some(ASN1Block::Integer(_, n)) => return Err(X509DecodeErr::UnsupportedVersion(n.clone())),
+                _ => return Err(X509DecodeErr::InvalidField("version")),
+            }
+            rest = remaining;
+        }
+        let (responder_id, rest) = ResponderID::from_asn1(rest)?;
+        let (produced_at, rest) = generalized_time(rest, "producedAt")?;
+        let (responses, rest) = next_block(rest, "responses")?;
+        let mut items = sequence(responses, "responses")?;
+        let mut responses = Vec::new();
+        while !items.is_empty() {
+            let (response, remaining) = SingleResponse::from_asn1(items)?;
+            responses.push(response);
+            items = remaining;
+        }
+        let (response_extensions, rest) = explicit_extensions(rest, 1, "responseExtensions")?;
+        expect_end(rest, "ResponseData")?;
+        Ok((ResponseData { responder_id, produced_at, responses, response_extensions }, tail))
+    }
+}
+
+/// The basic response type (RFC 6960 §4.2.1), which every responder
+/// returns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BasicOCSPResponse {
+    pub tbs_response_data: ResponseData,
+    pub signature_algorithm: AlgorithmIdentifier,
+    pub signature_value: Vec<u8>,
+    /// Certificates that help check the signature, such as a delegated
+    /// responder's.
+    pub certs: Vec<Certificate>,
+    pub(crate) signed_bytes: SignedBytes,
+}
+
+impl BasicOCSPResponse {
+    /// The response for `cert`, which `issuer` issued, if there is one.
+    pub fn response_for(&self, cert: &Certificate, issuer: &Certificate) -> Option<&SingleResponse> {
+        let issuer = &issuer.tbs_certificate;
+        let serial_number = &cert.tbs_certificate.serial_number;
+        self.tbs_response_data
+            .responses
+            .iter()
+            .find(|response| response.cert_id.matches(&issuer.subject, &issuer.subject_public_key_info, serial_number))
+    }
+
+    /// The nonce from `response_extensions`, if there is one.
+    pub fn nonce(&self) -> Result<Option<Nonce>, X509DecodeErr> {
+        match self.tbs_response_data.response_extensions {
+            Some(ref extensions) => extensions.get(),
+            None => Ok(None),
+        }
+    }
+
+    /// Check that this answers `request` by its nonce. A nonce that
+    /// differs from the request's is always rejected; a missing one is
+    /// rejected only under `NoncePolicy::Required`. Nothing is checked if
+    /// the request had no nonce.
+    pub fn check_nonce(&self, request: &OCSPRequest, policy: NoncePolicy) -> Result<(), OCSPError> {
+        let expected = match request.nonce()? {
+            Some(nonce) => nonce,
+            None => return Ok(()),
+        };
+        match self.nonce()? {
+            Some(ref nonce) if *nonce == expected => Ok(()),
+            Some(_) => Err(OCSPError::NonceMismatch),
+            None if policy == NoncePolicy::Required => Err(OCSPError::MissingNonce),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that `issuer`, or a responder `issuer` authorized, signed the
+    /// response, using the RustCrypto backend.
+    #[cfg(feature = "verify")]
+    pub fn verify_signed_by(&self, issuer: &Certificate) -> Result<(), OCSPError> {
+        self.verify_signed_by_with(issuer, &RustCrypto)
+    }
+
+    /// Check the response's signature with the given backend. A delegated
+    /// responder must have a certificate in `certs` that `issuer` issued,
+    /// that names the same signature algorithm inside and outside its
+    /// signed part, and that has id-kp-OCSPSigning in its extendedKeyUsage
+    /// (RFC 6960 §4.2.2.2). Each certificate matching the responder ID is
+    /// tried in turn.
+    pub fn verify_signed_by_with<V: SignatureVerifier + ?Sized>(
+        &self,
+        issuer: &Certificate,
+        verifier: &V,
+    ) -> Result<(), OCSPError> {
+        self.verify_signer(issuer, None, verifier)
+    }
+
+    /// The status of `cert`, which `issuer` issued, at time `t`, using the
+    /// RustCrypto backend.
+    #[cfg(feature = "verify")]
+    pub fn validate(
+        &self,
+        cert: &Certificate,
+        issuer: &Certificate,
+        t: PrimitiveDateTime,
+    ) -> Result<&SingleResponse, OCSPError> {
+        self.validate_with(cert, issuer, t, &RustCrypto)
+    }
+
+    /// The status of `cert`, which `issuer` issued, at time `t`. This
+    /// checks the signature as `verify_signed_by_with` does, that a
+    /// delegated responder's certificate is valid at `t`, and that the
+    /// status is current at `t`.
+    pub fn validate_with<V: SignatureVerifier + ?Sized>(
+        &self,
+        cert: &Certificate,
+        issuer: &Certificate,
+        t: PrimitiveDateTime,
+        verifier: &V,
+    ) -> Result<&SingleResponse, OCSPError> {
+        self.verify_signer(issuer, Some(t), verifier)?;
+        let response = self.response_for(cert, issuer).ok_or(OCSPError::NoStatus)?;
+        if !response.is_current_at(t) {
+            return Err(OCSPError::NotCurrent);
+        }
+        Ok(response)
+    }
+
+    // Check the signature, made by the issuer or by a delegated responder
+    // whose certificate is valid at `t`, if given. Every certificate in
+    // `certs` the responder ID matches is tried, since a responder may
+    // send its old and renewed certificates together; if none will do,
+    // the last one's failure is given.
+    fn verify_signer<V: SignatureVerifier + ?Sized>(
+        &self,
+        issuer: &Certificate,
+        t: Option<PrimitiveDateTime>,
+        verifier: &V,
+    ) -> Result<(), OCSPError> {
+        let responder_id = &self.tbs_response_data.responder_id;
+        if responder_id.matches(issuer) {
+            return Ok(self.verify_signature_with(&issuer.tbs_certificate.subject_public_key_info, verifier)?);
+        }
+        let mut failure = OCSPError::UnauthorizedResponder;
+        for responder in self.certs.iter().filter(|cert| responder_id.matches(cert)) {
+            match self.verify_delegated(responder, issuer, t, verifier) {
+                Ok(()) => return Ok(()),
+                Err(e) => failure = e,
+            }
+        }
+        Err(failure)
+    }
+
+    // Check that `responder` is authorized by `issuer`, and valid at `t`
+    // if given, and that it signed the response.
+    fn verify_delegated<V: SignatureVerifier + ?Sized>(
+        &self,
+        responder: &Certificate,
+        issuer: &Certificate,
+        t: Option<PrimitiveDateTime>,
+        verifier: &V,
+    ) -> Result<(), OCSPError> {
+        let tbs = &responder.tbs_certificate;
+        if !tbs.issuer.matches(&issuer.tbs_certificate.subject)
+            || tbs.signature != responder.signature_algorithm
+            || !responder.extension::<ExtendedKeyUsage>()?.is_some_and(|eku| eku.ocsp_signing())
+            || t.is_some_and(|t| !tbs.validity.is_valid_at(t))
+        {
+            return Err(OCSPError::UnauthorizedResponder);
+        }
+        responder.verify_signed_by_with(&issuer.tbs_certificate.subject_public_key_info, verifier)?;
+        Ok(self.verify_signature_with(&tbs.subject_public_key_info, verifier)?)
+    }
+
+    // Check the signature against `key` alone.
+    pub(crate) fn verify_signature_with<V: SignatureVerifier + ?Sized>(
+        &self,
+        key: &SubjectPublicKeyInfo,
+        verifier: &V,
+    ) -> Result<(), VerifyError> {
+        let message = self.signed_bytes.message(&self.tbs_response_data)?;
+        verifier.verify(&self.signature_algorithm, key, &message, &self.signature_value)
+    }
+}
+
+impl ToASN1 for BasicOCSPResponse {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.tbs_response_data.to_asn1_class(c)?;
+        items.extend(self.signature_algorithm.to_asn1_class(c)?);
+        let signature = self.signature_value.clone();
+        items.push(ASN1Block::BitString(0, signature.len() * 8, signature));
+        if !self.certs.is_empty() {
+            let mut certs = Vec::new();
+            for cert in &self.certs {
+                certs.extend(cert.to_asn1_class(c)?);
+            }
+            items.push(explicit(0, ASN1Block::Sequence(0, certs)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for BasicOCSPResponse {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "BasicOCSPResponse")?;
+        let items = sequence(head, "BasicOCSPResponse")?;
+        let (tbs_response_data, rest) = ResponseData::from_asn1(items)?;
+        let signed_bytes = SignedBytes::decoded(&items[0], &tbs_response_data);
+        let (signature_algorithm, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+        let (signature_value, rest) = match next_block(rest, "signature")? {
+            (ASN1Block::BitString(_, nbits, bytes), rest) if *nbits == bytes.len() * 8 => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("signature")),
+        };
+        let (certs, rest) = certificates(rest, 0)?;
+        expect_end(rest, "BasicOCSPResponse")?;
+        Ok((BasicOCSPResponse { tbs_response_data, signature_algorithm, signature_value, certs, signed_bytes }, tail))
+    }
+}
+
+#[cfg(test)]
+mod ocsp_response_tests {
+    #[cfg(feature = "verify")]
+    use num::bigint::BigInt;
+    use simple_asn1::der_encode;
+    use time::{Date, Duration, Month, PrimitiveDateTime, Time};
+
+    use super::{BasicOCSPResponse, CertStatus, OCSPError, OCSPResponse, OCSPResponseStatus, ResponderID};
+    use ocsp::{Nonce, NoncePolicy, OCSPRequest};
+    #[cfg(feature = "verify")]
+    use algorithm::AlgorithmIdentifier;
+    #[cfg(feature = "verify")]
+    use oids;
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use extensions::{CRLReason, InvalidityDate};
+    #[cfg(feature = "verify")]
+    use verify::VerifyError;
+    use x509::Certificate;
+    #[cfg(feature = "verify")]
+    use x509::CertificateSerialNumber;
+
+    const EC_ROOT: &[u8] = include_bytes!("../../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+    // Signed by the root, for the leaf.
+    const EC_LEAF_OCSP_RESP: &[u8] = include_bytes!("../../testdata/ec-leaf-ocsp-resp.der");
+    // Signed by the leaf, which the root did not authorize.
+    const EC_LEAF_OCSP_RESP_UNAUTHORIZED: &[u8] = include_bytes!("../../testdata/ec-leaf-ocsp-resp-unauthorized.der");
+    // Signed by the root, echoing the nonce from EC_LEAF_OCSP_REQ_NONCE.
+    const EC_LEAF_OCSP_RESP_NONCE: &[u8] = include_bytes!("../../testdata/ec-leaf-ocsp-resp-nonce.der");
+    const EC_LEAF_OCSP_REQ_NONCE: &[u8] = include_bytes!("../../testdata/ec-leaf-ocsp-req-nonce.der");
+    // Signed by a delegated responder, for serials 0x1001, 0x1002 and 0x1005.
+    const EC_ROOT_OCSP_RESP_DELEGATED: &[u8] = include_bytes!("../../testdata/ec-root-ocsp-resp-delegated.der");
+
+    fn date_time(day: u8, hour: u8, minute: u8, second: u8) -> PrimitiveDateTime {
+        let date = Date::from_calendar_date(2026, Month::October, day).unwrap();
+        PrimitiveDateTime::new(date, Time::from_hms(hour, minute, second).unwrap())
+    }
+
+    fn basic(der: &[u8]) -> BasicOCSPResponse {
+        der_decode::<OCSPResponse>(der).unwrap().basic_response().unwrap()
+    }
+
+    #[cfg(feature = "verify")]
+    fn with_serial(cert: &Certificate, serial: i64) -> Certificate {
+        let mut cert = cert.clone();
+        cert.tbs_certificate.serial_number = CertificateSerialNumber(BigInt::from(serial));
+        cert
+    }
+
+    #[test]
+    fn ocsp_response_decodes() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let response = basic(EC_LEAF_OCSP_RESP);
+        let data = &response.tbs_response_data;
+        assert_eq!(ResponderID::ByName(root.tbs_certificate.subject.clone()), data.responder_id);
+        assert_eq!(date_time(14, 19, 26, 20), data.produced_at);
+        assert!(response.certs.is_empty());
+        let single = response.response_for(&leaf, &root).unwrap();
+        match single.cert_status {
+            CertStatus::Revoked(info) => {
+                assert_eq!(date_time(14, 19, 0, 0), info.revocation_time);
+                assert_eq!(Some(CRLReason::KeyCompromise), info.revocation_reason);
+            }
+            status => panic!("unexpected status {:?}", status),
+        }
+        assert_eq!(date_time(14, 19, 26, 20), single.this_update);
+        assert_eq!(Some(date_time(21, 19, 26, 20)), single.next_update);
+        assert_eq!(Some(InvalidityDate(date_time(1, 0, 0, 0))), single.extension().unwrap());
+        assert!(response.response_for(&root, &root).is_none());
+    }
+
+    #[test]
+    fn ocsp_response_round_trips() {
+        let ders =
+            [EC_LEAF_OCSP_RESP, EC_LEAF_OCSP_RESP_NONCE, EC_LEAF_OCSP_RESP_UNAUTHORIZED, EC_ROOT_OCSP_RESP_DELEGATED];
+        for der in &ders {
+            let response = der_decode::<OCSPResponse>(der).unwrap();
+            assert_eq!(der.to_vec(), der_encode(&response).unwrap());
+            let basic = response.basic_response().unwrap();
+            assert_eq!(response.response_bytes.unwrap().response, der_encode(&basic).unwrap());
+        }
+    }
+
+    #[test]
+    fn ocsp_response_without_body() {
+        // tryLater
+        let response = der_decode::<OCSPResponse>(&[0x30, 0x03, 0x0A, 0x01, 0x03]).unwrap();
+        assert_eq!(OCSPResponseStatus::TryLater, response.response_status);
+        assert_eq!(Err(OCSPError::Status(OCSPResponseStatus::TryLater)), response.basic_response());
+        assert_eq!(vec![0x30, 0x03, 0x0A, 0x01, 0x03], der_encode(&response).unwrap());
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("responseStatus")),
+            der_decode::<OCSPResponse>(&[0x30, 0x03, 0x0A, 0x01, 0x04])
+        );
+    }
+
+    #[test]
+    fn ocsp_response_delegated_statuses() {
+        let response = basic(EC_ROOT_OCSP_RESP_DELEGATED);
+        let responder = &response.certs[0];
+        match response.tbs_response_data.responder_id {
+            ResponderID::ByKey(_) => assert!(response.tbs_response_data.responder_id.matches(responder)),
+            ref id => panic!("unexpected responder {:?}", id),
+        }
+        let statuses: Vec<_> = response.tbs_response_data.responses.iter().map(|r| r.cert_status).collect();
+        match statuses[..] {
+            [CertStatus::Revoked(_), CertStatus::Good, CertStatus::Unknown] => {}
+            _ => panic!("unexpected statuses {:?}", statuses),
+        }
+    }
+
+    #[test]
+    fn ocsp_response_checks_nonce() {
+        let request = der_decode::<OCSPRequest>(EC_LEAF_OCSP_REQ_NONCE).unwrap();
+        let response = basic(EC_LEAF_OCSP_RESP_NONCE);
+        assert_eq!(request.nonce().unwrap(), response.nonce().unwrap());
+        for policy in &[NoncePolicy::Optional, NoncePolicy::Required] {
+            assert_eq!(Ok(()), response.check_nonce(&request, *policy));
+        }
+
+        let mut other = request.clone();
+        other.set_nonce(&Nonce::generate()).unwrap();
+        assert_eq!(Err(OCSPError::NonceMismatch), response.check_nonce(&other, NoncePolicy::Optional));
+
+        let without = basic(EC_LEAF_OCSP_RESP);
+        assert_eq!(Ok(()), without.check_nonce(&request, NoncePolicy::default()));
+        assert_eq!(Err(OCSPError::MissingNonce), without.check_nonce(&request, NoncePolicy::Required));
+
+        let mut plain = request;
+        plain.tbs_request.request_extensions = None;
+        assert_eq!(Ok(()), response.check_nonce(&plain, NoncePolicy::Required));
+    }
+
+    #[test]
+    fn single_response_currency() {
+        let response = basic(EC_LEAF_OCSP_RESP);
+        let single = &response.tbs_response_data.responses[0];
+        assert!(!single.is_current_at(date_time(14, 19, 26, 19)));
+        assert!(single.is_current_at(date_time(14, 19, 26, 20)));
+        assert!(single.is_current_at(date_time(21, 19, 26, 20)));
+        assert!(!single.is_current_at(date_time(21, 19, 26, 21)));
+        let mut open = single.clone();
+        open.next_update = None;
+        assert!(open.is_current_at(date_time(21, 19, 26, 20) + Duration::days(365)));
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn ocsp_response_validates() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let now = date_time(15, 0, 0, 0);
+
+        let response = basic(EC_LEAF_OCSP_RESP);
+        assert_eq!(Ok(()), response.verify_signed_by(&root));
+        let single = response.validate(&leaf, &root, now).unwrap();
+        assert_eq!(Some(CRLReason::KeyCompromise), match single.cert_status {
+            CertStatus::Revoked(info) => info.revocation_reason,
+            _ => None,
+        });
+        assert_eq!(Err(OCSPError::NotCurrent), response.validate(&leaf, &root, date_time(22, 0, 0, 0)));
+        assert_eq!(Err(OCSPError::NoStatus), response.validate(&with_serial(&leaf, 0x1002), &root, now));
+
+        let response = basic(EC_ROOT_OCSP_RESP_DELEGATED);
+        assert_eq!(Ok(()), response.verify_signed_by(&root));
+        let good = response.validate(&with_serial(&leaf, 0x1002), &root, now).unwrap();
+        assert_eq!(CertStatus::Good, good.cert_status);
+        let unknown = response.validate(&with_serial(&leaf, 0x1005), &root, now).unwrap();
+        assert_eq!(CertStatus::Unknown, unknown.cert_status);
+        // The responder's certificate is not valid until after the response
+        // claims to be current.
+        assert_eq!(Err(OCSPError::UnauthorizedResponder), response.validate(&leaf, &root, date_time(14, 19, 26, 0)));
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn ocsp_response_verifies_over_decoded_bytes() {
+        use p256::ecdsa::SigningKey;
+
+        use der::pad_first_integer;
+
+        const EC_ROOT_KEY: [u8; 32] = [
+            0xBE, 0x92, 0x7C, 0xE1, 0x0C, 0x45, 0x2F, 0x7E, 0x07, 0xF0, 0x8C, 0xEC, 0x0A, 0x8C, 0x74, 0xDB, 0xFE,
+            0x33, 0x74, 0x4C, 0x7F, 0x09, 0x1B, 0xAF, 0x55, 0x0B, 0x5A, 0x52, 0x6F, 0xD3, 0x2D, 0x60,
+        ];
+
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let key = SigningKey::from_bytes(&EC_ROOT_KEY.into()).unwrap();
+        let der = der_encode(&basic(EC_LEAF_OCSP_RESP)).unwrap();
+        let signed = pad_first_integer(&der, Some(&key));
+        let response = der_decode::<BasicOCSPResponse>(&signed).unwrap();
+        assert_ne!(signed, der_encode(&response).unwrap());
+        assert_eq!(Ok(()), response.verify_signed_by(&root));
+
+        let altered = der_decode::<BasicOCSPResponse>(&pad_first_integer(&der, None)).unwrap();
+        assert_eq!(Err(OCSPError::Verify(VerifyError::BadSignature)), altered.verify_signed_by(&root));
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn ocsp_response_rejects_unauthorized_signers() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+
+        // The leaf is in certs and issued by the root, but is not an OCSP
+        // signer.
+        let response = basic(EC_LEAF_OCSP_RESP_UNAUTHORIZED);
+        assert_eq!(Err(OCSPError::UnauthorizedResponder), response.verify_signed_by(&root));
+
+        // A delegated response with the responder's certificate left out.
+        let mut response = basic(EC_ROOT_OCSP_RESP_DELEGATED);
+        let responder = response.certs.remove(0);
+        assert_eq!(Err(OCSPError::UnauthorizedResponder), response.verify_signed_by(&root));
+
+        // The responder's certificate altered after the root signed it.
+        let mut altered = responder.clone();
+        altered.tbs_certificate.serial_number = CertificateSerialNumber(BigInt::from(0x2002));
+        response.certs = vec![altered.clone()];
+        assert_eq!(Err(OCSPError::Verify(VerifyError::BadSignature)), response.verify_signed_by(&root));
+
+        // Alongside the genuine certificate, the altered one is passed over
+        // whichever comes first.
+        response.certs = vec![altered.clone(), responder.clone()];
+        assert_eq!(Ok(()), response.verify_signed_by(&root));
+        response.certs = vec![responder.clone(), altered];
+        assert_eq!(Ok(()), response.verify_signed_by(&root));
+
+        // The responder's certificate naming another algorithm outside its
+        // signed part than inside.
+        let mut mismatched = responder.clone();
+        mismatched.signature_algorithm = AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA384);
+        response.certs = vec![mismatched];
+        assert_eq!(Err(OCSPError::UnauthorizedResponder), response.verify_signed_by(&root));
+
+        // The response altered after the responder signed it.
+        response.certs = vec![responder];
+        response.tbs_response_data.responses.pop();
+        assert_eq!(Err(OCSPError::Verify(VerifyError::BadSignature)), response.verify_signed_by(&root));
+    }
+}
@@ -0,0 +1,202 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use num::bigint::BigUint;
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1, OID};
+
+use der::next_block;
+use error::X509DecodeErr;
+
+/// Build an `ObjectIdentifier` from its arcs. This works in `const` items:
+///
+/// ```
+/// #[macro_use]
+/// extern crate x509;
+///
+/// use x509::oid::ObjectIdentifier;
+///
+/// const SHA256_WITH_RSA: ObjectIdentifier = oid!(1, 2, 840, 113549, 1, 1, 11);
+///
+/// fn main() {
+///     assert_eq!("1.2.840.113549.1.1.11", SHA256_WITH_RSA.to_string());
+/// }
+/// ```
+#[macro_export]
+macro_rules! oid {
+    ( $( $arc:expr ),* ) => {
+        $crate::oid::ObjectIdentifier::from_static(&[$( $arc ),*])
+    };
+}
+
+/// An ASN.1 OBJECT IDENTIFIER.
+///
+/// Arcs are held as `u128`, which covers the UUID-based `2.25` arc.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectIdentifier(Cow<'static, [u128]>);
+
+impl ObjectIdentifier {
+    /// An OID over a static list of arcs; see also the `oid!` macro.
+    pub const fn from_static(arcs: &'static [u128]) -> ObjectIdentifier {
+        ObjectIdentifier(Cow::Borrowed(arcs))
+    }
+
+    pub fn new(arcs: Vec<u128>) -> ObjectIdentifier {
+        ObjectIdentifier(Cow::Owned(arcs))
+    }
+
+    pub fn arcs(&self) -> &[u128] {
+        &self.0
+    }
+
+    /// Whether `self` is `other` or lies beneath it in the OID tree.
+    pub fn starts_with(&self, other: &ObjectIdentifier) -> bool {
+        self.0.starts_with(&other.0)
+    }
+
+    pub(crate) fn to_block(&self) -> ASN1Block {
+        ASN1Block::ObjectIdentifier(0, OID::new(self.0.iter().map(|&a| BigUint::from(a)).collect()))
+    }
+
+    pub(crate) fn from_block(b: &ASN1Block, field: &'static str) -> Result<ObjectIdentifier, X509DecodeErr> {
+        match *b {
+            ASN1Block::ObjectIdentifier(_, ref oid) => match oid.as_vec::<u128>() {
+                Ok(arcs) => Ok(ObjectIdentifier::new(arcs)),
+                Err(_) => Err(X509DecodeErr::InvalidField(field)),
+            },
+            _ => Err(X509DecodeErr::InvalidField(field)),
+        }
+    }
+}
+
+impl fmt::Display for ObjectIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, arc) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", arc)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ObjectIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ObjectIdentifier({})", self)
+    }
+}
+
+/// An error parsing an OID from dotted-decimal text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseObjectIdentifierErr;
+
+impl fmt::Display for ParseObjectIdentifierErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid dotted-decimal object identifier")
+    }
+}
+
+impl Error for ParseObjectIdentifierErr {}
+
+impl FromStr for ObjectIdentifier {
+    type Err = ParseObjectIdentifierErr;
+
+    fn from_str(s: &str) -> Result<ObjectIdentifier, ParseObjectIdentifierErr> {
+        let mut arcs = Vec::new();
+        for part in s.split('.') {
+            if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) || (part.len() > 1 && part.starts_with('0')) {
+                return Err(ParseObjectIdentifierErr);
+            }
+            arcs.push(part.parse::<u128>().map_err(|_| ParseObjectIdentifierErr)?);
+        }
+        // X.660: the first arc is 0, 1 or 2, and under 0 and 1 the second
+        // arc is below 40.
+        match (arcs.first(), arcs.get(1)) {
+            (Some(&first), Some(&second)) if first < 2 && second < 40 => {}
+            (Some(&2), Some(_)) => {}
+            _ => return Err(ParseObjectIdentifierErr),
+        }
+        Ok(ObjectIdentifier::new(arcs))
+    }
+}
+
+impl ToASN1 for ObjectIdentifier {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![self.to_block()])
+    }
+}
+
+impl FromASN1 for ObjectIdentifier {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "OBJECT IDENTIFIER")?;
+        Ok((ObjectIdentifier::from_block(head, "OBJECT IDENTIFIER")?, tail))
+    }
+}
+
+#[cfg(test)]
+mod object_identifier_tests {
+    use simple_asn1::der_encode;
+
+    use super::{ObjectIdentifier, ParseObjectIdentifierErr};
+    use der::der_decode;
+
+    const SHA256_WITH_RSA: ObjectIdentifier = oid!(1, 2, 840, 113549, 1, 1, 11);
+    const SHA256_WITH_RSA_DER: &[u8] = &[0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0B];
+
+    #[test]
+    fn object_identifier_formats_dotted() {
+        assert_eq!("1.2.840.113549.1.1.11", SHA256_WITH_RSA.to_string());
+        assert_eq!("ObjectIdentifier(1.2.840.113549.1.1.11)", format!("{:?}", SHA256_WITH_RSA));
+    }
+
+    #[test]
+    fn object_identifier_parses_dotted() {
+        assert_eq!(SHA256_WITH_RSA, "1.2.840.113549.1.1.11".parse().unwrap());
+        assert_eq!(oid!(2, 999, 3), "2.999.3".parse().unwrap());
+    }
+
+    #[test]
+    fn object_identifier_rejects_bad_dotted() {
+        for input in &["", "1", "1.", ".1.2", "1..2", "3.1", "1.40", "1.2.a", "1.02", "-1.2"] {
+            assert_eq!(Err(ParseObjectIdentifierErr), input.parse::<ObjectIdentifier>(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn object_identifier_static_and_owned_are_equal() {
+        assert_eq!(SHA256_WITH_RSA, ObjectIdentifier::new(vec![1, 2, 840, 113549, 1, 1, 11]));
+    }
+
+    #[test]
+    fn object_identifier_encodes() {
+        assert_eq!(SHA256_WITH_RSA_DER.to_vec(), der_encode(&SHA256_WITH_RSA).unwrap());
+    }
+
+    #[test]
+    fn object_identifier_decodes() {
+        assert_eq!(SHA256_WITH_RSA, der_decode::<ObjectIdentifier>(SHA256_WITH_RSA_DER).unwrap());
+    }
+
+    #[test]
+    fn object_identifier_decodes_uuid_arc() {
+        // 2.25.329800735698586629295641978511506172918, from X.667.
+        let input = [
+            0x06, 0x14, 0x69, 0x83, 0xF0, 0x9D, 0xA7, 0xEB, 0xCF, 0xDE, 0xE0, 0xC7, 0xA1, 0xA7, 0xB2, 0xC0,
+            0x94, 0x8C, 0xC8, 0xF9, 0xD7, 0x76,
+        ];
+        let actual = der_decode::<ObjectIdentifier>(&input).unwrap();
+        assert_eq!("2.25.329800735698586629295641978511506172918", actual.to_string());
+    }
+
+    #[test]
+    fn object_identifier_starts_with() {
+        assert!(SHA256_WITH_RSA.starts_with(&oid!(1, 2, 840, 113549)));
+        assert!(!oid!(1, 2, 840).starts_with(&SHA256_WITH_RSA));
+    }
+}
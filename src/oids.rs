@@ -0,0 +1,231 @@
+//! Well-known object identifiers, and their conventional names.
+
+use oid::ObjectIdentifier;
+
+macro_rules! well_known {
+    ( $( $(#[$doc:meta])* $name:ident = ( $( $arc:expr ),* ), $friendly:expr; )* ) => {
+        $(
+            $(#[$doc])*
+            pub const $name: ObjectIdentifier = oid!($( $arc ),*);
+        )*
+
+        static NAMES: &[(ObjectIdentifier, &str)] = &[ $( ($name, $friendly) ),* ];
+    };
+}
+
+well_known! {
+    // Public key and signature algorithms
+    RSA_ENCRYPTION = (1, 2, 840, 113549, 1, 1, 1), "rsaEncryption";
+    SHA1_WITH_RSA_ENCRYPTION = (1, 2, 840, 113549, 1, 1, 5), "sha1WithRSAEncryption";
+    MGF1 = (1, 2, 840, 113549, 1, 1, 8), "id-mgf1";
+    RSASSA_PSS = (1, 2, 840, 113549, 1, 1, 10), "RSASSA-PSS";
+    SHA256_WITH_RSA_ENCRYPTION = (1, 2, 840, 113549, 1, 1, 11), "sha256WithRSAEncryption";
+    SHA384_WITH_RSA_ENCRYPTION = (1, 2, 840, 113549, 1, 1, 12), "sha384WithRSAEncryption";
+    SHA512_WITH_RSA_ENCRYPTION = (1, 2, 840, 113549, 1, 1, 13), "sha512WithRSAEncryption";
+    EC_PUBLIC_KEY = (1, 2, 840, 10045, 2, 1), "id-ecPublicKey";
+    ECDSA_WITH_SHA256 = (1, 2, 840, 10045, 4, 3, 2), "ecdsa-with-SHA256";
+    ECDSA_WITH_SHA384 = (1, 2, 840, 10045, 4, 3, 3), "ecdsa-with-SHA384";
+    ECDSA_WITH_SHA512 = (1, 2, 840, 10045, 4, 3, 4), "ecdsa-with-SHA512";
+    X25519 = (1, 3, 101, 110), "X25519";
+    X448 = (1, 3, 101, 111), "X448";
+    ED25519 = (1, 3, 101, 112), "Ed25519";
+    ED448 = (1, 3, 101, 113), "Ed448";
+
+    // Post-quantum signature algorithms: ML-DSA (FIPS 204) and SLH-DSA (FIPS 205)
+    ML_DSA_44 = (2, 16, 840, 1, 101, 3, 4, 3, 17), "id-ml-dsa-44";
+    ML_DSA_65 = (2, 16, 840, 1, 101, 3, 4, 3, 18), "id-ml-dsa-65";
+    ML_DSA_87 = (2, 16, 840, 1, 101, 3, 4, 3, 19), "id-ml-dsa-87";
+    SLH_DSA_SHA2_128S = (2, 16, 840, 1, 101, 3, 4, 3, 20), "id-slh-dsa-sha2-128s";
+    SLH_DSA_SHA2_128F = (2, 16, 840, 1, 101, 3, 4, 3, 21), "id-slh-dsa-sha2-128f";
+    SLH_DSA_SHA2_192S = (2, 16, 840, 1, 101, 3, 4, 3, 22), "id-slh-dsa-sha2-192s";
+    SLH_DSA_SHA2_192F = (2, 16, 840, 1, 101, 3, 4, 3, 23), "id-slh-dsa-sha2-192f";
+    SLH_DSA_SHA2_256S = (2, 16, 840, 1, 101, 3, 4, 3, 24), "id-slh-dsa-sha2-256s";
+    SLH_DSA_SHA2_256F = (2, 16, 840, 1, 101, 3, 4, 3, 25), "id-slh-dsa-sha2-256f";
+    SLH_DSA_SHAKE_128S = (2, 16, 840, 1, 101, 3, 4, 3, 26), "id-slh-dsa-shake-128s";
+    SLH_DSA_SHAKE_128F = (2, 16, 840, 1, 101, 3, 4, 3, 27), "id-slh-dsa-shake-128f";
+    SLH_DSA_SHAKE_192S = (2, 16, 840, 1, 101, 3, 4, 3, 28), "id-slh-dsa-shake-192s";
+    SLH_DSA_SHAKE_192F = (2, 16, 840, 1, 101, 3, 4, 3, 29), "id-slh-dsa-shake-192f";
+    SLH_DSA_SHAKE_256S = (2, 16, 840, 1, 101, 3, 4, 3, 30), "id-slh-dsa-shake-256s";
+    SLH_DSA_SHAKE_256F = (2, 16, 840, 1, 101, 3, 4, 3, 31), "id-slh-dsa-shake-256f";
+
+    // Composite ML-DSA (draft-ietf-lamps-pq-composite-sigs)
+    MLDSA44_RSA2048_PSS_SHA256 = (1, 3, 6, 1, 5, 5, 7, 6, 37), "id-MLDSA44-RSA2048-PSS-SHA256";
+    MLDSA44_RSA2048_PKCS15_SHA256 = (1, 3, 6, 1, 5, 5, 7, 6, 38), "id-MLDSA44-RSA2048-PKCS15-SHA256";
+    MLDSA44_ED25519_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 39), "id-MLDSA44-Ed25519-SHA512";
+    MLDSA44_ECDSA_P256_SHA256 = (1, 3, 6, 1, 5, 5, 7, 6, 40), "id-MLDSA44-ECDSA-P256-SHA256";
+    MLDSA65_RSA3072_PSS_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 41), "id-MLDSA65-RSA3072-PSS-SHA512";
+    MLDSA65_RSA3072_PKCS15_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 42), "id-MLDSA65-RSA3072-PKCS15-SHA512";
+    MLDSA65_RSA4096_PSS_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 43), "id-MLDSA65-RSA4096-PSS-SHA512";
+    MLDSA65_RSA4096_PKCS15_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 44), "id-MLDSA65-RSA4096-PKCS15-SHA512";
+    MLDSA65_ECDSA_P256_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 45), "id-MLDSA65-ECDSA-P256-SHA512";
+    MLDSA65_ECDSA_P384_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 46), "id-MLDSA65-ECDSA-P384-SHA512";
+    MLDSA65_ECDSA_BRAINPOOLP256R1_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 47), "id-MLDSA65-ECDSA-brainpoolP256r1-SHA512";
+    MLDSA65_ED25519_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 48), "id-MLDSA65-Ed25519-SHA512";
+    MLDSA87_ECDSA_P384_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 49), "id-MLDSA87-ECDSA-P384-SHA512";
+    MLDSA87_ECDSA_BRAINPOOLP384R1_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 50), "id-MLDSA87-ECDSA-brainpoolP384r1-SHA512";
+    MLDSA87_ED448_SHAKE256 = (1, 3, 6, 1, 5, 5, 7, 6, 51), "id-MLDSA87-Ed448-SHAKE256";
+    MLDSA87_RSA3072_PSS_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 52), "id-MLDSA87-RSA3072-PSS-SHA512";
+    MLDSA87_RSA4096_PSS_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 53), "id-MLDSA87-RSA4096-PSS-SHA512";
+    MLDSA87_ECDSA_P521_SHA512 = (1, 3, 6, 1, 5, 5, 7, 6, 54), "id-MLDSA87-ECDSA-P521-SHA512";
+
+    // Password-based encryption (RFC 8018)
+    PBKDF2 = (1, 2, 840, 113549, 1, 5, 12), "PBKDF2";
+    PBES2 = (1, 2, 840, 113549, 1, 5, 13), "PBES2";
+    HMAC_WITH_SHA1 = (1, 2, 840, 113549, 2, 7), "hmacWithSHA1";
+    HMAC_WITH_SHA256 = (1, 2, 840, 113549, 2, 9), "hmacWithSHA256";
+    HMAC_WITH_SHA384 = (1, 2, 840, 113549, 2, 10), "hmacWithSHA384";
+    HMAC_WITH_SHA512 = (1, 2, 840, 113549, 2, 11), "hmacWithSHA512";
+    AES128_CBC = (2, 16, 840, 1, 101, 3, 4, 1, 2), "aes128-CBC";
+    AES192_CBC = (2, 16, 840, 1, 101, 3, 4, 1, 22), "aes192-CBC";
+    AES256_CBC = (2, 16, 840, 1, 101, 3, 4, 1, 42), "aes256-CBC";
+
+    // PKCS#7 content types and PKCS#12 bag types (RFC 7292)
+    PKCS7_DATA = (1, 2, 840, 113549, 1, 7, 1), "data";
+    PKCS7_SIGNED_DATA = (1, 2, 840, 113549, 1, 7, 2), "signedData";
+    PKCS7_ENCRYPTED_DATA = (1, 2, 840, 113549, 1, 7, 6), "encryptedData";
+    KEY_BAG = (1, 2, 840, 113549, 1, 12, 10, 1, 1), "keyBag";
+    PKCS8_SHROUDED_KEY_BAG = (1, 2, 840, 113549, 1, 12, 10, 1, 2), "pkcs8ShroudedKeyBag";
+    CERT_BAG = (1, 2, 840, 113549, 1, 12, 10, 1, 3), "certBag";
+    X509_CERTIFICATE = (1, 2, 840, 113549, 1, 9, 22, 1), "x509Certificate";
+
+    // Named curves
+    SECP256R1 = (1, 2, 840, 10045, 3, 1, 7), "secp256r1";
+    SECP384R1 = (1, 3, 132, 0, 34), "secp384r1";
+    SECP521R1 = (1, 3, 132, 0, 35), "secp521r1";
+    BRAINPOOL_P256R1 = (1, 3, 36, 3, 3, 2, 8, 1, 1, 7), "brainpoolP256r1";
+    BRAINPOOL_P384R1 = (1, 3, 36, 3, 3, 2, 8, 1, 1, 11), "brainpoolP384r1";
+
+    // Digests
+    SHA1 = (1, 3, 14, 3, 2, 26), "SHA-1";
+    SHA256 = (2, 16, 840, 1, 101, 3, 4, 2, 1), "SHA-256";
+    SHA384 = (2, 16, 840, 1, 101, 3, 4, 2, 2), "SHA-384";
+    SHA512 = (2, 16, 840, 1, 101, 3, 4, 2, 3), "SHA-512";
+
+    // DN attribute types
+    COMMON_NAME = (2, 5, 4, 3), "CN";
+    SURNAME = (2, 5, 4, 4), "SN";
+    SERIAL_NUMBER = (2, 5, 4, 5), "serialNumber";
+    COUNTRY_NAME = (2, 5, 4, 6), "C";
+    LOCALITY_NAME = (2, 5, 4, 7), "L";
+    STATE_OR_PROVINCE_NAME = (2, 5, 4, 8), "ST";
+    STREET_ADDRESS = (2, 5, 4, 9), "street";
+    ORGANIZATION_NAME = (2, 5, 4, 10), "O";
+    ORGANIZATIONAL_UNIT_NAME = (2, 5, 4, 11), "OU";
+    TITLE = (2, 5, 4, 12), "title";
+    GIVEN_NAME = (2, 5, 4, 42), "GN";
+    ORGANIZATION_IDENTIFIER = (2, 5, 4, 97), "organizationIdentifier";
+    EMAIL_ADDRESS = (1, 2, 840, 113549, 1, 9, 1), "emailAddress";
+    DOMAIN_COMPONENT = (0, 9, 2342, 19200300, 100, 1, 25), "DC";
+    USER_ID = (0, 9, 2342, 19200300, 100, 1, 1), "UID";
+
+    // Attribute certificate attributes (RFC 5755)
+    ROLE = (2, 5, 4, 72), "role";
+    CLEARANCE = (2, 5, 4, 55), "clearance";
+    // The clearance OID RFC 3281 gave by mistake, which older ACs use.
+    CLEARANCE_RFC3281 = (2, 5, 1, 5, 55), "clearance (RFC 3281)";
+
+    // Personal data attributes (RFC 3739)
+    DATE_OF_BIRTH = (1, 3, 6, 1, 5, 5, 7, 9, 1), "dateOfBirth";
+    PLACE_OF_BIRTH = (1, 3, 6, 1, 5, 5, 7, 9, 2), "placeOfBirth";
+    GENDER = (1, 3, 6, 1, 5, 5, 7, 9, 3), "gender";
+    COUNTRY_OF_CITIZENSHIP = (1, 3, 6, 1, 5, 5, 7, 9, 4), "countryOfCitizenship";
+    COUNTRY_OF_RESIDENCE = (1, 3, 6, 1, 5, 5, 7, 9, 5), "countryOfResidence";
+
+    // PKCS#9 attributes (RFC 2985)
+    UNSTRUCTURED_NAME = (1, 2, 840, 113549, 1, 9, 2), "unstructuredName";
+    CHALLENGE_PASSWORD = (1, 2, 840, 113549, 1, 9, 7), "challengePassword";
+    EXTENSION_REQUEST = (1, 2, 840, 113549, 1, 9, 14), "extensionRequest";
+    FRIENDLY_NAME = (1, 2, 840, 113549, 1, 9, 20), "friendlyName";
+    LOCAL_KEY_ID = (1, 2, 840, 113549, 1, 9, 21), "localKeyId";
+
+    // Certificate and CRL extensions
+    SUBJECT_DIRECTORY_ATTRIBUTES = (2, 5, 29, 9), "subjectDirectoryAttributes";
+    SUBJECT_KEY_IDENTIFIER = (2, 5, 29, 14), "subjectKeyIdentifier";
+    KEY_USAGE = (2, 5, 29, 15), "keyUsage";
+    SUBJECT_ALT_NAME = (2, 5, 29, 17), "subjectAltName";
+    ISSUER_ALT_NAME = (2, 5, 29, 18), "issuerAltName";
+    BASIC_CONSTRAINTS = (2, 5, 29, 19), "basicConstraints";
+    CRL_NUMBER = (2, 5, 29, 20), "cRLNumber";
+    CRL_REASON = (2, 5, 29, 21), "cRLReason";
+    INVALIDITY_DATE = (2, 5, 29, 24), "invalidityDate";
+    DELTA_CRL_INDICATOR = (2, 5, 29, 27), "deltaCRLIndicator";
+    ISSUING_DISTRIBUTION_POINT = (2, 5, 29, 28), "issuingDistributionPoint";
+    CERTIFICATE_ISSUER = (2, 5, 29, 29), "certificateIssuer";
+    NAME_CONSTRAINTS = (2, 5, 29, 30), "nameConstraints";
+    CRL_DISTRIBUTION_POINTS = (2, 5, 29, 31), "cRLDistributionPoints";
+    CERTIFICATE_POLICIES = (2, 5, 29, 32), "certificatePolicies";
+    POLICY_MAPPINGS = (2, 5, 29, 33), "policyMappings";
+    AUTHORITY_KEY_IDENTIFIER = (2, 5, 29, 35), "authorityKeyIdentifier";
+    POLICY_CONSTRAINTS = (2, 5, 29, 36), "policyConstraints";
+    EXT_KEY_USAGE = (2, 5, 29, 37), "extKeyUsage";
+    FRESHEST_CRL = (2, 5, 29, 46), "freshestCRL";
+    INHIBIT_ANY_POLICY = (2, 5, 29, 54), "inhibitAnyPolicy";
+    AUTHORITY_INFO_ACCESS = (1, 3, 6, 1, 5, 5, 7, 1, 1), "authorityInfoAccess";
+    SUBJECT_INFO_ACCESS = (1, 3, 6, 1, 5, 5, 7, 1, 11), "subjectInfoAccess";
+
+    // Extended key usages
+    ANY_EXTENDED_KEY_USAGE = (2, 5, 29, 37, 0), "anyExtendedKeyUsage";
+    KP_SERVER_AUTH = (1, 3, 6, 1, 5, 5, 7, 3, 1), "serverAuth";
+    KP_CLIENT_AUTH = (1, 3, 6, 1, 5, 5, 7, 3, 2), "clientAuth";
+    KP_CODE_SIGNING = (1, 3, 6, 1, 5, 5, 7, 3, 3), "codeSigning";
+    KP_EMAIL_PROTECTION = (1, 3, 6, 1, 5, 5, 7, 3, 4), "emailProtection";
+    KP_TIME_STAMPING = (1, 3, 6, 1, 5, 5, 7, 3, 8), "timeStamping";
+    KP_OCSP_SIGNING = (1, 3, 6, 1, 5, 5, 7, 3, 9), "OCSPSigning";
+
+    // Access methods
+    AD_OCSP = (1, 3, 6, 1, 5, 5, 7, 48, 1), "OCSP";
+    AD_CA_ISSUERS = (1, 3, 6, 1, 5, 5, 7, 48, 2), "caIssuers";
+    AD_TIME_STAMPING = (1, 3, 6, 1, 5, 5, 7, 48, 3), "timeStamping";
+    AD_CA_REPOSITORY = (1, 3, 6, 1, 5, 5, 7, 48, 5), "caRepository";
+
+    // OCSP
+    OCSP_BASIC = (1, 3, 6, 1, 5, 5, 7, 48, 1, 1), "basicOCSPResponse";
+    OCSP_NONCE = (1, 3, 6, 1, 5, 5, 7, 48, 1, 2), "Nonce";
+
+    // Certificate policies and their qualifiers
+    ANY_POLICY = (2, 5, 29, 32, 0), "anyPolicy";
+    QT_CPS = (1, 3, 6, 1, 5, 5, 7, 2, 1), "cps";
+    QT_UNOTICE = (1, 3, 6, 1, 5, 5, 7, 2, 2), "unotice";
+    CABF_EXTENDED_VALIDATION = (2, 23, 140, 1, 1), "ev-guidelines";
+    CABF_DOMAIN_VALIDATED = (2, 23, 140, 1, 2, 1), "domain-validated";
+    CABF_ORGANIZATION_VALIDATED = (2, 23, 140, 1, 2, 2), "organization-validated";
+    CABF_INDIVIDUAL_VALIDATED = (2, 23, 140, 1, 2, 3), "individual-validated";
+}
+
+/// The conventional short name for a well-known OID, such as `CN` or
+/// `sha256WithRSAEncryption`.
+pub fn name(oid: &ObjectIdentifier) -> Option<&'static str> {
+    NAMES.iter().find(|(known, _)| known == oid).map(|&(_, name)| name)
+}
+
+/// The short name for `oid` if it is well known, otherwise its dotted form.
+pub fn display_name(oid: &ObjectIdentifier) -> String {
+    match name(oid) {
+        Some(name) => name.to_string(),
+        None => oid.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod oids_tests {
+    use super::*;
+
+    #[test]
+    fn oids_name_known() {
+        assert_eq!(Some("CN"), name(&COMMON_NAME));
+        assert_eq!(Some("sha256WithRSAEncryption"), name(&"1.2.840.113549.1.1.11".parse().unwrap()));
+    }
+
+    #[test]
+    fn oids_name_unknown() {
+        assert_eq!(None, name(&oid!(1, 2, 3, 4)));
+        assert_eq!("1.2.3.4", display_name(&oid!(1, 2, 3, 4)));
+    }
+
+    #[test]
+    fn oids_names_are_unique() {
+        for (i, (a, _)) in NAMES.iter().enumerate() {
+            assert!(NAMES[i + 1..].iter().all(|(b, _)| a != b), "{} listed twice", a);
+        }
+    }
+}
@@ -0,0 +1,734 @@
+//! Certification path validation (RFC 5280 §6).
+//!
+//! A path runs from a trust anchor to the certificate being checked, the
+//! target. `PathValidator` checks each certificate in turn, carrying the
+//! issuer name, public key, remaining path length and name constraints
+//! forward as §6.1 describes, and reports every problem it finds rather
+//! than only the first. Certificate policies are processed too, and the
+//! resulting policy tree returned for the relying party to act on.
+//! A trust anchor may itself constrain its paths, as one loaded from an
+//! RFC 5914 `TrustAnchorInfo` can. Revocation is not checked here.
+//!
+//! When the path is not known up front, `PathBuilder` finds the
+//! candidates from a pool of intermediates, starting from the anchors in
+//! a `TrustAnchorStore`. With the `native-roots` feature the store can be
+//! loaded from the platform's own trust store, and with `mozilla-roots`
+//! from a copy of Mozilla's built into the crate.
+
+use std::error::Error;
+use std::fmt;
+
+use time::PrimitiveDateTime;
+
+use error::X509DecodeErr;
+use extensions::{BasicConstraints, ExtensionRegistry, KeyUsage, NameConstraints, SubjectAltName};
+use general_name::GeneralName;
+use name::Name;
+use oid::ObjectIdentifier;
+use oids;
+use spki::SubjectPublicKeyInfo;
+#[cfg(feature = "verify")]
+use verify::RustCrypto;
+use verify::{SignatureVerifier, VerifyError};
+use x509::{Certificate, Version};
+
+mod builder;
+#[cfg(feature = "mozilla-roots")]
+mod mozilla;
+#[cfg(feature = "native-roots")]
+mod native;
+mod policy;
+mod store;
+
+pub use self::builder::{CandidatePath, PathBuilder};
+#[cfg(feature = "native-roots")]
+pub use self::native::NativeRootsError;
+pub use self::policy::PolicyNode;
+pub use self::store::TrustAnchorStore;
+use self::policy::PolicyState;
+
+// The extensions the validator processes, and so accepts when critical.
+const PROCESSED_EXTENSIONS: &[ObjectIdentifier] = &[
+    oids::BASIC_CONSTRAINTS,
+    oids::KEY_USAGE,
+    oids::NAME_CONSTRAINTS,
+    oids::CERTIFICATE_POLICIES,
+    oids::POLICY_MAPPINGS,
+    oids::POLICY_CONSTRAINTS,
+    oids::INHIBIT_ANY_POLICY,
+    oids::SUBJECT_ALT_NAME,
+];
+
+/// A CA trusted directly, that a path starts from. Only its name, key and
+/// constraints are used; a self-signed root certificate's own fields are
+/// not checked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrustAnchor {
+    pub name: Name,
+    pub public_key: SubjectPublicKeyInfo,
+    pub constraints: TrustAnchorConstraints,
+}
+
+impl TrustAnchor {
+    pub fn new(name: Name, public_key: SubjectPublicKeyInfo) -> TrustAnchor {
+        TrustAnchor { name, public_key, constraints: TrustAnchorConstraints::default() }
+    }
+
+    /// The anchor for a root certificate: its subject and public key.
+    pub fn from_certificate(cert: &Certificate) -> TrustAnchor {
+        let tbs = &cert.tbs_certificate;
+        TrustAnchor::new(tbs.subject.clone(), tbs.subject_public_key_info.clone())
+    }
+}
+
+/// Limits a trust anchor places on the paths that start from it, applied
+/// as RFC 5937 §3 describes. An anchor from a root certificate has none;
+/// one from a `TrustAnchorInfo` has those of its CertPathControls.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrustAnchorConstraints {
+    /// The policies paths may be valid for, which narrows the validator's
+    /// `initial_policy_set`. None allows any.
+    pub policy_set: Option<Vec<ObjectIdentifier>>,
+    /// Set along with the validator's `initial_policy_mapping_inhibit`.
+    pub inhibit_policy_mapping: bool,
+    /// Set along with the validator's `initial_explicit_policy`.
+    pub require_explicit_policy: bool,
+    /// Set along with the validator's `initial_any_policy_inhibit`.
+    pub inhibit_any_policy: bool,
+    /// Applied to every certificate in the path, as if the anchor were a
+    /// CA certificate with this extension.
+    pub name_constraints: Option<NameConstraints>,
+    /// How many intermediates, not counting self-issued ones, may follow
+    /// the anchor.
+    pub path_len_constraint: Option<u64>,
+}
+
+impl TrustAnchorConstraints {
+    fn constrains_policy(&self) -> bool {
+        self.policy_set.is_some()
+            || self.inhibit_policy_mapping
+            || self.require_explicit_policy
+            || self.inhibit_any_policy
+    }
+}
+
+/// A problem with one certificate in a path.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Failure {
+    /// There are no certificates to validate.
+    EmptyPath,
+    /// The issuer is not the subject of the certificate before it, or the
+    /// trust anchor.
+    IssuerMismatch,
+    /// The algorithm in the signed part differs from the one the
+    /// signature is made with.
+    SignatureAlgorithmMismatch,
+    /// The signature did not verify with the issuer's key.
+    Signature(VerifyError),
+    /// The validation time is before notBefore.
+    NotYetValid,
+    /// The validation time is after notAfter.
+    Expired,
+    /// An intermediate certificate is not a v3 certificate whose
+    /// basicConstraints says it is a CA.
+    NotCA,
+    /// An intermediate certificate is further from the anchor than a
+    /// pathLenConstraint allows.
+    PathLengthExceeded,
+    /// An intermediate certificate's keyUsage does not allow keyCertSign.
+    KeyCertSignNotAllowed,
+    /// A name in the subject or subjectAltName is outside the name
+    /// constraints of a CA earlier in the path.
+    NameNotPermitted(GeneralName),
+    /// An intermediate certificate's policyMappings maps to or from
+    /// anyPolicy.
+    AnyPolicyMapped,
+    /// An explicit policy is required, and the path is valid for none
+    /// the relying party accepts.
+    ExplicitPolicyRequired,
+    /// A critical extension is neither processed by the validator nor a
+    /// type registered with it.
+    UnhandledCriticalExtension(ObjectIdentifier),
+    /// An extension the validator needs could not be decoded.
+    Decode(X509DecodeErr),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Failure::EmptyPath => write!(f, "path is empty"),
+            Failure::IssuerMismatch => write!(f, "issuer does not match the previous subject"),
+            Failure::SignatureAlgorithmMismatch => write!(f, "signature algorithms differ"),
+            Failure::Signature(ref e) => write!(f, "signature check failed: {}", e),
+            Failure::NotYetValid => write!(f, "not yet valid"),
+            Failure::Expired => write!(f, "expired"),
+            Failure::NotCA => write!(f, "not a CA certificate"),
+            Failure::PathLengthExceeded => write!(f, "path length constraint exceeded"),
+            Failure::KeyCertSignNotAllowed => write!(f, "key usage does not allow certificate signing"),
+            Failure::NameNotPermitted(_) => write!(f, "name not permitted by name constraints"),
+            Failure::AnyPolicyMapped => write!(f, "policy mapping involves anyPolicy"),
+            Failure::ExplicitPolicyRequired => write!(f, "no acceptable policy"),
+            Failure::UnhandledCriticalExtension(ref oid) => write!(f, "unhandled critical extension: {}", oid),
+            Failure::Decode(ref e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// A failure and the position of the certificate it concerns, where 0 is
+/// the certificate the trust anchor issued.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathFailure {
+    pub index: usize,
+    pub failure: Failure,
+}
+
+/// Why a path is not valid: every failure found, in path order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    pub failures: Vec<PathFailure>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "path validation failed")?;
+        for (i, failure) in self.failures.iter().enumerate() {
+            let separator = if i == 0 { ": " } else { "; " };
+            write!(f, "{}certificate {}: {}", separator, failure.index, failure.failure)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ValidationError {}
+
+/// What validating a path establishes about its target.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidPath {
+    /// The target's public key, vouched for by the trust anchor.
+    pub public_key: SubjectPublicKeyInfo,
+    /// The valid policy tree, or None if the path is valid for no policy
+    /// the relying party accepts.
+    pub policy_tree: Option<PolicyNode>,
+}
+
+/// Runs the basic path validation algorithm of RFC 5280 §6.1.
+#[derive(Clone, Debug)]
+pub struct PathValidator {
+    /// The time the path must be valid at.
+    pub time: PrimitiveDateTime,
+    /// Extension types that may be critical without failing validation,
+    /// beyond those the validator processes itself. Registering a type
+    /// here means the application handles that extension. Only types
+    /// added with `register` count: the crate's own types that the
+    /// validator doesn't process, such as cRLDistributionPoints, still
+    /// fail when critical.
+    pub registry: ExtensionRegistry,
+    /// The policies the relying party accepts; anyPolicy for any.
+    pub initial_policy_set: Vec<ObjectIdentifier>,
+    /// Whether policy mapping is inhibited from the start.
+    pub initial_policy_mapping_inhibit: bool,
+    /// Whether the path must be valid for a policy in
+    /// `initial_policy_set`.
+    pub initial_explicit_policy: bool,
+    /// Whether anyPolicy in a certificate is ignored from the start.
+    pub initial_any_policy_inhibit: bool,
+}
+
+impl PathValidator {
+    /// A validator for paths valid at `time`.
+    pub fn new(time: PrimitiveDateTime) -> PathValidator {
+        PathValidator {
+            time,
+            registry: ExtensionRegistry::new(),
+            initial_policy_set: vec![oids::ANY_POLICY],
+            initial_policy_mapping_inhibit: false,
+            initial_explicit_policy: false,
+            initial_any_policy_inhibit: false,
+        }
+    }
+
+    /// Validate `path`, which starts with the certificate `anchor` issued
+    /// and ends with the target, using the RustCrypto backend.
+    #[cfg(feature = "verify")]
+    pub fn validate(&self, anchor: &TrustAnchor, path: &[Certificate]) -> Result<ValidPath, ValidationError> {
+        self.validate_with(anchor, path, &RustCrypto)
+    }
+
+    /// Validate `path` with the given backend.
+    pub fn validate_with<V: SignatureVerifier + ?Sized>(
+        &self,
+        anchor: &TrustAnchor,
+        path: &[Certificate],
+        verifier: &V,
+    ) -> Result<ValidPath, ValidationError> {
+        let mut failures = Vec::new();
+        if path.is_empty() {
+            failures.push(PathFailure { index: 0, failure: Failure::EmptyPath });
+            return Err(ValidationError { failures });
+        }
+        // The anchor's policy constraints are folded into the validator's
+        // own inputs (RFC 5937 §3.1).
+        let constrained;
+        let inputs = if anchor.constraints.constrains_policy() {
+            constrained = self.constrained_by(&anchor.constraints);
+            &constrained
+        } else {
+            self
+        };
+        let mut working_issuer_name = &anchor.name;
+        let mut working_public_key = &anchor.public_key;
+        let mut max_path_length = path.len().min(anchor.constraints.path_len_constraint.unwrap_or(u64::MAX) as usize);
+        let mut name_constraints: Vec<NameConstraints> = anchor.constraints.name_constraints.iter().cloned().collect();
+        let mut policy = PolicyState::new(inputs, path.len());
+        for (index, cert) in path.iter().enumerate() {
+            let mut fail = |failure| failures.push(PathFailure { index, failure });
+            let tbs = &cert.tbs_certificate;
+
+            // Basic certificate processing (§6.1.3).
+            if tbs.signature != cert.signature_algorithm {
+                fail(Failure::SignatureAlgorithmMismatch);
+            }
+            if let Err(e) = cert.verify_signed_by_with(working_public_key, verifier) {
+                fail(Failure::Signature(e));
+            }
+            if self.time < tbs.validity.not_before.date_time() {
+                fail(Failure::NotYetValid);
+            }
+            if self.time > tbs.validity.not_after.date_time() {
+                fail(Failure::Expired);
+            }
+            if !tbs.issuer.matches(working_issuer_name) {
+                fail(Failure::IssuerMismatch);
+            }
+            // A self-issued intermediate is a CA's own, so its names are
+            // not held to constraints placed on the CA.
+            if index + 1 == path.len() || !tbs.issuer.matches(&tbs.subject) {
+                match subject_names(cert) {
+                    Ok(names) => {
+                        for name in names {
+                            if name_constraints.iter().any(|c| !c.permits(&name) || c.excludes(&name)) {
+                                fail(Failure::NameNotPermitted(name));
+                            }
+                        }
+                    }
+                    Err(e) => fail(Failure::Decode(e)),
+                }
+            }
+            if let Err(failure) = policy.process(cert, index + 1, index + 1 == path.len()) {
+                fail(failure);
+            }
+
+            // Preparation for the next certificate (§6.1.4).
+            if index + 1 < path.len() {
+                let path_len_constraint = match cert.extension::<BasicConstraints>() {
+                    Ok(Some(ref bc)) if bc.ca && tbs.version == Version::V3 => bc.path_len_constraint,
+                    Ok(_) => {
+                        fail(Failure::NotCA);
+                        None
+                    }
+                    Err(e) => {
+                        fail(Failure::Decode(e));
+                        None
+                    }
+                };
+                // Self-issued certificates, such as a CA's key rollover
+                // certificates, don't count towards the path length.
+                if !tbs.issuer.matches(&tbs.subject) {
+                    if max_path_length == 0 {
+                        fail(Failure::PathLengthExceeded);
+                    }
+                    max_path_length = max_path_length.saturating_sub(1);
+                }
+                if let Some(len) = path_len_constraint {
+                    max_path_length = max_path_length.min(len as usize);
+                }
+                if let Err(failure) = policy.prepare(cert, index + 1) {
+                    fail(failure);
+                }
+                match cert.extension::<NameConstraints>() {
+                    Ok(Some(constraints)) => name_constraints.push(constraints),
+                    Ok(None) => {}
+                    Err(e) => fail(Failure::Decode(e)),
+                }
+                match cert.extension::<KeyUsage>() {
+                    Ok(Some(usage)) if !usage.key_cert_sign() => fail(Failure::KeyCertSignNotAllowed),
+                    Ok(_) => {}
+                    Err(e) => fail(Failure::Decode(e)),
+                }
+            }
+            if let Some(ref extensions) = tbs.extensions {
+                for extension in extensions.iter().filter(|e| e.critical) {
+                    let oid = &extension.extn_id;
+                    if !PROCESSED_EXTENSIONS.contains(oid) && !self.registry.is_custom(oid) {
+                        fail(Failure::UnhandledCriticalExtension(oid.clone()));
+                    }
+                }
+            }
+            working_issuer_name = &tbs.subject;
+            working_public_key = &tbs.subject_public_key_info;
+        }
+        // Wrap-up (§6.1.5).
+        let index = path.len() - 1;
+        if let Err(failure) = policy.wrap_up(&path[index], path.len(), &inputs.initial_policy_set) {
+            failures.push(PathFailure { index, failure });
+        }
+        if failures.is_empty() {
+            Ok(ValidPath { public_key: working_public_key.clone(), policy_tree: policy.into_tree() })
+        } else {
+            Err(ValidationError { failures })
+        }
+    }
+
+    // A validator whose policy inputs are narrowed by an anchor's.
+    fn constrained_by(&self, constraints: &TrustAnchorConstraints) -> PathValidator {
+        let mut validator = self.clone();
+        if let Some(ref policy_set) = constraints.policy_set {
+            if validator.initial_policy_set.contains(&oids::ANY_POLICY) {
+                validator.initial_policy_set = policy_set.clone();
+            } else if !policy_set.contains(&oids::ANY_POLICY) {
+                validator.initial_policy_set.retain(|policy| policy_set.contains(policy));
+            }
+        }
+        validator.initial_policy_mapping_inhibit |= constraints.inhibit_policy_mapping;
+        validator.initial_explicit_policy |= constraints.require_explicit_policy;
+        validator.initial_any_policy_inhibit |= constraints.inhibit_any_policy;
+        validator
+    }
+}
+
+// The names name constraints apply to: the subject, any email addresses
+// in it, and the subjectAltName entries.
+fn subject_names(cert: &Certificate) -> Result<Vec<GeneralName>, X509DecodeErr> {
+    let subject = &cert.tbs_certificate.subject;
+    let mut names = Vec::new();
+    if !subject.0.is_empty() {
+        names.push(GeneralName::DirectoryName(subject.clone()));
+    }
+    names.extend(subject.values(&oids::EMAIL_ADDRESS).map(|email| GeneralName::RFC822Name(email.to_string())));
+    if let Some(alt_names) = cert.extension::<SubjectAltName>()? {
+        names.extend(alt_names.iter().cloned());
+    }
+    Ok(names)
+}
+
+#[cfg(all(test, feature = "verify"))]
+mod path_tests {
+    use num::bigint::BigInt;
+    use p256::ecdsa::signature::Signer;
+    use p256::ecdsa::{Signature, SigningKey};
+    use simple_asn1::der_encode;
+    use time::{Date, Month, PrimitiveDateTime, Time};
+
+    use super::{Failure, PathFailure, PathValidator, TrustAnchor, ValidPath, ValidationError};
+    use der::der_decode;
+    use extensions::{
+        BasicConstraints, CRLDistributionPoints, DistributionPoint, GeneralSubtree, KeyUsage, NameConstraints,
+        SubjectAltName,
+    };
+    use general_name::GeneralName;
+    use name::{AttributeValue, DirectoryString, Name};
+    use oid::ObjectIdentifier;
+    use oids;
+    use verify::VerifyError;
+    use x509::{Certificate, CertificateSerialNumber, TBSCertificate};
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../testdata/ec-leaf.der");
+    const EC_INT: &[u8] = include_bytes!("../testdata/ec-int.der");
+    const EC_INT_LEAF: &[u8] = include_bytes!("../testdata/ec-int-leaf.der");
+    const EC_UNKNOWN_EXT: &[u8] = include_bytes!("../testdata/ec-unknown-ext.der");
+    // The private keys of EC_LEAF and EC_INT_LEAF, to issue certificates
+    // under them.
+    const EC_LEAF_KEY: [u8; 32] = [
+        0x51, 0x37, 0xB1, 0xAF, 0xBC, 0x7E, 0xEA, 0x34, 0x16, 0x50, 0x4E, 0x9E, 0xC5, 0xE5, 0xC3, 0xD9, 0x67, 0x37,
+        0x8F, 0x24, 0x01, 0xA4, 0x12, 0x56, 0x36, 0x25, 0x88, 0xCB, 0xC7, 0xFD, 0x3B, 0x4B,
+    ];
+    const EC_INT_KEY: [u8; 32] = [
+        0x85, 0x28, 0xF7, 0x65, 0x46, 0xC1, 0xE4, 0x0B, 0x7C, 0x64, 0xA6, 0xC1, 0xE7, 0x7E, 0x7C, 0x33, 0xAE, 0x46,
+        0x3F, 0x24, 0x87, 0xD5, 0x32, 0xBA, 0x5B, 0x36, 0xC9, 0xA5, 0xCF, 0xC8, 0x09, 0x23,
+    ];
+    const EC_INT_LEAF_KEY: [u8; 32] = [
+        0x68, 0xA9, 0x0B, 0xF7, 0x03, 0xB2, 0x3A, 0xC1, 0x0A, 0xE7, 0x2F, 0x24, 0xC3, 0x17, 0x54, 0xF3, 0x23, 0xE9,
+        0x66, 0x87, 0xBA, 0x6A, 0x0F, 0x79, 0x4D, 0x0E, 0x64, 0x10, 0xBD, 0x23, 0x97, 0x41,
+    ];
+
+    fn cert(der: &[u8]) -> Certificate {
+        der_decode(der).unwrap()
+    }
+
+    fn anchor() -> TrustAnchor {
+        TrustAnchor::from_certificate(&cert(EC_ROOT))
+    }
+
+    fn at(year: i32, month: Month, day: u8) -> PathValidator {
+        PathValidator::new(PrimitiveDateTime::new(Date::from_calendar_date(year, month, day).unwrap(), Time::MIDNIGHT))
+    }
+
+    fn validator() -> PathValidator {
+        at(2026, Month::October, 15)
+    }
+
+    fn failures(result: Result<ValidPath, ValidationError>) -> Vec<(usize, Failure)> {
+        result.unwrap_err().failures.into_iter().map(|PathFailure { index, failure }| (index, failure)).collect()
+    }
+
+    fn sign(tbs_certificate: TBSCertificate, key: &[u8; 32]) -> Certificate {
+        let key = SigningKey::from_bytes(key.into()).unwrap();
+        let signature: Signature = key.sign(&der_encode(&tbs_certificate).unwrap());
+        Certificate {
+            signature_algorithm: tbs_certificate.signature.clone(),
+            tbs_certificate,
+            signature_value: signature.to_der().as_bytes().to_vec(),
+            signed_bytes: Default::default(),
+        }
+    }
+
+    // A certificate for `subject`'s subject and key, issued by `issuer`.
+    fn issue(subject: &Certificate, issuer: &Certificate, key: &[u8; 32], ca: Option<BasicConstraints>) -> Certificate {
+        let mut tbs = subject.tbs_certificate.clone();
+        tbs.issuer = issuer.tbs_certificate.subject.clone();
+        let extensions = tbs.extensions.get_or_insert_with(Default::default);
+        if let Some(bc) = ca {
+            extensions.insert(&bc, true).unwrap();
+            extensions.insert(&(KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN), true).unwrap();
+        }
+        sign(tbs, key)
+    }
+
+    #[test]
+    fn path_validates() {
+        let validator = validator();
+        let leaf = cert(EC_INT_LEAF);
+        let valid = validator.validate(&anchor(), &[cert(EC_INT), leaf.clone()]).unwrap();
+        assert_eq!(leaf.tbs_certificate.subject_public_key_info, valid.public_key);
+        assert!(validator.validate(&anchor(), &[cert(EC_LEAF)]).is_ok());
+    }
+
+    #[test]
+    fn path_matches_names_across_string_types() {
+        // The leaf names its issuer in UTF8Strings, where the intermediate's
+        // subject has PrintableStrings.
+        let int = cert(EC_INT);
+        let mut issuer = int.tbs_certificate.subject.clone();
+        for attribute in issuer.0.iter_mut().flat_map(|rdn| rdn.0.iter_mut()) {
+            if let AttributeValue::String(DirectoryString::PrintableString(ref s)) = attribute.value {
+                attribute.value = AttributeValue::String(DirectoryString::UTF8String(s.clone()));
+            }
+        }
+        assert_ne!(int.tbs_certificate.subject, issuer);
+        let mut tbs = cert(EC_INT_LEAF).tbs_certificate;
+        tbs.issuer = issuer;
+        let leaf = sign(tbs, &EC_INT_KEY);
+        assert!(validator().validate(&anchor(), &[int, leaf]).is_ok());
+    }
+
+    #[test]
+    fn path_reports_chaining_failures() {
+        let validator = validator();
+        assert_eq!(vec![(0, Failure::EmptyPath)], failures(validator.validate(&anchor(), &[])));
+        assert_eq!(
+            vec![(0, Failure::Signature(VerifyError::BadSignature)), (0, Failure::IssuerMismatch)],
+            failures(validator.validate(&anchor(), &[cert(EC_INT_LEAF)]))
+        );
+        // Out of order, so neither link holds.
+        assert_eq!(
+            vec![
+                (0, Failure::Signature(VerifyError::BadSignature)),
+                (0, Failure::IssuerMismatch),
+                (0, Failure::NotCA),
+                (0, Failure::KeyCertSignNotAllowed),
+                (1, Failure::Signature(VerifyError::BadSignature)),
+                (1, Failure::IssuerMismatch),
+            ],
+            failures(validator.validate(&anchor(), &[cert(EC_INT_LEAF), cert(EC_INT)]))
+        );
+
+        let mut altered = cert(EC_INT);
+        altered.tbs_certificate.serial_number = CertificateSerialNumber(BigInt::from(0x3002));
+        assert_eq!(
+            vec![(0, Failure::Signature(VerifyError::BadSignature))],
+            failures(validator.validate(&anchor(), &[altered, cert(EC_INT_LEAF)]))
+        );
+        let mut altered = cert(EC_INT_LEAF);
+        altered.tbs_certificate.signature.algorithm = oids::ECDSA_WITH_SHA384;
+        assert_eq!(
+            vec![(1, Failure::SignatureAlgorithmMismatch), (1, Failure::Signature(VerifyError::BadSignature))],
+            failures(validator.validate(&anchor(), &[cert(EC_INT), altered]))
+        );
+    }
+
+    #[test]
+    fn path_checks_validity() {
+        let path = [cert(EC_INT), cert(EC_INT_LEAF)];
+        assert_eq!(
+            vec![(0, Failure::NotYetValid), (1, Failure::NotYetValid)],
+            failures(at(2026, Month::October, 14).validate(&anchor(), &path))
+        );
+        assert_eq!(vec![(1, Failure::Expired)], failures(at(2028, Month::January, 1).validate(&anchor(), &path)));
+        assert_eq!(
+            vec![(0, Failure::Expired), (1, Failure::Expired)],
+            failures(at(2031, Month::December, 1).validate(&anchor(), &path))
+        );
+    }
+
+    #[test]
+    fn path_requires_ca_intermediates() {
+        // Issued by EC_LEAF, which is not a CA and may only sign data.
+        let leaf = cert(EC_LEAF);
+        let child = issue(&cert(EC_INT_LEAF), &leaf, &EC_LEAF_KEY, None);
+        assert_eq!(
+            vec![(0, Failure::NotCA), (0, Failure::KeyCertSignNotAllowed)],
+            failures(validator().validate(&anchor(), &[leaf, child]))
+        );
+    }
+
+    #[test]
+    fn path_enforces_path_length() {
+        // EC_INT has pathLenConstraint 0, so no CA may follow it.
+        let int = cert(EC_INT);
+        let sub = issue(&cert(EC_INT_LEAF), &int, &EC_INT_KEY, Some(BasicConstraints::ca(None)));
+        let leaf = issue(&cert(EC_LEAF), &sub, &EC_INT_LEAF_KEY, None);
+        let path = [int.clone(), sub, leaf.clone()];
+        assert_eq!(vec![(1, Failure::PathLengthExceeded)], failures(validator().validate(&anchor(), &path)));
+
+        // A self-issued certificate doesn't count.
+        let rollover = issue(&int, &int, &EC_INT_KEY, Some(BasicConstraints::ca(None)));
+        let leaf = issue(&leaf, &int, &EC_INT_KEY, None);
+        assert!(validator().validate(&anchor(), &[int, rollover, leaf]).is_ok());
+    }
+
+    // A path under EC_LEAF as the anchor, through a CA constrained by
+    // `constraints`, to a certificate with EC_LEAF's names.
+    fn constrained_path(constraints: NameConstraints) -> (TrustAnchor, [Certificate; 2]) {
+        let root = cert(EC_LEAF);
+        let ca = issue(&cert(EC_INT_LEAF), &root, &EC_LEAF_KEY, Some(BasicConstraints::ca(None)));
+        let mut tbs = ca.tbs_certificate;
+        tbs.extensions.as_mut().unwrap().insert(&constraints, true).unwrap();
+        let ca = sign(tbs, &EC_LEAF_KEY);
+        let leaf = issue(&root, &ca, &EC_INT_LEAF_KEY, None);
+        (TrustAnchor::from_certificate(&root), [ca, leaf])
+    }
+
+    fn quicklime() -> Name {
+        let mut name = Name::new();
+        name.push(oids::COUNTRY_NAME, DirectoryString::new("CA"));
+        name.push(oids::ORGANIZATION_NAME, DirectoryString::new("QuickLime"));
+        name
+    }
+
+    #[test]
+    fn path_enforces_name_constraints() {
+        let permitted = NameConstraints {
+            permitted_subtrees: vec![
+                GeneralSubtree::new(GeneralName::DNSName("example.com".to_string())),
+                GeneralSubtree::new(GeneralName::DirectoryName(quicklime())),
+            ],
+            ..Default::default()
+        };
+        let (anchor, path) = constrained_path(permitted);
+        assert!(validator().validate(&anchor, &path).is_ok());
+
+        let excluded = NameConstraints {
+            excluded_subtrees: vec![
+                GeneralSubtree::new(GeneralName::IPAddress(vec![192, 0, 2, 0, 255, 255, 255, 0])),
+                GeneralSubtree::new(GeneralName::RFC822Name("example.com".to_string())),
+            ],
+            ..Default::default()
+        };
+        let (anchor, path) = constrained_path(excluded);
+        assert_eq!(
+            vec![
+                (1, Failure::NameNotPermitted(GeneralName::IPAddress(vec![192, 0, 2, 1]))),
+                (1, Failure::NameNotPermitted(GeneralName::RFC822Name("admin@example.com".to_string()))),
+            ],
+            failures(validator().validate(&anchor, &path))
+        );
+    }
+
+    #[test]
+    fn path_applies_name_constraints_to_subjects() {
+        let mut other = Name::new();
+        other.push(oids::COUNTRY_NAME, DirectoryString::new("CA"));
+        other.push(oids::ORGANIZATION_NAME, DirectoryString::new("Other"));
+        let (anchor, path) = constrained_path(NameConstraints {
+            permitted_subtrees: vec![GeneralSubtree::new(GeneralName::DirectoryName(other))],
+            ..Default::default()
+        });
+        let alt_names = path[1].extension::<SubjectAltName>().unwrap().unwrap();
+        let directory_name = alt_names.directory_names().next().unwrap();
+        assert_eq!(
+            vec![
+                (1, Failure::NameNotPermitted(GeneralName::DirectoryName(path[1].tbs_certificate.subject.clone()))),
+                (1, Failure::NameNotPermitted(GeneralName::DirectoryName(directory_name.clone()))),
+            ],
+            failures(validator().validate(&anchor, &path))
+        );
+    }
+
+    #[test]
+    fn path_applies_anchor_constraints() {
+        let path = [cert(EC_INT), cert(EC_INT_LEAF)];
+        let mut anchor = anchor();
+        anchor.constraints.path_len_constraint = Some(0);
+        assert_eq!(vec![(0, Failure::PathLengthExceeded)], failures(validator().validate(&anchor, &path)));
+        anchor.constraints.path_len_constraint = Some(1);
+        assert!(validator().validate(&anchor, &path).is_ok());
+
+        let mut anchor = self::anchor();
+        anchor.constraints.name_constraints = Some(NameConstraints {
+            excluded_subtrees: vec![GeneralSubtree::new(GeneralName::IPAddress(vec![192, 0, 2, 0, 255, 255, 255, 0]))],
+            ..Default::default()
+        });
+        assert_eq!(
+            vec![(0, Failure::NameNotPermitted(GeneralName::IPAddress(vec![192, 0, 2, 1])))],
+            failures(validator().validate(&anchor, &[cert(EC_LEAF)]))
+        );
+
+        // EC_LEAF asserts only the domain-validated policy.
+        let mut anchor = self::anchor();
+        anchor.constraints.policy_set = Some(vec![oid!(2, 23, 140, 1, 2, 1)]);
+        anchor.constraints.require_explicit_policy = true;
+        assert!(validator().validate(&anchor, &[cert(EC_LEAF)]).is_ok());
+        anchor.constraints.policy_set = Some(vec![oid!(2, 23, 140, 1, 2, 2)]);
+        anchor.constraints.require_explicit_policy = false;
+        assert!(validator().validate(&anchor, &[cert(EC_LEAF)]).unwrap().policy_tree.is_none());
+        anchor.constraints.require_explicit_policy = true;
+        assert_eq!(
+            vec![(0, Failure::ExplicitPolicyRequired)],
+            failures(validator().validate(&anchor, &[cert(EC_LEAF)]))
+        );
+    }
+
+    #[test]
+    fn path_rejects_unhandled_critical_extensions() {
+        let private = ObjectIdentifier::from_static(&[1, 3, 6, 1, 4, 1, 99999, 2, 1]);
+        assert_eq!(
+            vec![(0, Failure::UnhandledCriticalExtension(private))],
+            failures(validator().validate(&anchor(), &[cert(EC_UNKNOWN_EXT)]))
+        );
+    }
+
+    #[test]
+    fn path_rejects_critical_extensions_it_only_decodes() {
+        // The crate has a type for cRLDistributionPoints, but the validator
+        // doesn't process it.
+        let mut tbs = cert(EC_INT_LEAF).tbs_certificate;
+        let points = CRLDistributionPoints(vec![DistributionPoint::uri("http://crl.example.com/int.crl")]);
+        tbs.extensions.get_or_insert_with(Default::default).insert(&points, true).unwrap();
+        let path = [cert(EC_INT), sign(tbs, &EC_INT_KEY)];
+        assert_eq!(
+            vec![(1, Failure::UnhandledCriticalExtension(oids::CRL_DISTRIBUTION_POINTS))],
+            failures(validator().validate(&anchor(), &path))
+        );
+    }
+
+    #[test]
+    fn validation_error_display() {
+        let error = validator().validate(&anchor(), &[cert(EC_INT_LEAF)]).unwrap_err();
+        assert_eq!(
+            "path validation failed: certificate 0: signature check failed: signature does not match; \
+             certificate 0: issuer does not match the previous subject",
+            error.to_string()
+        );
+    }
+}
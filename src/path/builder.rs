@@ -0,0 +1,254 @@
+use extensions::{AuthorityKeyIdentifier, SubjectKeyIdentifier};
+use path::TrustAnchor;
+use x509::Certificate;
+
+/// A path for `PathValidator` to check: `certificates` runs from the one
+/// `anchor` issued to the target.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CandidatePath<'a> {
+    pub anchor: &'a TrustAnchor,
+    pub certificates: Vec<Certificate>,
+}
+
+/// Finds the paths that may lead from a target back to a trust anchor
+/// through a pool of intermediate certificates.
+///
+/// Issuers are matched by name, and by key identifier where both the
+/// authorityKeyIdentifier and the issuer's subjectKeyIdentifier are there
+/// to compare. Every match is followed, so cross-signed intermediates and
+/// CAs that share a subject give one candidate each. Nothing is verified;
+/// that is left to the validator.
+///
+/// The pool often comes from the peer, in a TLS handshake or a PKCS #7
+/// bundle, and certificates that share names can make the number of
+/// paths grow exponentially with their length. The search is therefore
+/// limited to `max_steps` issuers in all; once they are spent, the paths
+/// found so far are returned.
+#[derive(Clone, Debug)]
+pub struct PathBuilder<'a> {
+    pub anchors: &'a [TrustAnchor],
+    pub intermediates: &'a [Certificate],
+    /// The most intermediates a path may have.
+    pub max_intermediates: usize,
+    /// The most intermediates to try as issuers in all.
+    pub max_steps: usize,
+}
+
+impl<'a> PathBuilder<'a> {
+    pub fn new(anchors: &'a [TrustAnchor], intermediates: &'a [Certificate]) -> PathBuilder<'a> {
+        PathBuilder { anchors, intermediates, max_intermediates: 8, max_steps: 100 }
+    }
+
+    /// Every path from `target` to an anchor, shortest first.
+    pub fn candidates(&self, target: &Certificate) -> Vec<CandidatePath<'a>> {
+        let mut paths = Vec::new();
+        let mut steps = self.max_steps;
+        self.extend(target, target, &mut Vec::new(), &mut paths, &mut steps);
+        paths.sort_by_key(|path| path.certificates.len());
+        paths
+    }
+
+    // Add the paths through `cert`, which `chain` leads to from `target`,
+    // nearest first, trying at most `steps` more intermediates.
+    fn extend(
+        &self,
+        target: &Certificate,
+        cert: &Certificate,
+        chain: &mut Vec<&'a Certificate>,
+        paths: &mut Vec<CandidatePath<'a>>,
+        steps: &mut usize,
+    ) {
+        let issuer = &cert.tbs_certificate.issuer;
+        for anchor in self.anchors.iter().filter(|anchor| anchor.name.matches(issuer)) {
+            let mut certificates: Vec<Certificate> = chain.iter().rev().map(|&c| c.clone()).collect();
+            certificates.push(target.clone());
+            paths.push(CandidatePath { anchor, certificates });
+        }
+        if chain.len() == self.max_intermediates {
+            return;
+        }
+        let authority_key_id = cert.extension::<AuthorityKeyIdentifier>().ok().and_then(|aki| aki?.key_identifier);
+        for candidate in self.intermediates {
+            if !candidate.tbs_certificate.subject.matches(issuer) || *candidate == *target || chain.contains(&candidate) {
+                continue;
+            }
+            let subject_key_id = candidate.extension::<SubjectKeyIdentifier>().ok().and_then(|ski| ski);
+            if let (Some(ref aki), Some(ref ski)) = (&authority_key_id, subject_key_id) {
+                if *aki != ski.0 {
+                    continue;
+                }
+            }
+            if *steps == 0 {
+                return;
+            }
+            *steps -= 1;
+            chain.push(candidate);
+            self.extend(target, candidate, chain, paths, steps);
+            chain.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod path_builder_tests {
+    use std::time::{Duration, Instant};
+
+    use num::bigint::BigInt;
+
+    use super::PathBuilder;
+    use der::der_decode;
+    use extensions::SubjectKeyIdentifier;
+    use name::{AttributeValue, DirectoryString, Name};
+    use path::TrustAnchor;
+    use x509::{Certificate, CertificateSerialNumber};
+
+    const EC_ROOT: &[u8] = include_bytes!("../../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+    const EC_INT: &[u8] = include_bytes!("../../testdata/ec-int.der");
+    const EC_INT_LEAF: &[u8] = include_bytes!("../../testdata/ec-int-leaf.der");
+
+    fn cert(der: &[u8]) -> Certificate {
+        der_decode(der).unwrap()
+    }
+
+    fn issued_by(cert: &Certificate, issuer: &Name) -> Certificate {
+        let mut cert = cert.clone();
+        cert.tbs_certificate.issuer = issuer.clone();
+        cert
+    }
+
+    fn other_root() -> TrustAnchor {
+        let mut anchor = TrustAnchor::from_certificate(&cert(EC_INT_LEAF));
+        anchor.name = cert(EC_LEAF).tbs_certificate.subject;
+        anchor
+    }
+
+    #[test]
+    fn path_builder_finds_paths() {
+        let anchors = [TrustAnchor::from_certificate(&cert(EC_ROOT))];
+        let intermediates = [cert(EC_INT)];
+        let builder = PathBuilder::new(&anchors, &intermediates);
+
+        let paths = builder.candidates(&cert(EC_INT_LEAF));
+        assert_eq!(1, paths.len());
+        assert_eq!(&anchors[0], paths[0].anchor);
+        assert_eq!(vec![cert(EC_INT), cert(EC_INT_LEAF)], paths[0].certificates);
+
+        let paths = builder.candidates(&cert(EC_LEAF));
+        assert_eq!(vec![vec![cert(EC_LEAF)]], paths.into_iter().map(|p| p.certificates).collect::<Vec<_>>());
+
+        assert!(PathBuilder::new(&anchors, &[]).candidates(&cert(EC_INT_LEAF)).is_empty());
+    }
+
+    #[test]
+    fn path_builder_follows_cross_signs() {
+        // The intermediate is also cross-signed by another root.
+        let anchors = [TrustAnchor::from_certificate(&cert(EC_ROOT)), other_root()];
+        let cross = issued_by(&cert(EC_INT), &anchors[1].name);
+        let intermediates = [cross.clone(), cert(EC_INT)];
+        let paths = PathBuilder::new(&anchors, &intermediates).candidates(&cert(EC_INT_LEAF));
+        assert_eq!(2, paths.len());
+        assert_eq!((&anchors[1], &cross), (paths[0].anchor, &paths[0].certificates[0]));
+        assert_eq!((&anchors[0], &cert(EC_INT)), (paths[1].anchor, &paths[1].certificates[0]));
+    }
+
+    #[test]
+    fn path_builder_matches_names_across_string_types() {
+        // The intermediate's subject has a PrintableString that the leaf's
+        // issuer writes as a UTF8String.
+        let anchors = [TrustAnchor::from_certificate(&cert(EC_ROOT))];
+        let intermediates = [cert(EC_INT)];
+        let mut leaf = cert(EC_INT_LEAF);
+        for attribute in leaf.tbs_certificate.issuer.0.iter_mut().flat_map(|rdn| rdn.0.iter_mut()) {
+            if let AttributeValue::String(DirectoryString::PrintableString(ref s)) = attribute.value {
+                attribute.value = AttributeValue::String(DirectoryString::UTF8String(s.clone()));
+            }
+        }
+        assert_ne!(intermediates[0].tbs_certificate.subject, leaf.tbs_certificate.issuer);
+        let paths = PathBuilder::new(&anchors, &intermediates).candidates(&leaf);
+        assert_eq!(vec![cert(EC_INT), leaf], paths[0].certificates);
+    }
+
+    #[test]
+    fn path_builder_prefers_shorter_paths() {
+        // The target's issuer is both an anchor and, cross-signed, an
+        // intermediate under another anchor.
+        let root = cert(EC_ROOT);
+        let anchors = [other_root(), TrustAnchor::from_certificate(&root)];
+        let intermediates = [issued_by(&root, &anchors[0].name)];
+        let paths = PathBuilder::new(&anchors, &intermediates).candidates(&cert(EC_LEAF));
+        let lengths: Vec<_> = paths.iter().map(|p| p.certificates.len()).collect();
+        assert_eq!(vec![1, 2], lengths);
+        assert_eq!(&anchors[1], paths[0].anchor);
+    }
+
+    #[test]
+    fn path_builder_matches_key_identifiers() {
+        // Two intermediates share a subject; only one has the key the
+        // target's authorityKeyIdentifier names.
+        let anchors = [TrustAnchor::from_certificate(&cert(EC_ROOT))];
+        let mut rekeyed = cert(EC_INT);
+        let extensions = rekeyed.tbs_certificate.extensions.as_mut().unwrap();
+        extensions.insert(&SubjectKeyIdentifier(vec![0; 20]), false).unwrap();
+        let intermediates = [rekeyed, cert(EC_INT)];
+        let paths = PathBuilder::new(&anchors, &intermediates).candidates(&cert(EC_INT_LEAF));
+        assert_eq!(1, paths.len());
+        assert_eq!(cert(EC_INT), paths[0].certificates[0]);
+    }
+
+    #[test]
+    fn path_builder_stops_at_loops_and_depth() {
+        // Two CAs that cross-sign each other, with no anchor above them.
+        let int = cert(EC_INT);
+        let leaf_ca = cert(EC_INT_LEAF);
+        let intermediates =
+            [issued_by(&int, &leaf_ca.tbs_certificate.subject), issued_by(&leaf_ca, &int.tbs_certificate.subject)];
+        let target = issued_by(&cert(EC_LEAF), &int.tbs_certificate.subject);
+        let anchors = [other_root()];
+        assert!(PathBuilder::new(&anchors, &intermediates).candidates(&target).is_empty());
+
+        let anchors = [TrustAnchor::from_certificate(&cert(EC_ROOT))];
+        let intermediates = [cert(EC_INT)];
+        let mut builder = PathBuilder::new(&anchors, &intermediates);
+        builder.max_intermediates = 0;
+        assert!(builder.candidates(&cert(EC_INT_LEAF)).is_empty());
+    }
+
+    #[test]
+    fn path_builder_limits_search() {
+        // Twenty CAs that share a name and all issue one another give 20^8
+        // paths of the greatest length, none of them to an anchor.
+        let int = cert(EC_INT);
+        let mut intermediates = vec![int.clone()];
+        for serial in 0..20 {
+            let mut looped = issued_by(&int, &int.tbs_certificate.subject);
+            looped.tbs_certificate.serial_number = CertificateSerialNumber(BigInt::from(0x4000 + serial));
+            intermediates.push(looped);
+        }
+        let anchors = [TrustAnchor::from_certificate(&cert(EC_ROOT))];
+        let start = Instant::now();
+        let paths = PathBuilder::new(&anchors, &intermediates).candidates(&cert(EC_INT_LEAF));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(vec![int, cert(EC_INT_LEAF)], paths[0].certificates);
+        assert!(paths.len() <= 100);
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn path_builder_feeds_validator() {
+        use path::PathValidator;
+        use time::{Date, Month, PrimitiveDateTime, Time};
+
+        let anchors = [other_root(), TrustAnchor::from_certificate(&cert(EC_ROOT))];
+        let intermediates = [issued_by(&cert(EC_INT), &anchors[0].name), cert(EC_INT)];
+        let date = Date::from_calendar_date(2026, Month::October, 15).unwrap();
+        let validator = PathValidator::new(PrimitiveDateTime::new(date, Time::MIDNIGHT));
+        let results: Vec<_> = PathBuilder::new(&anchors, &intermediates)
+            .candidates(&cert(EC_INT_LEAF))
+            .into_iter()
+            .map(|path| validator.validate(path.anchor, &path.certificates).is_ok())
+            .collect();
+        // The forged cross-sign fails, the real path passes.
+        assert_eq!(vec![false, true], results);
+    }
+}
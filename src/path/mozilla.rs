@@ -0,0 +1,75 @@
+//! Mozilla's root store, embedded at compile time behind the
+//! `mozilla-roots` feature, for systems with no trust store of their own.
+//!
+//! The roots are those of `roots/mozilla.pem`, which is parsed the first
+//! time the store is asked for and kept for the life of the process.
+//!
+//! To refresh them, take `lib/ckfw/builtins/certdata.txt` from the NSS
+//! repository and keep each certificate whose trust object has
+//! `CKA_TRUST_SERVER_AUTH` set to `CKT_NSS_TRUSTED_DELEGATOR`. Leave out any
+//! with a `CKA_NSS_SERVER_DISTRUST_AFTER` date, as Mozilla gives roots it is
+//! phasing out: the store has no way to honour the date, so such a root is
+//! dropped rather than trusted for everything it issues. Write the rest
+//! to `roots/mozilla.pem` as PEM, each under a comment with its
+//! `CKA_LABEL`, update the header to say where they came from, and run the
+//! tests below with the feature on. Failing certdata.txt, curl's
+//! `cacert.pem` or certifi's bundle, both made from it, will do once any
+//! root with a distrust-after date is taken out.
+
+use std::sync::OnceLock;
+
+use path::TrustAnchorStore;
+use x509::Certificate;
+
+const MOZILLA_ROOTS: &str = include_str!("../../roots/mozilla.pem");
+
+static MOZILLA_STORE: OnceLock<TrustAnchorStore> = OnceLock::new();
+
+impl TrustAnchorStore {
+    /// A store of the roots Mozilla trusts for server authentication.
+    pub fn mozilla() -> TrustAnchorStore {
+        let store = MOZILLA_STORE.get_or_init(|| {
+            let mut store = TrustAnchorStore::new();
+            for cert in Certificate::from_pem_bundle(MOZILLA_ROOTS) {
+                // Every root is checked to decode by the tests below.
+                store.add_certificate(cert.expect("embedded root decodes"));
+            }
+            store
+        });
+        store.clone()
+    }
+}
+
+#[cfg(test)]
+mod mozilla_roots_tests {
+    use super::MOZILLA_ROOTS;
+    use path::{TrustAnchor, TrustAnchorStore};
+    use x509::Certificate;
+
+    #[test]
+    fn mozilla_roots_decode() {
+        let certs: Vec<Certificate> =
+            Certificate::from_pem_bundle(MOZILLA_ROOTS).into_iter().collect::<Result<_, _>>().unwrap();
+        let store = TrustAnchorStore::mozilla();
+        assert_eq!(certs.len(), store.len());
+        assert!(certs.iter().all(|cert| store.contains(&TrustAnchor::from_certificate(cert))));
+        assert_eq!(store, TrustAnchorStore::mozilla());
+
+        let isrg = certs.iter().find(|cert| cert.tbs_certificate.subject.common_name() == Some("ISRG Root X1"));
+        let isrg = isrg.unwrap();
+        let anchors: Vec<_> = store.find_by_subject(&isrg.tbs_certificate.subject).collect();
+        assert_eq!(1, anchors.len());
+        assert_eq!(Some(isrg), store.certificate(anchors[0]));
+    }
+
+    #[test]
+    fn mozilla_roots_leave_out_distrusted_cas() {
+        // Removed from Mozilla's program, or given a distrust-after date.
+        let distrusted = ["TrustCor", "E-Tugra", "Entrust"];
+        for cert in Certificate::from_pem_bundle(MOZILLA_ROOTS) {
+            let subject = cert.unwrap().tbs_certificate.subject;
+            let name = subject.common_name().unwrap_or_default();
+            assert!(!distrusted.iter().any(|ca| name.contains(ca)), "{} is distrusted", name);
+        }
+    }
+}
@@ -0,0 +1,473 @@
+//! Loading the platform's trust store, behind the `native-roots` feature.
+//!
+//! On Linux and the BSDs the roots come from the CA bundle the system's
+//! ca-certificates package maintains, or from the `SSL_CERT_FILE` and
+//! `SSL_CERT_DIR` variables where set, as with OpenSSL. On macOS they
+//! are read from the system keychains with `security(1)`, keeping only
+//! those the admin and system trust settings trust as roots for every
+//! use: a root that is denied, or trusted only for some policies or
+//! applications, is left out. On Windows they come from the ROOT system
+//! store.
+
+#[cfg(any(target_os = "macos", test))]
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use path::TrustAnchorStore;
+use x509::Certificate;
+
+/// Why the platform trust store could not be loaded.
+#[derive(Debug)]
+pub enum NativeRootsError {
+    /// A bundle, directory or helper could not be read.
+    Io(io::Error),
+    /// The platform API or helper reported a failure.
+    Platform(String),
+    /// Nothing that decodes as a certificate was found.
+    NoCertificates,
+}
+
+impl fmt::Display for NativeRootsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NativeRootsError::Io(ref e) => write!(f, "reading the trust store: {}", e),
+            NativeRootsError::Platform(ref msg) => write!(f, "reading the trust store: {}", msg),
+            NativeRootsError::NoCertificates => write!(f, "no certificates in the trust store"),
+        }
+    }
+}
+
+impl Error for NativeRootsError {}
+
+impl From<io::Error> for NativeRootsError {
+    fn from(e: io::Error) -> NativeRootsError {
+        NativeRootsError::Io(e)
+    }
+}
+
+impl TrustAnchorStore {
+    /// A store of the platform's root certificates. Entries that do not
+    /// decode are skipped, so one bad root does not cost the rest.
+    pub fn load_native() -> Result<TrustAnchorStore, NativeRootsError> {
+        let mut store = TrustAnchorStore::new();
+        for cert in platform::certificates()? {
+            store.add_certificate(cert);
+        }
+        if store.is_empty() {
+            return Err(NativeRootsError::NoCertificates);
+        }
+        Ok(store)
+    }
+}
+
+// The certificates of a PEM bundle that decode.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn pem_certificates(pem: &str) -> Vec<Certificate> {
+    Certificate::from_pem_bundle(pem).into_iter().filter_map(Result::ok).collect()
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use super::{pem_certificates, NativeRootsError};
+    use x509::Certificate;
+
+    // Where the common distributions keep their CA bundle.
+    const BUNDLES: &[&str] = &[
+        "/etc/ssl/certs/ca-certificates.crt",
+        "/etc/pki/tls/certs/ca-bundle.crt",
+        "/etc/pki/ca-trust/extracted/pem/tls-ca-bundle.pem",
+        "/etc/ssl/ca-bundle.pem",
+        "/etc/pki/tls/cacert.pem",
+        "/etc/ssl/cert.pem",
+    ];
+    const DIRECTORIES: &[&str] = &["/etc/ssl/certs", "/etc/pki/tls/certs"];
+
+    pub fn certificates() -> Result<Vec<Certificate>, NativeRootsError> {
+        let file = env::var_os("SSL_CERT_FILE");
+        let dirs = env::var_os("SSL_CERT_DIR");
+        if file.is_some() || dirs.is_some() {
+            let mut certs = Vec::new();
+            if let Some(file) = file {
+                certs.extend(read_bundle(Path::new(&file))?);
+            }
+            if let Some(dirs) = dirs {
+                for dir in env::split_paths(&dirs) {
+                    certs.extend(read_directory(&dir)?);
+                }
+            }
+            return Ok(certs);
+        }
+        if let Some(bundle) = BUNDLES.iter().map(Path::new).find(|path| path.is_file()) {
+            return read_bundle(bundle);
+        }
+        match DIRECTORIES.iter().map(Path::new).find(|path| path.is_dir()) {
+            Some(dir) => read_directory(dir),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn read_bundle(path: &Path) -> Result<Vec<Certificate>, NativeRootsError> {
+        Ok(pem_certificates(&fs::read_to_string(path)?))
+    }
+
+    // Every PEM certificate in the files of `dir`. The hashed links
+    // c_rehash leaves repeat the files they point to, but the store
+    // drops the duplicates.
+    pub fn read_directory(dir: &Path) -> Result<Vec<Certificate>, NativeRootsError> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            paths.push(entry?.path());
+        }
+        paths.sort();
+        let mut certs = Vec::new();
+        for path in paths.iter().filter(|path| path.is_file()) {
+            // Anything unreadable or not text is not a PEM bundle.
+            if let Ok(pem) = fs::read_to_string(path) {
+                certs.extend(pem_certificates(&pem));
+            }
+        }
+        Ok(certs)
+    }
+}
+
+// What a macOS trust settings domain says of a certificate.
+#[cfg(any(target_os = "macos", test))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Trust {
+    // Trusted as a root for every use.
+    Root,
+    // Denied, trusted only for some uses, or neither trusted nor denied.
+    NotRoot,
+}
+
+// The trust settings `security trust-settings-export` writes for a
+// domain, keyed by the upper-case hex SHA-1 of each certificate's DER.
+// The plist's trustList gives each certificate an array of settings: an
+// empty one trusts it as a root for everything, and otherwise each entry
+// has a result, TrustRoot (1) where it is left out, that may be limited to
+// a policy, application or key usage. A certificate is a root only if no
+// entry denies it (3) and some entry trusts it, as a root (1 or 2),
+// without limit.
+#[cfg(any(target_os = "macos", test))]
+fn parse_trust_settings(plist: &str) -> HashMap<String, Trust> {
+    const LIMITS: &[&str] = &[
+        "<key>kSecTrustSettingsPolicy</key>",
+        "<key>kSecTrustSettingsPolicyString</key>",
+        "<key>kSecTrustSettingsApplication</key>",
+        "<key>kSecTrustSettingsKeyUsage</key>",
+    ];
+    let list = match plist.find("<key>trustList</key>") {
+        Some(start) => &plist[start..],
+        None => return HashMap::new(),
+    };
+    // Where each certificate's entry starts, and its hash.
+    let mut starts = Vec::new();
+    for (at, _) in list.match_indices("<key>") {
+        let key = &list[at + 5..];
+        let hash = key.split("</key>").next().unwrap_or_default();
+        if hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            starts.push((at, hash.to_uppercase()));
+        }
+    }
+    let mut settings = HashMap::new();
+    for (i, (start, hash)) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).map_or(list.len(), |&(next, _)| next);
+        let entry = &list[*start..end];
+        let array = match entry.find("<key>trustSettings</key>") {
+            Some(at) => &entry[at..],
+            None => continue,
+        };
+        let array = match (array.find("<array>"), array.find("</array>")) {
+            (Some(open), Some(close)) if open < close => &array[open..close],
+            // <array/>
+            _ => "",
+        };
+        let mut root = !array.contains("<dict>");
+        let mut denied = false;
+        for setting in array.split("<dict>").skip(1) {
+            let result = setting
+                .split("<key>kSecTrustSettingsResult</key>")
+                .nth(1)
+                .and_then(|rest| rest.split("<integer>").nth(1)?.split("</integer>").next()?.trim().parse().ok())
+                .unwrap_or(1);
+            match result {
+                1 | 2 if !LIMITS.iter().any(|limit| setting.contains(limit)) => root = true,
+                3 => denied = true,
+                _ => {}
+            }
+        }
+        let trust = if root && !denied { Trust::Root } else { Trust::NotRoot };
+        settings.insert(hash.clone(), trust);
+    }
+    settings
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::collections::HashMap;
+    use std::env;
+    use std::fs;
+    use std::process::{self, Command};
+
+    use sha1::{Digest, Sha1};
+
+    use super::{parse_trust_settings, NativeRootsError, Trust};
+    use pem;
+    use x509::{Certificate, DecodeOptions};
+
+    // Apple's roots, which are trusted unless trust settings say otherwise,
+    // and the keychain administrators add theirs to, whose certificates are
+    // trusted only where the settings say so.
+    const KEYCHAINS: &[(&str, bool)] = &[
+        ("/System/Library/Keychains/SystemRootCertificates.keychain", true),
+        ("/Library/Keychains/System.keychain", false),
+    ];
+
+    pub fn certificates() -> Result<Vec<Certificate>, NativeRootsError> {
+        // The admin's settings for a certificate take precedence over the
+        // system's.
+        let admin = trust_settings("-d")?;
+        let system = trust_settings("-s")?;
+        let mut certs = Vec::new();
+        for &(keychain, trusted_by_default) in KEYCHAINS {
+            let output = security(&["find-certificate", "-a", "-p", keychain])?;
+            for block in pem::parse_all(&String::from_utf8_lossy(&output)) {
+                let der = match block.ok().and_then(|block| block.into_contents(pem::CERTIFICATE).ok()) {
+                    Some(der) => der,
+                    None => continue,
+                };
+                let hash: String = Sha1::digest(&der).iter().map(|b| format!("{:02X}", b)).collect();
+                let trusted = match admin.get(&hash).or_else(|| system.get(&hash)) {
+                    Some(&trust) => trust == Trust::Root,
+                    None => trusted_by_default,
+                };
+                if trusted {
+                    if let Ok(cert) = Certificate::decode(&der, &DecodeOptions::default()) {
+                        certs.push(cert);
+                    }
+                }
+            }
+        }
+        Ok(certs)
+    }
+
+    fn security(args: &[&str]) -> Result<Vec<u8>, NativeRootsError> {
+        let output = Command::new("/usr/bin/security").args(args).output()?;
+        if !output.status.success() {
+            let msg = String::from_utf8_lossy(&output.stderr);
+            return Err(NativeRootsError::Platform(format!("security: {}", msg.trim())));
+        }
+        Ok(output.stdout)
+    }
+
+    // The trust settings of the admin (`-d`) or system (`-s`) domain.
+    fn trust_settings(domain: &str) -> Result<HashMap<String, Trust>, NativeRootsError> {
+        let path = env::temp_dir().join(format!("x509-trust-settings{}-{}.plist", domain, process::id()));
+        let path_str = path.to_string_lossy().into_owned();
+        let exported = security(&["trust-settings-export", domain, &path_str]);
+        let plist = fs::read_to_string(&path);
+        let _ = fs::remove_file(&path);
+        match exported {
+            Ok(_) => Ok(parse_trust_settings(&plist?)),
+            // A domain with no settings at all is a failure to security(1).
+            Err(NativeRootsError::Platform(ref msg)) if msg.contains("No Trust Settings") => Ok(HashMap::new()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::io;
+    use std::os::raw::c_void;
+    use std::ptr;
+    use std::slice;
+
+    use super::NativeRootsError;
+    use der::der_decode;
+    use x509::Certificate;
+
+    // CERT_CONTEXT, from wincrypt.h.
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct CertContext {
+        encoding_type: u32,
+        encoded: *const u8,
+        encoded_len: u32,
+        info: *const c_void,
+        store: *mut c_void,
+    }
+
+    #[link(name = "crypt32")]
+    extern "system" {
+        fn CertOpenSystemStoreW(provider: usize, subsystem: *const u16) -> *mut c_void;
+        fn CertEnumCertificatesInStore(store: *mut c_void, previous: *const CertContext) -> *const CertContext;
+        fn CertCloseStore(store: *mut c_void, flags: u32) -> i32;
+    }
+
+    pub fn certificates() -> Result<Vec<Certificate>, NativeRootsError> {
+        let name: Vec<u16> = "ROOT".encode_utf16().chain(Some(0)).collect();
+        let store = unsafe { CertOpenSystemStoreW(0, name.as_ptr()) };
+        if store.is_null() {
+            return Err(NativeRootsError::Io(io::Error::last_os_error()));
+        }
+        let mut certs = Vec::new();
+        let mut context: *const CertContext = ptr::null();
+        loop {
+            // Each call frees the context passed in, so the encoding is
+            // copied out before the next.
+            context = unsafe { CertEnumCertificatesInStore(store, context) };
+            if context.is_null() {
+                break;
+            }
+            let der = unsafe { slice::from_raw_parts((*context).encoded, (*context).encoded_len as usize) };
+            if let Ok(cert) = der_decode(der) {
+                certs.push(cert);
+            }
+        }
+        unsafe { CertCloseStore(store, 0) };
+        Ok(certs)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use super::NativeRootsError;
+    use x509::Certificate;
+
+    pub fn certificates() -> Result<Vec<Certificate>, NativeRootsError> {
+        Err(NativeRootsError::Platform("no trust store on this platform".to_string()))
+    }
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod native_roots_tests {
+    use std::env;
+    use std::fs;
+
+    use super::platform::{read_bundle, read_directory};
+    use der::der_decode;
+    use path::TrustAnchorStore;
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../../testdata/ec-root.der");
+    const EC_INT: &[u8] = include_bytes!("../../testdata/ec-int.der");
+
+    fn cert(der: &[u8]) -> Certificate {
+        der_decode(der).unwrap()
+    }
+
+    #[test]
+    fn native_roots_reads_bundles_and_directories() {
+        let dir = env::temp_dir().join(format!("x509-native-roots-{}", ::std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (root_pem, int_pem) = (cert(EC_ROOT).to_pem().unwrap(), cert(EC_INT).to_pem().unwrap());
+        fs::write(dir.join("bundle.pem"), format!("{}not a certificate\n{}", root_pem, int_pem)).unwrap();
+        fs::write(dir.join("root.pem"), root_pem).unwrap();
+        fs::write(dir.join("binary.der"), EC_ROOT).unwrap();
+
+        assert_eq!(vec![cert(EC_ROOT), cert(EC_INT)], read_bundle(&dir.join("bundle.pem")).unwrap());
+        let certs = read_directory(&dir).unwrap();
+        assert_eq!(vec![cert(EC_ROOT), cert(EC_INT), cert(EC_ROOT)], certs);
+        assert!(read_bundle(&dir.join("missing.pem")).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+
+        let mut store = TrustAnchorStore::new();
+        for cert in certs {
+            store.add_certificate(cert);
+        }
+        assert_eq!(2, store.len());
+    }
+}
+
+#[cfg(test)]
+mod trust_settings_tests {
+    use super::{parse_trust_settings, Trust};
+
+    // As `security trust-settings-export` writes them, for a root trusted
+    // outright, one denied, one trusted only for SSL, one trusted with an
+    // empty array and one whose only setting is unspecified (4).
+    const TRUST_SETTINGS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>trustList</key>
+	<dict>
+		<key>0563B8630D62D75ABBC8AB1E4BDFB5A899B24D43</key>
+		<dict>
+			<key>issuerName</key>
+			<data>MBkxFzAVBgNVBAMMDlRydXN0ZWQgUm9vdA==</data>
+			<key>trustSettings</key>
+			<array>
+				<dict>
+					<key>kSecTrustSettingsResult</key>
+					<integer>1</integer>
+				</dict>
+			</array>
+		</dict>
+		<key>1111111111111111111111111111111111111111</key>
+		<dict>
+			<key>trustSettings</key>
+			<array>
+				<dict>
+					<key>kSecTrustSettingsResult</key>
+					<integer>3</integer>
+				</dict>
+			</array>
+		</dict>
+		<key>2222222222222222222222222222222222222222</key>
+		<dict>
+			<key>trustSettings</key>
+			<array>
+				<dict>
+					<key>kSecTrustSettingsPolicy</key>
+					<data>KoZIhvdjZAED</data>
+					<key>kSecTrustSettingsPolicyName</key>
+					<string>sslServer</string>
+					<key>kSecTrustSettingsResult</key>
+					<integer>1</integer>
+				</dict>
+			</array>
+		</dict>
+		<key>333333333333333333333333333333333333333a</key>
+		<dict>
+			<key>trustSettings</key>
+			<array/>
+		</dict>
+		<key>4444444444444444444444444444444444444444</key>
+		<dict>
+			<key>trustSettings</key>
+			<array>
+				<dict>
+					<key>kSecTrustSettingsAllowedError</key>
+					<integer>-2147409654</integer>
+					<key>kSecTrustSettingsResult</key>
+					<integer>4</integer>
+				</dict>
+			</array>
+		</dict>
+	</dict>
+	<key>trustVersion</key>
+	<integer>1</integer>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn trust_settings_parse() {
+        let settings = parse_trust_settings(TRUST_SETTINGS);
+        assert_eq!(5, settings.len());
+        assert_eq!(Some(&Trust::Root), settings.get("0563B8630D62D75ABBC8AB1E4BDFB5A899B24D43"));
+        assert_eq!(Some(&Trust::NotRoot), settings.get("1111111111111111111111111111111111111111"));
+        assert_eq!(Some(&Trust::NotRoot), settings.get("2222222222222222222222222222222222222222"));
+        assert_eq!(Some(&Trust::Root), settings.get("333333333333333333333333333333333333333A"));
+        assert_eq!(Some(&Trust::NotRoot), settings.get("4444444444444444444444444444444444444444"));
+        assert!(parse_trust_settings("").is_empty());
+    }
+}
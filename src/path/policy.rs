@@ -0,0 +1,462 @@
+use extensions::{
+    CertificatePolicies, InhibitAnyPolicy, PolicyConstraints, PolicyInformation, PolicyMappings, PolicyQualifierInfo,
+};
+use oid::ObjectIdentifier;
+use oids;
+use path::{Failure, PathValidator};
+use x509::Certificate;
+
+/// A node of the valid policy tree (RFC 5280 §6.1.2).
+///
+/// The root is anyPolicy. A node at depth `i` is a policy the first `i`
+/// certificates of the path are valid for, with the qualifiers the
+/// certificate at that depth gave it and, in `expected_policy_set`, the
+/// policies that satisfy it in the next certificate. After validation
+/// every leaf is at the depth of the target.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolicyNode {
+    pub valid_policy: ObjectIdentifier,
+    pub qualifier_set: Vec<PolicyQualifierInfo>,
+    pub expected_policy_set: Vec<ObjectIdentifier>,
+    pub children: Vec<PolicyNode>,
+}
+
+impl PolicyNode {
+    fn new(valid_policy: ObjectIdentifier, qualifier_set: Vec<PolicyQualifierInfo>) -> PolicyNode {
+        let expected_policy_set = vec![valid_policy.clone()];
+        PolicyNode { valid_policy, qualifier_set, expected_policy_set, children: Vec::new() }
+    }
+
+    /// The nodes with no children.
+    pub fn leaves(&self) -> Vec<&PolicyNode> {
+        if self.children.is_empty() {
+            vec![self]
+        } else {
+            self.children.iter().flat_map(PolicyNode::leaves).collect()
+        }
+    }
+
+    /// The policies of the leaves, without repeats. anyPolicy among them
+    /// means the path is valid for any policy.
+    pub fn policies(&self) -> Vec<&ObjectIdentifier> {
+        let mut policies = Vec::new();
+        for leaf in self.leaves() {
+            if !policies.contains(&&leaf.valid_policy) {
+                policies.push(&leaf.valid_policy);
+            }
+        }
+        policies
+    }
+
+    /// Whether the path is valid for `policy`.
+    pub fn accepts(&self, policy: &ObjectIdentifier) -> bool {
+        self.leaves().iter().any(|leaf| leaf.valid_policy == *policy || leaf.is_any_policy())
+    }
+
+    fn is_any_policy(&self) -> bool {
+        self.valid_policy == oids::ANY_POLICY
+    }
+
+    // Call `f` on each node `depth` levels below this one.
+    fn each_at_depth(&mut self, depth: usize, f: &mut dyn FnMut(&mut PolicyNode)) {
+        if depth == 0 {
+            f(self);
+        } else {
+            for child in &mut self.children {
+                child.each_at_depth(depth - 1, f);
+            }
+        }
+    }
+
+    // Delete the nodes less than `depth` levels down that have no
+    // children, repeatedly. False if this node goes too.
+    fn prune(&mut self, depth: usize) -> bool {
+        if depth == 0 {
+            return true;
+        }
+        self.children.retain_mut(|child| child.prune(depth - 1));
+        !self.children.is_empty()
+    }
+}
+
+// The policy state variables of §6.1.2. Depths count from 1, the
+// certificate the trust anchor issued.
+pub(super) struct PolicyState {
+    tree: Option<PolicyNode>,
+    explicit_policy: usize,
+    policy_mapping: usize,
+    inhibit_any_policy: usize,
+    // Whether the lack of an acceptable policy has been reported, so it
+    // is only reported once.
+    reported: bool,
+}
+
+impl PolicyState {
+    pub(super) fn new(validator: &PathValidator, n: usize) -> PolicyState {
+        let initial = |set: bool| if set { 0 } else { n + 1 };
+        PolicyState {
+            tree: Some(PolicyNode::new(oids::ANY_POLICY, Vec::new())),
+            explicit_policy: initial(validator.initial_explicit_policy),
+            policy_mapping: initial(validator.initial_policy_mapping_inhibit),
+            inhibit_any_policy: initial(validator.initial_any_policy_inhibit),
+            reported: false,
+        }
+    }
+
+    pub(super) fn into_tree(self) -> Option<PolicyNode> {
+        self.tree
+    }
+
+    // §6.1.3 (d) to (f).
+    pub(super) fn process(&mut self, cert: &Certificate, depth: usize, last: bool) -> Result<(), Failure> {
+        let policies = cert.extension::<CertificatePolicies>().map_err(Failure::Decode)?;
+        let tbs = &cert.tbs_certificate;
+        // A self-issued intermediate may always assert anyPolicy.
+        let any_policy_allowed = self.inhibit_any_policy > 0 || (!last && tbs.issuer.matches(&tbs.subject));
+        self.tree = match (self.tree.take(), policies) {
+            (Some(mut tree), Some(policies)) => {
+                add_policies(&mut tree, &policies, depth, any_policy_allowed);
+                if tree.prune(depth) {
+                    Some(tree)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        self.check_explicit_policy()
+    }
+
+    // §6.1.4 (a), (b) and (h) to (j), for an intermediate.
+    pub(super) fn prepare(&mut self, cert: &Certificate, depth: usize) -> Result<(), Failure> {
+        if let Some(mappings) = cert.extension::<PolicyMappings>().map_err(Failure::Decode)? {
+            if mappings.maps_any_policy() {
+                return Err(Failure::AnyPolicyMapped);
+            }
+            if let Some(mut tree) = self.tree.take() {
+                map_policies(&mut tree, &mappings, depth, self.policy_mapping > 0);
+                if tree.prune(depth) {
+                    self.tree = Some(tree);
+                }
+            }
+        }
+        let tbs = &cert.tbs_certificate;
+        if !tbs.issuer.matches(&tbs.subject) {
+            self.explicit_policy = self.explicit_policy.saturating_sub(1);
+            self.policy_mapping = self.policy_mapping.saturating_sub(1);
+            self.inhibit_any_policy = self.inhibit_any_policy.saturating_sub(1);
+        }
+        if let Some(constraints) = cert.extension::<PolicyConstraints>().map_err(Failure::Decode)? {
+            if let Some(skip) = constraints.require_explicit_policy {
+                self.explicit_policy = self.explicit_policy.min(skip as usize);
+            }
+            if let Some(skip) = constraints.inhibit_policy_mapping {
+                self.policy_mapping = self.policy_mapping.min(skip as usize);
+            }
+        }
+        if let Some(InhibitAnyPolicy(skip)) = cert.extension::<InhibitAnyPolicy>().map_err(Failure::Decode)? {
+            self.inhibit_any_policy = self.inhibit_any_policy.min(skip as usize);
+        }
+        Ok(())
+    }
+
+    // §6.1.5 (a), (b) and (g), for the target.
+    pub(super) fn wrap_up(
+        &mut self,
+        cert: &Certificate,
+        depth: usize,
+        initial_policy_set: &[ObjectIdentifier],
+    ) -> Result<(), Failure> {
+        self.explicit_policy = self.explicit_policy.saturating_sub(1);
+        if let Some(constraints) = cert.extension::<PolicyConstraints>().map_err(Failure::Decode)? {
+            if constraints.require_explicit_policy == Some(0) {
+                self.explicit_policy = 0;
+            }
+        }
+        if !initial_policy_set.contains(&oids::ANY_POLICY) {
+            if let Some(mut tree) = self.tree.take() {
+                let mut chosen = Vec::new();
+                chosen_policies(&tree, &mut chosen);
+                restrict(&mut tree, depth, initial_policy_set, &chosen);
+                if tree.prune(depth) {
+                    self.tree = Some(tree);
+                }
+            }
+        }
+        self.check_explicit_policy()
+    }
+
+    fn check_explicit_policy(&mut self) -> Result<(), Failure> {
+        if self.explicit_policy == 0 && self.tree.is_none() && !self.reported {
+            self.reported = true;
+            return Err(Failure::ExplicitPolicyRequired);
+        }
+        Ok(())
+    }
+}
+
+// Add the certificate's policies below the nodes at `depth - 1` they
+// satisfy, falling back to anyPolicy nodes for a policy nothing expects.
+fn add_policies(tree: &mut PolicyNode, policies: &CertificatePolicies, depth: usize, any_policy_allowed: bool) {
+    let mut any_policy = None;
+    for info in &policies.0 {
+        if info.policy_identifier == oids::ANY_POLICY {
+            any_policy = Some(info);
+            continue;
+        }
+        let mut matched = false;
+        tree.each_at_depth(depth - 1, &mut |node| {
+            if node.expected_policy_set.contains(&info.policy_identifier) {
+                node.children.push(child(info));
+                matched = true;
+            }
+        });
+        if !matched {
+            tree.each_at_depth(depth - 1, &mut |node| {
+                if node.is_any_policy() {
+                    node.children.push(child(info));
+                }
+            });
+        }
+    }
+    if let Some(any_policy) = any_policy.filter(|_| any_policy_allowed) {
+        tree.each_at_depth(depth - 1, &mut |node| {
+            for policy in node.expected_policy_set.clone() {
+                if !node.children.iter().any(|c| c.valid_policy == policy) {
+                    node.children.push(PolicyNode::new(policy, any_policy.policy_qualifiers.clone()));
+                }
+            }
+        });
+    }
+}
+
+fn child(info: &PolicyInformation) -> PolicyNode {
+    PolicyNode::new(info.policy_identifier.clone(), info.policy_qualifiers.clone())
+}
+
+// Apply the mappings to the nodes at `depth`, or when mapping is
+// inhibited delete the nodes for the mapped policies.
+fn map_policies(tree: &mut PolicyNode, mappings: &PolicyMappings, depth: usize, allowed: bool) {
+    let mut issuer_policies: Vec<&ObjectIdentifier> = Vec::new();
+    for mapping in &mappings.0 {
+        if !issuer_policies.contains(&&mapping.issuer_domain_policy) {
+            issuer_policies.push(&mapping.issuer_domain_policy);
+        }
+    }
+    for policy in issuer_policies {
+        if !allowed {
+            tree.each_at_depth(depth - 1, &mut |node| node.children.retain(|c| c.valid_policy != *policy));
+            continue;
+        }
+        let subject_policies: Vec<ObjectIdentifier> = mappings.subject_policies(policy).cloned().collect();
+        let mut found = false;
+        tree.each_at_depth(depth, &mut |node| {
+            if node.valid_policy == *policy {
+                node.expected_policy_set = subject_policies.clone();
+                found = true;
+            }
+        });
+        if found {
+            continue;
+        }
+        // Nothing asserted the policy, but anyPolicy stands in for it.
+        tree.each_at_depth(depth - 1, &mut |node| {
+            if !node.is_any_policy() {
+                return;
+            }
+            if let Some(any_policy) = node.children.iter().find(|c| c.is_any_policy()) {
+                let mut mapped = PolicyNode::new(policy.clone(), any_policy.qualifier_set.clone());
+                mapped.expected_policy_set = subject_policies.clone();
+                node.children.push(mapped);
+            }
+        });
+    }
+}
+
+// The policies of the nodes whose parent is anyPolicy: those the path
+// didn't constrain, which the relying party's set is applied to.
+fn chosen_policies(node: &PolicyNode, chosen: &mut Vec<ObjectIdentifier>) {
+    if !node.is_any_policy() {
+        return;
+    }
+    for child in &node.children {
+        if !chosen.contains(&child.valid_policy) {
+            chosen.push(child.valid_policy.clone());
+        }
+        chosen_policies(child, chosen);
+    }
+}
+
+// Delete the unconstrained nodes for policies outside `policies`, and
+// replace an anyPolicy leaf at `depth` with a node for each policy in it
+// that isn't already there.
+fn restrict(node: &mut PolicyNode, depth: usize, policies: &[ObjectIdentifier], chosen: &[ObjectIdentifier]) {
+    if !node.is_any_policy() {
+        return;
+    }
+    node.children.retain(|c| c.is_any_policy() || policies.contains(&c.valid_policy));
+    if depth > 1 {
+        for child in &mut node.children {
+            restrict(child, depth - 1, policies, chosen);
+        }
+    } else if let Some(i) = node.children.iter().position(PolicyNode::is_any_policy) {
+        let any_policy = node.children.remove(i);
+        for policy in policies.iter().filter(|p| !chosen.contains(p)) {
+            node.children.push(PolicyNode::new(policy.clone(), any_policy.qualifier_set.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod policy_tests {
+    use super::{PolicyNode, PolicyState};
+    use der::der_decode;
+    use extensions::{
+        CertificatePolicies, InhibitAnyPolicy, PolicyConstraints, PolicyInformation, PolicyMapping, PolicyMappings,
+        X509Extension,
+    };
+    use oid::ObjectIdentifier;
+    use oids;
+    use path::{Failure, PathValidator};
+    use time::{Date, Month, PrimitiveDateTime, Time};
+    use x509::Certificate;
+
+    const EC_INT: &[u8] = include_bytes!("../../testdata/ec-int.der");
+    const EC_INT_LEAF: &[u8] = include_bytes!("../../testdata/ec-int-leaf.der");
+
+    const P1: ObjectIdentifier = ObjectIdentifier::from_static(&[1, 3, 6, 1, 4, 1, 99999, 3, 1]);
+    const P2: ObjectIdentifier = ObjectIdentifier::from_static(&[1, 3, 6, 1, 4, 1, 99999, 3, 2]);
+
+    fn validator() -> PathValidator {
+        let date = Date::from_calendar_date(2026, Month::October, 15).unwrap();
+        PathValidator::new(PrimitiveDateTime::new(date, Time::MIDNIGHT))
+    }
+
+    fn policies(ids: &[ObjectIdentifier]) -> CertificatePolicies {
+        CertificatePolicies(ids.iter().cloned().map(PolicyInformation::new).collect())
+    }
+
+    fn with<T: X509Extension>(mut cert: Certificate, value: &T) -> Certificate {
+        cert.tbs_certificate.extensions.get_or_insert_with(Default::default).insert(value, false).unwrap();
+        cert
+    }
+
+    fn ca() -> Certificate {
+        der_decode(EC_INT).unwrap()
+    }
+
+    fn leaf(ids: &[ObjectIdentifier]) -> Certificate {
+        with(der_decode(EC_INT_LEAF).unwrap(), &policies(ids))
+    }
+
+    // The policy steps of path validation alone, signatures and all else
+    // aside.
+    fn run(validator: &PathValidator, path: &[Certificate]) -> (Option<PolicyNode>, Vec<(usize, Failure)>) {
+        let mut state = PolicyState::new(validator, path.len());
+        let mut failures = Vec::new();
+        for (index, cert) in path.iter().enumerate() {
+            let last = index + 1 == path.len();
+            if let Err(e) = state.process(cert, index + 1, last) {
+                failures.push((index, e));
+            }
+            let result = if last {
+                state.wrap_up(cert, index + 1, &validator.initial_policy_set)
+            } else {
+                state.prepare(cert, index + 1)
+            };
+            if let Err(e) = result {
+                failures.push((index, e));
+            }
+        }
+        (state.into_tree(), failures)
+    }
+
+    #[test]
+    fn policy_tree_follows_asserted_policies() {
+        let path = [with(ca(), &policies(&[P1, P2])), leaf(&[P1])];
+        let (tree, failures) = run(&validator(), &path);
+        let tree = tree.unwrap();
+        assert!(failures.is_empty());
+        assert_eq!(vec![&P1], tree.policies());
+        assert!(tree.accepts(&P1));
+        assert!(!tree.accepts(&P2));
+
+        let mut validator = validator();
+        validator.initial_policy_set = vec![P2];
+        assert_eq!((None, vec![]), run(&validator, &path));
+        validator.initial_explicit_policy = true;
+        assert_eq!((None, vec![(1, Failure::ExplicitPolicyRequired)]), run(&validator, &path));
+    }
+
+    #[test]
+    fn policy_tree_expands_any_policy() {
+        let path = [with(ca(), &policies(&[oids::ANY_POLICY])), leaf(&[P2])];
+        let (tree, _) = run(&validator(), &path);
+        assert_eq!(vec![&P2], tree.unwrap().policies());
+
+        let path = [with(ca(), &policies(&[P1])), leaf(&[oids::ANY_POLICY])];
+        let (tree, _) = run(&validator(), &path);
+        assert_eq!(vec![&P1], tree.unwrap().policies());
+
+        // Under anyPolicy throughout, the relying party's set is what the
+        // path is valid for.
+        let path = [with(ca(), &policies(&[oids::ANY_POLICY])), leaf(&[oids::ANY_POLICY])];
+        let mut validator = validator();
+        validator.initial_policy_set = vec![P1, P2];
+        let (tree, _) = run(&validator, &path);
+        assert_eq!(vec![&P1, &P2], tree.unwrap().policies());
+    }
+
+    #[test]
+    fn policy_tree_needs_every_certificate_to_assert_policies() {
+        let path = [ca(), leaf(&[P1])];
+        assert_eq!((None, vec![]), run(&validator(), &path));
+
+        let required = PolicyConstraints { require_explicit_policy: Some(0), ..Default::default() };
+        let path = [with(with(ca(), &policies(&[P1])), &required), der_decode(EC_INT_LEAF).unwrap()];
+        assert_eq!((None, vec![(1, Failure::ExplicitPolicyRequired)]), run(&validator(), &path));
+    }
+
+    #[test]
+    fn policy_tree_applies_mappings() {
+        let mapping = PolicyMappings(vec![PolicyMapping { issuer_domain_policy: P1, subject_domain_policy: P2 }]);
+        let path = [with(with(ca(), &policies(&[oids::ANY_POLICY])), &mapping), leaf(&[P2])];
+        let mut validator = validator();
+        validator.initial_policy_set = vec![P1];
+        let (tree, failures) = run(&validator, &path);
+        let tree = tree.unwrap();
+        assert!(failures.is_empty());
+        assert_eq!(P1, tree.children[0].valid_policy);
+        assert_eq!(vec![P2], tree.children[0].expected_policy_set);
+        assert_eq!(vec![&P2], tree.policies());
+
+        validator.initial_policy_mapping_inhibit = true;
+        assert_eq!((None, vec![]), run(&validator, &path));
+
+        // With mapping inhibited, a mapped policy is dropped.
+        let path = [with(with(ca(), &policies(&[P1])), &mapping), leaf(&[P2])];
+        validator.initial_policy_set = vec![oids::ANY_POLICY];
+        assert_eq!((None, vec![]), run(&validator, &path));
+        validator.initial_policy_mapping_inhibit = false;
+        assert_eq!(vec![&P2], run(&validator, &path).0.unwrap().policies());
+    }
+
+    #[test]
+    fn policy_tree_rejects_mapping_any_policy() {
+        let mapping =
+            PolicyMappings(vec![PolicyMapping { issuer_domain_policy: oids::ANY_POLICY, subject_domain_policy: P2 }]);
+        let path = [with(with(ca(), &policies(&[P1])), &mapping), leaf(&[P2])];
+        assert_eq!(vec![(0, Failure::AnyPolicyMapped)], run(&validator(), &path).1);
+    }
+
+    #[test]
+    fn policy_tree_honours_inhibit_any_policy() {
+        let path = [with(with(ca(), &policies(&[P1])), &InhibitAnyPolicy(0)), leaf(&[oids::ANY_POLICY])];
+        assert_eq!((None, vec![]), run(&validator(), &path));
+
+        let mut validator = validator();
+        validator.initial_any_policy_inhibit = true;
+        let path = [with(ca(), &policies(&[oids::ANY_POLICY])), leaf(&[P1])];
+        assert_eq!((None, vec![]), run(&validator, &path));
+    }
+}
@@ -0,0 +1,236 @@
+use extensions::{KeyIdentifierMethod, SubjectKeyIdentifier};
+use name::Name;
+use path::TrustAnchor;
+use trust_anchor::{TrustAnchorChoice, TrustAnchorList};
+use x509::Certificate;
+
+/// A set of trust anchors, each either a root certificate or a bare name
+/// and key.
+///
+/// `anchors` gives the set as a slice for `PathBuilder`. A root
+/// certificate's subjectKeyIdentifier, where it has one, is kept for
+/// lookups, as is a `TrustAnchorInfo`'s keyId; every anchor also answers
+/// to the SHA-1 and truncated SHA-256 identifiers of its key.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrustAnchorStore {
+    anchors: Vec<TrustAnchor>,
+    certificates: Vec<Option<Certificate>>,
+    key_ids: Vec<Option<Vec<u8>>>,
+}
+
+impl TrustAnchorStore {
+    pub fn new() -> TrustAnchorStore {
+        TrustAnchorStore::default()
+    }
+
+    /// Add a bare anchor. Returns false, leaving the store as it was, if
+    /// the anchor is already there.
+    pub fn add(&mut self, anchor: TrustAnchor) -> bool {
+        self.insert(anchor, None, None)
+    }
+
+    /// Add a root certificate as an anchor. Returns false if its anchor
+    /// is already there.
+    pub fn add_certificate(&mut self, cert: Certificate) -> bool {
+        self.insert(TrustAnchor::from_certificate(&cert), Some(cert), None)
+    }
+
+    /// Add the anchors of an RFC 5914 list, with their constraints, and
+    /// return how many were new. `TrustAnchorInfo`s not meant for path
+    /// validation are skipped.
+    pub fn add_trust_anchor_list(&mut self, list: &TrustAnchorList) -> usize {
+        let mut added = 0;
+        for choice in &list.0 {
+            let anchor = match choice.to_trust_anchor() {
+                Some(anchor) => anchor,
+                None => continue,
+            };
+            let inserted = match *choice {
+                TrustAnchorChoice::Certificate(ref cert) => self.insert(anchor, Some(cert.clone()), None),
+                TrustAnchorChoice::TbsCertificate(_) => self.insert(anchor, None, None),
+                TrustAnchorChoice::TrustAnchorInfo(ref info) => {
+                    let cert = info.cert_path.as_ref().and_then(|cert_path| cert_path.certificate.clone());
+                    self.insert(anchor, cert, Some(info.key_id.clone()))
+                }
+            };
+            if inserted {
+                added += 1;
+            }
+        }
+        added
+    }
+
+    fn insert(&mut self, anchor: TrustAnchor, cert: Option<Certificate>, key_id: Option<Vec<u8>>) -> bool {
+        if self.anchors.contains(&anchor) {
+            return false;
+        }
+        self.anchors.push(anchor);
+        self.certificates.push(cert);
+        self.key_ids.push(key_id);
+        true
+    }
+
+    /// Remove an anchor, with its certificate if it came from one.
+    /// Returns false if it was not there.
+    pub fn remove(&mut self, anchor: &TrustAnchor) -> bool {
+        match self.anchors.iter().position(|a| a == anchor) {
+            Some(i) => {
+                self.anchors.remove(i);
+                self.certificates.remove(i);
+                self.key_ids.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn anchors(&self) -> &[TrustAnchor] {
+        &self.anchors
+    }
+
+    pub fn len(&self) -> usize {
+        self.anchors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchors.is_empty()
+    }
+
+    pub fn contains(&self, anchor: &TrustAnchor) -> bool {
+        self.anchors.contains(anchor)
+    }
+
+    /// The root certificate `anchor` was added from, if it was.
+    pub fn certificate(&self, anchor: &TrustAnchor) -> Option<&Certificate> {
+        let i = self.anchors.iter().position(|a| a == anchor)?;
+        self.certificates[i].as_ref()
+    }
+
+    /// The anchors named `subject`; rekeyed roots can share a name.
+    pub fn find_by_subject<'a>(&'a self, subject: &'a Name) -> impl Iterator<Item = &'a TrustAnchor> + 'a {
+        self.anchors.iter().filter(move |anchor| anchor.name.matches(subject))
+    }
+
+    /// The anchor whose key `key_id` identifies, as in an
+    /// authorityKeyIdentifier.
+    pub fn find_by_key_identifier(&self, key_id: &[u8]) -> Option<&TrustAnchor> {
+        let mut entries = self.anchors.iter().zip(&self.certificates).zip(&self.key_ids);
+        let found = entries.find(|&((anchor, cert), id)| {
+            let ski = cert.as_ref().and_then(|cert| cert.extension::<SubjectKeyIdentifier>().ok()?);
+            ski.is_some_and(|ski| ski.0 == key_id)
+                || id.as_ref().is_some_and(|id| id[..] == *key_id)
+                || [KeyIdentifierMethod::Sha1, KeyIdentifierMethod::Sha256Truncated]
+                    .iter()
+                    .any(|method| method.key_identifier(&anchor.public_key) == key_id)
+        });
+        found.map(|((anchor, _), _)| anchor)
+    }
+}
+
+#[cfg(test)]
+mod trust_anchor_store_tests {
+    use super::TrustAnchorStore;
+    use der::der_decode;
+    use extensions::{KeyIdentifierMethod, SubjectKeyIdentifier};
+    use path::TrustAnchor;
+    use trust_anchor::{CertPathControls, TrustAnchorChoice, TrustAnchorInfo, TrustAnchorList};
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+
+    fn cert(der: &[u8]) -> Certificate {
+        der_decode(der).unwrap()
+    }
+
+    #[test]
+    fn trust_anchor_store_adds_and_removes() {
+        let root = cert(EC_ROOT);
+        let bare = TrustAnchor::from_certificate(&cert(EC_LEAF));
+        let mut store = TrustAnchorStore::new();
+        assert!(store.is_empty());
+        assert!(store.add_certificate(root.clone()));
+        assert!(store.add(bare.clone()));
+        assert!(!store.add(TrustAnchor::from_certificate(&root)));
+        assert_eq!(2, store.len());
+
+        let root_anchor = TrustAnchor::from_certificate(&root);
+        assert_eq!(Some(&root), store.certificate(&root_anchor));
+        assert_eq!(None, store.certificate(&bare));
+
+        assert!(store.remove(&root_anchor));
+        assert!(!store.remove(&root_anchor));
+        assert!(!store.contains(&root_anchor));
+        assert_eq!(None, store.certificate(&root_anchor));
+        assert_eq!(&[bare], store.anchors());
+    }
+
+    #[test]
+    fn trust_anchor_store_finds_anchors() {
+        let root = cert(EC_ROOT);
+        let leaf = cert(EC_LEAF);
+        let mut rekeyed = TrustAnchor::from_certificate(&leaf);
+        rekeyed.name = root.tbs_certificate.subject.clone();
+        let mut store = TrustAnchorStore::new();
+        store.add_certificate(root.clone());
+        store.add(rekeyed.clone());
+        store.add(TrustAnchor::from_certificate(&leaf));
+
+        let named: Vec<_> = store.find_by_subject(&root.tbs_certificate.subject).collect();
+        assert_eq!(vec![&TrustAnchor::from_certificate(&root), &rekeyed], named);
+
+        let ski = root.extension::<SubjectKeyIdentifier>().unwrap().unwrap();
+        assert_eq!(Some(&store.anchors()[0]), store.find_by_key_identifier(&ski.0));
+        let key_id = KeyIdentifierMethod::Sha256Truncated.key_identifier(&rekeyed.public_key);
+        assert_eq!(Some(&rekeyed), store.find_by_key_identifier(&key_id));
+        assert_eq!(None, store.find_by_key_identifier(&[0; 20]));
+    }
+
+    #[test]
+    fn trust_anchor_store_loads_trust_anchor_lists() {
+        let root = cert(EC_ROOT);
+        let leaf = cert(EC_LEAF);
+        let tbs = &leaf.tbs_certificate;
+        let mut info = TrustAnchorInfo::new(tbs.subject_public_key_info.clone(), vec![7; 20]);
+        let mut controls = CertPathControls::new(tbs.subject.clone());
+        controls.path_len_constraint = Some(0);
+        info.cert_path = Some(controls);
+        let unusable = TrustAnchorInfo::new(tbs.subject_public_key_info.clone(), vec![8; 20]);
+        let list = TrustAnchorList(vec![
+            TrustAnchorChoice::Certificate(root.clone()),
+            TrustAnchorChoice::TrustAnchorInfo(Box::new(info.clone())),
+            TrustAnchorChoice::TrustAnchorInfo(Box::new(unusable)),
+            TrustAnchorChoice::TbsCertificate(root.tbs_certificate.clone()),
+        ]);
+
+        let mut store = TrustAnchorStore::new();
+        assert_eq!(2, store.add_trust_anchor_list(&list));
+        let constrained = TrustAnchorChoice::TrustAnchorInfo(Box::new(info)).to_trust_anchor().unwrap();
+        assert_eq!(&[TrustAnchor::from_certificate(&root), constrained.clone()], store.anchors());
+        assert_eq!(Some(&root), store.certificate(&store.anchors()[0]));
+        assert_eq!(Some(&constrained), store.find_by_key_identifier(&[7; 20]));
+        assert_eq!(None, store.find_by_key_identifier(&[8; 20]));
+        assert!(store.remove(&constrained));
+        assert_eq!(None, store.find_by_key_identifier(&[7; 20]));
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn trust_anchor_store_feeds_path_builder() {
+        use path::{PathBuilder, PathValidator};
+        use time::{Date, Month, PrimitiveDateTime, Time};
+
+        const EC_INT: &[u8] = include_bytes!("../../testdata/ec-int.der");
+        const EC_INT_LEAF: &[u8] = include_bytes!("../../testdata/ec-int-leaf.der");
+
+        let mut store = TrustAnchorStore::new();
+        store.add_certificate(cert(EC_ROOT));
+        let intermediates = [cert(EC_INT)];
+        let paths = PathBuilder::new(store.anchors(), &intermediates).candidates(&cert(EC_INT_LEAF));
+        assert_eq!(1, paths.len());
+
+        let date = Date::from_calendar_date(2026, Month::October, 15).unwrap();
+        let validator = PathValidator::new(PrimitiveDateTime::new(date, Time::MIDNIGHT));
+        assert!(validator.validate(paths[0].anchor, &paths[0].certificates).is_ok());
+    }
+}
@@ -0,0 +1,294 @@
+//! PEM armor (RFC 7468) around DER structures.
+//!
+//! Parsing follows the lax rules of RFC 7468 §2: text outside the
+//! BEGIN/END lines is ignored, as is whitespace anywhere in the base64,
+//! so CRLF line endings and `openssl x509 -text` output both read fine.
+
+use std::error::Error;
+use std::fmt;
+
+pub const CERTIFICATE: &str = "CERTIFICATE";
+pub const X509_CRL: &str = "X509 CRL";
+pub const CERTIFICATE_REQUEST: &str = "CERTIFICATE REQUEST";
+pub const PUBLIC_KEY: &str = "PUBLIC KEY";
+pub const PRIVATE_KEY: &str = "PRIVATE KEY";
+pub const ENCRYPTED_PRIVATE_KEY: &str = "ENCRYPTED PRIVATE KEY";
+pub const PKCS7: &str = "PKCS7";
+pub const ATTRIBUTE_CERTIFICATE: &str = "ATTRIBUTE CERTIFICATE";
+
+const BEGIN: &str = "-----BEGIN ";
+const END: &str = "-----END ";
+const DASHES: &str = "-----";
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// An error that can arise reading PEM.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PemDecodeErr {
+    /// There is no BEGIN line in the input.
+    MissingBegin,
+    /// The block with the given label has no matching END line.
+    MissingEnd(String),
+    /// The text between the BEGIN and END lines is not valid base64.
+    InvalidBase64,
+    /// The block has a different label from the one asked for.
+    UnexpectedLabel(String),
+}
+
+impl fmt::Display for PemDecodeErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PemDecodeErr::MissingBegin => write!(f, "no PEM BEGIN line"),
+            PemDecodeErr::MissingEnd(ref label) => write!(f, "no PEM END line for {}", label),
+            PemDecodeErr::InvalidBase64 => write!(f, "invalid base64 in PEM"),
+            PemDecodeErr::UnexpectedLabel(ref label) => write!(f, "unexpected PEM label: {}", label),
+        }
+    }
+}
+
+impl Error for PemDecodeErr {}
+
+/// A labelled DER structure, as found between `-----BEGIN <label>-----`
+/// and `-----END <label>-----`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pem {
+    pub label: String,
+    pub contents: Vec<u8>,
+}
+
+impl Pem {
+    pub fn new(label: &str, contents: Vec<u8>) -> Pem {
+        Pem { label: label.to_string(), contents }
+    }
+
+    /// The contents of the block, failing unless it has `label`.
+    pub fn into_contents(self, label: &str) -> Result<Vec<u8>, PemDecodeErr> {
+        if self.label == label {
+            Ok(self.contents)
+        } else {
+            Err(PemDecodeErr::UnexpectedLabel(self.label))
+        }
+    }
+
+    /// The block's armored form, with the base64 wrapped at 64 columns.
+    pub fn encode(&self) -> String {
+        let encoded = base64_encode(&self.contents);
+        let mut res = format!("{}{}{}\n", BEGIN, self.label, DASHES);
+        for line in encoded.as_bytes().chunks(64) {
+            // base64 is ASCII, so every chunk is valid UTF-8.
+            res.push_str(::std::str::from_utf8(line).unwrap());
+            res.push('\n');
+        }
+        res.push_str(&format!("{}{}{}\n", END, self.label, DASHES));
+        res
+    }
+}
+
+/// The first PEM block in `input`.
+pub fn parse(input: &str) -> Result<Pem, PemDecodeErr> {
+    match parse_next(input) {
+        Some((pem, _)) => pem,
+        None => Err(PemDecodeErr::MissingBegin),
+    }
+}
+
+/// Every PEM block in `input`, in order, such as the certificates of a
+/// chain or CA bundle. A malformed block is reported in its place and
+/// parsing carries on from the next BEGIN line.
+pub fn parse_all(input: &str) -> PemBlocks<'_> {
+    PemBlocks(input)
+}
+
+/// An iterator over the PEM blocks in some text, from `parse_all`.
+#[derive(Clone, Debug)]
+pub struct PemBlocks<'a>(&'a str);
+
+impl<'a> Iterator for PemBlocks<'a> {
+    type Item = Result<Pem, PemDecodeErr>;
+
+    fn next(&mut self) -> Option<Result<Pem, PemDecodeErr>> {
+        let (pem, rest) = parse_next(self.0)?;
+        self.0 = rest;
+        Some(pem)
+    }
+}
+
+/// The next PEM block in `input` and the text after it, or `None` if there
+/// are no more BEGIN lines.
+fn parse_next(input: &str) -> Option<(Result<Pem, PemDecodeErr>, &str)> {
+    let mut lines = Lines(input);
+    let label = loop {
+        let line = lines.next()?.trim();
+        if line.starts_with(BEGIN) && line.ends_with(DASHES) && line.len() >= BEGIN.len() + DASHES.len() {
+            break &line[BEGIN.len()..line.len() - DASHES.len()];
+        }
+    };
+    let mut body = String::new();
+    loop {
+        let unread = lines.0;
+        let line = match lines.next() {
+            Some(line) => line.trim(),
+            None => return Some((Err(PemDecodeErr::MissingEnd(label.to_string())), lines.0)),
+        };
+        // Leave a following block to be read in its own right.
+        if line.starts_with(BEGIN) {
+            return Some((Err(PemDecodeErr::MissingEnd(label.to_string())), unread));
+        }
+        if line.starts_with(END) {
+            if line != format!("{}{}{}", END, label, DASHES) {
+                return Some((Err(PemDecodeErr::MissingEnd(label.to_string())), lines.0));
+            }
+            break;
+        }
+        body.push_str(line);
+    }
+    let pem = base64_decode(&body).map(|contents| Pem::new(label, contents));
+    Some((pem, lines.0))
+}
+
+// Like str::lines, but keeps hold of the unread text.
+struct Lines<'a>(&'a str);
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let (line, rest) = match self.0.find('\n') {
+            Some(i) => (&self.0[..i], &self.0[i + 1..]),
+            None => (self.0, ""),
+        };
+        self.0 = rest;
+        Some(line)
+    }
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut res = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                res.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                res.push('=');
+            }
+        }
+    }
+    res
+}
+
+fn base64_decode(text: &str) -> Result<Vec<u8>, PemDecodeErr> {
+    let chars: Vec<u8> = text.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    if !chars.len().is_multiple_of(4) {
+        return Err(PemDecodeErr::InvalidBase64);
+    }
+    let mut res = Vec::with_capacity(chars.len() / 4 * 3);
+    for (i, quad) in chars.chunks(4).enumerate() {
+        let last = i == chars.len() / 4 - 1;
+        let padding = quad.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return Err(PemDecodeErr::InvalidBase64);
+        }
+        let mut n = 0u32;
+        for &c in &quad[..4 - padding] {
+            let v = ALPHABET.iter().position(|&a| a == c).ok_or(PemDecodeErr::InvalidBase64)?;
+            n = n << 6 | v as u32;
+        }
+        n <<= 6 * padding;
+        let bytes = n.to_be_bytes();
+        res.extend_from_slice(&bytes[1..4 - padding]);
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod pem_tests {
+    use super::{base64_decode, base64_encode, parse, parse_all, Pem, PemDecodeErr, CERTIFICATE};
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_ROOT_PEM: &str = include_str!("../testdata/ec-root.pem");
+    const EC_LEAF: &[u8] = include_bytes!("../testdata/ec-leaf.der");
+
+    #[test]
+    fn base64_round_trips() {
+        for (bytes, text) in &[(&b""[..], ""), (b"f", "Zg=="), (b"fo", "Zm8="), (b"foo", "Zm9v"), (b"foob", "Zm9vYg==")] {
+            assert_eq!(*text, base64_encode(bytes));
+            assert_eq!(bytes.to_vec(), base64_decode(text).unwrap());
+        }
+    }
+
+    #[test]
+    fn base64_rejects_bad_padding() {
+        for text in &["Zg=", "Zg==Zg==", "Z===", "Zm9*"] {
+            assert_eq!(Err(PemDecodeErr::InvalidBase64), base64_decode(text));
+        }
+    }
+
+    #[test]
+    fn pem_parses_certificate() {
+        let pem = parse(EC_ROOT_PEM).unwrap();
+        assert_eq!(CERTIFICATE, pem.label);
+        assert_eq!(EC_ROOT.to_vec(), pem.contents);
+    }
+
+    #[test]
+    fn pem_round_trips() {
+        let pem = Pem::new(CERTIFICATE, EC_ROOT.to_vec());
+        assert_eq!(EC_ROOT_PEM, pem.encode());
+        assert_eq!(pem, parse(&pem.encode()).unwrap());
+    }
+
+    #[test]
+    fn pem_tolerates_crlf_and_surrounding_text() {
+        let input = format!("subject=CN = Example\r\n\r\n{}trailing\r\n", EC_ROOT_PEM.replace('\n', "\r\n"));
+        assert_eq!(EC_ROOT.to_vec(), parse(&input).unwrap().contents);
+    }
+
+    #[test]
+    fn pem_requires_matching_end() {
+        let input = "-----BEGIN CERTIFICATE-----\nZm9v\n-----END X509 CRL-----\n";
+        assert_eq!(Err(PemDecodeErr::MissingEnd(CERTIFICATE.to_string())), parse(input));
+        let input = "-----BEGIN CERTIFICATE-----\nZm9v\n";
+        assert_eq!(Err(PemDecodeErr::MissingEnd(CERTIFICATE.to_string())), parse(input));
+    }
+
+    #[test]
+    fn pem_requires_begin() {
+        assert_eq!(Err(PemDecodeErr::MissingBegin), parse("no armor here"));
+    }
+
+    #[test]
+    fn pem_checks_label() {
+        let pem = Pem::new("PUBLIC KEY", vec![1]);
+        assert_eq!(Err(PemDecodeErr::UnexpectedLabel("PUBLIC KEY".to_string())), pem.into_contents(CERTIFICATE));
+    }
+
+    #[test]
+    fn pem_parses_all_blocks() {
+        let input = format!("{}{}", Pem::new(CERTIFICATE, EC_LEAF.to_vec()).encode(), EC_ROOT_PEM);
+        let blocks: Vec<_> = parse_all(&input).map(|pem| pem.unwrap().contents).collect();
+        assert_eq!(vec![EC_LEAF.to_vec(), EC_ROOT.to_vec()], blocks);
+        assert_eq!(0, parse_all("no armor here").count());
+    }
+
+    #[test]
+    fn pem_parse_all_continues_after_errors() {
+        let truncated = "-----BEGIN CERTIFICATE-----\nZm9v\n";
+        let garbled = "-----BEGIN CERTIFICATE-----\n!!!!\n-----END CERTIFICATE-----\n";
+        let input = format!("{}{}{}", truncated, EC_ROOT_PEM, garbled);
+        let blocks: Vec<_> = parse_all(&input).collect();
+        assert_eq!(
+            vec![
+                Err(PemDecodeErr::MissingEnd(CERTIFICATE.to_string())),
+                Ok(Pem::new(CERTIFICATE, EC_ROOT.to_vec())),
+                Err(PemDecodeErr::InvalidBase64),
+            ],
+            blocks
+        );
+    }
+}
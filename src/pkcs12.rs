@@ -0,0 +1,388 @@
+//! PKCS#12 files (RFC 7292), the `.p12` or `.pfx` bundles Windows and many
+//! CAs deliver a certificate chain and its private key in.
+//!
+//! Only reading is supported, and only files whose encrypted parts use
+//! PBES2, as OpenSSL 3 and current Windows versions write by default.
+//! Older files use the PKCS#12 PBE schemes with RC2 or 3DES, which fail
+//! with `DecryptError::UnsupportedAlgorithm`; `openssl pkcs12 -legacy` can
+//! convert them.
+
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use num::ToPrimitive;
+use sha1::{Digest, Sha1};
+use sha2::{Sha256, Sha384, Sha512};
+use simple_asn1::{ASN1Block, FromASN1};
+
+use algorithm::AlgorithmIdentifier;
+use attribute::Attribute;
+use der::{der_decode, explicit_inner, expect_end, from_der, implicit_bytes, next_block, sequence};
+use error::X509DecodeErr;
+use oid::ObjectIdentifier;
+use oids;
+use pkcs8::{iteration_count, pbes2_decrypt, DecryptError, EncryptedPrivateKeyInfo, PrivateKeyInfo};
+use x509::Certificate;
+
+/// A certificate or key from a PKCS#12 file, with the attributes that tie
+/// them together.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SafeBag<T> {
+    pub value: T,
+    /// The friendlyName attribute, which Windows shows as the
+    /// certificate's name.
+    pub friendly_name: Option<String>,
+    /// The localKeyId attribute, which a key shares with its certificate.
+    pub local_key_id: Option<Vec<u8>>,
+}
+
+/// The certificates and keys of a PKCS#12 file, in the order they appear.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pkcs12 {
+    pub certificates: Vec<SafeBag<Certificate>>,
+    pub private_keys: Vec<SafeBag<PrivateKeyInfo>>,
+}
+
+impl Pkcs12 {
+    /// Decode a PKCS#12 file, checking its MAC and decrypting it with
+    /// `password`. Bags of other types, such as CRLs or secrets, are
+    /// skipped.
+    pub fn from_der(der: &[u8], password: &str) -> Result<Pkcs12, DecryptError> {
+        let blocks = from_der(der).map_err(X509DecodeErr::from)?;
+        let (head, rest) = next_block(&blocks, "PFX")?;
+        expect_end(rest, "PFX")?;
+        let items = sequence(head, "PFX")?;
+        match next_block(items, "version")? {
+            (ASN1Block::Integer(_, v), _) if v.to_u8() == Some(3) => {}
+            (ASN1Block::Integer(_, v), _) => return Err(X509DecodeErr::UnsupportedVersion(v.clone()).into()),
+            _ => return Err(X509DecodeErr::InvalidField("version").into()),
+        }
+        // Public-key integrity mode signs authSafe as SignedData instead,
+        // which nothing in practice writes.
+        let (auth_safe, rest) = next_block(&items[1..], "authSafe")?;
+        let auth_safe = content_info(auth_safe, &oids::PKCS7_DATA, "authSafe")?;
+        if let Some((mac_data, rest)) = rest.split_first() {
+            expect_end(rest, "PFX")?;
+            verify_mac(mac_data, password, auth_safe)?;
+        }
+
+        let mut pkcs12 = Pkcs12 { certificates: Vec::new(), private_keys: Vec::new() };
+        let blocks = from_der(auth_safe).map_err(X509DecodeErr::from)?;
+        let (head, rest) = next_block(&blocks, "AuthenticatedSafe")?;
+        expect_end(rest, "AuthenticatedSafe")?;
+        for content in sequence(head, "AuthenticatedSafe")? {
+            let safe_contents = match content_info(content, &oids::PKCS7_DATA, "ContentInfo") {
+                Ok(data) => data.to_vec(),
+                Err(_) => encrypted_data(content, password)?,
+            };
+            pkcs12.add_bags(&safe_contents, password)?;
+        }
+        Ok(pkcs12)
+    }
+
+    /// The certificate for `key`: the one with the same localKeyId.
+    pub fn certificate_for(&self, key: &SafeBag<PrivateKeyInfo>) -> Option<&Certificate> {
+        let id = key.local_key_id.as_ref()?;
+        self.certificates.iter().find(|bag| bag.local_key_id.as_ref() == Some(id)).map(|bag| &bag.value)
+    }
+
+    // The bags of a SafeContents.
+    fn add_bags(&mut self, der: &[u8], password: &str) -> Result<(), DecryptError> {
+        let blocks = from_der(der).map_err(X509DecodeErr::from)?;
+        let (head, rest) = next_block(&blocks, "SafeContents")?;
+        expect_end(rest, "SafeContents")?;
+        for bag in sequence(head, "SafeContents")? {
+            let items = sequence(bag, "SafeBag")?;
+            let (bag_id, rest) = next_block(items, "bagId")?;
+            let bag_id = ObjectIdentifier::from_block(bag_id, "bagId")?;
+            let (value, rest) = next_block(rest, "bagValue")?;
+            let value = explicit_inner(value, 0).ok_or(X509DecodeErr::InvalidField("bagValue"))?;
+            let attributes = match rest.split_first() {
+                Some((attributes, rest)) => {
+                    expect_end(rest, "SafeBag")?;
+                    bag_attributes(attributes)?
+                }
+                None => (None, None),
+            };
+
+            if bag_id == oids::KEY_BAG {
+                let (key, _) = PrivateKeyInfo::from_asn1(std::slice::from_ref(value))?;
+                self.private_keys.push(safe_bag(key, &attributes));
+            } else if bag_id == oids::PKCS8_SHROUDED_KEY_BAG {
+                let (key, _) = EncryptedPrivateKeyInfo::from_asn1(std::slice::from_ref(value))?;
+                self.private_keys.push(safe_bag(key.decrypt(password.as_bytes())?, &attributes));
+            } else if bag_id == oids::CERT_BAG {
+                self.certificates.push(safe_bag(cert_bag(value)?, &attributes));
+            }
+        }
+        Ok(())
+    }
+}
+
+// The content of a ContentInfo of type `content_type`, which for data is
+// `[0] EXPLICIT OCTET STRING`.
+fn content_info<'a>(
+    b: &'a ASN1Block,
+    content_type: &ObjectIdentifier,
+    field: &'static str,
+) -> Result<&'a [u8], X509DecodeErr> {
+    let items = sequence(b, field)?;
+    let (oid, rest) = next_block(items, "contentType")?;
+    if ObjectIdentifier::from_block(oid, "contentType")? != *content_type {
+        return Err(X509DecodeErr::InvalidField("contentType"));
+    }
+    let (content, rest) = next_block(rest, "content")?;
+    expect_end(rest, field)?;
+    match explicit_inner(content, 0) {
+        Some(ASN1Block::OctetString(_, data)) => Ok(data),
+        _ => Err(X509DecodeErr::InvalidField("content")),
+    }
+}
+
+// The decrypted content of an encryptedData ContentInfo (RFC 5652 §8).
+fn encrypted_data(b: &ASN1Block, password: &str) -> Result<Vec<u8>, DecryptError> {
+    let items = sequence(b, "ContentInfo")?;
+    let (oid, rest) = next_block(items, "contentType")?;
+    let content_type = ObjectIdentifier::from_block(oid, "contentType")?;
+    if content_type != oids::PKCS7_ENCRYPTED_DATA {
+        // Most likely envelopedData, in public-key privacy mode.
+        return Err(DecryptError::UnsupportedAlgorithm(content_type));
+    }
+    let (content, rest) = next_block(rest, "content")?;
+    expect_end(rest, "ContentInfo")?;
+    let content = explicit_inner(content, 0).ok_or(X509DecodeErr::InvalidField("content"))?;
+    let items = sequence(content, "EncryptedData")?;
+    match next_block(items, "version")? {
+        (ASN1Block::Integer(_, v), _) if v.to_u8() == Some(0) => {}
+        (ASN1Block::Integer(_, v), _) => return Err(X509DecodeErr::UnsupportedVersion(v.clone()).into()),
+        _ => return Err(X509DecodeErr::InvalidField("version").into()),
+    }
+    // Unprotected attributes may follow, and are ignored.
+    let (info, _) = next_block(&items[1..], "encryptedContentInfo")?;
+    let items = sequence(info, "EncryptedContentInfo")?;
+    let (oid, rest) = next_block(items, "contentType")?;
+    if ObjectIdentifier::from_block(oid, "contentType")? != oids::PKCS7_DATA {
+        return Err(X509DecodeErr::InvalidField("contentType").into());
+    }
+    let (algorithm, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+    let (encrypted, rest) = next_block(rest, "encryptedContent")?;
+    expect_end(rest, "EncryptedContentInfo")?;
+    let encrypted = implicit_bytes(encrypted, 0).ok_or(X509DecodeErr::InvalidField("encryptedContent"))?;
+    pbes2_decrypt(&algorithm, password.as_bytes(), encrypted)
+}
+
+// The certificate in a CertBag: an x509Certificate is the DER of the
+// certificate, in a `[0] EXPLICIT OCTET STRING`.
+fn cert_bag(b: &ASN1Block) -> Result<Certificate, X509DecodeErr> {
+    let items = sequence(b, "CertBag")?;
+    let (oid, rest) = next_block(items, "certId")?;
+    let cert_id = ObjectIdentifier::from_block(oid, "certId")?;
+    if cert_id != oids::X509_CERTIFICATE {
+        return Err(X509DecodeErr::InvalidField("certId"));
+    }
+    let (value, rest) = next_block(rest, "certValue")?;
+    expect_end(rest, "CertBag")?;
+    match explicit_inner(value, 0) {
+        Some(ASN1Block::OctetString(_, der)) => der_decode(der),
+        _ => Err(X509DecodeErr::InvalidField("certValue")),
+    }
+}
+
+// The friendlyName and localKeyId of a bag.
+type BagAttributes = (Option<String>, Option<Vec<u8>>);
+
+fn safe_bag<T>(value: T, attributes: &BagAttributes) -> SafeBag<T> {
+    let (ref friendly_name, ref local_key_id) = *attributes;
+    SafeBag { value, friendly_name: friendly_name.clone(), local_key_id: local_key_id.clone() }
+}
+
+fn bag_attributes(b: &ASN1Block) -> Result<BagAttributes, X509DecodeErr> {
+    let mut blocks = match *b {
+        ASN1Block::Set(_, ref blocks) => &blocks[..],
+        _ => return Err(X509DecodeErr::InvalidField("bagAttributes")),
+    };
+    let (mut friendly_name, mut local_key_id) = (None, None);
+    while !blocks.is_empty() {
+        let (attribute, rest) = Attribute::from_asn1(blocks)?;
+        blocks = rest;
+        if attribute.attr_type == oids::FRIENDLY_NAME {
+            match attribute.values[..] {
+                [ASN1Block::BMPString(_, ref name)] => friendly_name = Some(name.clone()),
+                _ => return Err(X509DecodeErr::InvalidField("friendlyName")),
+            }
+        } else if attribute.attr_type == oids::LOCAL_KEY_ID {
+            match attribute.values[..] {
+                [ASN1Block::OctetString(_, ref id)] => local_key_id = Some(id.clone()),
+                _ => return Err(X509DecodeErr::InvalidField("localKeyId")),
+            }
+        }
+    }
+    Ok((friendly_name, local_key_id))
+}
+
+// Check the MacData over `auth_safe` (RFC 7292 §5.1), with a key from the
+// PKCS#12 KDF. A wrong password is caught here, before anything is
+// decrypted.
+fn verify_mac(b: &ASN1Block, password: &str, auth_safe: &[u8]) -> Result<(), DecryptError> {
+    let items = sequence(b, "MacData")?;
+    let (mac, rest) = next_block(items, "mac")?;
+    let digest_info = sequence(mac, "DigestInfo")?;
+    let (digest_algorithm, digest_rest) = AlgorithmIdentifier::from_asn1(digest_info)?;
+    let expected = match next_block(digest_rest, "digest")? {
+        (ASN1Block::OctetString(_, digest), digest_rest) => {
+            expect_end(digest_rest, "DigestInfo")?;
+            digest
+        }
+        _ => return Err(X509DecodeErr::InvalidField("digest").into()),
+    };
+    let (salt, rest) = match next_block(rest, "macSalt")? {
+        (ASN1Block::OctetString(_, salt), rest) => (salt, rest),
+        _ => return Err(X509DecodeErr::InvalidField("macSalt").into()),
+    };
+    let iterations = match rest.split_first() {
+        Some((ASN1Block::Integer(_, n), rest)) => {
+            expect_end(rest, "MacData")?;
+            iteration_count(n, "iterations")?
+        }
+        Some(_) => return Err(X509DecodeErr::InvalidField("iterations").into()),
+        None => 1,
+    };
+
+    // The KDF takes the password as a NUL-terminated BMPString.
+    let mut bmp_password: Vec<u8> = password.encode_utf16().flat_map(|c| c.to_be_bytes()).collect();
+    bmp_password.extend([0, 0]);
+    let algorithm = digest_algorithm.algorithm;
+    let verified = if algorithm == oids::SHA1 {
+        let key = pkcs12_kdf::<Sha1>(&bmp_password, salt, iterations, 64);
+        mac_matches::<Hmac<Sha1>>(&key, auth_safe, expected)
+    } else if algorithm == oids::SHA256 {
+        let key = pkcs12_kdf::<Sha256>(&bmp_password, salt, iterations, 64);
+        mac_matches::<Hmac<Sha256>>(&key, auth_safe, expected)
+    } else if algorithm == oids::SHA384 {
+        let key = pkcs12_kdf::<Sha384>(&bmp_password, salt, iterations, 128);
+        mac_matches::<Hmac<Sha384>>(&key, auth_safe, expected)
+    } else if algorithm == oids::SHA512 {
+        let key = pkcs12_kdf::<Sha512>(&bmp_password, salt, iterations, 128);
+        mac_matches::<Hmac<Sha512>>(&key, auth_safe, expected)
+    } else {
+        return Err(DecryptError::UnsupportedAlgorithm(algorithm));
+    };
+    if verified {
+        Ok(())
+    } else {
+        Err(DecryptError::BadPassword)
+    }
+}
+
+fn mac_matches<M: Mac + KeyInit>(key: &[u8], data: &[u8], expected: &[u8]) -> bool {
+    match <M as Mac>::new_from_slice(key) {
+        Ok(mut mac) => {
+            mac.update(data);
+            mac.verify_slice(expected).is_ok()
+        }
+        Err(_) => false,
+    }
+}
+
+// The integrity key derivation of RFC 7292 App. B.2 (ID 3), giving a key
+// as long as the digest. `v` is the digest's block size in bytes.
+fn pkcs12_kdf<D: Digest>(password: &[u8], salt: &[u8], iterations: u32, v: usize) -> Vec<u8> {
+    // Repeat `s` to fill a multiple of v bytes.
+    let fill = |s: &[u8]| -> Vec<u8> { s.iter().cycle().take(s.len().div_ceil(v) * v).cloned().collect() };
+    let diversifier = vec![3; v];
+    let mut i = fill(salt);
+    i.extend(fill(password));
+
+    let n = <D as Digest>::output_size();
+    let mut key = Vec::with_capacity(n);
+    loop {
+        let mut a = D::new().chain_update(&diversifier).chain_update(&i).finalize();
+        for _ in 1..iterations {
+            a = D::digest(&a);
+        }
+        key.extend_from_slice(&a[..(n - key.len()).min(a.len())]);
+        if key.len() == n {
+            return key;
+        }
+        // Each v byte block of I becomes (I_j + B + 1) mod 2^(8v), where
+        // B is A repeated to v bytes.
+        let b: Vec<u8> = a.iter().cycle().take(v).cloned().collect();
+        for block in i.chunks_mut(v) {
+            let mut carry = 1u16;
+            for (x, y) in block.iter_mut().zip(&b).rev() {
+                let sum = *x as u16 + *y as u16 + carry;
+                *x = sum as u8;
+                carry = sum >> 8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod pkcs12_tests {
+    use super::Pkcs12;
+    use der::der_decode;
+    use pkcs8::{DecryptError, PrivateKeyInfo};
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_ROOT_KEY: &[u8] = include_bytes!("../testdata/ec-root-key.der");
+    // EC_ROOT, the EC intermediate and EC_ROOT_KEY, as `openssl pkcs12
+    // -export -name "QuickLime EC Root"` writes them with password
+    // "quicklime": an HMAC-SHA256 MAC and PBES2 with AES-256-CBC.
+    const EC_ROOT_P12: &[u8] = include_bytes!("../testdata/ec-root.p12");
+    // The same with `-legacy`: an HMAC-SHA1 MAC, RC2-40 certificates and a
+    // 3DES key.
+    const EC_ROOT_LEGACY_P12: &[u8] = include_bytes!("../testdata/ec-root-legacy.p12");
+
+    #[test]
+    fn pkcs12_decodes() {
+        let pkcs12 = Pkcs12::from_der(EC_ROOT_P12, "quicklime").unwrap();
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        assert_eq!(2, pkcs12.certificates.len());
+        assert_eq!(root, pkcs12.certificates[0].value);
+        assert_eq!(Some("QuickLime EC Root".to_string()), pkcs12.certificates[0].friendly_name);
+        assert_eq!(None, pkcs12.certificates[1].local_key_id);
+
+        assert_eq!(1, pkcs12.private_keys.len());
+        let key = &pkcs12.private_keys[0];
+        assert_eq!(der_decode::<PrivateKeyInfo>(EC_ROOT_KEY).unwrap(), key.value);
+        assert_eq!(Some("QuickLime EC Root".to_string()), key.friendly_name);
+        assert_eq!(Some(&root), pkcs12.certificate_for(key));
+    }
+
+    #[test]
+    fn pkcs12_checks_the_password() {
+        assert_eq!(Err(DecryptError::BadPassword), Pkcs12::from_der(EC_ROOT_P12, "quicklim3"));
+        assert_eq!(Err(DecryptError::BadPassword), Pkcs12::from_der(EC_ROOT_LEGACY_P12, "quicklim3"));
+    }
+
+    #[test]
+    fn pkcs12_limits_mac_iterations() {
+        use num::bigint::BigInt;
+        use simple_asn1::{to_der, ASN1Block};
+
+        use der::from_der;
+        use pkcs8::MAX_ITERATIONS;
+
+        // The iteration count ends the MacData, which ends the PFX.
+        let mut blocks = from_der(EC_ROOT_P12).unwrap();
+        if let ASN1Block::Sequence(_, ref mut pfx) = blocks[0] {
+            if let Some(ASN1Block::Sequence(_, ref mut mac_data)) = pfx.last_mut() {
+                *mac_data.last_mut().unwrap() = ASN1Block::Integer(0, BigInt::from(MAX_ITERATIONS + 1));
+            }
+        }
+        assert_eq!(
+            Err(DecryptError::TooManyIterations(MAX_ITERATIONS + 1)),
+            Pkcs12::from_der(&to_der(&blocks[0]).unwrap(), "quicklime")
+        );
+    }
+
+    #[test]
+    fn pkcs12_rejects_legacy_encryption() {
+        // The SHA-1 MAC checks out, but RC2 is not supported.
+        assert_eq!(
+            Err(DecryptError::UnsupportedAlgorithm(oid!(1, 2, 840, 113549, 1, 12, 1, 6))),
+            Pkcs12::from_der(EC_ROOT_LEGACY_P12, "quicklime")
+        );
+    }
+}
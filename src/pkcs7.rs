@@ -0,0 +1,196 @@
+//! Certificates-only PKCS#7 bundles (`.p7b` or `.p7c` files), which CAs
+//! commonly deliver intermediate chains in.
+//!
+//! These are CMS SignedData (RFC 5652 §5) with no content and no signers,
+//! used only to carry certificates and CRLs (RFC 8551 §3.2.2 calls them
+//! degenerate). Signed messages are not supported.
+
+use num::bigint::BigInt;
+use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use crl::CertificateList;
+use der::{
+    context_tag, decode_with, expect_end, explicit, explicit_inner, implicit, implicit_inner, next_block, sequence,
+};
+use error::X509DecodeErr;
+use oid::ObjectIdentifier;
+use oids;
+use pem::{self, Pem};
+use x509::Certificate;
+
+/// A certificates-only SignedData, in its ContentInfo.
+///
+/// Certificates and CRLs are kept in the order they were decoded, and
+/// written in the order given, as most tools expect rather than in the
+/// sorted order DER asks of a SET OF. Other certificate and revocation
+/// formats, such as attribute certificates, are skipped when decoding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertsOnly {
+    pub certificates: Vec<Certificate>,
+    pub crls: Vec<CertificateList>,
+}
+
+impl CertsOnly {
+    pub fn new(certificates: Vec<Certificate>) -> CertsOnly {
+        CertsOnly { certificates, crls: Vec::new() }
+    }
+
+    /// Decode the first `PKCS7` PEM block in `input`.
+    pub fn from_pem(input: &str) -> Result<CertsOnly, X509DecodeErr> {
+        let der = pem::parse(input)?.into_contents(pem::PKCS7)?;
+        decode_with(&der, |blocks| {
+            let (bundle, rest) = CertsOnly::from_asn1(blocks)?;
+            expect_end(rest, "ContentInfo").map(|_| bundle)
+        })
+    }
+
+    /// The bundle as a `PKCS7` PEM block.
+    pub fn to_pem(&self) -> Result<String, ASN1EncodeErr> {
+        Ok(Pem::new(pem::PKCS7, der_encode(self)?).encode())
+    }
+}
+
+impl ToASN1 for CertsOnly {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = vec![
+            ASN1Block::Integer(0, BigInt::from(1)),
+            ASN1Block::Set(0, Vec::new()),
+            ASN1Block::Sequence(0, vec![oids::PKCS7_DATA.to_block()]),
+        ];
+        if !self.certificates.is_empty() {
+            let mut certificates = Vec::new();
+            for certificate in &self.certificates {
+                certificates.extend(certificate.to_asn1_class(c)?);
+            }
+            items.push(implicit(0, &ASN1Block::Set(0, certificates))?);
+        }
+        if !self.crls.is_empty() {
+            let mut crls = Vec::new();
+            for crl in &self.crls {
+                crls.extend(crl.to_asn1_class(c)?);
+            }
+            items.push(implicit(1, &ASN1Block::Set(0, crls))?);
+        }
+        items.push(ASN1Block::Set(0, Vec::new()));
+        let signed_data = ASN1Block::Sequence(0, items);
+        Ok(vec![ASN1Block::Sequence(0, vec![oids::PKCS7_SIGNED_DATA.to_block(), explicit(0, signed_data)])])
+    }
+}
+
+impl FromASN1 for CertsOnly {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "ContentInfo")?;
+        let items = sequence(head, "ContentInfo")?;
+        let (content_type, rest) = next_block(items, "contentType")?;
+        if ObjectIdentifier::from_block(content_type, "contentType")? != oids::PKCS7_SIGNED_DATA {
+            return Err(X509DecodeErr::InvalidField("contentType"));
+        }
+        let (content, rest) = next_block(rest, "content")?;
+        expect_end(rest, "ContentInfo")?;
+        let content = explicit_inner(content, 0).ok_or(X509DecodeErr::InvalidField("content"))?;
+
+        let items = sequence(content, "SignedData")?;
+        // Versions 1, 3, 4 and 5 depend only on which optional formats
+        // appear (RFC 5652 §5.1), so any is accepted.
+        let rest = match next_block(items, "version")? {
+            (ASN1Block::Integer(_, _), rest) => rest,
+            _ => return Err(X509DecodeErr::InvalidField("version")),
+        };
+        let rest = match next_block(rest, "digestAlgorithms")? {
+            (ASN1Block::Set(_, _), rest) => rest,
+            _ => return Err(X509DecodeErr::InvalidField("digestAlgorithms")),
+        };
+        // There is no content to sign, so no eContent.
+        let (encap_content_info, mut rest) = next_block(rest, "encapContentInfo")?;
+        match sequence(encap_content_info, "encapContentInfo")? {
+            [content_type] => ObjectIdentifier::from_block(content_type, "eContentType").map(|_| ())?,
+            _ => return Err(X509DecodeErr::InvalidField("encapContentInfo")),
+        }
+
+        let mut bundle = CertsOnly { certificates: Vec::new(), crls: Vec::new() };
+        if let Some(b) = rest.first().filter(|b| context_tag(b) == Some(0)) {
+            for choice in &set(b, 0, "certificates")? {
+                // The other CertificateChoices are all tagged.
+                if context_tag(choice).is_none() {
+                    bundle.certificates.push(Certificate::from_asn1(std::slice::from_ref(choice))?.0);
+                }
+            }
+            rest = &rest[1..];
+        }
+        if let Some(b) = rest.first().filter(|b| context_tag(b) == Some(1)) {
+            for choice in &set(b, 1, "crls")? {
+                if context_tag(choice).is_none() {
+                    bundle.crls.push(CertificateList::from_asn1(std::slice::from_ref(choice))?.0);
+                }
+            }
+            rest = &rest[1..];
+        }
+        match next_block(rest, "signerInfos")? {
+            (ASN1Block::Set(_, signers), rest) if signers.is_empty() => expect_end(rest, "SignedData")?,
+            _ => return Err(X509DecodeErr::InvalidField("signerInfos")),
+        }
+        Ok((bundle, tail))
+    }
+}
+
+// The members of an `[tag] IMPLICIT SET OF`.
+fn set(b: &ASN1Block, tag: u64, field: &'static str) -> Result<Vec<ASN1Block>, X509DecodeErr> {
+    match implicit_inner(b, tag, 0x11) {
+        Some(ASN1Block::Set(_, items)) => Ok(items),
+        _ => Err(X509DecodeErr::InvalidField(field)),
+    }
+}
+
+#[cfg(test)]
+mod certs_only_tests {
+    use simple_asn1::der_encode;
+
+    use super::CertsOnly;
+    use crl::CertificateList;
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_INT: &[u8] = include_bytes!("../testdata/ec-int.der");
+    const EC_ROOT_CRL: &[u8] = include_bytes!("../testdata/ec-root-crl.der");
+    // EC_INT, EC_ROOT and EC_ROOT_CRL, from `openssl crl2pkcs7`.
+    const EC_CHAIN_P7B: &[u8] = include_bytes!("../testdata/ec-chain.p7b");
+
+    #[test]
+    fn certs_only_decodes() {
+        let bundle = der_decode::<CertsOnly>(EC_CHAIN_P7B).unwrap();
+        let chain = vec![der_decode::<Certificate>(EC_INT).unwrap(), der_decode::<Certificate>(EC_ROOT).unwrap()];
+        assert_eq!(chain, bundle.certificates);
+        assert_eq!(vec![der_decode::<CertificateList>(EC_ROOT_CRL).unwrap()], bundle.crls);
+    }
+
+    #[test]
+    fn certs_only_round_trips() {
+        let bundle = der_decode::<CertsOnly>(EC_CHAIN_P7B).unwrap();
+        assert_eq!(EC_CHAIN_P7B.to_vec(), der_encode(&bundle).unwrap());
+        assert_eq!(bundle, CertsOnly::from_pem(&bundle.to_pem().unwrap()).unwrap());
+
+        // A single certificate, which the reader sees as EXPLICIT.
+        let bundle = CertsOnly::new(vec![der_decode::<Certificate>(EC_ROOT).unwrap()]);
+        assert_eq!(bundle, der_decode::<CertsOnly>(&der_encode(&bundle).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn certs_only_rejects_signed_data_with_signers() {
+        let mut der = EC_CHAIN_P7B.to_vec();
+        // Replace the empty signerInfos with a SET holding a NULL. The
+        // lengths of the enclosing SEQUENCEs grow by two.
+        der.truncate(der.len() - 2);
+        der.extend([0x31, 0x02, 0x05, 0x00]);
+        for offset in [2, 17, 21] {
+            let len = u16::from_be_bytes([der[offset], der[offset + 1]]) + 2;
+            der[offset..offset + 2].copy_from_slice(&len.to_be_bytes());
+        }
+        assert_eq!(Err(X509DecodeErr::InvalidField("signerInfos")), der_decode::<CertsOnly>(&der));
+    }
+}
@@ -0,0 +1,289 @@
+use std::error::Error;
+use std::fmt;
+
+use aes;
+use cbc;
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockCipher, BlockDecryptMut, KeyInit, KeyIvInit};
+use num::bigint::BigInt;
+use num::ToPrimitive;
+use pbkdf2::pbkdf2_hmac;
+use sha1::Sha1;
+use sha2::{Sha256, Sha384, Sha512};
+use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use algorithm::{AlgorithmIdentifier, AlgorithmParameters};
+use der::{der_decode, expect_end, from_der, next_block, sequence};
+use error::X509DecodeErr;
+use oid::ObjectIdentifier;
+use oids;
+use pem::{self, Pem};
+use pkcs8::PrivateKeyInfo;
+
+/// The most iterations of PBKDF2, or of the PKCS#12 key derivation, that
+/// decryption will run. The count comes from the encrypted data, so
+/// without a limit a crafted key could keep a thread busy for hours;
+/// current tools write counts in the thousands or low millions.
+pub const MAX_ITERATIONS: u64 = 10_000_000;
+
+/// Why an `EncryptedPrivateKeyInfo` could not be decrypted.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecryptError {
+    /// The encryption scheme, key derivation function, PRF or cipher is
+    /// not one this crate supports.
+    UnsupportedAlgorithm(ObjectIdentifier),
+    /// The key did not decrypt, almost always because the password is
+    /// wrong.
+    BadPassword,
+    /// The key derivation asks for more than `MAX_ITERATIONS` iterations.
+    TooManyIterations(u64),
+    /// The encryption parameters could not be decoded.
+    Decode(X509DecodeErr),
+}
+
+impl From<X509DecodeErr> for DecryptError {
+    fn from(e: X509DecodeErr) -> DecryptError {
+        DecryptError::Decode(e)
+    }
+}
+
+impl fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecryptError::UnsupportedAlgorithm(ref oid) => write!(f, "unsupported algorithm: {}", oid),
+            DecryptError::BadPassword => write!(f, "the key did not decrypt; wrong password?"),
+            DecryptError::TooManyIterations(n) => {
+                write!(f, "{} key derivation iterations is over the limit of {}", n, MAX_ITERATIONS)
+            }
+            DecryptError::Decode(ref e) => write!(f, "decoding the encryption parameters failed: {}", e),
+        }
+    }
+}
+
+impl Error for DecryptError {}
+
+/// A password-protected private key (RFC 5958 §3), the `ENCRYPTED PRIVATE
+/// KEY` PEM form.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptedPrivateKeyInfo {
+    pub encryption_algorithm: AlgorithmIdentifier,
+    pub encrypted_data: Vec<u8>,
+}
+
+impl EncryptedPrivateKeyInfo {
+    /// Decode the first `ENCRYPTED PRIVATE KEY` PEM block in `input`.
+    pub fn from_pem(input: &str) -> Result<EncryptedPrivateKeyInfo, X509DecodeErr> {
+        let der = pem::parse(input)?.into_contents(pem::ENCRYPTED_PRIVATE_KEY)?;
+        let blocks = from_der(&der)?;
+        let (key, rest) = EncryptedPrivateKeyInfo::from_asn1(&blocks)?;
+        expect_end(rest, "EncryptedPrivateKeyInfo")?;
+        Ok(key)
+    }
+
+    /// The key as an `ENCRYPTED PRIVATE KEY` PEM block.
+    pub fn to_pem(&self) -> Result<String, ASN1EncodeErr> {
+        Ok(Pem::new(pem::ENCRYPTED_PRIVATE_KEY, der_encode(self)?).encode())
+    }
+
+    /// Decrypt the key with `password`. Only PBES2 (RFC 8018 §6.2) with
+    /// PBKDF2 and AES-CBC is supported, as OpenSSL and most current tools
+    /// write; the older PBES1 schemes are not.
+    pub fn decrypt(&self, password: &[u8]) -> Result<PrivateKeyInfo, DecryptError> {
+        let decrypted = pbes2_decrypt(&self.encryption_algorithm, password, &self.encrypted_data)?;
+        // A wrong key gives bad padding or, rarely, garbage that happens
+        // to pad correctly but won't decode.
+        der_decode(&decrypted).map_err(|_| DecryptError::BadPassword)
+    }
+}
+
+/// Decrypt `data`, encrypted under `password` with the PBES2 scheme and
+/// parameters of `algorithm`.
+pub(crate) fn pbes2_decrypt(
+    algorithm: &AlgorithmIdentifier,
+    password: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, DecryptError> {
+    if algorithm.algorithm != oids::PBES2 {
+        return Err(DecryptError::UnsupportedAlgorithm(algorithm.algorithm.clone()));
+    }
+    // PBES2-params: the key derivation function, then the cipher.
+    let params = parameters(algorithm, "PBES2-params")?;
+    let (kdf, rest) = AlgorithmIdentifier::from_asn1(params)?;
+    let (cipher, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+    expect_end(rest, "PBES2-params")?;
+
+    let key_len = if cipher.algorithm == oids::AES128_CBC {
+        16
+    } else if cipher.algorithm == oids::AES192_CBC {
+        24
+    } else if cipher.algorithm == oids::AES256_CBC {
+        32
+    } else {
+        return Err(DecryptError::UnsupportedAlgorithm(cipher.algorithm));
+    };
+    let iv = match cipher.parameters {
+        AlgorithmParameters::Other(ASN1Block::OctetString(_, ref iv)) if iv.len() == 16 => iv,
+        _ => return Err(X509DecodeErr::InvalidField("iv").into()),
+    };
+    let key = pbkdf2(&kdf, password, key_len)?;
+
+    let decrypted = match key_len {
+        16 => aes_cbc::<aes::Aes128>(&key, iv, data),
+        24 => aes_cbc::<aes::Aes192>(&key, iv, data),
+        _ => aes_cbc::<aes::Aes256>(&key, iv, data),
+    };
+    decrypted.ok_or(DecryptError::BadPassword)
+}
+
+// The lengths are checked before this, so only the padding can fail.
+fn aes_cbc<C: BlockDecryptMut + BlockCipher + KeyInit>(key: &[u8], iv: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+    let cipher = cbc::Decryptor::<C>::new_from_slices(key, iv).ok()?;
+    cipher.decrypt_padded_vec_mut::<Pkcs7>(data).ok()
+}
+
+// The contents of a SEQUENCE parameters field.
+fn parameters<'a>(algorithm: &'a AlgorithmIdentifier, field: &'static str) -> Result<&'a [ASN1Block], X509DecodeErr> {
+    match algorithm.parameters {
+        AlgorithmParameters::Other(ref params) => sequence(params, field),
+        _ => Err(X509DecodeErr::InvalidField(field)),
+    }
+}
+
+// A `key_len` byte key from PBKDF2-params (RFC 8018 §A.2).
+fn pbkdf2(kdf: &AlgorithmIdentifier, password: &[u8], key_len: usize) -> Result<Vec<u8>, DecryptError> {
+    if kdf.algorithm != oids::PBKDF2 {
+        return Err(DecryptError::UnsupportedAlgorithm(kdf.algorithm.clone()));
+    }
+    let params = parameters(kdf, "PBKDF2-params")?;
+    // The salt may instead name an algorithm that makes it, which nothing
+    // uses.
+    let (salt, rest) = match next_block(params, "salt")? {
+        (ASN1Block::OctetString(_, salt), rest) => (salt, rest),
+        _ => return Err(X509DecodeErr::InvalidField("salt").into()),
+    };
+    let (iterations, mut rest) = match next_block(rest, "iterationCount")? {
+        (ASN1Block::Integer(_, n), rest) => (iteration_count(n, "iterationCount")?, rest),
+        _ => return Err(X509DecodeErr::InvalidField("iterationCount").into()),
+    };
+    if let Some((ASN1Block::Integer(_, n), remaining)) = rest.split_first() {
+        if n.to_usize() != Some(key_len) {
+            return Err(X509DecodeErr::InvalidField("keyLength").into());
+        }
+        rest = remaining;
+    }
+    let prf = match rest.split_first() {
+        Some(_) => {
+            let (prf, remaining) = AlgorithmIdentifier::from_asn1(rest)?;
+            expect_end(remaining, "PBKDF2-params")?;
+            prf.algorithm
+        }
+        None => oids::HMAC_WITH_SHA1,
+    };
+
+    let mut key = vec![0; key_len];
+    if prf == oids::HMAC_WITH_SHA1 {
+        pbkdf2_hmac::<Sha1>(password, salt, iterations, &mut key);
+    } else if prf == oids::HMAC_WITH_SHA256 {
+        pbkdf2_hmac::<Sha256>(password, salt, iterations, &mut key);
+    } else if prf == oids::HMAC_WITH_SHA384 {
+        pbkdf2_hmac::<Sha384>(password, salt, iterations, &mut key);
+    } else if prf == oids::HMAC_WITH_SHA512 {
+        pbkdf2_hmac::<Sha512>(password, salt, iterations, &mut key);
+    } else {
+        return Err(DecryptError::UnsupportedAlgorithm(prf));
+    }
+    Ok(key)
+}
+
+/// A key derivation's iteration count `n`, which must be positive and at
+/// most `MAX_ITERATIONS`.
+pub(crate) fn iteration_count(n: &BigInt, field: &'static str) -> Result<u32, DecryptError> {
+    match n.to_u64().filter(|&n| n > 0) {
+        Some(n) if n > MAX_ITERATIONS => Err(DecryptError::TooManyIterations(n)),
+        Some(n) => Ok(n as u32),
+        None => Err(X509DecodeErr::InvalidField(field).into()),
+    }
+}
+
+impl ToASN1 for EncryptedPrivateKeyInfo {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.encryption_algorithm.to_asn1_class(c)?;
+        items.push(ASN1Block::OctetString(0, self.encrypted_data.clone()));
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for EncryptedPrivateKeyInfo {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "EncryptedPrivateKeyInfo")?;
+        let items = sequence(head, "EncryptedPrivateKeyInfo")?;
+        let (encryption_algorithm, rest) = AlgorithmIdentifier::from_asn1(items)?;
+        let (encrypted_data, rest) = match next_block(rest, "encryptedData")? {
+            (ASN1Block::OctetString(_, data), rest) => (data.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("encryptedData")),
+        };
+        expect_end(rest, "EncryptedPrivateKeyInfo")?;
+        Ok((EncryptedPrivateKeyInfo { encryption_algorithm, encrypted_data }, tail))
+    }
+}
+
+#[cfg(test)]
+mod encrypted_private_key_info_tests {
+    use num::bigint::BigInt;
+    use simple_asn1::{der_encode, to_der, ASN1Block};
+
+    use super::{DecryptError, EncryptedPrivateKeyInfo, MAX_ITERATIONS};
+    use der::{der_decode, from_der};
+    use oids;
+    use pkcs8::PrivateKeyInfo;
+
+    const EC_ROOT_KEY: &[u8] = include_bytes!("../../testdata/ec-root-key.der");
+    // EC_ROOT_KEY under PBES2 with PBKDF2-HMAC-SHA256 and AES-256-CBC, as
+    // `openssl pkcs8 -topk8 -v2 aes-256-cbc` writes it, password
+    // "quicklime".
+    const EC_ROOT_KEY_ENCRYPTED: &[u8] = include_bytes!("../../testdata/ec-root-key-encrypted.der");
+
+    #[test]
+    fn encrypted_private_key_info_decrypts() {
+        let encrypted = der_decode::<EncryptedPrivateKeyInfo>(EC_ROOT_KEY_ENCRYPTED).unwrap();
+        assert_eq!(oids::PBES2, encrypted.encryption_algorithm.algorithm);
+        assert_eq!(EC_ROOT_KEY_ENCRYPTED.to_vec(), der_encode(&encrypted).unwrap());
+        assert_eq!(encrypted, EncryptedPrivateKeyInfo::from_pem(&encrypted.to_pem().unwrap()).unwrap());
+
+        let key = encrypted.decrypt(b"quicklime").unwrap();
+        assert_eq!(der_decode::<PrivateKeyInfo>(EC_ROOT_KEY).unwrap(), key);
+        assert_eq!(Err(DecryptError::BadPassword), encrypted.decrypt(b"quicklim3"));
+    }
+
+    #[test]
+    fn encrypted_private_key_info_rejects_unsupported_schemes() {
+        let mut encrypted = der_decode::<EncryptedPrivateKeyInfo>(EC_ROOT_KEY_ENCRYPTED).unwrap();
+        encrypted.encryption_algorithm.algorithm = oids::PBKDF2;
+        assert_eq!(Err(DecryptError::UnsupportedAlgorithm(oids::PBKDF2)), encrypted.decrypt(b"quicklime"));
+    }
+
+    #[test]
+    fn encrypted_private_key_info_limits_iterations() {
+        // Set every INTEGER, of which the iteration count is the only one.
+        fn set_integers(b: &mut ASN1Block, n: u64) {
+            match *b {
+                ASN1Block::Integer(_, ref mut value) => *value = BigInt::from(n),
+                ASN1Block::Sequence(_, ref mut items) => items.iter_mut().for_each(|b| set_integers(b, n)),
+                _ => {}
+            }
+        }
+
+        let mut blocks = from_der(EC_ROOT_KEY_ENCRYPTED).unwrap();
+        set_integers(&mut blocks[0], MAX_ITERATIONS + 1);
+        let encrypted = der_decode::<EncryptedPrivateKeyInfo>(&to_der(&blocks[0]).unwrap()).unwrap();
+        assert_eq!(Err(DecryptError::TooManyIterations(MAX_ITERATIONS + 1)), encrypted.decrypt(b"quicklime"));
+
+        set_integers(&mut blocks[0], u64::MAX);
+        let encrypted = der_decode::<EncryptedPrivateKeyInfo>(&to_der(&blocks[0]).unwrap()).unwrap();
+        assert_eq!(Err(DecryptError::TooManyIterations(u64::MAX)), encrypted.decrypt(b"quicklime"));
+    }
+}
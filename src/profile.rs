@@ -0,0 +1,255 @@
+//! Preset extension sets for common kinds of certificate.
+//!
+//! A profile produces the BasicConstraints, KeyUsage, ExtendedKeyUsage and
+//! key identifier extensions a certificate of its kind needs, and checks
+//! an extension set for combinations that contradict it, such as a TLS
+//! server certificate that may sign other certificates.
+
+use std::error::Error;
+use std::fmt;
+
+use simple_asn1::ASN1EncodeErr;
+
+use error::X509DecodeErr;
+use extensions::{
+    AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, Extensions, KeyIdentifierMethod, KeyUsage,
+    SubjectKeyIdentifier,
+};
+use oids;
+use spki::SubjectPublicKeyInfo;
+
+/// An error that can arise applying a profile.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProfileError {
+    /// The profile requires the named extension.
+    MissingExtension(&'static str),
+    /// The named extension contradicts the profile.
+    Inconsistent(&'static str),
+    /// An extension could not be decoded for checking.
+    Decode(X509DecodeErr),
+    /// An extension could not be encoded.
+    Encode(ASN1EncodeErr),
+}
+
+impl From<X509DecodeErr> for ProfileError {
+    fn from(e: X509DecodeErr) -> ProfileError {
+        ProfileError::Decode(e)
+    }
+}
+
+impl From<ASN1EncodeErr> for ProfileError {
+    fn from(e: ASN1EncodeErr) -> ProfileError {
+        ProfileError::Encode(e)
+    }
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProfileError::MissingExtension(name) => write!(f, "profile requires {}", name),
+            ProfileError::Inconsistent(name) => write!(f, "{} contradicts the profile", name),
+            ProfileError::Decode(ref e) => write!(f, "{}", e),
+            ProfileError::Encode(ref e) => write!(f, "ASN.1 encoding failed: {}", e),
+        }
+    }
+}
+
+impl Error for ProfileError {}
+
+/// A kind of certificate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    /// A TLS server leaf certificate.
+    TlsServer,
+    /// A TLS client leaf certificate.
+    TlsClient,
+    /// A CA certified by another CA, optionally limiting the number of
+    /// CAs that may follow it in a path.
+    IntermediateCa { path_len: Option<u64> },
+    /// A self-signed trust anchor.
+    RootCa,
+}
+
+impl Profile {
+    /// The profile's extensions for a certificate of `subject_key`, issued
+    /// by the CA whose key has identifier `issuer_key_id`. Only a root CA
+    /// may leave out the issuer, as it is its own.
+    pub fn extensions(
+        self,
+        subject_key: &SubjectPublicKeyInfo,
+        issuer_key_id: Option<&SubjectKeyIdentifier>,
+    ) -> Result<Extensions, ProfileError> {
+        let ski = SubjectKeyIdentifier::from_spki(subject_key, KeyIdentifierMethod::Sha1);
+        let aki = match (self, issuer_key_id) {
+            (_, Some(issuer)) => AuthorityKeyIdentifier::from_key_identifier(issuer),
+            (Profile::RootCa, None) => AuthorityKeyIdentifier::from_key_identifier(&ski),
+            (_, None) => return Err(ProfileError::MissingExtension("authorityKeyIdentifier")),
+        };
+
+        let mut extensions = Extensions::new();
+        match self {
+            Profile::TlsServer | Profile::TlsClient => {
+                let mut usage = KeyUsage::DIGITAL_SIGNATURE;
+                // TLS 1.2 RSA key exchange encrypts to the server's key.
+                if self == Profile::TlsServer && subject_key.algorithm.algorithm == oids::RSA_ENCRYPTION {
+                    usage = usage | KeyUsage::KEY_ENCIPHERMENT;
+                }
+                let purpose = match self {
+                    Profile::TlsServer => ExtendedKeyUsage::SERVER_AUTH,
+                    _ => ExtendedKeyUsage::CLIENT_AUTH,
+                };
+                extensions.insert(&BasicConstraints::end_entity(), true)?;
+                extensions.insert(&usage, true)?;
+                extensions.insert(&ExtendedKeyUsage::new(vec![purpose]), false)?;
+            }
+            Profile::IntermediateCa { path_len } => {
+                extensions.insert(&BasicConstraints::ca(path_len), true)?;
+                extensions.insert(&(KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN), true)?;
+            }
+            Profile::RootCa => {
+                extensions.insert(&BasicConstraints::ca(None), true)?;
+                extensions.insert(&(KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN), true)?;
+            }
+        }
+        extensions.insert(&ski, false)?;
+        extensions.insert(&aki, false)?;
+        self.check(&extensions)?;
+        Ok(extensions)
+    }
+
+    /// Check `extensions`, such as a profile's set after customisation,
+    /// against the profile.
+    pub fn check(self, extensions: &Extensions) -> Result<(), ProfileError> {
+        let constraints = extensions
+            .get::<BasicConstraints>()?
+            .ok_or(ProfileError::MissingExtension("basicConstraints"))?;
+        let usage = extensions.get::<KeyUsage>()?.ok_or(ProfileError::MissingExtension("keyUsage"))?;
+        let eku = extensions.get::<ExtendedKeyUsage>()?;
+        if extensions.get::<SubjectKeyIdentifier>()?.is_none() {
+            return Err(ProfileError::MissingExtension("subjectKeyIdentifier"));
+        }
+        if extensions.get::<AuthorityKeyIdentifier>()?.is_none() {
+            return Err(ProfileError::MissingExtension("authorityKeyIdentifier"));
+        }
+
+        let cert_sign = KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN;
+        let purpose = match self {
+            Profile::TlsServer => Some(ExtendedKeyUsage::SERVER_AUTH),
+            Profile::TlsClient => Some(ExtendedKeyUsage::CLIENT_AUTH),
+            Profile::IntermediateCa { .. } | Profile::RootCa => None,
+        };
+        match purpose {
+            Some(purpose) => {
+                if constraints.ca {
+                    return Err(ProfileError::Inconsistent("basicConstraints"));
+                }
+                if !usage.digital_signature() || usage.key_cert_sign() || usage.crl_sign() {
+                    return Err(ProfileError::Inconsistent("keyUsage"));
+                }
+                match eku {
+                    Some(ref eku) if eku.permits(&purpose) => {}
+                    Some(_) => return Err(ProfileError::Inconsistent("extKeyUsage")),
+                    None => return Err(ProfileError::MissingExtension("extKeyUsage")),
+                }
+            }
+            None => {
+                if !constraints.ca {
+                    return Err(ProfileError::Inconsistent("basicConstraints"));
+                }
+                if !usage.contains(cert_sign) {
+                    return Err(ProfileError::Inconsistent("keyUsage"));
+                }
+            }
+        }
+        if let Profile::IntermediateCa { path_len } = self {
+            if constraints.path_len_constraint != path_len {
+                return Err(ProfileError::Inconsistent("basicConstraints"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod profile_tests {
+    use super::{Profile, ProfileError};
+    use der::der_decode;
+    use extensions::{AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectKeyIdentifier};
+    use spki::SubjectPublicKeyInfo;
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../testdata/ec-leaf.der");
+    const RSA_SPKI: &[u8] = include_bytes!("../testdata/rsa2048-spki.der");
+
+    fn root() -> Certificate {
+        der_decode::<Certificate>(EC_ROOT).unwrap()
+    }
+
+    fn root_key_id() -> SubjectKeyIdentifier {
+        root().extension::<SubjectKeyIdentifier>().unwrap().unwrap()
+    }
+
+    #[test]
+    fn profile_tls_server_matches_leaf() {
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let extensions = Profile::TlsServer
+            .extensions(&leaf.tbs_certificate.subject_public_key_info, Some(&root_key_id()))
+            .unwrap();
+        for extn_id in &[oid!(2, 5, 29, 19), oid!(2, 5, 29, 15), oid!(2, 5, 29, 14), oid!(2, 5, 29, 35)] {
+            let leaf_extensions = leaf.tbs_certificate.extensions.as_ref().unwrap();
+            assert_eq!(leaf_extensions.find(extn_id), extensions.find(extn_id));
+        }
+        assert!(extensions.get::<ExtendedKeyUsage>().unwrap().unwrap().server_auth());
+    }
+
+    #[test]
+    fn profile_tls_server_rsa_allows_key_encipherment() {
+        let spki = der_decode::<SubjectPublicKeyInfo>(RSA_SPKI).unwrap();
+        let extensions = Profile::TlsServer.extensions(&spki, Some(&root_key_id())).unwrap();
+        assert!(extensions.get::<KeyUsage>().unwrap().unwrap().key_encipherment());
+        let extensions = Profile::TlsClient.extensions(&spki, Some(&root_key_id())).unwrap();
+        assert!(!extensions.get::<KeyUsage>().unwrap().unwrap().key_encipherment());
+    }
+
+    #[test]
+    fn profile_root_ca_matches_root() {
+        let root = root();
+        let extensions = Profile::RootCa.extensions(&root.tbs_certificate.subject_public_key_info, None).unwrap();
+        assert_eq!(Some(BasicConstraints::ca(None)), extensions.get().unwrap());
+        let aki = extensions.get::<AuthorityKeyIdentifier>().unwrap().unwrap();
+        assert_eq!(Some(root_key_id().0), aki.key_identifier);
+    }
+
+    #[test]
+    fn profile_requires_issuer_below_root() {
+        let spki = &root().tbs_certificate.subject_public_key_info;
+        assert_eq!(
+            Err(ProfileError::MissingExtension("authorityKeyIdentifier")),
+            Profile::IntermediateCa { path_len: Some(0) }.extensions(spki, None)
+        );
+    }
+
+    #[test]
+    fn profile_rejects_nonsensical_combinations() {
+        let spki = &root().tbs_certificate.subject_public_key_info;
+        let issuer = root_key_id();
+
+        let mut extensions = Profile::TlsServer.extensions(spki, Some(&issuer)).unwrap();
+        extensions.insert(&(KeyUsage::DIGITAL_SIGNATURE | KeyUsage::KEY_CERT_SIGN), true).unwrap();
+        assert_eq!(Err(ProfileError::Inconsistent("keyUsage")), Profile::TlsServer.check(&extensions));
+
+        let extensions = Profile::TlsClient.extensions(spki, Some(&issuer)).unwrap();
+        assert_eq!(Err(ProfileError::Inconsistent("extKeyUsage")), Profile::TlsServer.check(&extensions));
+
+        let mut extensions = Profile::IntermediateCa { path_len: Some(0) }.extensions(spki, Some(&issuer)).unwrap();
+        extensions.insert(&BasicConstraints::end_entity(), true).unwrap();
+        assert_eq!(
+            Err(ProfileError::Inconsistent("basicConstraints")),
+            Profile::IntermediateCa { path_len: Some(0) }.check(&extensions)
+        );
+
+        let extensions = Profile::RootCa.extensions(spki, None).unwrap();
+        assert_eq!(Err(ProfileError::Inconsistent("basicConstraints")), Profile::TlsClient.check(&extensions));
+    }
+}
@@ -0,0 +1,779 @@
+//! Revocation checking against whichever OCSP responders and CRLs an
+//! application has.
+//!
+//! `RevocationChecker` asks its OCSP sources first and falls back to its
+//! CRL sources, checking every response and CRL before it is believed.
+//! Fetching them, usually over HTTP from the URLs in the certificate, is
+//! left to the sources, and keeping them between checks to a
+//! `RevocationCache`.
+
+use std::error::Error;
+use std::fmt;
+
+use time::PrimitiveDateTime;
+
+use crl::CertificateList;
+use error::X509DecodeErr;
+use extensions::{BasicConstraints, CRLDistributionPoints, Extensions, IssuingDistributionPoint, KeyUsage};
+use general_name::GeneralName;
+use ocsp::{BasicOCSPResponse, CertID, CertIDHash, CertStatus, OCSPError, OCSPRequest, OCSPResponse, RevokedInfo};
+#[cfg(feature = "verify")]
+use verify::RustCrypto;
+use oid::ObjectIdentifier;
+use oids;
+use verify::{SignatureVerifier, VerifyError};
+use x509::Certificate;
+
+// The CRL extensions the checker can process when critical. A delta CRL
+// indicator is refused before these are looked at.
+const HANDLED_CRL_EXTENSIONS: &[ObjectIdentifier] = &[
+    oids::AUTHORITY_KEY_IDENTIFIER,
+    oids::ISSUER_ALT_NAME,
+    oids::CRL_NUMBER,
+    oids::ISSUING_DISTRIBUTION_POINT,
+];
+
+// The CRL entry extensions the checker can process when critical.
+const HANDLED_CRL_ENTRY_EXTENSIONS: &[ObjectIdentifier] =
+    &[oids::CRL_REASON, oids::INVALIDITY_DATE, oids::CERTIFICATE_ISSUER];
+
+/// Where OCSP responses come from, usually an HTTP POST of the request to
+/// one of the certificate's `ocsp_urls`.
+pub trait OCSPSource {
+    /// The response to `request`, which asks about `cert`, or None if there
+    /// was none to be had.
+    fn fetch(&self, cert: &Certificate, request: &OCSPRequest) -> Option<OCSPResponse>;
+}
+
+impl<F: Fn(&Certificate, &OCSPRequest) -> Option<OCSPResponse>> OCSPSource for F {
+    fn fetch(&self, cert: &Certificate, request: &OCSPRequest) -> Option<OCSPResponse> {
+        self(cert, request)
+    }
+}
+
+/// Where CRLs come from, usually a download from the certificate's
+/// cRLDistributionPoints or a local store.
+pub trait CRLSource {
+    /// The CRLs that may cover `cert`, which `issuer` issued.
+    fn fetch(&self, cert: &Certificate, issuer: &Certificate) -> Vec<CertificateList>;
+}
+
+impl<F: Fn(&Certificate, &Certificate) -> Vec<CertificateList>> CRLSource for F {
+    fn fetch(&self, cert: &Certificate, issuer: &Certificate) -> Vec<CertificateList> {
+        self(cert, issuer)
+    }
+}
+
+/// Somewhere to keep CRLs and OCSP responses between checks, such as an
+/// in-memory map or a directory on disk.
+///
+/// Only what passed its checks is stored, and whatever comes back is
+/// checked again before use, so a stale entry is simply passed over for
+/// the sources.
+pub trait RevocationCache {
+    /// A CRL that `issuer` issued.
+    fn crl(&self, issuer: &Certificate) -> Option<CertificateList>;
+    fn store_crl(&self, issuer: &Certificate, crl: &CertificateList);
+    /// A response with the status of the certificate `cert_id` identifies.
+    fn ocsp_response(&self, cert_id: &CertID) -> Option<BasicOCSPResponse>;
+    fn store_ocsp_response(&self, cert_id: &CertID, response: &BasicOCSPResponse);
+}
+
+/// What to do when no source can give a certificate's status.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RevocationPolicy {
+    /// Fail the check.
+    #[default]
+    HardFail,
+    /// Give the status as `CertStatus::Unknown`, for the caller to accept.
+    SoftFail,
+}
+
+/// Why one source could not give a certificate's status.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SourceFailure {
+    /// An OCSP source had no response.
+    NoOCSPResponse,
+    /// An OCSP response failed its checks.
+    OCSP(OCSPError),
+    /// The responder does not know the certificate.
+    UnknownToResponder,
+    /// A CRL source had no CRLs.
+    NoCRL,
+    /// A CRL's signature did not verify with the issuer's key.
+    CRLSignature(VerifyError),
+    /// A CRL is not current at the time checked at.
+    CRLNotCurrent,
+    /// A CRL is from another issuer, or its scope leaves the certificate
+    /// out.
+    CRLScope,
+    /// The issuer's key usage doesn't allow it to sign CRLs.
+    CRLSignNotAllowed,
+    /// A CRL is a delta CRL, which only lists changes since a base CRL.
+    DeltaCRL,
+    /// A CRL, or one of its entries, has a critical extension the checker
+    /// can't process, so the CRL can't be used (RFC 5280 §5.2, §5.3).
+    CRLUnhandledCriticalExtension(ObjectIdentifier),
+    /// A CRL, or an extension of the certificate's, could not be decoded.
+    Decode(X509DecodeErr),
+}
+
+impl fmt::Display for SourceFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SourceFailure::NoOCSPResponse => write!(f, "no OCSP response"),
+            SourceFailure::OCSP(ref e) => write!(f, "{}", e),
+            SourceFailure::UnknownToResponder => write!(f, "OCSP responder does not know the certificate"),
+            SourceFailure::NoCRL => write!(f, "no CRL"),
+            SourceFailure::CRLSignature(ref e) => write!(f, "CRL signature check failed: {}", e),
+            SourceFailure::CRLNotCurrent => write!(f, "CRL is not current"),
+            SourceFailure::CRLScope => write!(f, "CRL does not cover the certificate"),
+            SourceFailure::CRLSignNotAllowed => write!(f, "CRL issuer may not sign CRLs"),
+            SourceFailure::DeltaCRL => write!(f, "delta CRLs are not supported"),
+            SourceFailure::CRLUnhandledCriticalExtension(ref oid) => {
+                write!(f, "CRL has an unhandled critical extension: {}", oid)
+            }
+            SourceFailure::Decode(ref e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Why a certificate's status could not be established: what went wrong
+/// with each source, in the order they were tried. It is empty if there
+/// are no sources.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RevocationError {
+    pub failures: Vec<SourceFailure>,
+}
+
+impl fmt::Display for RevocationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "revocation status unavailable")?;
+        for (i, failure) in self.failures.iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { ": " } else { "; " }, failure)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for RevocationError {}
+
+/// Establishes whether certificates are revoked.
+///
+/// OCSP sources are tried in order, then CRL sources, and the first good
+/// or revoked status found is the answer. A response must be signed by
+/// the certificate's issuer, by a responder the issuer authorized, or by
+/// one of `trusted_responders`, which the relying party trusts for every
+/// issuer (RFC 6960 §4.2.2.2). CRLs must be complete, direct CRLs signed
+/// by the issuer whose scope covers the certificate, with a key usage
+/// that allows signing CRLs if the issuer has one. Delta CRLs, and CRLs
+/// with critical extensions the checker can't process, are refused.
+#[derive(Clone)]
+pub struct RevocationChecker<'a> {
+    /// The time the status must be current at.
+    pub time: PrimitiveDateTime,
+    pub ocsp_sources: Vec<&'a dyn OCSPSource>,
+    pub crl_sources: Vec<&'a dyn CRLSource>,
+    pub trusted_responders: Vec<Certificate>,
+    pub cache: Option<&'a dyn RevocationCache>,
+    pub policy: RevocationPolicy,
+}
+
+impl<'a> RevocationChecker<'a> {
+    /// A hard-failing checker for statuses current at `time`, with no
+    /// sources yet.
+    pub fn new(time: PrimitiveDateTime) -> RevocationChecker<'a> {
+        RevocationChecker {
+            time,
+            ocsp_sources: Vec::new(),
+            crl_sources: Vec::new(),
+            trusted_responders: Vec::new(),
+            cache: None,
+            policy: RevocationPolicy::default(),
+        }
+    }
+
+    /// The status of `cert`, which `issuer` issued, using the RustCrypto
+    /// backend.
+    #[cfg(feature = "verify")]
+    pub fn check(&self, cert: &Certificate, issuer: &Certificate) -> Result<CertStatus, RevocationError> {
+        self.check_with(cert, issuer, &RustCrypto)
+    }
+
+    /// The status of `cert`, which `issuer` issued, checking signatures
+    /// with the given backend. Under `RevocationPolicy::SoftFail` the
+    /// status is `Unknown` rather than an error when no source gives one.
+    pub fn check_with<V: SignatureVerifier + ?Sized>(
+        &self,
+        cert: &Certificate,
+        issuer: &Certificate,
+        verifier: &V,
+    ) -> Result<CertStatus, RevocationError> {
+        let mut failures = Vec::new();
+        let status = match self.ocsp_status(cert, issuer, verifier, &mut failures) {
+            Some(status) => Some(status),
+            None => self.crl_status(cert, issuer, verifier, &mut failures),
+        };
+        match status {
+            Some(status) => Ok(status),
+            None if self.policy == RevocationPolicy::SoftFail => Ok(CertStatus::Unknown),
+            None => Err(RevocationError { failures }),
+        }
+    }
+
+    /// The status of each certificate in `chain` after the first, using
+    /// the RustCrypto backend.
+    #[cfg(feature = "verify")]
+    pub fn check_chain(&self, chain: &[Certificate]) -> Vec<Result<CertStatus, RevocationError>> {
+        self.check_chain_with(chain, &RustCrypto)
+    }
+
+    /// The status of each certificate in `chain` after the first, where
+    /// each certificate issued the one after it. A path `PathValidator`
+    /// checks starts with the certificate the anchor issued, so the root
+    /// certificate goes in front of it here.
+    pub fn check_chain_with<V: SignatureVerifier + ?Sized>(
+        &self,
+        chain: &[Certificate],
+        verifier: &V,
+    ) -> Vec<Result<CertStatus, RevocationError>> {
+        chain.windows(2).map(|pair| self.check_with(&pair[1], &pair[0], verifier)).collect()
+    }
+
+    fn ocsp_status<V: SignatureVerifier + ?Sized>(
+        &self,
+        cert: &Certificate,
+        issuer: &Certificate,
+        verifier: &V,
+        failures: &mut Vec<SourceFailure>,
+    ) -> Option<CertStatus> {
+        if self.ocsp_sources.is_empty() {
+            return None;
+        }
+        // Only encoding the issuer's name can fail, and it was decoded.
+        let cert_id = CertID::for_certificate(CertIDHash::Sha1, cert, issuer).ok()?;
+        if let Some(cached) = self.cache.and_then(|cache| cache.ocsp_response(&cert_id)) {
+            match self.ocsp_response_status(&cached, cert, issuer, verifier) {
+                Ok(CertStatus::Unknown) | Err(_) => {}
+                Ok(status) => return Some(status),
+            }
+        }
+        let request = OCSPRequest::new(vec![cert_id.clone()]);
+        for source in &self.ocsp_sources {
+            let response = match source.fetch(cert, &request) {
+                Some(response) => response,
+                None => {
+                    failures.push(SourceFailure::NoOCSPResponse);
+                    continue;
+                }
+            };
+            let checked = response.basic_response().and_then(|basic| {
+                let status = self.ocsp_response_status(&basic, cert, issuer, verifier)?;
+                Ok((basic, status))
+            });
+            match checked {
+                Ok((_, CertStatus::Unknown)) => failures.push(SourceFailure::UnknownToResponder),
+                Ok((basic, status)) => {
+                    if let Some(cache) = self.cache {
+                        cache.store_ocsp_response(&cert_id, &basic);
+                    }
+                    return Some(status);
+                }
+                Err(e) => failures.push(SourceFailure::OCSP(e)),
+            }
+        }
+        None
+    }
+
+    fn ocsp_response_status<V: SignatureVerifier + ?Sized>(
+        &self,
+        basic: &BasicOCSPResponse,
+        cert: &Certificate,
+        issuer: &Certificate,
+        verifier: &V,
+    ) -> Result<CertStatus, OCSPError> {
+        let responder_id = &basic.tbs_response_data.responder_id;
+        let single = match self.trusted_responders.iter().find(|responder| responder_id.matches(responder)) {
+            Some(responder) => {
+                basic.verify_signature_with(&responder.tbs_certificate.subject_public_key_info, verifier)?;
+                let single = basic.response_for(cert, issuer).ok_or(OCSPError::NoStatus)?;
+                if !single.is_current_at(self.time) {
+                    return Err(OCSPError::NotCurrent);
+                }
+                single
+            }
+            None => basic.validate_with(cert, issuer, self.time, verifier)?,
+        };
+        Ok(single.cert_status)
+    }
+
+    fn crl_status<V: SignatureVerifier + ?Sized>(
+        &self,
+        cert: &Certificate,
+        issuer: &Certificate,
+        verifier: &V,
+        failures: &mut Vec<SourceFailure>,
+    ) -> Option<CertStatus> {
+        if self.crl_sources.is_empty() {
+            return None;
+        }
+        let is_ca = match cert.extension::<BasicConstraints>() {
+            Ok(bc) => bc.is_some_and(|bc| bc.ca),
+            Err(e) => {
+                failures.push(SourceFailure::Decode(e));
+                return None;
+            }
+        };
+        if let Some(cached) = self.cache.and_then(|cache| cache.crl(issuer)) {
+            if let Ok(status) = self.crl_entry_status(&cached, cert, issuer, is_ca, verifier) {
+                return Some(status);
+            }
+        }
+        for source in &self.crl_sources {
+            let crls = source.fetch(cert, issuer);
+            if crls.is_empty() {
+                failures.push(SourceFailure::NoCRL);
+            }
+            for crl in crls {
+                match self.crl_entry_status(&crl, cert, issuer, is_ca, verifier) {
+                    Ok(status) => {
+                        if let Some(cache) = self.cache {
+                            cache.store_crl(issuer, &crl);
+                        }
+                        return Some(status);
+                    }
+                    Err(failure) => failures.push(failure),
+                }
+            }
+        }
+        None
+    }
+
+    fn crl_entry_status<V: SignatureVerifier + ?Sized>(
+        &self,
+        crl: &CertificateList,
+        cert: &Certificate,
+        issuer: &Certificate,
+        is_ca: bool,
+        verifier: &V,
+    ) -> Result<CertStatus, SourceFailure> {
+        let issuer = &issuer.tbs_certificate;
+        if !crl.tbs_cert_list.issuer.matches(&issuer.subject) {
+            return Err(SourceFailure::CRLScope);
+        }
+        let key_usage = match issuer.extensions {
+            Some(ref extensions) => extensions.get::<KeyUsage>().map_err(SourceFailure::Decode)?,
+            None => None,
+        };
+        if key_usage.is_some_and(|usage| !usage.crl_sign()) {
+            return Err(SourceFailure::CRLSignNotAllowed);
+        }
+        crl.verify_signed_by_with(&issuer.subject_public_key_info, verifier).map_err(SourceFailure::CRLSignature)?;
+        if !crl.is_current_at(self.time) {
+            return Err(SourceFailure::CRLNotCurrent);
+        }
+        let tbs = &crl.tbs_cert_list;
+        if let Some(ref extensions) = tbs.crl_extensions {
+            if extensions.find(&oids::DELTA_CRL_INDICATOR).is_some() {
+                return Err(SourceFailure::DeltaCRL);
+            }
+            check_critical(extensions, HANDLED_CRL_EXTENSIONS)?;
+        }
+        for entry in &tbs.revoked_certificates {
+            if let Some(ref extensions) = entry.crl_entry_extensions {
+                check_critical(extensions, HANDLED_CRL_ENTRY_EXTENSIONS)?;
+            }
+        }
+        // A CRL for only some reasons can't show that a certificate is not
+        // revoked, and one for a distribution point covers only the
+        // certificates that name it (RFC 5280 §6.3.3(b)(2)(i)).
+        if let Some(idp) = crl.extension::<IssuingDistributionPoint>().map_err(SourceFailure::Decode)? {
+            if !idp.covers_certificate(is_ca) || idp.only_some_reasons.is_some() {
+                return Err(SourceFailure::CRLScope);
+            }
+            if let Some(ref name) = idp.distribution_point {
+                if !names_distribution_point(cert, &name.full_names(&tbs.issuer))? {
+                    return Err(SourceFailure::CRLScope);
+                }
+            }
+        }
+        let serial_number = &cert.tbs_certificate.serial_number;
+        let entry = match crl.find_revoked_issued_by(&issuer.subject, serial_number).map_err(SourceFailure::Decode)? {
+            Some(entry) => entry,
+            None => return Ok(CertStatus::Good),
+        };
+        Ok(CertStatus::Revoked(RevokedInfo {
+            revocation_time: entry.revocation_date.date_time(),
+            revocation_reason: entry.reason().map_err(SourceFailure::Decode)?,
+        }))
+    }
+}
+
+// Whether one of `cert`'s cRLDistributionPoints goes by one of `names`.
+fn names_distribution_point(cert: &Certificate, names: &[GeneralName]) -> Result<bool, SourceFailure> {
+    let points = match cert.extension::<CRLDistributionPoints>().map_err(SourceFailure::Decode)? {
+        Some(points) => points,
+        None => return Ok(false),
+    };
+    let issuer = &cert.tbs_certificate.issuer;
+    Ok(points.iter().any(|point| point.names(issuer).iter().any(|name| names.contains(name))))
+}
+
+// Fail on the first critical extension in `extensions` not in `handled`.
+fn check_critical(extensions: &Extensions, handled: &[ObjectIdentifier]) -> Result<(), SourceFailure> {
+    match extensions.iter().find(|e| e.critical && !handled.contains(&e.extn_id)) {
+        Some(e) => Err(SourceFailure::CRLUnhandledCriticalExtension(e.extn_id.clone())),
+        None => Ok(()),
+    }
+}
+
+#[cfg(all(test, feature = "verify"))]
+mod revocation_tests {
+    use std::cell::{Cell, RefCell};
+
+    use num::bigint::BigInt;
+    use p256::ecdsa::SigningKey;
+    use time::{Date, Duration, Month, PrimitiveDateTime, Time};
+
+    use super::{RevocationCache, RevocationChecker, RevocationError, RevocationPolicy, SourceFailure};
+    use crl::{CertificateList, TBSCertList};
+    use der::der_decode;
+    use extensions::{CRLReason, DistributionPoint, Extension, Extensions, IssuingDistributionPoint, KeyUsage};
+    use name::{AttributeValue, DirectoryString};
+    use ocsp::{BasicOCSPResponse, CertID, CertStatus, OCSPError, OCSPRequest, OCSPResponse, Responder, RevokedInfo};
+    use oids;
+    use x509::{Certificate, CertificateSerialNumber};
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../testdata/ec-leaf.der");
+    const EC_ROOT_CRL: &[u8] = include_bytes!("../testdata/ec-root-crl.der");
+    // The private keys of EC_ROOT and EC_LEAF.
+    const EC_ROOT_KEY: [u8; 32] = [
+        0xBE, 0x92, 0x7C, 0xE1, 0x0C, 0x45, 0x2F, 0x7E, 0x07, 0xF0, 0x8C, 0xEC, 0x0A, 0x8C, 0x74, 0xDB, 0xFE, 0x33,
+        0x74, 0x4C, 0x7F, 0x09, 0x1B, 0xAF, 0x55, 0x0B, 0x5A, 0x52, 0x6F, 0xD3, 0x2D, 0x60,
+    ];
+    const EC_LEAF_KEY: [u8; 32] = [
+        0x51, 0x37, 0xB1, 0xAF, 0xBC, 0x7E, 0xEA, 0x34, 0x16, 0x50, 0x4E, 0x9E, 0xC5, 0xE5, 0xC3, 0xD9, 0x67, 0x37,
+        0x8F, 0x24, 0x01, 0xA4, 0x12, 0x56, 0x36, 0x25, 0x88, 0xCB, 0xC7, 0xFD, 0x3B, 0x4B,
+    ];
+
+    fn now() -> PrimitiveDateTime {
+        let date = Date::from_calendar_date(2026, Month::October, 15).unwrap();
+        PrimitiveDateTime::new(date, Time::MIDNIGHT)
+    }
+
+    fn root() -> Certificate {
+        der_decode(EC_ROOT).unwrap()
+    }
+
+    fn leaf() -> Certificate {
+        der_decode(EC_LEAF).unwrap()
+    }
+
+    // EC_LEAF with a serial number the CRL doesn't list.
+    fn good_leaf() -> Certificate {
+        let mut leaf = leaf();
+        leaf.tbs_certificate.serial_number = CertificateSerialNumber(BigInt::from(0x1002));
+        leaf
+    }
+
+    fn crls(_: &Certificate, _: &Certificate) -> Vec<CertificateList> {
+        vec![der_decode(EC_ROOT_CRL).unwrap()]
+    }
+
+    // EC_ROOT_CRL, changed by `change` and signed again.
+    fn root_crl<F: FnOnce(&mut TBSCertList)>(change: F) -> CertificateList {
+        let mut tbs = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap().tbs_cert_list;
+        change(&mut tbs);
+        tbs.sign(&SigningKey::from_bytes(&EC_ROOT_KEY.into()).unwrap()).unwrap()
+    }
+
+    // An extension no checker knows, with an INTEGER value.
+    fn private_extension(critical: bool) -> Extension {
+        Extension::raw(oid!(1, 3, 6, 1, 4, 1, 99999, 9), critical, vec![0x02, 0x01, 0x01])
+    }
+
+    // The status of `cert`, which `issuer` issued, from `crl` alone.
+    fn crl_check(
+        crl: CertificateList,
+        cert: &Certificate,
+        issuer: &Certificate,
+    ) -> Result<CertStatus, RevocationError> {
+        let source = move |_: &Certificate, _: &Certificate| vec![crl.clone()];
+        let checker = RevocationChecker { crl_sources: vec![&source], ..RevocationChecker::new(now()) };
+        checker.check(cert, issuer)
+    }
+
+    fn crl_failure(failure: SourceFailure) -> Result<CertStatus, RevocationError> {
+        Err(RevocationError { failures: vec![failure] })
+    }
+
+    fn no_crls(_: &Certificate, _: &Certificate) -> Vec<CertificateList> {
+        Vec::new()
+    }
+
+    fn no_response(_: &Certificate, _: &OCSPRequest) -> Option<OCSPResponse> {
+        None
+    }
+
+    // A response to `request` from `responder`, signed with `key`, giving
+    // every certificate `status`.
+    fn respond(responder: &Responder, key: &[u8; 32], request: &OCSPRequest, status: CertStatus) -> OCSPResponse {
+        let key = SigningKey::from_bytes(key.into()).unwrap();
+        responder.respond(request, now(), |_| status, &key).unwrap()
+    }
+
+    fn root_responder() -> Responder {
+        Responder::new(&root())
+    }
+
+    fn good_response(_: &Certificate, request: &OCSPRequest) -> Option<OCSPResponse> {
+        Some(respond(&root_responder(), &EC_ROOT_KEY, request, CertStatus::Good))
+    }
+
+    #[test]
+    fn revocation_checker_reads_crls() {
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        let entry = crl.find_revoked(&leaf().tbs_certificate.serial_number).unwrap();
+        let revocation_time = entry.revocation_date.date_time();
+        let mut checker = RevocationChecker::new(now());
+        checker.crl_sources.push(&crls);
+        assert_eq!(
+            Ok(CertStatus::Revoked(RevokedInfo { revocation_time, revocation_reason: Some(CRLReason::KeyCompromise) })),
+            checker.check(&leaf(), &root())
+        );
+        assert_eq!(Ok(CertStatus::Good), checker.check(&good_leaf(), &root()));
+
+        checker.time = now() + Duration::days(60);
+        assert_eq!(
+            Err(RevocationError { failures: vec![SourceFailure::CRLNotCurrent] }),
+            checker.check(&good_leaf(), &root())
+        );
+        // Issued by the leaf, which didn't sign the CRL.
+        assert_eq!(
+            Err(RevocationError { failures: vec![SourceFailure::CRLScope] }),
+            RevocationChecker { crl_sources: vec![&crls], ..RevocationChecker::new(now()) }.check(&root(), &leaf())
+        );
+    }
+
+    #[test]
+    fn revocation_checker_matches_crl_issuer_across_string_types() {
+        let crl = root_crl(|tbs| {
+            for attribute in tbs.issuer.0.iter_mut().flat_map(|rdn| rdn.0.iter_mut()) {
+                if let AttributeValue::String(DirectoryString::PrintableString(ref s)) = attribute.value {
+                    attribute.value = AttributeValue::String(DirectoryString::UTF8String(s.clone()));
+                }
+            }
+        });
+        assert_ne!(root().tbs_certificate.subject, crl.tbs_cert_list.issuer);
+        assert!(matches!(crl_check(crl, &leaf(), &root()), Ok(CertStatus::Revoked(_))));
+    }
+
+    #[test]
+    fn revocation_checker_refuses_delta_crls() {
+        let delta = root_crl(|tbs| {
+            let mut indicator = private_extension(true);
+            indicator.extn_id = oids::DELTA_CRL_INDICATOR;
+            tbs.crl_extensions.get_or_insert_with(Extensions::new).0.push(indicator);
+        });
+        assert_eq!(crl_failure(SourceFailure::DeltaCRL), crl_check(delta, &good_leaf(), &root()));
+    }
+
+    #[test]
+    fn revocation_checker_refuses_unhandled_critical_crl_extensions() {
+        let private = private_extension(true).extn_id;
+        let with_private = |critical| {
+            root_crl(|tbs| tbs.crl_extensions.get_or_insert_with(Extensions::new).0.push(private_extension(critical)))
+        };
+        let critical = with_private(true);
+        assert_eq!(
+            crl_failure(SourceFailure::CRLUnhandledCriticalExtension(private.clone())),
+            crl_check(critical, &good_leaf(), &root())
+        );
+        assert_eq!(Ok(CertStatus::Good), crl_check(with_private(false), &good_leaf(), &root()));
+
+        // Even on an entry for another certificate.
+        let entry = root_crl(|tbs| {
+            let extensions = tbs.revoked_certificates[0].crl_entry_extensions.get_or_insert_with(Extensions::new);
+            extensions.0.push(private_extension(true));
+        });
+        assert_eq!(
+            crl_failure(SourceFailure::CRLUnhandledCriticalExtension(private)),
+            crl_check(entry, &good_leaf(), &root())
+        );
+    }
+
+    #[test]
+    fn revocation_checker_matches_crl_distribution_points() {
+        // The leaf names http://crl.example.com/root.crl as its distribution
+        // point.
+        let partition = |uri: &str| {
+            let idp = IssuingDistributionPoint {
+                distribution_point: Some(DistributionPoint::uri(uri).distribution_point.unwrap()),
+                ..Default::default()
+            };
+            root_crl(|tbs| tbs.crl_extensions.get_or_insert_with(Extensions::new).insert(&idp, true).unwrap())
+        };
+        let other = partition("http://crl.example.com/root-2.crl");
+        assert_eq!(crl_failure(SourceFailure::CRLScope), crl_check(other.clone(), &leaf(), &root()));
+        assert_eq!(crl_failure(SourceFailure::CRLScope), crl_check(other, &good_leaf(), &root()));
+        let own = partition("http://crl.example.com/root.crl");
+        assert!(matches!(crl_check(own.clone(), &leaf(), &root()), Ok(CertStatus::Revoked(_))));
+
+        // A certificate naming no distribution point is covered by no
+        // partition.
+        let mut leaf = leaf();
+        leaf.tbs_certificate.extensions.as_mut().unwrap().0.retain(|e| e.extn_id != oids::CRL_DISTRIBUTION_POINTS);
+        assert_eq!(crl_failure(SourceFailure::CRLScope), crl_check(own, &leaf, &root()));
+    }
+
+    #[test]
+    fn revocation_checker_requires_crl_sign() {
+        let mut root = root();
+        let extensions = root.tbs_certificate.extensions.get_or_insert_with(Extensions::new);
+        extensions.insert(&KeyUsage::KEY_CERT_SIGN, true).unwrap();
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        assert_eq!(crl_failure(SourceFailure::CRLSignNotAllowed), crl_check(crl.clone(), &good_leaf(), &root));
+
+        let extensions = root.tbs_certificate.extensions.as_mut().unwrap();
+        extensions.insert(&(KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN), true).unwrap();
+        assert_eq!(Ok(CertStatus::Good), crl_check(crl, &good_leaf(), &root));
+    }
+
+    #[test]
+    fn revocation_checker_keeps_removed_from_crl_revoked() {
+        let crl = root_crl(|tbs| {
+            let serial_number = &leaf().tbs_certificate.serial_number;
+            let entry = tbs.revoked_certificates.iter_mut().find(|e| e.user_certificate == *serial_number).unwrap();
+            let extensions = entry.crl_entry_extensions.get_or_insert_with(Extensions::new);
+            extensions.insert(&CRLReason::RemoveFromCRL, false).unwrap();
+        });
+        match crl_check(crl, &leaf(), &root()) {
+            Ok(CertStatus::Revoked(info)) => assert_eq!(Some(CRLReason::RemoveFromCRL), info.revocation_reason),
+            status => panic!("Not revoked: {:?}", status),
+        }
+    }
+
+    #[test]
+    fn revocation_checker_prefers_ocsp() {
+        let mut checker = RevocationChecker::new(now());
+        checker.ocsp_sources.push(&good_response);
+        checker.crl_sources.push(&crls);
+        // The CRL says otherwise, but isn't consulted.
+        assert_eq!(Ok(CertStatus::Good), checker.check(&leaf(), &root()));
+
+        // A responder that doesn't answer leaves it to the CRL.
+        checker.ocsp_sources = vec![&no_response];
+        assert!(matches!(checker.check(&leaf(), &root()), Ok(CertStatus::Revoked(_))));
+    }
+
+    #[test]
+    fn revocation_checker_soft_and_hard_fails() {
+        let mut checker = RevocationChecker::new(now());
+        checker.ocsp_sources.push(&no_response);
+        checker.crl_sources.push(&no_crls);
+        let expected = RevocationError { failures: vec![SourceFailure::NoOCSPResponse, SourceFailure::NoCRL] };
+        assert_eq!(Err(expected), checker.check(&leaf(), &root()));
+        assert_eq!(
+            "revocation status unavailable: no OCSP response; no CRL",
+            checker.check(&leaf(), &root()).unwrap_err().to_string()
+        );
+        checker.policy = RevocationPolicy::SoftFail;
+        assert_eq!(Ok(CertStatus::Unknown), checker.check(&leaf(), &root()));
+        assert_eq!(Err(RevocationError { failures: vec![] }), RevocationChecker::new(now()).check(&leaf(), &root()));
+    }
+
+    #[test]
+    fn revocation_checker_trusts_designated_responders() {
+        // The leaf isn't authorized by the root to sign responses, but the
+        // relying party may trust it anyway.
+        let delegated = Responder::delegated(&root(), &leaf());
+        let source = move |_: &Certificate, request: &OCSPRequest| {
+            Some(respond(&delegated, &EC_LEAF_KEY, request, CertStatus::Good))
+        };
+        let mut checker = RevocationChecker::new(now());
+        checker.ocsp_sources.push(&source);
+        assert_eq!(
+            Err(RevocationError { failures: vec![SourceFailure::OCSP(OCSPError::UnauthorizedResponder)] }),
+            checker.check(&good_leaf(), &root())
+        );
+        checker.trusted_responders.push(leaf());
+        assert_eq!(Ok(CertStatus::Good), checker.check(&good_leaf(), &root()));
+    }
+
+    #[test]
+    fn revocation_checker_treats_unknown_as_no_answer() {
+        let source = |_: &Certificate, request: &OCSPRequest| {
+            Some(respond(&root_responder(), &EC_ROOT_KEY, request, CertStatus::Unknown))
+        };
+        let mut checker = RevocationChecker::new(now());
+        checker.ocsp_sources.push(&source);
+        assert_eq!(
+            Err(RevocationError { failures: vec![SourceFailure::UnknownToResponder] }),
+            checker.check(&leaf(), &root())
+        );
+    }
+
+    #[derive(Default)]
+    struct Cache {
+        crls: RefCell<Vec<CertificateList>>,
+        responses: RefCell<Vec<(CertID, BasicOCSPResponse)>>,
+    }
+
+    impl RevocationCache for Cache {
+        fn crl(&self, _issuer: &Certificate) -> Option<CertificateList> {
+            self.crls.borrow().first().cloned()
+        }
+
+        fn store_crl(&self, _issuer: &Certificate, crl: &CertificateList) {
+            self.crls.borrow_mut().push(crl.clone());
+        }
+
+        fn ocsp_response(&self, cert_id: &CertID) -> Option<BasicOCSPResponse> {
+            self.responses.borrow().iter().find(|(id, _)| id == cert_id).map(|(_, response)| response.clone())
+        }
+
+        fn store_ocsp_response(&self, cert_id: &CertID, response: &BasicOCSPResponse) {
+            self.responses.borrow_mut().push((cert_id.clone(), response.clone()));
+        }
+    }
+
+    #[test]
+    fn revocation_checker_uses_cache() {
+        let fetches = Cell::new(0);
+        let ocsp = |cert: &Certificate, request: &OCSPRequest| {
+            fetches.set(fetches.get() + 1);
+            good_response(cert, request)
+        };
+        let crl = |cert: &Certificate, issuer: &Certificate| {
+            fetches.set(fetches.get() + 1);
+            crls(cert, issuer)
+        };
+        let cache = Cache::default();
+        let mut checker = RevocationChecker::new(now());
+        checker.cache = Some(&cache);
+        checker.ocsp_sources.push(&ocsp);
+        assert_eq!(Ok(CertStatus::Good), checker.check(&good_leaf(), &root()));
+        assert_eq!(Ok(CertStatus::Good), checker.check(&good_leaf(), &root()));
+        assert_eq!(1, fetches.get());
+
+        checker.ocsp_sources.clear();
+        checker.crl_sources.push(&crl);
+        assert!(matches!(checker.check(&leaf(), &root()), Ok(CertStatus::Revoked(_))));
+        assert!(matches!(checker.check(&leaf(), &root()), Ok(CertStatus::Revoked(_))));
+        assert_eq!(2, fetches.get());
+        // Once stale, the cached CRL is passed over.
+        checker.time = now() + Duration::days(60);
+        assert!(checker.check(&leaf(), &root()).is_err());
+        assert_eq!(3, fetches.get());
+    }
+
+    #[test]
+    fn revocation_checker_checks_chains() {
+        let mut checker = RevocationChecker::new(now());
+        checker.crl_sources.push(&crls);
+        let statuses = checker.check_chain(&[root(), good_leaf()]);
+        assert_eq!(vec![Ok(CertStatus::Good)], statuses);
+        assert!(checker.check_chain(&[root()]).is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+//! Signing, for issuing certificates, certification requests, CRLs and
+//! OCSP responses.
+//!
+//! The signed structures take a `Signer` rather than a key, so the key can
+//! be held in memory, in an HSM or by a remote service. With the `verify`
+//! feature the RustCrypto ECDSA and Ed25519 signing keys are `Signer`s.
+//!
+//! Signing services reached over the network, such as a cloud KMS, can
+//! implement `AsyncSigner` instead, and the `sign_async` methods give a
+//! future of the signed structure that any executor can await.
+
+use std::error::Error;
+use std::fmt;
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use simple_asn1::ASN1EncodeErr;
+
+use algorithm::AlgorithmIdentifier;
+
+#[cfg(feature = "verify")]
+mod rust_crypto;
+
+/// An error that can arise making a signature.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SignError {
+    /// The structure to be signed could not be encoded.
+    Encode(ASN1EncodeErr),
+    /// The signer failed, such as an HSM or signing service that could not
+    /// be reached.
+    Signer(String),
+}
+
+impl From<ASN1EncodeErr> for SignError {
+    fn from(e: ASN1EncodeErr) -> SignError {
+        SignError::Encode(e)
+    }
+}
+
+impl fmt::Display for SignError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SignError::Encode(ref e) => write!(f, "ASN.1 encoding failed: {}", e),
+            SignError::Signer(ref msg) => write!(f, "signing failed: {}", msg),
+        }
+    }
+}
+
+impl Error for SignError {}
+
+/// Something that signs with one private key and algorithm.
+pub trait Signer {
+    /// The algorithm the signatures are made with, as it is written in
+    /// the signed structure.
+    fn signature_algorithm(&self) -> AlgorithmIdentifier;
+
+    /// The signature over `message`, encoded as it goes in a signature
+    /// BIT STRING: the DER Ecdsa-Sig-Value for ECDSA, for instance.
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignError>;
+}
+
+impl<S: Signer + ?Sized> Signer for &S {
+    fn signature_algorithm(&self) -> AlgorithmIdentifier {
+        (**self).signature_algorithm()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignError> {
+        (**self).sign(message)
+    }
+}
+
+/// A signature being made by an `AsyncSigner`.
+pub type SignFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<u8>, SignError>> + Send + 'a>>;
+
+/// Something that signs with one private key and algorithm, taking its
+/// time about it, such as a remote HSM. Every `Signer` is also an
+/// `AsyncSigner` whose signatures are ready at once.
+pub trait AsyncSigner {
+    /// The algorithm the signatures are made with, as it is written in
+    /// the signed structure. Known up front, without asking the service.
+    fn signature_algorithm(&self) -> AlgorithmIdentifier;
+
+    /// The signature over `message`, encoded as for `Signer::sign`.
+    fn sign(&self, message: Vec<u8>) -> SignFuture<'_>;
+}
+
+impl<S: Signer + ?Sized> AsyncSigner for S {
+    fn signature_algorithm(&self) -> AlgorithmIdentifier {
+        Signer::signature_algorithm(self)
+    }
+
+    fn sign(&self, message: Vec<u8>) -> SignFuture<'_> {
+        Box::pin(future::ready(Signer::sign(self, &message)))
+    }
+}
+
+/// A structure being signed by an `AsyncSigner`, from the `sign_async`
+/// methods. Resolves to the signed structure once the signature is made.
+pub struct Signing<'a, T> {
+    signature: SignFuture<'a>,
+    finish: Option<Box<dyn FnOnce(Vec<u8>) -> T + Send + 'a>>,
+}
+
+impl<'a, T> Signing<'a, T> {
+    /// Sign `message` with `signer` and pass the signature to `finish`. An
+    /// error encoding the message resolves at once.
+    pub(crate) fn new<S, F>(signer: &'a S, message: Result<Vec<u8>, ASN1EncodeErr>, finish: F) -> Signing<'a, T>
+    where
+        S: AsyncSigner + ?Sized,
+        F: FnOnce(Vec<u8>) -> T + Send + 'a,
+    {
+        let signature = match message {
+            Ok(message) => signer.sign(message),
+            Err(e) => Box::pin(future::ready(Err(SignError::Encode(e)))),
+        };
+        Signing { signature, finish: Some(Box::new(finish)) }
+    }
+}
+
+impl<'a, T> Future for Signing<'a, T> {
+    type Output = Result<T, SignError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<T, SignError>> {
+        let signature = match self.signature.as_mut().poll(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(signature) => signature?,
+        };
+        let finish = self.finish.take().expect("Signing polled after completion");
+        Poll::Ready(Ok(finish(signature)))
+    }
+}
+
+#[cfg(test)]
+mod sign_tests {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+
+    use simple_asn1::der_encode;
+
+    use super::{AsyncSigner, SignError, SignFuture, Signer};
+    use algorithm::AlgorithmIdentifier;
+    use crl::CertificateList;
+    use der::der_decode;
+    use oids;
+    use x509::Certificate;
+
+    const EC_LEAF: &[u8] = include_bytes!("../testdata/ec-leaf.der");
+    const EC_ROOT_CRL: &[u8] = include_bytes!("../testdata/ec-root-crl.der");
+
+    // Stands in for a remote service: not ready the first time it is
+    // polled, then "signs" by reversing the message.
+    struct Remote;
+
+    struct Reply(Option<Vec<u8>>, bool);
+
+    impl Future for Reply {
+        type Output = Result<Vec<u8>, SignError>;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+            if !self.1 {
+                self.1 = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            let mut message = self.0.take().unwrap();
+            message.reverse();
+            Poll::Ready(Ok(message))
+        }
+    }
+
+    impl AsyncSigner for Remote {
+        fn signature_algorithm(&self) -> AlgorithmIdentifier {
+            AlgorithmIdentifier::new(oids::ED25519)
+        }
+
+        fn sign(&self, message: Vec<u8>) -> SignFuture<'_> {
+            Box::pin(Reply(Some(message), false))
+        }
+    }
+
+    struct Failing;
+
+    impl Signer for Failing {
+        fn signature_algorithm(&self) -> AlgorithmIdentifier {
+            AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA256)
+        }
+
+        fn sign(&self, _message: &[u8]) -> Result<Vec<u8>, SignError> {
+            Err(SignError::Signer("unavailable".to_string()))
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn sign_async_awaits_the_signer() {
+        let tbs = der_decode::<Certificate>(EC_LEAF).unwrap().tbs_certificate;
+        let cert = block_on(tbs.sign_async(&Remote)).unwrap();
+        assert_eq!(AlgorithmIdentifier::new(oids::ED25519), cert.signature_algorithm);
+        assert_eq!(cert.signature_algorithm, cert.tbs_certificate.signature);
+        let mut expected = der_encode(&cert.tbs_certificate).unwrap();
+        expected.reverse();
+        assert_eq!(expected, cert.signature_value);
+    }
+
+    #[test]
+    fn sign_async_reports_signer_failures() {
+        let tbs = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap().tbs_cert_list;
+        let expected = SignError::Signer("unavailable".to_string());
+        assert_eq!(Err(expected.clone()), tbs.clone().sign(&Failing).map(|_| ()));
+        assert_eq!(Err(expected), block_on(tbs.sign_async(&Failing)).map(|_| ()));
+    }
+}
@@ -0,0 +1,127 @@
+use ed25519_dalek;
+use p256;
+use p256::ecdsa::signature::Signer as _;
+use p384;
+
+use algorithm::AlgorithmIdentifier;
+use oids;
+use sign::{SignError, Signer};
+
+/// ECDSA over P-256 with SHA-256.
+impl Signer for p256::ecdsa::SigningKey {
+    fn signature_algorithm(&self) -> AlgorithmIdentifier {
+        AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA256)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignError> {
+        let signature: p256::ecdsa::Signature = self.try_sign(message).map_err(|e| SignError::Signer(e.to_string()))?;
+        Ok(signature.to_der().as_bytes().to_vec())
+    }
+}
+
+/// ECDSA over P-384 with SHA-384.
+impl Signer for p384::ecdsa::SigningKey {
+    fn signature_algorithm(&self) -> AlgorithmIdentifier {
+        AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA384)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignError> {
+        let signature: p384::ecdsa::Signature = self.try_sign(message).map_err(|e| SignError::Signer(e.to_string()))?;
+        Ok(signature.to_der().as_bytes().to_vec())
+    }
+}
+
+impl Signer for ed25519_dalek::SigningKey {
+    fn signature_algorithm(&self) -> AlgorithmIdentifier {
+        AlgorithmIdentifier::new(oids::ED25519)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignError> {
+        let signature = self.try_sign(message).map_err(|e| SignError::Signer(e.to_string()))?;
+        Ok(signature.to_bytes().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod sign_rust_crypto_tests {
+    use ed25519_dalek;
+    use p256;
+    use p384;
+
+    use algorithm::{AlgorithmIdentifier, AlgorithmParameters};
+    use crl::CertificateList;
+    use csr::CertificationRequest;
+    use der::der_decode;
+    use oids;
+    use sign::Signer;
+    use spki::SubjectPublicKeyInfo;
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../../testdata/ec-root.der");
+    const EC_LEAF: &[u8] = include_bytes!("../../testdata/ec-leaf.der");
+    const EC_LEAF_CSR: &[u8] = include_bytes!("../../testdata/ec-leaf-csr.der");
+    const EC_ROOT_CRL: &[u8] = include_bytes!("../../testdata/ec-root-crl.der");
+    // The private keys of EC_ROOT and EC_LEAF.
+    const EC_ROOT_KEY: [u8; 32] = [
+        0xBE, 0x92, 0x7C, 0xE1, 0x0C, 0x45, 0x2F, 0x7E, 0x07, 0xF0, 0x8C, 0xEC, 0x0A, 0x8C, 0x74, 0xDB, 0xFE, 0x33,
+        0x74, 0x4C, 0x7F, 0x09, 0x1B, 0xAF, 0x55, 0x0B, 0x5A, 0x52, 0x6F, 0xD3, 0x2D, 0x60,
+    ];
+    const EC_LEAF_KEY: [u8; 32] = [
+        0x51, 0x37, 0xB1, 0xAF, 0xBC, 0x7E, 0xEA, 0x34, 0x16, 0x50, 0x4E, 0x9E, 0xC5, 0xE5, 0xC3, 0xD9, 0x67, 0x37,
+        0x8F, 0x24, 0x01, 0xA4, 0x12, 0x56, 0x36, 0x25, 0x88, 0xCB, 0xC7, 0xFD, 0x3B, 0x4B,
+    ];
+
+    fn root_key() -> p256::ecdsa::SigningKey {
+        p256::ecdsa::SigningKey::from_bytes(&EC_ROOT_KEY.into()).unwrap()
+    }
+
+    #[test]
+    fn sign_issues_certificates() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let mut tbs = leaf.tbs_certificate.clone();
+        tbs.signature = AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA512);
+        let issued = tbs.sign(&root_key()).unwrap();
+        assert_eq!(AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA256), issued.tbs_certificate.signature);
+        assert_eq!(issued.tbs_certificate.signature, issued.signature_algorithm);
+        assert_eq!(Ok(()), issued.verify_signed_by(&root.tbs_certificate.subject_public_key_info));
+    }
+
+    #[test]
+    fn sign_signs_requests_and_crls() {
+        let csr = der_decode::<CertificationRequest>(EC_LEAF_CSR).unwrap();
+        let leaf_key = p256::ecdsa::SigningKey::from_bytes(&EC_LEAF_KEY.into()).unwrap();
+        let signed = csr.certification_request_info.clone().sign(&leaf_key).unwrap();
+        assert_eq!(Ok(()), signed.verify_signature());
+        assert!(csr.certification_request_info.sign(&root_key()).unwrap().verify_signature().is_err());
+
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+        let signed = crl.tbs_cert_list.sign(&root_key()).unwrap();
+        assert_eq!(Ok(()), signed.verify_signed_by(&root.tbs_certificate.subject_public_key_info));
+    }
+
+    #[test]
+    fn sign_with_p384_and_ed25519() {
+        let crl = der_decode::<CertificateList>(EC_ROOT_CRL).unwrap();
+
+        let key = p384::ecdsa::SigningKey::from_slice(&[7; 48]).unwrap();
+        let point = key.verifying_key().to_encoded_point(false).as_bytes().to_vec();
+        let spki = SubjectPublicKeyInfo {
+            algorithm: AlgorithmIdentifier {
+                algorithm: oids::EC_PUBLIC_KEY,
+                parameters: AlgorithmParameters::ObjectIdentifier(oids::SECP384R1),
+            },
+            subject_public_key: point,
+        };
+        let signed = crl.tbs_cert_list.clone().sign(&key).unwrap();
+        assert_eq!(AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA384), signed.signature_algorithm);
+        assert_eq!(Ok(()), signed.verify_signed_by(&spki));
+
+        let key = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        let spki = SubjectPublicKeyInfo::from_curve_public_key(oids::ED25519, key.verifying_key().as_bytes()).unwrap();
+        let signed = crl.tbs_cert_list.sign(&key).unwrap();
+        assert_eq!(Ok(()), signed.verify_signed_by(&spki));
+        assert_eq!(AlgorithmIdentifier::new(oids::ED25519), key.signature_algorithm());
+    }
+}
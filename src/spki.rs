@@ -0,0 +1,449 @@
+use num::bigint::{BigInt, BigUint, Sign};
+use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use algorithm::{AlgorithmIdentifier, AlgorithmParameters, RsaPssParameters};
+use der::{der_decode, expect_end, next_block, sequence};
+use error::X509DecodeErr;
+use oid::ObjectIdentifier;
+use oids;
+
+/// A public key and the algorithm it is used with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubjectPublicKeyInfo {
+    pub algorithm: AlgorithmIdentifier,
+    pub subject_public_key: Vec<u8>,
+}
+
+impl SubjectPublicKeyInfo {
+    /// An rsaEncryption key, with the NULL parameters RFC 3279 requires.
+    pub fn from_rsa_public_key(key: &RsaPublicKey) -> Result<SubjectPublicKeyInfo, ASN1EncodeErr> {
+        Ok(SubjectPublicKeyInfo {
+            algorithm: AlgorithmIdentifier::with_null_parameters(oids::RSA_ENCRYPTION),
+            subject_public_key: der_encode(key)?,
+        })
+    }
+
+    /// A key restricted to RSASSA-PSS. With `params` its signatures must
+    /// use the same hash and MGF and at least the same salt length; without
+    /// them it makes PSS signatures of any kind (RFC 4055 §3.1).
+    pub fn from_rsa_pss_public_key(
+        key: &RsaPublicKey,
+        params: Option<&RsaPssParameters>,
+    ) -> Result<SubjectPublicKeyInfo, ASN1EncodeErr> {
+        let algorithm = match params {
+            Some(params) => AlgorithmIdentifier::rsa_pss(params)?,
+            None => AlgorithmIdentifier::new(oids::RSASSA_PSS),
+        };
+        Ok(SubjectPublicKeyInfo { algorithm, subject_public_key: der_encode(key)? })
+    }
+
+    /// The key as an RSAPublicKey, if this is an rsaEncryption key.
+    pub fn rsa_public_key(&self) -> Result<RsaPublicKey, X509DecodeErr> {
+        if self.algorithm.algorithm != oids::RSA_ENCRYPTION {
+            return Err(X509DecodeErr::InvalidField("algorithm"));
+        }
+        der_decode(&self.subject_public_key)
+    }
+
+    /// The curve and point, if this is an id-ecPublicKey key over a named
+    /// curve.
+    pub fn ec_public_key(&self) -> Result<EcPublicKey, X509DecodeErr> {
+        if self.algorithm.algorithm != oids::EC_PUBLIC_KEY {
+            return Err(X509DecodeErr::InvalidField("algorithm"));
+        }
+        match self.algorithm.parameters {
+            AlgorithmParameters::ObjectIdentifier(ref curve) => {
+                EcPublicKey::new(curve.clone(), self.subject_public_key.clone())
+            }
+            _ => Err(X509DecodeErr::InvalidField("parameters")),
+        }
+    }
+
+    /// The bare key of an Ed25519, Ed448, X25519 or X448 SPKI (RFC 8410
+    /// §4), which must have absent parameters and a key of the right
+    /// length for the algorithm.
+    pub fn curve_public_key(&self) -> Result<&[u8], X509DecodeErr> {
+        self.bare_public_key(curve_key_len(&self.algorithm.algorithm))
+    }
+
+    /// An SPKI for an RFC 8410 key, where `algorithm` is one of ED25519,
+    /// ED448, X25519 and X448.
+    pub fn from_curve_public_key(
+        algorithm: ObjectIdentifier,
+        key: &[u8],
+    ) -> Result<SubjectPublicKeyInfo, X509DecodeErr> {
+        let spki =
+            SubjectPublicKeyInfo { algorithm: AlgorithmIdentifier::new(algorithm), subject_public_key: key.to_vec() };
+        spki.curve_public_key()?;
+        Ok(spki)
+    }
+
+    /// The bare key of an ML-DSA (FIPS 204) or SLH-DSA (FIPS 205) SPKI.
+    /// Like the RFC 8410 keys these have absent parameters and a length
+    /// fixed by the parameter set.
+    pub fn post_quantum_public_key(&self) -> Result<&[u8], X509DecodeErr> {
+        self.bare_public_key(post_quantum_key_len(&self.algorithm.algorithm))
+    }
+
+    /// An SPKI for an ML-DSA or SLH-DSA key, where `algorithm` is one of
+    /// the `ML_DSA_*` or `SLH_DSA_*` identifiers.
+    pub fn from_post_quantum_public_key(
+        algorithm: ObjectIdentifier,
+        key: &[u8],
+    ) -> Result<SubjectPublicKeyInfo, X509DecodeErr> {
+        let spki =
+            SubjectPublicKeyInfo { algorithm: AlgorithmIdentifier::new(algorithm), subject_public_key: key.to_vec() };
+        spki.post_quantum_public_key()?;
+        Ok(spki)
+    }
+
+    // The key as it stands, for an algorithm whose keys are `len` bytes
+    // with absent parameters; `None` if the algorithm is not one of them.
+    fn bare_public_key(&self, len: Option<usize>) -> Result<&[u8], X509DecodeErr> {
+        let len = len.ok_or(X509DecodeErr::InvalidField("algorithm"))?;
+        if self.algorithm.parameters != AlgorithmParameters::Absent {
+            return Err(X509DecodeErr::InvalidField("parameters"));
+        }
+        if self.subject_public_key.len() != len {
+            return Err(X509DecodeErr::InvalidField("subjectPublicKey"));
+        }
+        Ok(&self.subject_public_key)
+    }
+
+    /// An id-ecPublicKey key naming its curve, as RFC 5480 requires.
+    pub fn from_ec_public_key(key: &EcPublicKey) -> SubjectPublicKeyInfo {
+        SubjectPublicKeyInfo {
+            algorithm: AlgorithmIdentifier {
+                algorithm: oids::EC_PUBLIC_KEY,
+                parameters: AlgorithmParameters::ObjectIdentifier(key.curve.clone()),
+            },
+            subject_public_key: key.point.clone(),
+        }
+    }
+}
+
+/// An RSA public key (RFC 8017 §A.1.1), the PKCS#1 structure an
+/// rsaEncryption subjectPublicKey holds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RsaPublicKey {
+    pub modulus: BigUint,
+    pub public_exponent: BigUint,
+}
+
+impl RsaPublicKey {
+    pub fn new(modulus: BigUint, public_exponent: BigUint) -> RsaPublicKey {
+        RsaPublicKey { modulus, public_exponent }
+    }
+
+    /// The key size: the length of the modulus in bits.
+    pub fn bits(&self) -> u64 {
+        self.modulus.bits()
+    }
+}
+
+impl ToASN1 for RsaPublicKey {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![ASN1Block::Sequence(0, vec![
+            ASN1Block::Integer(0, BigInt::from_biguint(Sign::Plus, self.modulus.clone())),
+            ASN1Block::Integer(0, BigInt::from_biguint(Sign::Plus, self.public_exponent.clone())),
+        ])])
+    }
+}
+
+impl FromASN1 for RsaPublicKey {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "RSAPublicKey")?;
+        let items = sequence(head, "RSAPublicKey")?;
+        let (modulus, rest) = match next_block(items, "modulus")? {
+            (ASN1Block::Integer(_, n), rest) if n.sign() == Sign::Plus => (n.magnitude().clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("modulus")),
+        };
+        let (public_exponent, rest) = match next_block(rest, "publicExponent")? {
+            (ASN1Block::Integer(_, e), rest) if e.sign() == Sign::Plus => (e.magnitude().clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("publicExponent")),
+        };
+        expect_end(rest, "RSAPublicKey")?;
+        Ok((RsaPublicKey { modulus, public_exponent }, tail))
+    }
+}
+
+/// An elliptic curve public key: the named curve, and the point as an
+/// ECPoint octet string (SEC 1 §2.3.3).
+#[derive(Clone, Debug, PartialEq)]
+pub struct EcPublicKey {
+    pub curve: ObjectIdentifier,
+    pub point: Vec<u8>,
+}
+
+impl EcPublicKey {
+    /// A key on `curve`, failing unless `point` is a compressed or
+    /// uncompressed point. On P-256, P-384, P-521 and the brainpool
+    /// curves of the composite algorithms its length is checked too;
+    /// other curves are taken on trust. Whether the point is actually on
+    /// the curve is left to the verifier.
+    pub fn new(curve: ObjectIdentifier, point: Vec<u8>) -> Result<EcPublicKey, X509DecodeErr> {
+        let len = coordinate_len(&curve);
+        let valid = match point.first() {
+            Some(&0x04) => point.len() % 2 == 1 && len.is_none_or(|len| point.len() == 1 + 2 * len),
+            Some(&0x02) | Some(&0x03) => len.is_none_or(|len| point.len() == 1 + len),
+            _ => false,
+        };
+        if !valid || point.len() < 2 {
+            return Err(X509DecodeErr::InvalidField("subjectPublicKey"));
+        }
+        Ok(EcPublicKey { curve, point })
+    }
+
+    /// Whether the point is in compressed form, x and the sign of y.
+    pub fn is_compressed(&self) -> bool {
+        self.point[0] != 0x04
+    }
+
+    /// The x and y coordinates of an uncompressed point.
+    pub fn coordinates(&self) -> Option<(&[u8], &[u8])> {
+        if self.is_compressed() {
+            return None;
+        }
+        Some(self.point[1..].split_at(self.point.len() / 2))
+    }
+}
+
+// The length in bytes of the keys of the RFC 8410 algorithms.
+pub(crate) fn curve_key_len(algorithm: &ObjectIdentifier) -> Option<usize> {
+    if *algorithm == oids::ED25519 || *algorithm == oids::X25519 {
+        Some(32)
+    } else if *algorithm == oids::ED448 {
+        Some(57)
+    } else if *algorithm == oids::X448 {
+        Some(56)
+    } else {
+        None
+    }
+}
+
+// The length in bytes of ML-DSA (FIPS 204 §4) and SLH-DSA (FIPS 205 §11)
+// public keys.
+static POST_QUANTUM_KEY_LENS: &[(ObjectIdentifier, usize)] = &[
+    (oids::ML_DSA_44, 1312),
+    (oids::ML_DSA_65, 1952),
+    (oids::ML_DSA_87, 2592),
+    (oids::SLH_DSA_SHA2_128S, 32),
+    (oids::SLH_DSA_SHA2_128F, 32),
+    (oids::SLH_DSA_SHA2_192S, 48),
+    (oids::SLH_DSA_SHA2_192F, 48),
+    (oids::SLH_DSA_SHA2_256S, 64),
+    (oids::SLH_DSA_SHA2_256F, 64),
+    (oids::SLH_DSA_SHAKE_128S, 32),
+    (oids::SLH_DSA_SHAKE_128F, 32),
+    (oids::SLH_DSA_SHAKE_192S, 48),
+    (oids::SLH_DSA_SHAKE_192F, 48),
+    (oids::SLH_DSA_SHAKE_256S, 64),
+    (oids::SLH_DSA_SHAKE_256F, 64),
+];
+
+pub(crate) fn post_quantum_key_len(algorithm: &ObjectIdentifier) -> Option<usize> {
+    POST_QUANTUM_KEY_LENS.iter().find(|&(oid, _)| oid == algorithm).map(|&(_, len)| len)
+}
+
+// The length in bytes of a coordinate on the named curves RFC 5480 lists,
+// and the brainpool curves (RFC 5639) of the composite algorithms.
+fn coordinate_len(curve: &ObjectIdentifier) -> Option<usize> {
+    if *curve == oids::SECP256R1 || *curve == oids::BRAINPOOL_P256R1 {
+        Some(32)
+    } else if *curve == oids::SECP384R1 || *curve == oids::BRAINPOOL_P384R1 {
+        Some(48)
+    } else if *curve == oids::SECP521R1 {
+        Some(66)
+    } else {
+        None
+    }
+}
+
+impl ToASN1 for SubjectPublicKeyInfo {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.algorithm.to_asn1_class(c)?;
+        let key = self.subject_public_key.clone();
+        items.push(ASN1Block::BitString(0, key.len() * 8, key));
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for SubjectPublicKeyInfo {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "SubjectPublicKeyInfo")?;
+        let items = sequence(head, "SubjectPublicKeyInfo")?;
+        let (algorithm, rest) = AlgorithmIdentifier::from_asn1(items)?;
+        let (subject_public_key, rest) = match next_block(rest, "subjectPublicKey")? {
+            (ASN1Block::BitString(_, nbits, bytes), rest) if *nbits == bytes.len() * 8 => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("subjectPublicKey")),
+        };
+        expect_end(rest, "SubjectPublicKeyInfo")?;
+        Ok((SubjectPublicKeyInfo { algorithm, subject_public_key }, tail))
+    }
+}
+
+#[cfg(test)]
+mod subject_public_key_info_tests {
+    use simple_asn1::der_encode;
+
+    use num::bigint::BigUint;
+
+    use super::{EcPublicKey, RsaPublicKey, SubjectPublicKeyInfo};
+    use algorithm::AlgorithmParameters;
+    use der::der_decode;
+    use error::X509DecodeErr;
+    use oids;
+
+    const RSA_2048: &[u8] = include_bytes!("../testdata/rsa2048-spki.der");
+    const EC_P256: &[u8] = include_bytes!("../testdata/ec-p256-spki.der");
+
+    // An Ed25519 key from RFC 8410 §10.1.
+    const ED25519: &[u8] = &[
+        0x30, 0x2A, 0x30, 0x05, 0x06, 0x03, 0x2B, 0x65, 0x70, 0x03, 0x21, 0x00, 0x19, 0xBF, 0x44, 0x09,
+        0x69, 0x84, 0xCD, 0xFE, 0x85, 0x41, 0xBA, 0xC1, 0x67, 0xDC, 0x3B, 0x96, 0xC8, 0x50, 0x86, 0xAA,
+        0x30, 0xB6, 0xB6, 0xCB, 0x0C, 0x5C, 0x38, 0xAD, 0x70, 0x31, 0x66, 0xE1,
+    ];
+
+    #[test]
+    fn subject_public_key_info_decodes() {
+        let actual = der_decode::<SubjectPublicKeyInfo>(ED25519).unwrap();
+        assert_eq!(oid!(1, 3, 101, 112), actual.algorithm.algorithm);
+        assert_eq!(AlgorithmParameters::Absent, actual.algorithm.parameters);
+        assert_eq!(&ED25519[12..], &actual.subject_public_key[..]);
+    }
+
+    #[test]
+    fn subject_public_key_info_round_trips() {
+        let decoded = der_decode::<SubjectPublicKeyInfo>(ED25519).unwrap();
+        assert_eq!(ED25519.to_vec(), der_encode(&decoded).unwrap());
+    }
+
+    #[test]
+    fn subject_public_key_info_curve_public_key() {
+        let spki = der_decode::<SubjectPublicKeyInfo>(ED25519).unwrap();
+        assert_eq!(Ok(&ED25519[12..]), spki.curve_public_key());
+        assert_eq!(Ok(spki.clone()), SubjectPublicKeyInfo::from_curve_public_key(oids::ED25519, &ED25519[12..]));
+        assert_eq!(Err(X509DecodeErr::InvalidField("algorithm")), spki.rsa_public_key());
+
+        let x448 = SubjectPublicKeyInfo::from_curve_public_key(oids::X448, &[9; 56]).unwrap();
+        let decoded = der_decode::<SubjectPublicKeyInfo>(&der_encode(&x448).unwrap()).unwrap();
+        assert_eq!(Ok(&[9; 56][..]), decoded.curve_public_key());
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("subjectPublicKey")),
+            SubjectPublicKeyInfo::from_curve_public_key(oids::ED448, &[9; 56])
+        );
+        assert_eq!(
+            Err(X509DecodeErr::InvalidField("algorithm")),
+            SubjectPublicKeyInfo::from_curve_public_key(oids::EC_PUBLIC_KEY, &[9; 32])
+        );
+
+        // RFC 8410 §3: the parameters must be absent, not NULL.
+        let mut null = spki;
+        null.algorithm.parameters = AlgorithmParameters::Null;
+        assert_eq!(Err(X509DecodeErr::InvalidField("parameters")), null.curve_public_key());
+    }
+
+    #[test]
+    fn subject_public_key_info_post_quantum_public_key() {
+        let key = vec![0x5A; 1952];
+        let spki = SubjectPublicKeyInfo::from_post_quantum_public_key(oids::ML_DSA_65, &key).unwrap();
+        let decoded = der_decode::<SubjectPublicKeyInfo>(&der_encode(&spki).unwrap()).unwrap();
+        assert_eq!(AlgorithmParameters::Absent, decoded.algorithm.parameters);
+        assert_eq!(Ok(&key[..]), decoded.post_quantum_public_key());
+
+        let slh = SubjectPublicKeyInfo::from_post_quantum_public_key(oids::SLH_DSA_SHAKE_256F, &[1; 64]).unwrap();
+        assert_eq!(Ok(&[1; 64][..]), slh.post_quantum_public_key());
+        assert_eq!(Err(X509DecodeErr::InvalidField("algorithm")), slh.curve_public_key());
+
+        let error = SubjectPublicKeyInfo::from_post_quantum_public_key(oids::ML_DSA_44, &key);
+        assert_eq!(Err(X509DecodeErr::InvalidField("subjectPublicKey")), error);
+        let error = SubjectPublicKeyInfo::from_post_quantum_public_key(oids::ED25519, &[1; 32]);
+        assert_eq!(Err(X509DecodeErr::InvalidField("algorithm")), error);
+        let mut null = slh;
+        null.algorithm.parameters = AlgorithmParameters::Null;
+        assert_eq!(Err(X509DecodeErr::InvalidField("parameters")), null.post_quantum_public_key());
+    }
+
+    #[test]
+    fn subject_public_key_info_rejects_partial_octets() {
+        let mut input = ED25519.to_vec();
+        input[11] = 0x01;
+        let error = der_decode::<SubjectPublicKeyInfo>(&input).unwrap_err();
+        assert_eq!(X509DecodeErr::InvalidField("subjectPublicKey"), error);
+    }
+
+    #[test]
+    fn subject_public_key_info_rsa_public_key() {
+        let spki = der_decode::<SubjectPublicKeyInfo>(RSA_2048).unwrap();
+        let key = spki.rsa_public_key().unwrap();
+        assert_eq!(2048, key.modulus.bits());
+        assert_eq!(BigUint::from(65537u32), key.public_exponent);
+        assert_eq!(Err(X509DecodeErr::InvalidField("algorithm")), spki.ec_public_key());
+    }
+
+    #[test]
+    fn subject_public_key_info_ec_public_key() {
+        let spki = der_decode::<SubjectPublicKeyInfo>(EC_P256).unwrap();
+        let key = spki.ec_public_key().unwrap();
+        assert_eq!(oids::SECP256R1, key.curve);
+        assert_eq!(65, key.point.len());
+        assert_eq!(0x04, key.point[0]);
+        assert_eq!(Err(X509DecodeErr::InvalidField("algorithm")), spki.rsa_public_key());
+    }
+
+    #[test]
+    fn subject_public_key_info_from_rsa_public_key() {
+        let spki = der_decode::<SubjectPublicKeyInfo>(RSA_2048).unwrap();
+        let key = spki.rsa_public_key().unwrap();
+        assert_eq!(2048, key.bits());
+        assert_eq!(spki, SubjectPublicKeyInfo::from_rsa_public_key(&key).unwrap());
+        assert_eq!(RSA_2048.to_vec(), der_encode(&SubjectPublicKeyInfo::from_rsa_public_key(&key).unwrap()).unwrap());
+
+        let small = RsaPublicKey::new(BigUint::from(3233u32), BigUint::from(17u32));
+        let spki = SubjectPublicKeyInfo::from_rsa_public_key(&small).unwrap();
+        assert_eq!(12, spki.rsa_public_key().unwrap().bits());
+    }
+
+    #[test]
+    fn ec_public_key_checks_points() {
+        let spki = der_decode::<SubjectPublicKeyInfo>(EC_P256).unwrap();
+        let key = spki.ec_public_key().unwrap();
+        assert!(!key.is_compressed());
+        let (x, y) = key.coordinates().unwrap();
+        assert_eq!((&key.point[1..33], &key.point[33..]), (x, y));
+        assert_eq!(spki, SubjectPublicKeyInfo::from_ec_public_key(&key));
+
+        let invalid = Err(X509DecodeErr::InvalidField("subjectPublicKey"));
+        let mut compressed = vec![0x02 | (y[31] & 1)];
+        compressed.extend_from_slice(x);
+        let key = EcPublicKey::new(oids::SECP256R1, compressed.clone()).unwrap();
+        assert!(key.is_compressed());
+        assert_eq!(None, key.coordinates());
+        assert_eq!(invalid, EcPublicKey::new(oids::SECP384R1, compressed));
+        assert_eq!(invalid, EcPublicKey::new(oids::SECP256R1, spki.subject_public_key[..64].to_vec()));
+        assert_eq!(invalid, EcPublicKey::new(oids::SECP256R1, vec![0x00]));
+        assert_eq!(invalid, EcPublicKey::new(oids::SECP256R1, vec![]));
+
+        let p521 = EcPublicKey::new(oids::SECP521R1, [vec![0x04], vec![1; 132]].concat()).unwrap();
+        let spki = SubjectPublicKeyInfo::from_ec_public_key(&p521);
+        assert_eq!(Ok(p521), der_decode::<SubjectPublicKeyInfo>(&der_encode(&spki).unwrap()).unwrap().ec_public_key());
+        let mut short = spki.clone();
+        short.subject_public_key.pop();
+        assert_eq!(invalid, short.ec_public_key());
+    }
+
+    #[test]
+    fn rsa_public_key_round_trips() {
+        let spki = der_decode::<SubjectPublicKeyInfo>(RSA_2048).unwrap();
+        let key = spki.rsa_public_key().unwrap();
+        assert_eq!(spki.subject_public_key, der_encode(&key).unwrap());
+    }
+}
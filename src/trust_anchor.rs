@@ -0,0 +1,439 @@
+//! Trust anchor formats (RFC 5914).
+//!
+//! A `TrustAnchorList` holds anchors as root certificates, bare
+//! TBSCertificates or `TrustAnchorInfo`s. The last carries a name and key
+//! without a certificate, and can limit the paths the anchor starts with
+//! name constraints, policies and a path length; `to_trust_anchor` turns
+//! those into a `path::TrustAnchor` whose constraints the validator
+//! applies, and `TrustAnchorStore::add_trust_anchor_list` loads a list.
+
+use num::bigint::BigInt;
+use num::ToPrimitive;
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use der::{
+    context_tag, expect_end, explicit, explicit_inner, implicit, implicit_inner, named_bits, named_bits_value,
+    next_block, sequence,
+};
+use error::X509DecodeErr;
+use extensions::{CertificatePolicies, Extensions, NameConstraints};
+use name::Name;
+use path::{TrustAnchor, TrustAnchorConstraints};
+use spki::SubjectPublicKeyInfo;
+use x509::{Certificate, TBSCertificate};
+
+/// How a trust anchor may be used in path validation (RFC 5914 §2.5).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertPathControls {
+    /// The name the anchor issues certificates under.
+    pub ta_name: Name,
+    /// A certificate for the anchor, for applications that want one.
+    pub certificate: Option<Certificate>,
+    pub policy_set: Option<CertificatePolicies>,
+    pub inhibit_policy_mapping: bool,
+    pub require_explicit_policy: bool,
+    pub inhibit_any_policy: bool,
+    pub name_constraints: Option<NameConstraints>,
+    pub path_len_constraint: Option<u64>,
+}
+
+impl CertPathControls {
+    pub fn new(ta_name: Name) -> CertPathControls {
+        CertPathControls {
+            ta_name,
+            certificate: None,
+            policy_set: None,
+            inhibit_policy_mapping: false,
+            require_explicit_policy: false,
+            inhibit_any_policy: false,
+            name_constraints: None,
+            path_len_constraint: None,
+        }
+    }
+
+    /// The constraints these place on paths from the anchor.
+    pub fn constraints(&self) -> TrustAnchorConstraints {
+        TrustAnchorConstraints {
+            policy_set: self.policy_set.as_ref().map(|policies| policies.policy_identifiers().cloned().collect()),
+            inhibit_policy_mapping: self.inhibit_policy_mapping,
+            require_explicit_policy: self.require_explicit_policy,
+            inhibit_any_policy: self.inhibit_any_policy,
+            name_constraints: self.name_constraints.clone(),
+            path_len_constraint: self.path_len_constraint,
+        }
+    }
+
+    // CertPolicyFlags: inhibitPolicyMapping(0), requireExplicitPolicy(1),
+    // inhibitAnyPolicy(2).
+    fn policy_flags(&self) -> u16 {
+        self.inhibit_policy_mapping as u16
+            | (self.require_explicit_policy as u16) << 1
+            | (self.inhibit_any_policy as u16) << 2
+    }
+}
+
+impl ToASN1 for CertPathControls {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.ta_name.to_asn1_class(c)?;
+        if let Some(ref certificate) = self.certificate {
+            items.push(implicit(0, &certificate.to_asn1_class(c)?[0])?);
+        }
+        if let Some(ref policies) = self.policy_set {
+            items.push(implicit(1, &policies.to_asn1_class(c)?[0])?);
+        }
+        // All-false flags are the same as absent ones.
+        if self.policy_flags() != 0 {
+            items.push(implicit(2, &named_bits(self.policy_flags()))?);
+        }
+        if let Some(ref constraints) = self.name_constraints {
+            items.push(implicit(3, &constraints.to_asn1_class(c)?[0])?);
+        }
+        if let Some(len) = self.path_len_constraint {
+            items.push(implicit(4, &ASN1Block::Integer(0, BigInt::from(len)))?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for CertPathControls {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "CertPathControls")?;
+        let items = sequence(head, "CertPathControls")?;
+        let (ta_name, mut rest) = Name::from_asn1(items)?;
+        let mut controls = CertPathControls::new(ta_name);
+        // Each field is IMPLICIT, and only the tag tells them apart.
+        let mut field = |tag: u64, universal: u8, name: &'static str| -> Result<Option<ASN1Block>, X509DecodeErr> {
+            match rest.split_first() {
+                Some((b, remaining)) if context_tag(b) == Some(tag) => {
+                    rest = remaining;
+                    implicit_inner(b, tag, universal).map(Some).ok_or(X509DecodeErr::InvalidField(name))
+                }
+                _ => Ok(None),
+            }
+        };
+        if let Some(b) = field(0, 0x10, "certificate")? {
+            controls.certificate = Some(Certificate::from_asn1(&[b])?.0);
+        }
+        if let Some(b) = field(1, 0x10, "policySet")? {
+            controls.policy_set = Some(CertificatePolicies::from_asn1(&[b])?.0);
+        }
+        match field(2, 0x03, "policyFlags")? {
+            Some(ASN1Block::BitString(_, nbits, bytes)) => {
+                let flags = named_bits_value(nbits, &bytes, 3);
+                controls.inhibit_policy_mapping = flags & 1 != 0;
+                controls.require_explicit_policy = flags & 2 != 0;
+                controls.inhibit_any_policy = flags & 4 != 0;
+            }
+            Some(_) => return Err(X509DecodeErr::InvalidField("policyFlags")),
+            None => {}
+        }
+        if let Some(b) = field(3, 0x10, "nameConstr")? {
+            controls.name_constraints = Some(NameConstraints::from_asn1(&[b])?.0);
+        }
+        match field(4, 0x02, "pathLenConstraint")? {
+            Some(ASN1Block::Integer(_, n)) => {
+                let len = n.to_u64().ok_or(X509DecodeErr::InvalidField("pathLenConstraint"))?;
+                controls.path_len_constraint = Some(len);
+            }
+            Some(_) => return Err(X509DecodeErr::InvalidField("pathLenConstraint")),
+            None => {}
+        }
+        expect_end(rest, "CertPathControls")?;
+        Ok((controls, tail))
+    }
+}
+
+/// A trust anchor as a name and key rather than a certificate (RFC 5914
+/// §2).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrustAnchorInfo {
+    pub public_key: SubjectPublicKeyInfo,
+    /// The subjectKeyIdentifier certificates the anchor issues carry in
+    /// their authorityKeyIdentifier.
+    pub key_id: Vec<u8>,
+    /// A human-readable name for the anchor, of at most 64 characters.
+    pub ta_title: Option<String>,
+    /// Without these the anchor is not meant for path validation.
+    pub cert_path: Option<CertPathControls>,
+    pub extensions: Option<Extensions>,
+    /// The language of `ta_title`, as an RFC 5646 tag.
+    pub ta_title_lang_tag: Option<String>,
+}
+
+impl TrustAnchorInfo {
+    pub fn new(public_key: SubjectPublicKeyInfo, key_id: Vec<u8>) -> TrustAnchorInfo {
+        TrustAnchorInfo {
+            public_key,
+            key_id,
+            ta_title: None,
+            cert_path: None,
+            extensions: None,
+            ta_title_lang_tag: None,
+        }
+    }
+}
+
+impl ToASN1 for TrustAnchorInfo {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        // The version is always v1, the DEFAULT, so it is left out.
+        let mut items = self.public_key.to_asn1_class(c)?;
+        items.push(ASN1Block::OctetString(0, self.key_id.clone()));
+        if let Some(ref title) = self.ta_title {
+            items.push(ASN1Block::UTF8String(0, title.clone()));
+        }
+        if let Some(ref cert_path) = self.cert_path {
+            items.extend(cert_path.to_asn1_class(c)?);
+        }
+        if let Some(ref extensions) = self.extensions {
+            items.push(explicit(1, extensions.to_asn1_class(c)?.remove(0)));
+        }
+        if let Some(ref tag) = self.ta_title_lang_tag {
+            items.push(implicit(2, &ASN1Block::UTF8String(0, tag.clone()))?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for TrustAnchorInfo {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "TrustAnchorInfo")?;
+        let mut rest = sequence(head, "TrustAnchorInfo")?;
+        if let Some((ASN1Block::Integer(_, version), remaining)) = rest.split_first() {
+            if version.to_u8() != Some(1) {
+                return Err(X509DecodeErr::UnsupportedVersion(version.clone()));
+            }
+            rest = remaining;
+        }
+        let (public_key, rest) = SubjectPublicKeyInfo::from_asn1(rest)?;
+        let (key_id, mut rest) = match next_block(rest, "keyId")? {
+            (ASN1Block::OctetString(_, id), rest) => (id.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("keyId")),
+        };
+        let mut info = TrustAnchorInfo::new(public_key, key_id);
+        if let Some((ASN1Block::UTF8String(_, title), remaining)) = rest.split_first() {
+            if title.is_empty() || title.chars().count() > 64 {
+                return Err(X509DecodeErr::InvalidField("taTitle"));
+            }
+            info.ta_title = Some(title.clone());
+            rest = remaining;
+        }
+        if let Some(ASN1Block::Sequence(_, _)) = rest.first() {
+            let (cert_path, remaining) = CertPathControls::from_asn1(rest)?;
+            info.cert_path = Some(cert_path);
+            rest = remaining;
+        }
+        if let Some((b, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(1)) {
+            let inner = explicit_inner(b, 1).ok_or(X509DecodeErr::InvalidField("exts"))?;
+            info.extensions = Some(Extensions::from_asn1(std::slice::from_ref(inner))?.0);
+            rest = remaining;
+        }
+        if let Some((b, remaining)) = rest.split_first().filter(|&(b, _)| context_tag(b) == Some(2)) {
+            match implicit_inner(b, 2, 0x0C) {
+                Some(ASN1Block::UTF8String(_, tag)) => info.ta_title_lang_tag = Some(tag),
+                _ => return Err(X509DecodeErr::InvalidField("taTitleLangTag")),
+            }
+            rest = remaining;
+        }
+        expect_end(rest, "TrustAnchorInfo")?;
+        Ok((info, tail))
+    }
+}
+
+/// One entry of a `TrustAnchorList`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrustAnchorChoice {
+    Certificate(Certificate),
+    /// A certificate without its signature, `[1] EXPLICIT`.
+    TbsCertificate(TBSCertificate),
+    /// `[2] EXPLICIT`, boxed as it is much the largest.
+    TrustAnchorInfo(Box<TrustAnchorInfo>),
+}
+
+impl TrustAnchorChoice {
+    /// The anchor for path validation: the subject and key of a
+    /// certificate, or the name, key and constraints of a
+    /// `TrustAnchorInfo`. None for a `TrustAnchorInfo` without
+    /// CertPathControls, which is not meant for path validation.
+    ///
+    /// The extensions of a certificate are not turned into constraints.
+    pub fn to_trust_anchor(&self) -> Option<TrustAnchor> {
+        match *self {
+            TrustAnchorChoice::Certificate(ref cert) => Some(TrustAnchor::from_certificate(cert)),
+            TrustAnchorChoice::TbsCertificate(ref tbs) => {
+                Some(TrustAnchor::new(tbs.subject.clone(), tbs.subject_public_key_info.clone()))
+            }
+            TrustAnchorChoice::TrustAnchorInfo(ref info) => {
+                let cert_path = info.cert_path.as_ref()?;
+                let mut anchor = TrustAnchor::new(cert_path.ta_name.clone(), info.public_key.clone());
+                anchor.constraints = cert_path.constraints();
+                Some(anchor)
+            }
+        }
+    }
+}
+
+impl ToASN1 for TrustAnchorChoice {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        match *self {
+            TrustAnchorChoice::Certificate(ref cert) => cert.to_asn1_class(c),
+            TrustAnchorChoice::TbsCertificate(ref tbs) => Ok(vec![explicit(1, tbs.to_asn1_class(c)?.remove(0))]),
+            TrustAnchorChoice::TrustAnchorInfo(ref info) => Ok(vec![explicit(2, info.to_asn1_class(c)?.remove(0))]),
+        }
+    }
+}
+
+impl FromASN1 for TrustAnchorChoice {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "TrustAnchorChoice")?;
+        let choice = match context_tag(head) {
+            None => TrustAnchorChoice::Certificate(Certificate::from_asn1(v)?.0),
+            Some(1) => {
+                let inner = explicit_inner(head, 1).ok_or(X509DecodeErr::InvalidField("tbsCert"))?;
+                TrustAnchorChoice::TbsCertificate(TBSCertificate::from_asn1(std::slice::from_ref(inner))?.0)
+            }
+            Some(2) => {
+                let inner = explicit_inner(head, 2).ok_or(X509DecodeErr::InvalidField("taInfo"))?;
+                TrustAnchorChoice::TrustAnchorInfo(Box::new(TrustAnchorInfo::from_asn1(std::slice::from_ref(inner))?.0))
+            }
+            Some(_) => return Err(X509DecodeErr::InvalidField("TrustAnchorChoice")),
+        };
+        Ok((choice, tail))
+    }
+}
+
+/// A list of trust anchors, in order (RFC 5914 §4).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrustAnchorList(pub Vec<TrustAnchorChoice>);
+
+impl ToASN1 for TrustAnchorList {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        for choice in &self.0 {
+            items.extend(choice.to_asn1_class(c)?);
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for TrustAnchorList {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "TrustAnchorList")?;
+        let mut items = sequence(head, "TrustAnchorList")?;
+        // at least one anchor is required
+        if items.is_empty() {
+            return Err(X509DecodeErr::InvalidField("TrustAnchorList"));
+        }
+        let mut anchors = Vec::new();
+        while !items.is_empty() {
+            let (choice, rest) = TrustAnchorChoice::from_asn1(items)?;
+            anchors.push(choice);
+            items = rest;
+        }
+        Ok((TrustAnchorList(anchors), tail))
+    }
+}
+
+#[cfg(test)]
+mod trust_anchor_tests {
+    use num::bigint::BigInt;
+    use simple_asn1::{der_encode, to_der, ASN1Block};
+
+    use super::{CertPathControls, TrustAnchorChoice, TrustAnchorInfo, TrustAnchorList};
+    use der::{der_decode, from_der};
+    use error::X509DecodeErr;
+    use extensions::{CertificatePolicies, GeneralSubtree, NameConstraints, PolicyInformation};
+    use general_name::GeneralName;
+    use path::TrustAnchor;
+    use x509::Certificate;
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+
+    fn constrained_info() -> TrustAnchorInfo {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let tbs = root.tbs_certificate;
+        let mut controls = CertPathControls::new(tbs.subject.clone());
+        controls.policy_set = Some(CertificatePolicies(vec![PolicyInformation::new(oid!(2, 23, 140, 1, 2, 1))]));
+        controls.require_explicit_policy = true;
+        controls.inhibit_any_policy = true;
+        controls.name_constraints = Some(NameConstraints {
+            permitted_subtrees: vec![GeneralSubtree::new(GeneralName::DNSName("example.com".to_string()))],
+            excluded_subtrees: Vec::new(),
+        });
+        controls.path_len_constraint = Some(1);
+        let mut info = TrustAnchorInfo::new(tbs.subject_public_key_info, vec![1, 2, 3, 4]);
+        info.ta_title = Some("QuickLime EC Root".to_string());
+        info.ta_title_lang_tag = Some("en".to_string());
+        info.cert_path = Some(controls);
+        info
+    }
+
+    #[test]
+    fn trust_anchor_info_round_trips() {
+        let info = constrained_info();
+        let der = der_encode(&info).unwrap();
+        assert_eq!(info, der_decode::<TrustAnchorInfo>(&der).unwrap());
+
+        // With the version written out.
+        let versioned = |version: u8| {
+            let mut items = match from_der(&der).unwrap().remove(0) {
+                ASN1Block::Sequence(_, items) => items,
+                _ => unreachable!(),
+            };
+            items.insert(0, ASN1Block::Integer(0, BigInt::from(version)));
+            to_der(&ASN1Block::Sequence(0, items)).unwrap()
+        };
+        assert_eq!(info, der_decode::<TrustAnchorInfo>(&versioned(1)).unwrap());
+        assert_eq!(
+            Err(X509DecodeErr::UnsupportedVersion(BigInt::from(2))),
+            der_decode::<TrustAnchorInfo>(&versioned(2))
+        );
+    }
+
+    #[test]
+    fn trust_anchor_list_round_trips() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let list = TrustAnchorList(vec![
+            TrustAnchorChoice::Certificate(root.clone()),
+            TrustAnchorChoice::TbsCertificate(root.tbs_certificate.clone()),
+            TrustAnchorChoice::TrustAnchorInfo(Box::new(constrained_info())),
+        ]);
+        let der = der_encode(&list).unwrap();
+        assert_eq!(list, der_decode::<TrustAnchorList>(&der).unwrap());
+        assert_eq!(Err(X509DecodeErr::InvalidField("TrustAnchorList")), der_decode::<TrustAnchorList>(&[0x30, 0x00]));
+    }
+
+    #[test]
+    fn trust_anchor_choice_to_trust_anchor() {
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let plain = TrustAnchor::from_certificate(&root);
+        assert_eq!(Some(plain.clone()), TrustAnchorChoice::Certificate(root.clone()).to_trust_anchor());
+        assert_eq!(Some(plain.clone()), TrustAnchorChoice::TbsCertificate(root.tbs_certificate).to_trust_anchor());
+
+        let anchor = TrustAnchorChoice::TrustAnchorInfo(Box::new(constrained_info())).to_trust_anchor().unwrap();
+        assert_eq!((&plain.name, &plain.public_key), (&anchor.name, &anchor.public_key));
+        assert_eq!(Some(vec![oid!(2, 23, 140, 1, 2, 1)]), anchor.constraints.policy_set);
+        assert!(anchor.constraints.require_explicit_policy && anchor.constraints.inhibit_any_policy);
+        assert!(!anchor.constraints.inhibit_policy_mapping);
+        assert_eq!(Some(1), anchor.constraints.path_len_constraint);
+
+        let mut info = constrained_info();
+        info.cert_path = None;
+        assert_eq!(None, TrustAnchorChoice::TrustAnchorInfo(Box::new(info)).to_trust_anchor());
+    }
+}
@@ -0,0 +1,159 @@
+use simple_asn1::{ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+use der::{expect_end, next_block, sequence};
+use error::X509DecodeErr;
+
+/// A certificate time (RFC 5280 §4.1.2.5). All times are UTC.
+///
+/// The encoding a time was decoded from is kept so that it re-encodes
+/// identically. UTCTime can only represent 1950 through 2049; a `UTCTime`
+/// outside that range does not encode correctly, so build times with
+/// `Time::new` unless a particular encoding is needed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Time {
+    UTCTime(PrimitiveDateTime),
+    GeneralizedTime(PrimitiveDateTime),
+}
+
+impl Time {
+    /// The encoding RFC 5280 requires: UTCTime through 2049, and
+    /// GeneralizedTime from 2050.
+    pub fn new(t: PrimitiveDateTime) -> Time {
+        if t.year() >= 1950 && t.year() <= 2049 {
+            Time::UTCTime(t)
+        } else {
+            Time::GeneralizedTime(t)
+        }
+    }
+
+    pub fn date_time(&self) -> PrimitiveDateTime {
+        match *self {
+            Time::UTCTime(t) | Time::GeneralizedTime(t) => t,
+        }
+    }
+
+    pub(crate) fn to_block(self) -> ASN1Block {
+        match self {
+            Time::UTCTime(t) => ASN1Block::UTCTime(0, t),
+            Time::GeneralizedTime(t) => ASN1Block::GeneralizedTime(0, t),
+        }
+    }
+
+    pub(crate) fn from_block(b: &ASN1Block, field: &'static str) -> Result<Time, X509DecodeErr> {
+        match *b {
+            ASN1Block::UTCTime(_, t) => Ok(Time::UTCTime(t)),
+            ASN1Block::GeneralizedTime(_, t) => Ok(Time::GeneralizedTime(t)),
+            _ => Err(X509DecodeErr::InvalidField(field)),
+        }
+    }
+}
+
+/// The period a certificate is valid for. Both ends are inclusive.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Validity {
+    pub not_before: Time,
+    pub not_after: Time,
+}
+
+impl Validity {
+    pub fn new(not_before: PrimitiveDateTime, not_after: PrimitiveDateTime) -> Validity {
+        Validity { not_before: Time::new(not_before), not_after: Time::new(not_after) }
+    }
+
+    /// Whether `t`, in UTC, falls within the validity period.
+    pub fn is_valid_at(&self, t: PrimitiveDateTime) -> bool {
+        self.not_before.date_time() <= t && t <= self.not_after.date_time()
+    }
+
+    /// Whether the current system time falls within the validity period.
+    pub fn is_valid_now(&self) -> bool {
+        let now = OffsetDateTime::now_utc();
+        self.is_valid_at(PrimitiveDateTime::new(now.date(), now.time()))
+    }
+}
+
+impl ToASN1 for Validity {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Ok(vec![ASN1Block::Sequence(0, vec![self.not_before.to_block(), self.not_after.to_block()])])
+    }
+}
+
+impl FromASN1 for Validity {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "validity")?;
+        let items = sequence(head, "validity")?;
+        let (not_before, rest) = next_block(items, "notBefore")?;
+        let not_before = Time::from_block(not_before, "notBefore")?;
+        let (not_after, rest) = next_block(rest, "notAfter")?;
+        let not_after = Time::from_block(not_after, "notAfter")?;
+        expect_end(rest, "validity")?;
+        Ok((Validity { not_before, not_after }, tail))
+    }
+}
+
+#[cfg(test)]
+mod validity_tests {
+    use simple_asn1::der_encode;
+    use time::{Date, Month, PrimitiveDateTime, Time as TimeOfDay};
+
+    use super::{Time, Validity};
+    use der::der_decode;
+    use error::X509DecodeErr;
+
+    fn at(year: i32, month: Month, day: u8) -> PrimitiveDateTime {
+        PrimitiveDateTime::new(Date::from_calendar_date(year, month, day).unwrap(), TimeOfDay::MIDNIGHT)
+    }
+
+    // 490101000000Z to 20500101000000Z
+    const MIXED: &[u8] = &[
+        0x30, 0x20, 0x17, 0x0D, 0x34, 0x39, 0x30, 0x31, 0x30, 0x31, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30,
+        0x5A, 0x18, 0x0F, 0x32, 0x30, 0x35, 0x30, 0x30, 0x31, 0x30, 0x31, 0x30, 0x30, 0x30, 0x30, 0x30,
+        0x30, 0x5A,
+    ];
+
+    #[test]
+    fn validity_decodes_mixed_encodings() {
+        let actual = der_decode::<Validity>(MIXED).unwrap();
+        assert_eq!(Time::UTCTime(at(2049, Month::January, 1)), actual.not_before);
+        assert_eq!(Time::GeneralizedTime(at(2050, Month::January, 1)), actual.not_after);
+    }
+
+    #[test]
+    fn validity_encodes_per_rfc5280() {
+        let validity = Validity::new(at(2049, Month::January, 1), at(2050, Month::January, 1));
+        assert_eq!(MIXED.to_vec(), der_encode(&validity).unwrap());
+    }
+
+    #[test]
+    fn validity_utc_window() {
+        // 500101000000Z is 1950, not 2050.
+        let input = [
+            0x30, 0x20, 0x17, 0x0D, 0x35, 0x30, 0x30, 0x31, 0x30, 0x31, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30,
+            0x5A, 0x18, 0x0F, 0x32, 0x30, 0x35, 0x30, 0x30, 0x31, 0x30, 0x31, 0x30, 0x30, 0x30, 0x30, 0x30,
+            0x30, 0x5A,
+        ];
+        let actual = der_decode::<Validity>(&input).unwrap();
+        assert_eq!(at(1950, Month::January, 1), actual.not_before.date_time());
+    }
+
+    #[test]
+    fn validity_is_valid_at_inclusive() {
+        let validity = Validity::new(at(2020, Month::January, 1), at(2021, Month::January, 1));
+        assert!(!validity.is_valid_at(at(2019, Month::December, 31)));
+        assert!(validity.is_valid_at(at(2020, Month::January, 1)));
+        assert!(validity.is_valid_at(at(2020, Month::June, 15)));
+        assert!(validity.is_valid_at(at(2021, Month::January, 1)));
+        assert!(!validity.is_valid_at(at(2021, Month::January, 2)));
+    }
+
+    #[test]
+    fn validity_rejects_non_time() {
+        let input = [0x30, 0x06, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00];
+        assert_eq!(Err(X509DecodeErr::InvalidField("notBefore")), der_decode::<Validity>(&input));
+    }
+}
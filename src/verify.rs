@@ -0,0 +1,80 @@
+//! Signature verification.
+//!
+//! Checking a signature goes through a `SignatureVerifier`, so the crypto
+//! can come from whichever backend suits the application. The `verify`
+//! feature provides `RustCrypto`, built on the RustCrypto crates, and the
+//! `ring` feature provides `Ring`. Either can be passed to the `_with`
+//! methods; the methods without a backend argument use `RustCrypto`.
+//!
+//! Neither backend checks the post-quantum ML-DSA and SLH-DSA signatures
+//! yet. A `SignatureVerifier` over a library that does can take the key
+//! from `SubjectPublicKeyInfo::post_quantum_public_key` and be passed to
+//! the same `_with` methods.
+
+use std::error::Error;
+use std::fmt;
+
+use simple_asn1::ASN1EncodeErr;
+
+use algorithm::AlgorithmIdentifier;
+use oid::ObjectIdentifier;
+use spki::SubjectPublicKeyInfo;
+
+#[cfg(any(feature = "verify", feature = "ring"))]
+mod pss;
+#[cfg(feature = "ring")]
+mod ring;
+#[cfg(feature = "verify")]
+mod rust_crypto;
+
+#[cfg(feature = "ring")]
+pub use self::ring::Ring;
+#[cfg(feature = "verify")]
+pub use self::rust_crypto::RustCrypto;
+
+/// An error that can arise checking a signature.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VerifyError {
+    /// The backend does not implement the signature algorithm, or the
+    /// curve of the key.
+    UnsupportedAlgorithm(ObjectIdentifier),
+    /// The public key is malformed, or is not a key for the signature
+    /// algorithm.
+    InvalidKey,
+    /// The signature does not match the signed data and key.
+    BadSignature,
+    /// The signed structure could not be encoded to check it.
+    Encode(ASN1EncodeErr),
+}
+
+impl From<ASN1EncodeErr> for VerifyError {
+    fn from(e: ASN1EncodeErr) -> VerifyError {
+        VerifyError::Encode(e)
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerifyError::UnsupportedAlgorithm(ref oid) => write!(f, "unsupported signature algorithm: {}", oid),
+            VerifyError::InvalidKey => write!(f, "invalid public key for the signature algorithm"),
+            VerifyError::BadSignature => write!(f, "signature does not match"),
+            VerifyError::Encode(ref e) => write!(f, "ASN.1 encoding failed: {}", e),
+        }
+    }
+}
+
+impl Error for VerifyError {}
+
+/// A backend that checks signatures.
+pub trait SignatureVerifier {
+    /// Check that `signature` was made over `message` by the private half
+    /// of `key`, using `algorithm`.
+    fn verify(
+        &self,
+        algorithm: &AlgorithmIdentifier,
+        key: &SubjectPublicKeyInfo,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), VerifyError>;
+}
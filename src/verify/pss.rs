@@ -0,0 +1,45 @@
+use algorithm::{AlgorithmIdentifier, RsaPssParameters};
+use oid::ObjectIdentifier;
+use oids;
+use spki::SubjectPublicKeyInfo;
+use verify::VerifyError;
+
+// The digest and salt length of an RSASSA-PSS signature (RFC 4055 §3.1).
+// MGF1 must use the same digest as the message, as RFC 4055 recommends,
+// and the trailer field must be the usual one. The defaults are SHA-1,
+// which is then refused like any other unsupported digest.
+//
+// A key restricted to PSS by its own RSASSA-PSS-params only makes
+// signatures with the same digest and MGF and at least its salt length
+// (RFC 4055 §3.3); others are refused as made with the wrong key.
+pub fn pss_parameters(
+    algorithm: &AlgorithmIdentifier,
+    key: &SubjectPublicKeyInfo,
+) -> Result<(ObjectIdentifier, usize), VerifyError> {
+    let unsupported = || VerifyError::UnsupportedAlgorithm(oids::RSASSA_PSS);
+    let params = algorithm.rsa_pss_parameters().ok().and_then(|params| params).ok_or_else(unsupported)?;
+    let digest = params.hash_algorithm.algorithm.clone();
+    let mgf_digest = params.mgf1_hash_algorithm().ok_or_else(unsupported)?.algorithm;
+    if mgf_digest != digest || params.trailer_field != 1 {
+        return Err(unsupported());
+    }
+    if key.algorithm.algorithm == oids::RSASSA_PSS {
+        match key.algorithm.rsa_pss_parameters() {
+            Ok(None) => {}
+            Ok(Some(ref restriction)) if permits(restriction, &params) => {}
+            _ => return Err(VerifyError::InvalidKey),
+        }
+    }
+    let salt_len = params.salt_length as usize;
+    Ok((digest, salt_len))
+}
+
+// Hash identifiers may have NULL or absent parameters (RFC 4055 §2.1), so
+// only the algorithms are compared.
+fn permits(restriction: &RsaPssParameters, params: &RsaPssParameters) -> bool {
+    let mgf_digest = |params: &RsaPssParameters| params.mgf1_hash_algorithm().map(|hash| hash.algorithm);
+    restriction.hash_algorithm.algorithm == params.hash_algorithm.algorithm
+        && restriction.mask_gen_algorithm.algorithm == params.mask_gen_algorithm.algorithm
+        && mgf_digest(restriction) == mgf_digest(params)
+        && restriction.salt_length <= params.salt_length
+}
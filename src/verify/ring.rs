@@ -0,0 +1,165 @@
+use ring::signature::{self, UnparsedPublicKey, VerificationAlgorithm};
+
+use algorithm::AlgorithmIdentifier;
+use der::der_decode;
+use oid::ObjectIdentifier;
+use oids;
+use spki::{RsaPublicKey, SubjectPublicKeyInfo};
+use verify::pss::pss_parameters;
+use verify::{SignatureVerifier, VerifyError};
+
+/// Verification with ring.
+///
+/// Supports RSA PKCS#1 v1.5 and RSA-PSS with 2048 to 8192 bit keys, each
+/// with SHA-256, SHA-384 or SHA-512; ECDSA over P-256 and P-384 with
+/// SHA-256 or SHA-384; and Ed25519. ring only checks PSS signatures whose
+/// salt is as long as the digest. SHA-1 signatures are not accepted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ring;
+
+impl SignatureVerifier for Ring {
+    fn verify(
+        &self,
+        algorithm: &AlgorithmIdentifier,
+        key: &SubjectPublicKeyInfo,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), VerifyError> {
+        let oid = &algorithm.algorithm;
+        let verification: Algorithm = if *oid == oids::SHA256_WITH_RSA_ENCRYPTION {
+            check_rsa_key(key, false)?;
+            &signature::RSA_PKCS1_2048_8192_SHA256
+        } else if *oid == oids::SHA384_WITH_RSA_ENCRYPTION {
+            check_rsa_key(key, false)?;
+            &signature::RSA_PKCS1_2048_8192_SHA384
+        } else if *oid == oids::SHA512_WITH_RSA_ENCRYPTION {
+            check_rsa_key(key, false)?;
+            &signature::RSA_PKCS1_2048_8192_SHA512
+        } else if *oid == oids::RSASSA_PSS {
+            let (digest, salt_len) = pss_parameters(algorithm, key)?;
+            check_rsa_key(key, true)?;
+            pss(digest, salt_len)?
+        } else if *oid == oids::ECDSA_WITH_SHA256 {
+            ecdsa(key, oids::SHA256)?
+        } else if *oid == oids::ECDSA_WITH_SHA384 {
+            ecdsa(key, oids::SHA384)?
+        } else if *oid == oids::ED25519 {
+            if key.algorithm.algorithm != oids::ED25519 || key.curve_public_key().is_err() {
+                return Err(VerifyError::InvalidKey);
+            }
+            &signature::ED25519
+        } else {
+            return Err(VerifyError::UnsupportedAlgorithm(oid.clone()));
+        };
+        // ring takes RSA keys as the RSAPublicKey, EC keys as the point
+        // and Ed25519 keys as the bare key, each just as the SPKI holds it.
+        UnparsedPublicKey::new(verification, &key.subject_public_key)
+            .verify(message, signature)
+            .map_err(|_| VerifyError::BadSignature)
+    }
+}
+
+// ring reports a key it won't use as a bad signature, so the key is
+// checked here first to tell the two apart.
+fn check_rsa_key(key: &SubjectPublicKeyInfo, pss: bool) -> Result<(), VerifyError> {
+    let oid = &key.algorithm.algorithm;
+    if *oid != oids::RSA_ENCRYPTION && !(pss && *oid == oids::RSASSA_PSS) {
+        return Err(VerifyError::InvalidKey);
+    }
+    match der_decode::<RsaPublicKey>(&key.subject_public_key).map(|rsa_key| rsa_key.bits()) {
+        Ok(2048..=8192) => Ok(()),
+        _ => Err(VerifyError::InvalidKey),
+    }
+}
+
+type Algorithm = &'static dyn VerificationAlgorithm;
+
+fn pss(digest: ObjectIdentifier, salt_len: usize) -> Result<Algorithm, VerifyError> {
+    let (verification, digest_len): (Algorithm, usize) = if digest == oids::SHA256 {
+        (&signature::RSA_PSS_2048_8192_SHA256, 32)
+    } else if digest == oids::SHA384 {
+        (&signature::RSA_PSS_2048_8192_SHA384, 48)
+    } else if digest == oids::SHA512 {
+        (&signature::RSA_PSS_2048_8192_SHA512, 64)
+    } else {
+        return Err(VerifyError::UnsupportedAlgorithm(digest));
+    };
+    if salt_len != digest_len {
+        return Err(VerifyError::UnsupportedAlgorithm(oids::RSASSA_PSS));
+    }
+    Ok(verification)
+}
+
+// `digest` is SHA-256 or SHA-384.
+fn ecdsa(key: &SubjectPublicKeyInfo, digest: ObjectIdentifier) -> Result<Algorithm, VerifyError> {
+    let curve = key.ec_public_key().map_err(|_| VerifyError::InvalidKey)?.curve;
+    let sha256 = digest == oids::SHA256;
+    if curve == oids::SECP256R1 {
+        Ok(if sha256 { &signature::ECDSA_P256_SHA256_ASN1 } else { &signature::ECDSA_P256_SHA384_ASN1 })
+    } else if curve == oids::SECP384R1 {
+        Ok(if sha256 { &signature::ECDSA_P384_SHA256_ASN1 } else { &signature::ECDSA_P384_SHA384_ASN1 })
+    } else {
+        Err(VerifyError::UnsupportedAlgorithm(curve))
+    }
+}
+
+#[cfg(test)]
+mod ring_tests {
+    use simple_asn1::der_encode;
+
+    use super::Ring;
+    use algorithm::AlgorithmIdentifier;
+    use csr::CertificationRequest;
+    use der::der_decode;
+    use oids;
+    use verify::{SignatureVerifier, VerifyError};
+    use x509::Certificate;
+
+    const EC_LEAF_CSR: &[u8] = include_bytes!("../../testdata/ec-leaf-csr.der");
+    const RSA_CSR: &[u8] = include_bytes!("../../testdata/rsa2048-csr.der");
+    const RSA_PSS_ROOT: &[u8] = include_bytes!("../../testdata/rsa-pss-root.der");
+    const ED25519_ROOT: &[u8] = include_bytes!("../../testdata/ed25519-root.der");
+
+    fn check(csr: &CertificationRequest) -> Result<(), VerifyError> {
+        let info = &csr.certification_request_info;
+        let message = der_encode(info).unwrap();
+        Ring.verify(&csr.signature_algorithm, &info.subject_pk_info, &message, &csr.signature)
+    }
+
+    fn check_self_signed(cert: &Certificate) -> Result<(), VerifyError> {
+        cert.verify_signed_by_with(&cert.tbs_certificate.subject_public_key_info, &Ring)
+    }
+
+    #[test]
+    fn ring_verifies_csrs() {
+        for der in &[EC_LEAF_CSR, RSA_CSR] {
+            let mut csr = der_decode::<CertificationRequest>(der).unwrap();
+            assert_eq!(Ok(()), check(&csr));
+            csr.signature[4] ^= 1;
+            assert_eq!(Err(VerifyError::BadSignature), check(&csr));
+        }
+    }
+
+    #[test]
+    fn ring_verifies_certificates() {
+        for der in &[RSA_PSS_ROOT, ED25519_ROOT] {
+            let mut cert = der_decode::<Certificate>(der).unwrap();
+            assert_eq!(Ok(()), check_self_signed(&cert));
+            cert.signature_value[0] ^= 1;
+            assert_eq!(Err(VerifyError::BadSignature), check_self_signed(&cert));
+        }
+    }
+
+    #[test]
+    fn ring_rejects_unsupported() {
+        let mut csr = der_decode::<CertificationRequest>(EC_LEAF_CSR).unwrap();
+        csr.signature_algorithm = AlgorithmIdentifier::with_null_parameters(oids::SHA256_WITH_RSA_ENCRYPTION);
+        assert_eq!(Err(VerifyError::InvalidKey), check(&csr));
+        csr.signature_algorithm = AlgorithmIdentifier::new(oids::ECDSA_WITH_SHA512);
+        assert_eq!(Err(VerifyError::UnsupportedAlgorithm(oids::ECDSA_WITH_SHA512)), check(&csr));
+
+        let mut csr = der_decode::<CertificationRequest>(RSA_CSR).unwrap();
+        csr.signature_algorithm = AlgorithmIdentifier::with_null_parameters(oids::SHA1_WITH_RSA_ENCRYPTION);
+        assert_eq!(Err(VerifyError::UnsupportedAlgorithm(oids::SHA1_WITH_RSA_ENCRYPTION)), check(&csr));
+    }
+}
@@ -0,0 +1,235 @@
+use std::convert::TryFrom;
+
+use ed25519_dalek;
+use p256;
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use p384;
+use rsa::{self, Pkcs1v15Sign, Pss};
+use sha2::digest::const_oid::AssociatedOid;
+use sha2::digest::DynDigest;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use algorithm::AlgorithmIdentifier;
+use der::der_decode;
+use oids;
+use spki::{self, SubjectPublicKeyInfo};
+use verify::pss::pss_parameters;
+use verify::{SignatureVerifier, VerifyError};
+
+/// Verification with the RustCrypto crates.
+///
+/// Supports RSA PKCS#1 v1.5, RSA-PSS with MGF1, and ECDSA over P-256 and
+/// P-384, each with SHA-256, SHA-384 or SHA-512, and Ed25519. SHA-1
+/// signatures are not accepted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RustCrypto;
+
+impl SignatureVerifier for RustCrypto {
+    fn verify(
+        &self,
+        algorithm: &AlgorithmIdentifier,
+        key: &SubjectPublicKeyInfo,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), VerifyError> {
+        let oid = &algorithm.algorithm;
+        if *oid == oids::SHA256_WITH_RSA_ENCRYPTION {
+            pkcs1v15::<Sha256>(key, message, signature)
+        } else if *oid == oids::SHA384_WITH_RSA_ENCRYPTION {
+            pkcs1v15::<Sha384>(key, message, signature)
+        } else if *oid == oids::SHA512_WITH_RSA_ENCRYPTION {
+            pkcs1v15::<Sha512>(key, message, signature)
+        } else if *oid == oids::RSASSA_PSS {
+            let (digest, salt_len) = pss_parameters(algorithm, key)?;
+            if digest == oids::SHA256 {
+                pss::<Sha256>(key, message, signature, salt_len)
+            } else if digest == oids::SHA384 {
+                pss::<Sha384>(key, message, signature, salt_len)
+            } else if digest == oids::SHA512 {
+                pss::<Sha512>(key, message, signature, salt_len)
+            } else {
+                Err(VerifyError::UnsupportedAlgorithm(digest))
+            }
+        } else if *oid == oids::ECDSA_WITH_SHA256 {
+            ecdsa(key, &Sha256::digest(message), signature)
+        } else if *oid == oids::ECDSA_WITH_SHA384 {
+            ecdsa(key, &Sha384::digest(message), signature)
+        } else if *oid == oids::ECDSA_WITH_SHA512 {
+            ecdsa(key, &Sha512::digest(message), signature)
+        } else if *oid == oids::ED25519 {
+            ed25519(key, message, signature)
+        } else {
+            Err(VerifyError::UnsupportedAlgorithm(oid.clone()))
+        }
+    }
+}
+
+fn pkcs1v15<D: Digest + AssociatedOid>(
+    key: &SubjectPublicKeyInfo,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), VerifyError> {
+    let key = rsa_key(key.rsa_public_key().map_err(|_| VerifyError::InvalidKey)?)?;
+    key.verify(Pkcs1v15Sign::new::<D>(), &D::digest(message), signature).map_err(|_| VerifyError::BadSignature)
+}
+
+// A PSS signature may be made with an rsaEncryption key or with one
+// restricted to RSASSA-PSS; both hold an RSAPublicKey.
+fn pss<D: 'static + Digest + DynDigest + Send + Sync>(
+    key: &SubjectPublicKeyInfo,
+    message: &[u8],
+    signature: &[u8],
+    salt_len: usize,
+) -> Result<(), VerifyError> {
+    let oid = &key.algorithm.algorithm;
+    if *oid != oids::RSA_ENCRYPTION && *oid != oids::RSASSA_PSS {
+        return Err(VerifyError::InvalidKey);
+    }
+    let key = rsa_key(der_decode(&key.subject_public_key).map_err(|_| VerifyError::InvalidKey)?)?;
+    key.verify(Pss::new_with_salt::<D>(salt_len), &D::digest(message), signature).map_err(|_| VerifyError::BadSignature)
+}
+
+fn rsa_key(key: spki::RsaPublicKey) -> Result<rsa::RsaPublicKey, VerifyError> {
+    rsa::RsaPublicKey::new(
+        rsa::BigUint::from_bytes_be(&key.modulus.to_bytes_be()),
+        rsa::BigUint::from_bytes_be(&key.public_exponent.to_bytes_be()),
+    )
+    .map_err(|_| VerifyError::InvalidKey)
+}
+
+// The digest is truncated to the curve's order as ECDSA requires, so any
+// of the SHA-2 digests works with either curve.
+fn ecdsa(key: &SubjectPublicKeyInfo, digest: &[u8], signature: &[u8]) -> Result<(), VerifyError> {
+    let key = key.ec_public_key().map_err(|_| VerifyError::InvalidKey)?;
+    if key.curve == oids::SECP256R1 {
+        let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&key.point).map_err(|_| VerifyError::InvalidKey)?;
+        let signature = p256::ecdsa::Signature::from_der(signature).map_err(|_| VerifyError::BadSignature)?;
+        key.verify_prehash(digest, &signature).map_err(|_| VerifyError::BadSignature)
+    } else if key.curve == oids::SECP384R1 {
+        let key = p384::ecdsa::VerifyingKey::from_sec1_bytes(&key.point).map_err(|_| VerifyError::InvalidKey)?;
+        let signature = p384::ecdsa::Signature::from_der(signature).map_err(|_| VerifyError::BadSignature)?;
+        key.verify_prehash(digest, &signature).map_err(|_| VerifyError::BadSignature)
+    } else {
+        Err(VerifyError::UnsupportedAlgorithm(key.curve))
+    }
+}
+
+// Ed25519 keys are the bare 32 byte point (RFC 8410 §4). Verification is
+// strict, refusing small-order keys and non-canonical signatures.
+fn ed25519(key: &SubjectPublicKeyInfo, message: &[u8], signature: &[u8]) -> Result<(), VerifyError> {
+    if key.algorithm.algorithm != oids::ED25519 {
+        return Err(VerifyError::InvalidKey);
+    }
+    let key = key.curve_public_key().map_err(|_| VerifyError::InvalidKey)?;
+    let key = ed25519_dalek::VerifyingKey::try_from(key).map_err(|_| VerifyError::InvalidKey)?;
+    let signature = ed25519_dalek::Signature::from_slice(signature).map_err(|_| VerifyError::BadSignature)?;
+    key.verify_strict(message, &signature).map_err(|_| VerifyError::BadSignature)
+}
+
+#[cfg(test)]
+mod rust_crypto_tests {
+    use simple_asn1::{der_encode, ASN1Block};
+
+    use super::RustCrypto;
+    use algorithm::{AlgorithmIdentifier, AlgorithmParameters, RsaPssParameters};
+    use csr::CertificationRequest;
+    use der::der_decode;
+    use oids;
+    use spki::SubjectPublicKeyInfo;
+    use verify::{SignatureVerifier, VerifyError};
+    use x509::Certificate;
+
+    const EC_LEAF_CSR: &[u8] = include_bytes!("../../testdata/ec-leaf-csr.der");
+    const RSA_CSR: &[u8] = include_bytes!("../../testdata/rsa2048-csr.der");
+    // Self-signed with RSA-PSS, SHA-256, MGF1 with SHA-256 and a 32 byte
+    // salt.
+    const RSA_PSS_ROOT: &[u8] = include_bytes!("../../testdata/rsa-pss-root.der");
+    const ED25519_ROOT: &[u8] = include_bytes!("../../testdata/ed25519-root.der");
+
+    fn check(csr: &CertificationRequest) -> Result<(), VerifyError> {
+        let info = &csr.certification_request_info;
+        let message = der_encode(info).unwrap();
+        RustCrypto.verify(&csr.signature_algorithm, &info.subject_pk_info, &message, &csr.signature)
+    }
+
+    #[test]
+    fn rust_crypto_verifies_ecdsa() {
+        let mut csr = der_decode::<CertificationRequest>(EC_LEAF_CSR).unwrap();
+        assert_eq!(Ok(()), check(&csr));
+        let last = csr.signature.len() - 1;
+        csr.signature[last] ^= 1;
+        assert_eq!(Err(VerifyError::BadSignature), check(&csr));
+    }
+
+    #[test]
+    fn rust_crypto_verifies_rsa() {
+        let mut csr = der_decode::<CertificationRequest>(RSA_CSR).unwrap();
+        assert_eq!(Ok(()), check(&csr));
+        csr.signature[0] ^= 1;
+        assert_eq!(Err(VerifyError::BadSignature), check(&csr));
+    }
+
+    #[test]
+    fn rust_crypto_rejects_mismatched_key() {
+        let mut csr = der_decode::<CertificationRequest>(EC_LEAF_CSR).unwrap();
+        csr.signature_algorithm = AlgorithmIdentifier::with_null_parameters(oids::SHA256_WITH_RSA_ENCRYPTION);
+        assert_eq!(Err(VerifyError::InvalidKey), check(&csr));
+    }
+
+    #[test]
+    fn rust_crypto_rejects_sha1() {
+        let mut csr = der_decode::<CertificationRequest>(RSA_CSR).unwrap();
+        csr.signature_algorithm = AlgorithmIdentifier::with_null_parameters(oids::SHA1_WITH_RSA_ENCRYPTION);
+        assert_eq!(Err(VerifyError::UnsupportedAlgorithm(oids::SHA1_WITH_RSA_ENCRYPTION)), check(&csr));
+    }
+
+    #[test]
+    fn rust_crypto_verifies_pss() {
+        let mut cert = der_decode::<Certificate>(RSA_PSS_ROOT).unwrap();
+        let key = cert.tbs_certificate.subject_public_key_info.clone();
+        assert_eq!(Ok(()), cert.verify_signed_by_with(&key, &RustCrypto));
+
+        // The same key restricted to PSS, with and without parameters.
+        let rsa_key = key.rsa_public_key().unwrap();
+        let pss_key = SubjectPublicKeyInfo::from_rsa_pss_public_key(&rsa_key, None).unwrap();
+        assert_eq!(Ok(()), cert.verify_signed_by_with(&pss_key, &RustCrypto));
+        let params = RsaPssParameters::new(oids::SHA256, 20);
+        let pss_key = SubjectPublicKeyInfo::from_rsa_pss_public_key(&rsa_key, Some(&params)).unwrap();
+        assert_eq!(Ok(()), cert.verify_signed_by_with(&pss_key, &RustCrypto));
+        // A key that only makes SHA-384 or longer salts can't have signed it.
+        for params in &[RsaPssParameters::new(oids::SHA384, 48), RsaPssParameters::new(oids::SHA256, 64)] {
+            let pss_key = SubjectPublicKeyInfo::from_rsa_pss_public_key(&rsa_key, Some(params)).unwrap();
+            assert_eq!(Err(VerifyError::InvalidKey), cert.verify_signed_by_with(&pss_key, &RustCrypto));
+        }
+
+        let params = cert.signature_algorithm.parameters.clone();
+        cert.signature_value[0] ^= 1;
+        assert_eq!(Err(VerifyError::BadSignature), cert.verify_signed_by_with(&key, &RustCrypto));
+
+        // RSASSA-PSS-params with every field left to its SHA-1 default.
+        cert.signature_algorithm.parameters = AlgorithmParameters::Other(ASN1Block::Sequence(0, vec![]));
+        assert_eq!(Err(VerifyError::UnsupportedAlgorithm(oids::SHA1)), cert.verify_signed_by_with(&key, &RustCrypto));
+
+        // SHA-256 for the message but the default MGF1 with SHA-1.
+        let mut fields = match params {
+            AlgorithmParameters::Other(ASN1Block::Sequence(_, fields)) => fields,
+            other => panic!("unexpected parameters {:?}", other),
+        };
+        fields.remove(1);
+        cert.signature_algorithm.parameters = AlgorithmParameters::Other(ASN1Block::Sequence(0, fields));
+        let unsupported = VerifyError::UnsupportedAlgorithm(oids::RSASSA_PSS);
+        assert_eq!(Err(unsupported), cert.verify_signed_by_with(&key, &RustCrypto));
+    }
+
+    #[test]
+    fn rust_crypto_verifies_ed25519() {
+        let mut cert = der_decode::<Certificate>(ED25519_ROOT).unwrap();
+        let key = cert.tbs_certificate.subject_public_key_info.clone();
+        assert_eq!(Ok(()), cert.verify_signed_by_with(&key, &RustCrypto));
+        cert.signature_value[0] ^= 1;
+        assert_eq!(Err(VerifyError::BadSignature), cert.verify_signed_by_with(&key, &RustCrypto));
+
+        let rsa_key = der_decode::<Certificate>(RSA_PSS_ROOT).unwrap().tbs_certificate.subject_public_key_info;
+        assert_eq!(Err(VerifyError::InvalidKey), cert.verify_signed_by_with(&rsa_key, &RustCrypto));
+    }
+}
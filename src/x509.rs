@@ -1,90 +1,426 @@
-pub mod x509 {
-    use simple_asn1::{ToASN1, FromASN1, ASN1Block, ASN1Class, ASN1DecodeErr, ASN1EncodeErr};
-    use num::bigint::BigInt;
-    use num::ToPrimitive;
+use std::net::IpAddr;
+
+use num::bigint::BigInt;
+use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+use algorithm::AlgorithmIdentifier;
+use der::{
+    context_tag, decode_with, expect_end, explicit, explicit_inner, implicit_bytes, implicit_primitive, next_block,
+    sequence, SignedBytes,
+};
+use error::X509DecodeErr;
+use extensions::{AuthorityInfoAccess, ExtensionRegistry, Extensions, SubjectAltName, X509Extension};
+use name::Name;
+use pem::{self, Pem};
+use sign::{AsyncSigner, SignError, Signer, Signing};
+use spki::SubjectPublicKeyInfo;
+use validity::Validity;
+#[cfg(feature = "verify")]
+use verify::RustCrypto;
+use verify::{SignatureVerifier, VerifyError};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Version {
+    V1,
+    V2,
+    V3
+}
+
+impl ToASN1 for Version {
+    type Error = ASN1EncodeErr;
 
-    #[derive(Debug, PartialEq)]
-    pub enum Version {
-        V1,
-        V2,
-        V3   
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let val = match *self {
+            Version::V1 => 0,
+            Version::V2 => 1,
+            Version::V3 => 2,
+        };
+        Result::Ok(vec![ASN1Block::Integer(0, BigInt::from(val))])
     }
+}
 
-    impl ToASN1 for Version {
-        type Error = ASN1EncodeErr;
+impl FromASN1 for Version {
+    type Error = X509DecodeErr;
 
-        fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
-            let val = match self {
-                &Version::V1 => 0,
-                &Version::V2 => 1,
-                &Version::V3 => 2,
-            };
-            Result::Ok(vec![ASN1Block::Integer(ASN1Class::Universal, 0, BigInt::from(val))])
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "version")?;
+        match *head {
+            ASN1Block::Integer(_, ref val) => {
+                if val == &BigInt::from(0) {
+                    Ok((Version::V1, tail))
+                }
+                else if val == &BigInt::from(1) {
+                    Ok((Version::V2, tail))
+                }
+                else if val == &BigInt::from(2) {
+                    Ok((Version::V3, tail))
+                }
+                else {
+                    Err(X509DecodeErr::UnsupportedVersion(val.clone()))
+                }
+            },
+            _ => Err(X509DecodeErr::InvalidField("version"))
         }
     }
+}
 
-    impl FromASN1 for Version {
-        type Error = ASN1DecodeErr;
+/// A certificate serial number. RFC 5280 allows up to 20 octets, so this
+/// is not limited to a machine integer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertificateSerialNumber(pub BigInt);
 
-        fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
-            let (head, tail) = v.split_at(1);
-            match head[0] {
-                ASN1Block::Integer(class, _, ref val) => {
-                    match class {
-                        ASN1Class::Universal => {
-                            if val < &BigInt::from(0) || val > &BigInt::from(2) {
-                                return Err(ASN1DecodeErr::UTF8DecodeFailure);
-                            }
-                            else if val == &BigInt::from(0) {
-                                return Ok((Version::V1, &tail));
-                            }
-                            else if val == &BigInt::from(1) {
-                                return Ok((Version::V2, &tail));
-                            }
-                            Ok((Version::V3, &tail))
-                        },
-                        _ => Err(ASN1DecodeErr::UTF8DecodeFailure)
-                    }
-                },
-                _ => Err(ASN1DecodeErr::UTF8DecodeFailure)
-            }
+impl ToASN1 for CertificateSerialNumber {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        Result::Ok(vec![ASN1Block::Integer(0, self.0.clone())])
+    }
+}
+
+impl FromASN1 for CertificateSerialNumber {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "serialNumber")?;
+        match *head {
+            ASN1Block::Integer(_, ref val) => Ok((CertificateSerialNumber(val.clone()), tail)),
+            _ => Err(X509DecodeErr::InvalidField("serialNumber"))
         }
     }
+}
 
-    #[derive(Debug, PartialEq)]
-    pub struct CertificateSerialNumber(pub i64);
+/// The issuerUniqueID and subjectUniqueID BIT STRINGs: the first `nbits`
+/// bits of `bytes`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniqueIdentifier {
+    nbits: usize,
+    bytes: Vec<u8>,
+}
 
-    impl ToASN1 for CertificateSerialNumber {
-        type Error = ASN1EncodeErr;
+impl UniqueIdentifier {
+    /// The first `nbits` bits of `bytes`, failing unless `bytes` is the
+    /// fewest whole bytes that hold them.
+    pub fn new(bytes: Vec<u8>, nbits: usize) -> Result<UniqueIdentifier, X509DecodeErr> {
+        if nbits.div_ceil(8) != bytes.len() {
+            return Err(X509DecodeErr::InvalidField("UniqueIdentifier"));
+        }
+        Ok(UniqueIdentifier { nbits, bytes })
+    }
 
-        fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
-            Result::Ok(vec![ASN1Block::Integer(ASN1Class::Universal, 0, BigInt::from(self.0))])
+    /// The number of bits in the identifier.
+    pub fn nbits(&self) -> usize {
+        self.nbits
+    }
+
+    /// The identifier's bits, padded with zeros to whole bytes.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    // `new` ensures there are from 0 to 7 unused bits.
+    fn to_implicit(&self, tag: u64) -> ASN1Block {
+        let mut body = vec![(self.bytes.len() * 8 - self.nbits) as u8];
+        body.extend_from_slice(&self.bytes);
+        implicit_primitive(tag, body)
+    }
+
+    fn from_optional<'a>(
+        v: &'a [ASN1Block],
+        tag: u64,
+        field: &'static str,
+    ) -> Result<(Option<UniqueIdentifier>, &'a [ASN1Block]), X509DecodeErr> {
+        let (head, tail) = match v.split_first() {
+            Some((head, tail)) if context_tag(head) == Some(tag) => (head, tail),
+            _ => return Ok((None, v))
+        };
+        match implicit_bytes(head, tag).and_then(|body| body.split_first()) {
+            Some((&unused, bytes)) if unused < 8 && (unused == 0 || !bytes.is_empty()) => {
+                let id = UniqueIdentifier { nbits: bytes.len() * 8 - unused as usize, bytes: bytes.to_vec() };
+                Ok((Some(id), tail))
+            },
+            _ => Err(X509DecodeErr::InvalidField(field))
         }
     }
+}
+
+/// The to-be-signed body of a certificate (RFC 5280 §4.1.2).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TBSCertificate {
+    pub version: Version,
+    pub serial_number: CertificateSerialNumber,
+    pub signature: AlgorithmIdentifier,
+    pub issuer: Name,
+    pub validity: Validity,
+    pub subject: Name,
+    pub subject_public_key_info: SubjectPublicKeyInfo,
+    pub issuer_unique_id: Option<UniqueIdentifier>,
+    pub subject_unique_id: Option<UniqueIdentifier>,
+    pub extensions: Option<Extensions>,
+}
 
-    impl FromASN1 for CertificateSerialNumber {
-        type Error = ASN1DecodeErr;
+impl TBSCertificate {
+    /// Sign the certificate with `signer`, setting `signature` to the
+    /// signer's algorithm first so the two match.
+    pub fn sign<S: Signer + ?Sized>(mut self, signer: &S) -> Result<Certificate, SignError> {
+        self.signature = signer.signature_algorithm();
+        let signature_value = signer.sign(&der_encode(&self)?)?;
+        Ok(Certificate {
+            signature_algorithm: self.signature.clone(),
+            tbs_certificate: self,
+            signature_value,
+            signed_bytes: SignedBytes::default(),
+        })
+    }
 
-        fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
-            let (head, tail) = v.split_at(1);
-            match head[0] {
-                ASN1Block::Integer(class, _, ref val) => {
-                    match class {
-                        ASN1Class::Universal => Ok((CertificateSerialNumber(BigInt::to_i64(val).unwrap()), &tail)),
-                        _ => Err(ASN1DecodeErr::UTF8DecodeFailure)
-                    }
-                },
-                _ => Err(ASN1DecodeErr::UTF8DecodeFailure)
+    /// Sign the certificate with `signer`, as for `sign`, once the
+    /// returned future is awaited.
+    pub fn sign_async<S: AsyncSigner + ?Sized>(mut self, signer: &S) -> Signing<'_, Certificate> {
+        self.signature = signer.signature_algorithm();
+        let message = der_encode(&self);
+        Signing::new(signer, message, move |signature_value| Certificate {
+            signature_algorithm: self.signature.clone(),
+            tbs_certificate: self,
+            signature_value,
+            signed_bytes: SignedBytes::default(),
+        })
+    }
+}
+
+impl ToASN1 for TBSCertificate {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = Vec::new();
+        // version is DEFAULT v1, so DER leaves it out for v1 certificates.
+        if self.version != Version::V1 {
+            let version = self.version.to_asn1_class(c)?.remove(0);
+            items.push(explicit(0, version));
+        }
+        items.extend(self.serial_number.to_asn1_class(c)?);
+        items.extend(self.signature.to_asn1_class(c)?);
+        items.extend(self.issuer.to_asn1_class(c)?);
+        items.extend(self.validity.to_asn1_class(c)?);
+        items.extend(self.subject.to_asn1_class(c)?);
+        items.extend(self.subject_public_key_info.to_asn1_class(c)?);
+        if let Some(ref id) = self.issuer_unique_id {
+            items.push(id.to_implicit(1));
+        }
+        if let Some(ref id) = self.subject_unique_id {
+            items.push(id.to_implicit(2));
+        }
+        if let Some(ref extensions) = self.extensions {
+            items.push(explicit(3, extensions.to_asn1_class(c)?.remove(0)));
+        }
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for TBSCertificate {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "TBSCertificate")?;
+        let items = sequence(head, "TBSCertificate")?;
+
+        let (version, rest) = match items.split_first() {
+            Some((first, rest)) if context_tag(first) == Some(0) => {
+                let inner = explicit_inner(first, 0).ok_or(X509DecodeErr::InvalidField("version"))?;
+                let (version, _) = Version::from_asn1(::std::slice::from_ref(inner))?;
+                (version, rest)
+            },
+            _ => (Version::V1, items)
+        };
+        let (serial_number, rest) = CertificateSerialNumber::from_asn1(rest)?;
+        let (signature, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+        let (issuer, rest) = Name::from_asn1(rest)?;
+        let (validity, rest) = Validity::from_asn1(rest)?;
+        let (subject, rest) = Name::from_asn1(rest)?;
+        let (subject_public_key_info, rest) = SubjectPublicKeyInfo::from_asn1(rest)?;
+        let (issuer_unique_id, rest) = UniqueIdentifier::from_optional(rest, 1, "issuerUniqueID")?;
+        let (subject_unique_id, rest) = UniqueIdentifier::from_optional(rest, 2, "subjectUniqueID")?;
+        let (extensions, rest) = match rest.split_first() {
+            Some((block, rest)) if context_tag(block) == Some(3) => {
+                let inner = explicit_inner(block, 3).ok_or(X509DecodeErr::InvalidField("extensions"))?;
+                let (extensions, _) = Extensions::from_asn1(::std::slice::from_ref(inner))?;
+                (Some(extensions), rest)
+            },
+            _ => (None, rest)
+        };
+        expect_end(rest, "TBSCertificate")?;
+
+        Ok((TBSCertificate {
+            version,
+            serial_number,
+            signature,
+            issuer,
+            validity,
+            subject,
+            subject_public_key_info,
+            issuer_unique_id,
+            subject_unique_id,
+            extensions,
+        }, tail))
+    }
+}
+
+/// What to do with a critical extension that no registered type handles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CriticalExtensions {
+    /// Decode the certificate anyway. `Extensions::unhandled_critical`
+    /// lists the extensions concerned.
+    #[default]
+    Allow,
+    /// Fail with `UnhandledCriticalExtension`, as RFC 5280 requires of a
+    /// relying party.
+    Reject,
+}
+
+/// Options for `Certificate::decode`.
+#[derive(Clone, Debug, Default)]
+pub struct DecodeOptions {
+    /// The extension types understood beyond this crate's own.
+    pub registry: ExtensionRegistry,
+    pub critical_extensions: CriticalExtensions,
+}
+
+/// A signed certificate (RFC 5280 §4.1).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Certificate {
+    pub tbs_certificate: TBSCertificate,
+    pub signature_algorithm: AlgorithmIdentifier,
+    pub signature_value: Vec<u8>,
+    pub(crate) signed_bytes: SignedBytes,
+}
+
+impl Certificate {
+    /// Decode a DER certificate, applying `options`.
+    pub fn decode(der: &[u8], options: &DecodeOptions) -> Result<Certificate, X509DecodeErr> {
+        let cert = decode_with(der, |blocks| {
+            let (cert, rest) = Certificate::from_asn1(blocks)?;
+            expect_end(rest, "Certificate").map(|_| cert)
+        })?;
+        if options.critical_extensions == CriticalExtensions::Reject {
+            if let Some(ref extensions) = cert.tbs_certificate.extensions {
+                if let Some(e) = extensions.unhandled_critical(&options.registry).next() {
+                    return Err(X509DecodeErr::UnhandledCriticalExtension(e.extn_id.clone()));
+                }
             }
         }
+        Ok(cert)
+    }
+
+    /// Decode the first `CERTIFICATE` PEM block in `input`.
+    pub fn from_pem(input: &str) -> Result<Certificate, X509DecodeErr> {
+        let der = pem::parse(input)?.into_contents(pem::CERTIFICATE)?;
+        Certificate::decode(&der, &DecodeOptions::default())
+    }
+
+    /// Decode every `CERTIFICATE` PEM block in `input`, such as a chain or
+    /// CA bundle, reporting a failure for each block that doesn't decode.
+    /// Collect the result into a `Result<Vec<Certificate>, _>` to stop at
+    /// the first failure instead.
+    pub fn from_pem_bundle(input: &str) -> Vec<Result<Certificate, X509DecodeErr>> {
+        pem::parse_all(input)
+            .map(|block| {
+                let der = block?.into_contents(pem::CERTIFICATE)?;
+                Certificate::decode(&der, &DecodeOptions::default())
+            })
+            .collect()
+    }
+
+    /// The certificate as a `CERTIFICATE` PEM block.
+    pub fn to_pem(&self) -> Result<String, ASN1EncodeErr> {
+        Ok(Pem::new(pem::CERTIFICATE, der_encode(self)?).encode())
+    }
+
+    /// The extension of type `T`, decoded, if the certificate has one.
+    pub fn extension<T: X509Extension>(&self) -> Result<Option<T>, X509DecodeErr> {
+        match self.tbs_certificate.extensions {
+            Some(ref extensions) => extensions.get(),
+            None => Ok(None),
+        }
+    }
+
+    /// The OCSP responder URLs from the authorityInfoAccess extension.
+    pub fn ocsp_urls(&self) -> Result<Vec<String>, X509DecodeErr> {
+        let aia = self.extension::<AuthorityInfoAccess>()?;
+        Ok(aia.iter().flat_map(|aia| aia.ocsp_urls()).map(String::from).collect())
+    }
+
+    /// Whether `addr` is one of the iPAddress names in the
+    /// subjectAltName extension, as `GeneralNames::matches_ip` compares
+    /// them. The subject's common name is never consulted for addresses.
+    pub fn matches_ip(&self, addr: IpAddr) -> Result<bool, X509DecodeErr> {
+        let san = self.extension::<SubjectAltName>()?;
+        Ok(san.is_some_and(|san| san.matches_ip(addr)))
+    }
+
+    /// The issuing CA certificate URLs from the authorityInfoAccess
+    /// extension.
+    pub fn ca_issuers_urls(&self) -> Result<Vec<String>, X509DecodeErr> {
+        let aia = self.extension::<AuthorityInfoAccess>()?;
+        Ok(aia.iter().flat_map(|aia| aia.ca_issuers_urls()).map(String::from).collect())
+    }
+
+    /// Check that the certificate was signed by the private half of
+    /// `issuer_key`, the key of the CA named as its issuer. Only the
+    /// signature is checked; use `PathValidator` to check the issuer
+    /// can be trusted.
+    #[cfg(feature = "verify")]
+    pub fn verify_signed_by(&self, issuer_key: &SubjectPublicKeyInfo) -> Result<(), VerifyError> {
+        self.verify_signed_by_with(issuer_key, &RustCrypto)
+    }
+
+    /// Check the certificate's signature with the given backend. The
+    /// signature is checked over the bytes `tbs_certificate` was decoded
+    /// from, or its DER encoding if it was built or has been changed.
+    pub fn verify_signed_by_with<V: SignatureVerifier + ?Sized>(
+        &self,
+        issuer_key: &SubjectPublicKeyInfo,
+        verifier: &V,
+    ) -> Result<(), VerifyError> {
+        let message = self.signed_bytes.message(&self.tbs_certificate)?;
+        verifier.verify(&self.signature_algorithm, issuer_key, &message, &self.signature_value)
+    }
+}
+
+impl ToASN1 for Certificate {
+    type Error = ASN1EncodeErr;
+
+    fn to_asn1_class(&self, c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+        let mut items = self.tbs_certificate.to_asn1_class(c)?;
+        items.extend(self.signature_algorithm.to_asn1_class(c)?);
+        let signature = self.signature_value.clone();
+        items.push(ASN1Block::BitString(0, signature.len() * 8, signature));
+        Ok(vec![ASN1Block::Sequence(0, items)])
+    }
+}
+
+impl FromASN1 for Certificate {
+    type Error = X509DecodeErr;
+
+    fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+        let (head, tail) = next_block(v, "Certificate")?;
+        let items = sequence(head, "Certificate")?;
+        let (tbs_certificate, rest) = TBSCertificate::from_asn1(items)?;
+        let signed_bytes = SignedBytes::decoded(&items[0], &tbs_certificate);
+        let (signature_algorithm, rest) = AlgorithmIdentifier::from_asn1(rest)?;
+        let (signature_value, rest) = match next_block(rest, "signatureValue")? {
+            (ASN1Block::BitString(_, nbits, bytes), rest) if *nbits == bytes.len() * 8 => (bytes.clone(), rest),
+            _ => return Err(X509DecodeErr::InvalidField("signatureValue"))
+        };
+        expect_end(rest, "Certificate")?;
+        Ok((Certificate { tbs_certificate, signature_algorithm, signature_value, signed_bytes }, tail))
     }
 }
 
 #[cfg(test)]
 mod version_tests {
-    use simple_asn1::{der_decode, der_encode, from_der, FromASN1, ASN1Block, ASN1DecodeErr};
+    use num::bigint::BigInt;
+    use simple_asn1::{der_decode, der_encode, from_der, FromASN1, ASN1Block};
 
-    use super::x509::Version;
+    use super::Version;
+    use error::X509DecodeErr;
 
     #[test]
     fn version_encodes_v1_correctly() {
@@ -110,41 +446,41 @@ mod version_tests {
     #[test]
     fn version_decodes_v1_correctly() {
         let expected = Version::V1;
-        let actual = der_decode::<Version>(&vec![0x02, 0x01, 0x00]).unwrap();
+        let actual = der_decode::<Version>(&[0x02, 0x01, 0x00]).unwrap();
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn version_decodes_v2_correctly() {
         let expected = Version::V2;
-        let actual = der_decode::<Version>(&vec![0x02, 0x01, 0x01]).unwrap();
+        let actual = der_decode::<Version>(&[0x02, 0x01, 0x01]).unwrap();
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn version_decodes_v3_correctly() {
         let expected = Version::V3;
-        let actual = der_decode::<Version>(&vec![0x02, 0x01, 0x02]).unwrap();
+        let actual = der_decode::<Version>(&[0x02, 0x01, 0x02]).unwrap();
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn version_should_err_when_unsupported_version_supplied() {
-        let error = der_decode::<Version>(&vec![0x02, 0x01, 0x03]).unwrap_err();
-        assert_eq!(error, ASN1DecodeErr::UTF8DecodeFailure)
+        let error = der_decode::<Version>(&[0x02, 0x01, 0x03]).unwrap_err();
+        assert_eq!(error, X509DecodeErr::UnsupportedVersion(BigInt::from(3)))
     }
 
     #[test]
     fn version_should_not_break_decoding_subsequent_blocks() {
         // ASN.1 Sequence of 3 Versions: v1, v2 & v3
-        let test_data = vec![0x30, 0x09, 0x02, 0x01, 0x00, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02];
-        let expected = vec![Version::V1, Version::V2, Version::V3];
+        let test_data = [0x30, 0x09, 0x02, 0x01, 0x00, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02];
+        let expected = [Version::V1, Version::V2, Version::V3];
         let seq = &from_der(&test_data).unwrap()[0];
-        match seq {
-            &ASN1Block::Sequence(_, _, ref blocks) => {
-                let (first_actual, first_tail) = Version::from_asn1(&blocks).unwrap();
-                let (second_actual, second_tail) = Version::from_asn1(&first_tail).unwrap();
-                let (third_actual, _) = Version::from_asn1(&second_tail).unwrap();
+        match *seq {
+            ASN1Block::Sequence(_, ref blocks) => {
+                let (first_actual, first_tail) = Version::from_asn1(blocks).unwrap();
+                let (second_actual, second_tail) = Version::from_asn1(first_tail).unwrap();
+                let (third_actual, _) = Version::from_asn1(second_tail).unwrap();
                 assert_eq!(expected[0], first_actual);
                 assert_eq!(expected[1], second_actual);
                 assert_eq!(expected[2], third_actual);
@@ -156,16 +492,18 @@ mod version_tests {
 
 #[cfg(test)]
 mod certificate_serial_numbers_tests {
+    use num::bigint::BigInt;
     use simple_asn1::{der_decode, der_encode};
 
-    use super::x509::CertificateSerialNumber;
+    use super::CertificateSerialNumber;
 
     macro_rules! decoding_test {
         ($name:ident, $input:expr, $expected:expr) => {
             #[test]
             fn $name() {
                 let actual = der_decode::<CertificateSerialNumber>($input).unwrap();
-                let expected = CertificateSerialNumber($expected);
+                let expected: i64 = $expected;
+                let expected = CertificateSerialNumber(BigInt::from(expected));
                 assert_eq!(expected, actual);
             }
         }
@@ -175,20 +513,21 @@ mod certificate_serial_numbers_tests {
         ($name:ident, $input:expr, $expected:expr) => {
             #[test]
             fn $name() {
-                let actual = der_encode(&CertificateSerialNumber($input)).unwrap();
+                let input: i64 = $input;
+                let actual = der_encode(&CertificateSerialNumber(BigInt::from(input))).unwrap();
                 let expected = $expected;
                 assert_eq!(expected, actual);
             }
         }
     }
 
-    decoding_test!(certificate_serial_number_should_decode_0, &vec![0x02, 0x01, 0x00], 0);
-    decoding_test!(certificate_serial_number_should_decode_1, &vec![0x02, 0x01, 0x01], 1);
-    decoding_test!(certificate_serial_number_should_decode_negative_1, &vec![0x02, 0x01, 0xFF], -1);
-    decoding_test!(certificate_serial_number_should_decode_negative_42, &vec![0x02, 0x01, 0xD6], -42);
-    decoding_test!(certificate_serial_number_should_decode_42, &vec![0x02, 0x01, 0x2A], 42);
-    decoding_test!(certificate_serial_number_should_decode_i64_max, &vec![0x02, 0x08, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], 9223372036854775807);
-    decoding_test!(certificate_serial_number_should_decode_i64_min, &vec![0x02, 0x08, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], -9223372036854775808);
+    decoding_test!(certificate_serial_number_should_decode_0, &[0x02, 0x01, 0x00], 0);
+    decoding_test!(certificate_serial_number_should_decode_1, &[0x02, 0x01, 0x01], 1);
+    decoding_test!(certificate_serial_number_should_decode_negative_1, &[0x02, 0x01, 0xFF], -1);
+    decoding_test!(certificate_serial_number_should_decode_negative_42, &[0x02, 0x01, 0xD6], -42);
+    decoding_test!(certificate_serial_number_should_decode_42, &[0x02, 0x01, 0x2A], 42);
+    decoding_test!(certificate_serial_number_should_decode_i64_max, &[0x02, 0x08, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], 9223372036854775807);
+    decoding_test!(certificate_serial_number_should_decode_i64_min, &[0x02, 0x08, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], -9223372036854775808);
 
     encoding_test!(certificate_serial_number_should_encode_0, 0, vec![0x02, 0x01, 0x00]);
     encoding_test!(certificate_serial_number_should_encode_1, 1, vec![0x02, 0x01, 0x01]);
@@ -197,4 +536,299 @@ mod certificate_serial_numbers_tests {
     encoding_test!(certificate_serial_number_should_encode_42, 42, vec![0x02, 0x01, 0x2A]);
     encoding_test!(certificate_serial_number_should_encode_i64_max, 9223372036854775807, vec![0x02, 0x08, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
     encoding_test!(certificate_serial_number_should_encode_i64_min, -9223372036854775808, vec![0x02, 0x08, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
-}
\ No newline at end of file
+
+    #[test]
+    fn certificate_serial_number_should_round_trip_20_octets() {
+        let input = [0x02, 0x14, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0x01, 0x23, 0x45, 0x67];
+        let actual = der_decode::<CertificateSerialNumber>(&input).unwrap();
+        assert_eq!(BigInt::parse_bytes(b"0123456789abcdef0123456789abcdef01234567", 16).unwrap(), actual.0);
+        assert_eq!(input.to_vec(), der_encode(&actual).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod tbs_certificate_tests {
+    use num::bigint::BigInt;
+    use simple_asn1::{der_encode, to_der, ASN1Block, FromASN1};
+
+    use super::{TBSCertificate, UniqueIdentifier, Version};
+    use der::{der_decode, from_der};
+    use error::X509DecodeErr;
+    use time::{Date, Month, PrimitiveDateTime, Time as TimeOfDay};
+    use validity::Time;
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_V1: &[u8] = include_bytes!("../testdata/ec-v1.der");
+
+    fn utc(year: i32, month: Month, day: u8, hour: u8, minute: u8, second: u8) -> PrimitiveDateTime {
+        let date = Date::from_calendar_date(year, month, day).unwrap();
+        PrimitiveDateTime::new(date, TimeOfDay::from_hms(hour, minute, second).unwrap())
+    }
+
+    // The DER of the tbsCertificate field of a whole certificate.
+    fn tbs_der(cert: &[u8]) -> Vec<u8> {
+        match from_der(cert).unwrap()[0] {
+            ASN1Block::Sequence(_, ref items) => to_der(&items[0]).unwrap(),
+            _ => panic!("Not a sequence")
+        }
+    }
+
+    #[test]
+    fn tbs_certificate_decodes_v3_fields() {
+        let tbs = der_decode::<TBSCertificate>(&tbs_der(EC_ROOT)).unwrap();
+        assert_eq!(Version::V3, tbs.version);
+        assert_eq!(BigInt::parse_bytes(b"0123456789abcdef0123456789abcdef01234567", 16).unwrap(), tbs.serial_number.0);
+        assert_eq!(oid!(1, 2, 840, 10045, 4, 3, 2), tbs.signature.algorithm);
+        assert_eq!(oid!(1, 2, 840, 10045, 2, 1), tbs.subject_public_key_info.algorithm.algorithm);
+        assert_eq!(tbs.issuer, tbs.subject);
+        assert_eq!(Some("Test Root"), tbs.subject.common_name());
+        assert_eq!(Some("QuickLime"), tbs.subject.organization());
+        assert_eq!(Time::UTCTime(utc(2026, Month::October, 14, 18, 44, 27)), tbs.validity.not_before);
+        assert_eq!(Time::UTCTime(utc(2036, Month::October, 11, 18, 44, 27)), tbs.validity.not_after);
+        let extensions = tbs.extensions.unwrap();
+        assert!(extensions.iter().any(|e| e.extn_id == oid!(2, 5, 29, 15) && e.critical));
+    }
+
+    #[test]
+    fn tbs_certificate_decodes_v1_without_extensions() {
+        let tbs = der_decode::<TBSCertificate>(&tbs_der(EC_V1)).unwrap();
+        assert_eq!(Version::V1, tbs.version);
+        assert_eq!(BigInt::from(7), tbs.serial_number.0);
+        assert_eq!(None, tbs.extensions);
+    }
+
+    #[test]
+    fn tbs_certificate_round_trips() {
+        for cert in &[EC_ROOT, EC_V1] {
+            let input = tbs_der(cert);
+            let tbs = der_decode::<TBSCertificate>(&input).unwrap();
+            assert_eq!(input, der_encode(&tbs).unwrap());
+        }
+    }
+
+    #[test]
+    fn tbs_certificate_round_trips_unique_identifiers() {
+        let mut tbs = der_decode::<TBSCertificate>(&tbs_der(EC_ROOT)).unwrap();
+        tbs.issuer_unique_id = Some(UniqueIdentifier::new(vec![0xAB, 0xC0], 12).unwrap());
+        tbs.subject_unique_id = Some(UniqueIdentifier::new(vec![0x42], 8).unwrap());
+        let encoded = der_encode(&tbs).unwrap();
+        assert_eq!(tbs, der_decode::<TBSCertificate>(&encoded).unwrap());
+    }
+
+    #[test]
+    fn unique_identifier_checks_its_length() {
+        let invalid = Err(X509DecodeErr::InvalidField("UniqueIdentifier"));
+        assert_eq!(invalid, UniqueIdentifier::new(vec![0xAB], 12));
+        assert_eq!(invalid, UniqueIdentifier::new(vec![0xAB, 0x00], 8));
+        assert_eq!(invalid, UniqueIdentifier::new(vec![0x00], 0));
+        let empty = UniqueIdentifier::new(vec![], 0).unwrap();
+        assert_eq!((0, &[][..]), (empty.nbits(), empty.bytes()));
+    }
+
+    #[test]
+    fn tbs_certificate_rejects_unknown_trailing_fields() {
+        let mut blocks = from_der(&tbs_der(EC_V1)).unwrap();
+        if let ASN1Block::Sequence(_, ref mut items) = blocks[0] {
+            items.push(ASN1Block::Null(0));
+        }
+        let error = TBSCertificate::from_asn1(&blocks).unwrap_err();
+        assert_eq!(X509DecodeErr::TrailingData("TBSCertificate"), error);
+    }
+}
+
+#[cfg(test)]
+mod certificate_tests {
+    use simple_asn1::{der_encode, ASN1Block, ASN1Class, ASN1EncodeErr, FromASN1, ToASN1};
+
+    use super::{Certificate, CriticalExtensions, DecodeOptions, Version};
+    use der::{der_decode, next_block};
+    use error::X509DecodeErr;
+    use extensions::X509Extension;
+    use oid::ObjectIdentifier;
+    use pem::PemDecodeErr;
+
+    const EC_ROOT: &[u8] = include_bytes!("../testdata/ec-root.der");
+    const EC_V1: &[u8] = include_bytes!("../testdata/ec-v1.der");
+    const EC_LEAF: &[u8] = include_bytes!("../testdata/ec-leaf.der");
+    const EC_UNKNOWN_EXT: &[u8] = include_bytes!("../testdata/ec-unknown-ext.der");
+    const EC_ROOT_PEM: &str = include_str!("../testdata/ec-root.pem");
+
+    // The critical private extension in EC_UNKNOWN_EXT, kept opaque.
+    struct Private;
+
+    impl ToASN1 for Private {
+        type Error = ASN1EncodeErr;
+
+        fn to_asn1_class(&self, _c: ASN1Class) -> Result<Vec<ASN1Block>, Self::Error> {
+            Ok(vec![ASN1Block::Null(0)])
+        }
+    }
+
+    impl FromASN1 for Private {
+        type Error = X509DecodeErr;
+
+        fn from_asn1(v: &[ASN1Block]) -> Result<(Self, &[ASN1Block]), Self::Error> {
+            next_block(v, "Private").map(|(_, rest)| (Private, rest))
+        }
+    }
+
+    impl X509Extension for Private {
+        const OID: ObjectIdentifier = oid!(1, 3, 6, 1, 4, 1, 99999, 2, 1);
+    }
+
+    #[test]
+    fn certificate_ocsp_urls() {
+        let cert = der_decode::<Certificate>(EC_LEAF).unwrap();
+        assert_eq!(vec!["http://ocsp.example.com/".to_string()], cert.ocsp_urls().unwrap());
+        assert_eq!(vec!["http://ca.example.com/root.der".to_string()], cert.ca_issuers_urls().unwrap());
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        assert!(root.ocsp_urls().unwrap().is_empty());
+    }
+
+    #[test]
+    fn certificate_matches_ip() {
+        let cert = der_decode::<Certificate>(EC_LEAF).unwrap();
+        assert_eq!(Ok(true), cert.matches_ip("192.0.2.1".parse().unwrap()));
+        assert_eq!(Ok(true), cert.matches_ip("2001:db8::1".parse().unwrap()));
+        assert_eq!(Ok(false), cert.matches_ip("192.0.2.2".parse().unwrap()));
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        assert_eq!(Ok(false), root.matches_ip("192.0.2.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn certificate_decodes() {
+        let cert = der_decode::<Certificate>(EC_ROOT).unwrap();
+        assert_eq!(Version::V3, cert.tbs_certificate.version);
+        assert_eq!(oid!(1, 2, 840, 10045, 4, 3, 2), cert.signature_algorithm.algorithm);
+        assert_eq!(cert.tbs_certificate.signature, cert.signature_algorithm);
+        // An ECDSA signature is a DER SEQUENCE of two INTEGERs.
+        assert_eq!(0x30, cert.signature_value[0]);
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn certificate_verify_signed_by() {
+        use verify::VerifyError;
+
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let mut leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let root_key = &root.tbs_certificate.subject_public_key_info;
+        assert_eq!(Ok(()), leaf.verify_signed_by(root_key));
+        assert_eq!(Ok(()), root.verify_signed_by(root_key));
+        let leaf_key = leaf.tbs_certificate.subject_public_key_info.clone();
+        assert_eq!(Err(VerifyError::BadSignature), leaf.verify_signed_by(&leaf_key));
+        leaf.tbs_certificate.subject = root.tbs_certificate.subject.clone();
+        assert_eq!(Err(VerifyError::BadSignature), leaf.verify_signed_by(root_key));
+    }
+
+    #[cfg(feature = "verify")]
+    #[test]
+    fn certificate_verifies_over_decoded_bytes() {
+        use p256::ecdsa::SigningKey;
+
+        use der::pad_first_integer;
+        use verify::VerifyError;
+
+        const EC_ROOT_KEY: [u8; 32] = [
+            0xBE, 0x92, 0x7C, 0xE1, 0x0C, 0x45, 0x2F, 0x7E, 0x07, 0xF0, 0x8C, 0xEC, 0x0A, 0x8C, 0x74, 0xDB, 0xFE,
+            0x33, 0x74, 0x4C, 0x7F, 0x09, 0x1B, 0xAF, 0x55, 0x0B, 0x5A, 0x52, 0x6F, 0xD3, 0x2D, 0x60,
+        ];
+
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let root_key = &root.tbs_certificate.subject_public_key_info;
+        let key = SigningKey::from_bytes(&EC_ROOT_KEY.into()).unwrap();
+        // Signed as it is encoded, which re-encoding the leaf won't give.
+        let signed = pad_first_integer(EC_LEAF, Some(&key));
+        let leaf = der_decode::<Certificate>(&signed).unwrap();
+        assert_ne!(signed, der_encode(&leaf).unwrap());
+        assert_eq!(Ok(()), leaf.verify_signed_by(root_key));
+        assert_eq!(Ok(()), Certificate::decode(&signed, &DecodeOptions::default()).unwrap().verify_signed_by(root_key));
+
+        // The root signed the DER, not this.
+        let altered = der_decode::<Certificate>(&pad_first_integer(EC_LEAF, None)).unwrap();
+        assert_eq!(Err(VerifyError::BadSignature), altered.verify_signed_by(root_key));
+
+        // A decoded certificate that is then changed is checked as it is.
+        let mut changed = leaf.clone();
+        changed.tbs_certificate.subject = root.tbs_certificate.subject.clone();
+        assert_eq!(Err(VerifyError::BadSignature), changed.verify_signed_by(root_key));
+    }
+
+    #[test]
+    fn certificate_round_trips() {
+        for input in &[EC_ROOT, EC_V1] {
+            let cert = der_decode::<Certificate>(input).unwrap();
+            assert_eq!(input.to_vec(), der_encode(&cert).unwrap());
+        }
+    }
+
+    #[test]
+    fn certificate_requires_signature() {
+        let cert = der_decode::<Certificate>(EC_V1).unwrap();
+        let mut items = cert.tbs_certificate.to_asn1().unwrap();
+        items.extend(cert.signature_algorithm.to_asn1().unwrap());
+        let error = Certificate::from_asn1(&[ASN1Block::Sequence(0, items)]).unwrap_err();
+        assert_eq!(X509DecodeErr::MissingField("signatureValue"), error);
+    }
+
+    #[test]
+    fn certificate_decode_allows_unhandled_critical_by_default() {
+        let options = DecodeOptions::default();
+        let cert = Certificate::decode(EC_UNKNOWN_EXT, &options).unwrap();
+        let extensions = cert.tbs_certificate.extensions.unwrap();
+        let unhandled: Vec<_> = extensions.unhandled_critical(&options.registry).map(|e| &e.extn_id).collect();
+        assert_eq!(vec![&Private::OID], unhandled);
+    }
+
+    #[test]
+    fn certificate_decode_rejects_unhandled_critical() {
+        let mut options = DecodeOptions { critical_extensions: CriticalExtensions::Reject, ..Default::default() };
+        assert_eq!(
+            Err(X509DecodeErr::UnhandledCriticalExtension(Private::OID)),
+            Certificate::decode(EC_UNKNOWN_EXT, &options)
+        );
+        assert!(Certificate::decode(EC_LEAF, &options).is_ok());
+
+        options.registry.register::<Private>();
+        assert!(Certificate::decode(EC_UNKNOWN_EXT, &options).is_ok());
+    }
+
+    #[test]
+    fn certificate_pem_round_trips() {
+        let cert = Certificate::from_pem(EC_ROOT_PEM).unwrap();
+        assert_eq!(der_decode::<Certificate>(EC_ROOT).unwrap(), cert);
+        assert_eq!(EC_ROOT_PEM, cert.to_pem().unwrap());
+    }
+
+    #[test]
+    fn certificate_from_pem_checks_label() {
+        let input = EC_ROOT_PEM.replace("CERTIFICATE", "X509 CRL");
+        assert_eq!(
+            Err(X509DecodeErr::PemError(PemDecodeErr::UnexpectedLabel("X509 CRL".to_string()))),
+            Certificate::from_pem(&input)
+        );
+    }
+
+    #[test]
+    fn certificate_from_pem_bundle() {
+        let leaf = der_decode::<Certificate>(EC_LEAF).unwrap();
+        let root = der_decode::<Certificate>(EC_ROOT).unwrap();
+        let bundle = format!("{}\n# not a certificate\n{}", leaf.to_pem().unwrap(), EC_ROOT_PEM);
+        let certs = Certificate::from_pem_bundle(&bundle).into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(vec![leaf, root], certs);
+    }
+
+    #[test]
+    fn certificate_from_pem_bundle_reports_each_entry() {
+        let key = "-----BEGIN PUBLIC KEY-----\nZm9v\n-----END PUBLIC KEY-----\n";
+        let junk = "-----BEGIN CERTIFICATE-----\nZm9v\n-----END CERTIFICATE-----\n";
+        let results = Certificate::from_pem_bundle(&format!("{}{}{}", key, EC_ROOT_PEM, junk));
+        assert_eq!(3, results.len());
+        assert_eq!(
+            Err(X509DecodeErr::PemError(PemDecodeErr::UnexpectedLabel("PUBLIC KEY".to_string()))),
+            results[0]
+        );
+        assert!(results[1].is_ok());
+        assert!(results[2].is_err());
+    }
+}